@@ -0,0 +1,56 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  comment::{GetCommentHistory, GetCommentHistoryResponse},
+  context::LemmyContext,
+  utils::{
+    check_community_visible,
+    check_private_instance,
+    is_mod_or_admin_opt,
+    local_user_view_from_jwt_opt,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    comment::Comment,
+    community::Community,
+    edit_history::CommentEditHistory,
+    local_site::LocalSite,
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetCommentHistory {
+  type Response = GetCommentHistoryResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<GetCommentHistoryResponse, LemmyError> {
+    let data: &GetCommentHistory = self;
+    let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), context).await;
+    let local_site = LocalSite::read(context.pool()).await?;
+    check_private_instance(&local_user_view, &local_site)?;
+
+    let comment = Comment::read(context.pool(), data.comment_id)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_object"))?;
+    let post = Post::read(context.pool(), comment.post_id).await?;
+    let community = Community::read(context.pool(), post.community_id).await?;
+    check_community_visible(&community, local_user_view.as_ref(), context.pool()).await?;
+
+    // Mods need the history to review reports, for everyone else the community has to allow it
+    // and the comment must still be visible
+    let visible = !comment.removed && !comment.deleted && !post.removed && !post.deleted;
+    if !community.edit_history_public || !visible {
+      is_mod_or_admin_opt(context.pool(), local_user_view.as_ref(), Some(community.id)).await?;
+    }
+
+    let history = CommentEditHistory::list(context.pool(), comment.id).await?;
+    Ok(GetCommentHistoryResponse { history })
+  }
+}
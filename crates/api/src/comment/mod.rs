@@ -1,3 +1,4 @@
 mod distinguish;
+mod history;
 mod like;
 mod save;
@@ -3,6 +3,7 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   comment::{CommentReportResponse, CreateCommentReport},
   context::LemmyContext,
+  live_notification::LiveNotification,
   utils::{check_community_ban, local_user_view_from_jwt, send_new_report_email_to_admins},
 };
 use lemmy_db_schema::{
@@ -51,6 +52,12 @@ impl Perform for CreateCommentReport {
 
     let comment_report_view = CommentReportView::read(context.pool(), report.id, person_id).await?;
 
+    LiveNotification::Report {
+      community_id: Some(comment_view.community.id),
+    }
+    .send(context.pool())
+    .await;
+
     // Email the admins
     if local_site.reports_email_admins {
       send_new_report_email_to_admins(
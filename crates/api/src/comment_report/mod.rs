@@ -1,3 +1,5 @@
+mod assign;
 mod create;
+mod escalate;
 mod list;
 mod resolve;
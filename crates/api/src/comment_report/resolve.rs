@@ -1,11 +1,18 @@
-use crate::Perform;
+use crate::{check_report_reason, Perform};
 use actix_web::web::Data;
 use lemmy_api_common::{
   comment::{CommentReportResponse, ResolveCommentReport},
   context::LemmyContext,
   utils::{is_mod_or_admin, local_user_view_from_jwt},
 };
-use lemmy_db_schema::{source::comment_report::CommentReport, traits::Reportable};
+use lemmy_db_schema::{
+  source::{
+    comment_report::CommentReport,
+    local_site::LocalSite,
+    moderator::{ModResolveReport, ModResolveReportForm},
+  },
+  traits::{Crud, Reportable},
+};
 use lemmy_db_views::structs::CommentReportView;
 use lemmy_utils::error::LemmyError;
 
@@ -29,8 +36,19 @@ impl Perform for ResolveCommentReport {
     let person_id = local_user_view.person.id;
     is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
 
+    let reason = data
+      .reason
+      .as_deref()
+      .map(str::trim)
+      .filter(|r| !r.is_empty());
+    if let Some(reason) = reason {
+      let local_site = LocalSite::read(context.pool()).await?;
+      check_report_reason(reason, &local_site)?;
+    }
+    let reason = reason.map(ToOwned::to_owned);
+
     if data.resolved {
-      CommentReport::resolve(context.pool(), report_id, person_id)
+      CommentReport::resolve(context.pool(), report_id, person_id, reason.clone())
         .await
         .map_err(|e| LemmyError::from_error_message(e, "couldnt_resolve_report"))?;
     } else {
@@ -39,6 +57,17 @@ impl Perform for ResolveCommentReport {
         .map_err(|e| LemmyError::from_error_message(e, "couldnt_resolve_report"))?;
     }
 
+    // Mod tables
+    let form = ModResolveReportForm {
+      mod_person_id: person_id,
+      post_id: Some(report.post.id),
+      comment_id: Some(report.comment.id),
+      private_message_report_id: None,
+      resolved: Some(data.resolved),
+      reason,
+    };
+    ModResolveReport::create(context.pool(), &form).await?;
+
     let report_id = data.report_id;
     let comment_report_view = CommentReportView::read(context.pool(), report_id, person_id).await?;
 
@@ -0,0 +1,56 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{ApproveFollow, ApproveFollowResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::community::{Community, CommunityFollower},
+  traits::{Crud, Followable},
+};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ApproveFollow {
+  type Response = ApproveFollowResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ApproveFollowResponse, LemmyError> {
+    let data: &ApproveFollow = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let community_id = data.community_id;
+    let person_id = data.person_id;
+
+    // Verify that only mods or admins can approve follows
+    is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
+    let community = Community::read(context.pool(), community_id).await?;
+    if !community.local {
+      return Err(LemmyError::from_message("not_a_moderator"));
+    }
+
+    if data.approve {
+      CommunityFollower::follow_accepted(context.pool(), community_id, person_id)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_follow"))?;
+    } else {
+      // Only a pending follow can be rejected, approved follows are kept
+      let deleted = CommunityFollower::delete_pending(context.pool(), community_id, person_id)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_follow"))?;
+      if deleted == 0 {
+        return Err(LemmyError::from_message("couldnt_find_follow"));
+      }
+    }
+
+    Ok(ApproveFollowResponse {
+      community_id,
+      person_id,
+      approved: data.approve,
+    })
+  }
+}
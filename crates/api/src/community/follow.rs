@@ -11,6 +11,7 @@ use lemmy_db_schema::{
     community::{Community, CommunityFollower, CommunityFollowerForm},
   },
   traits::{Crud, Followable},
+  CommunityVisibility,
 };
 use lemmy_db_views_actor::structs::CommunityView;
 use lemmy_utils::error::LemmyError;
@@ -37,6 +38,9 @@ impl Perform for FollowCommunity {
         check_community_ban(local_user_view.person.id, community_id, context.pool()).await?;
         check_community_deleted_or_removed(community_id, context.pool()).await?;
 
+        // Private communities need a mod to approve the follow first
+        community_follower_form.pending =
+          community.visibility == CommunityVisibility::ApprovalRequired;
         CommunityFollower::follow(context.pool(), &community_follower_form)
           .await
           .map_err(|e| LemmyError::from_error_message(e, "community_follower_already_exists"))?;
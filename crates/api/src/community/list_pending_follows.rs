@@ -0,0 +1,40 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{ListPendingFollows, ListPendingFollowsResponse},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_views_actor::structs::CommunityFollowerView;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListPendingFollows {
+  type Response = ListPendingFollowsResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListPendingFollowsResponse, LemmyError> {
+    let data: &ListPendingFollows = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let person_id = local_user_view.person.id;
+
+    if let Some(community_id) = data.community_id {
+      is_mod_or_admin(context.pool(), person_id, community_id).await?;
+    }
+
+    let items = CommunityFollowerView::list_pending(
+      context.pool(),
+      person_id,
+      local_user_view.person.admin,
+      data.community_id,
+      data.page,
+      data.limit,
+    )
+    .await?;
+
+    Ok(ListPendingFollowsResponse { items })
+  }
+}
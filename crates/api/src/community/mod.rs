@@ -1,6 +1,8 @@
 mod add_mod;
+mod approve_follow;
 mod ban;
 mod block;
 mod follow;
 mod hide;
+mod list_pending_follows;
 mod transfer;
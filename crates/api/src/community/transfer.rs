@@ -95,6 +95,7 @@ impl Perform for TransferCommunity {
       site: None,
       moderators,
       discussion_languages: vec![],
+      post_tags: vec![],
     })
   }
 }
@@ -8,11 +8,12 @@ use std::io::Cursor;
 mod comment;
 mod comment_report;
 mod community;
-mod local_user;
+pub mod local_user;
 mod post;
 mod post_report;
 mod private_message;
 mod private_message_report;
+mod report_note;
 mod site;
 
 #[async_trait::async_trait(?Send)]
@@ -82,6 +83,7 @@ mod tests {
     source::{
       instance::Instance,
       local_user::{LocalUser, LocalUserInsertForm},
+      login_token::LoginToken,
       person::{Person, PersonInsertForm},
       secret::Secret,
     },
@@ -90,6 +92,8 @@ mod tests {
   };
   use lemmy_utils::{claims::Claims, settings::SETTINGS};
   use serial_test::serial;
+  use std::time::Duration;
+  use tokio::time::sleep;
 
   #[tokio::test]
   #[serial]
@@ -138,4 +142,52 @@ mod tests {
     let num_deleted = Person::delete(pool, inserted_person.id).await.unwrap();
     assert_eq!(1, num_deleted);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_should_not_validate_legacy_token_after_logout_all() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("Gerry9813".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("123456".to_string())
+      .build();
+
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+
+    // A token from before the upgrade, which has no expiry and isn't stored
+    let claims = Claims {
+      sub: inserted_local_user.id.0,
+      iss: "my_domain.tld".to_string(),
+      iat: inserted_local_user.validator_time.timestamp(),
+      exp: None,
+    };
+    let check = check_validator_time(&inserted_local_user.validator_time, &claims);
+    assert!(check.is_ok());
+
+    // The check should fail, so that the token isn't stored on its next use. Tokens only have a
+    // precision of seconds.
+    sleep(Duration::from_secs(1)).await;
+    LoginToken::invalidate_all(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    let updated_local_user = LocalUser::read(pool, inserted_local_user.id).await.unwrap();
+    let check_after = check_validator_time(&updated_local_user.validator_time, &claims);
+    assert!(check_after.is_err());
+
+    let num_deleted = Person::delete(pool, inserted_person.id).await.unwrap();
+    assert_eq!(1, num_deleted);
+  }
 }
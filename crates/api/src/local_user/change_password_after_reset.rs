@@ -3,14 +3,18 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
   person::{LoginResponse, PasswordChangeAfterReset},
-  utils::password_length_check,
+  utils::{create_login_token, password_length_check},
 };
 use lemmy_db_schema::{
-  source::{local_user::LocalUser, password_reset_request::PasswordResetRequest},
+  source::{
+    local_user::LocalUser,
+    login_token::LoginToken,
+    password_reset_request::PasswordResetRequest,
+  },
   RegistrationMode,
 };
 use lemmy_db_views::structs::SiteView;
-use lemmy_utils::{claims::Claims, error::LemmyError};
+use lemmy_utils::error::LemmyError;
 
 #[async_trait::async_trait(?Send)]
 impl Perform for PasswordChangeAfterReset {
@@ -39,6 +43,9 @@ impl Perform for PasswordChangeAfterReset {
       .await
       .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_user"))?;
 
+    // Logout all existing sessions, as the old password may have been compromised
+    LoginToken::invalidate_all(context.pool(), local_user_id).await?;
+
     // Return the jwt if login is allowed
     let site_view = SiteView::read_local(context.pool()).await?;
     let jwt = if site_view.local_site.registration_mode == RegistrationMode::RequireApplication
@@ -46,14 +53,7 @@ impl Perform for PasswordChangeAfterReset {
     {
       None
     } else {
-      Some(
-        Claims::jwt(
-          updated_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      )
+      Some(create_login_token(updated_local_user.id, None, None, context).await?)
     };
 
     Ok(LoginResponse {
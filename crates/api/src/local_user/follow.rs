@@ -0,0 +1,89 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{FollowPerson, FollowPersonResponse, UnfollowPerson},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::person::{PersonFollower, PersonFollowerForm},
+  traits::Followable,
+};
+use lemmy_db_views_actor::structs::PersonView;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for FollowPerson {
+  type Response = FollowPersonResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<FollowPersonResponse, LemmyError> {
+    let data: &FollowPerson = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let target_id = data.person_id;
+    let person_id = local_user_view.person.id;
+
+    // Don't let a person follow themselves
+    if target_id == person_id {
+      return Err(LemmyError::from_message("cant_follow_yourself"));
+    }
+
+    let target_person_view = PersonView::read(context.pool(), target_id).await?;
+    if target_person_view.person.deleted || target_person_view.person.banned {
+      return Err(LemmyError::from_message("couldnt_find_person"));
+    }
+
+    // Remote persons are marked as pending until they accept, the actual federation activity is
+    // sent via `SendActivity` handler
+    let pending = !target_person_view.person.local;
+    let person_follower_form = PersonFollowerForm {
+      person_id: target_id,
+      follower_id: person_id,
+      pending,
+    };
+    PersonFollower::follow(context.pool(), &person_follower_form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "person_follower_already_exists"))?;
+
+    Ok(FollowPersonResponse {
+      person_view: target_person_view,
+      followed: true,
+      pending,
+    })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for UnfollowPerson {
+  type Response = FollowPersonResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<FollowPersonResponse, LemmyError> {
+    let data: &UnfollowPerson = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let person_follower_form = PersonFollowerForm {
+      person_id: data.person_id,
+      follower_id: local_user_view.person.id,
+      pending: false,
+    };
+    PersonFollower::unfollow(context.pool(), &person_follower_form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "person_follower_already_exists"))?;
+
+    let target_person_view = PersonView::read(context.pool(), data.person_id).await?;
+
+    Ok(FollowPersonResponse {
+      person_view: target_person_view,
+      followed: false,
+      pending: false,
+    })
+  }
+}
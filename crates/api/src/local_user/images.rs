@@ -0,0 +1,61 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{DeleteImage, DeleteImageResponse, ListMyImages, ListMyImagesResponse},
+  request::delete_image,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::local_image::LocalImage;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListMyImages {
+  type Response = ListMyImagesResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListMyImagesResponse, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+
+    let images = LocalImage::list_for_local_user(
+      context.pool(),
+      local_user_view.local_user.id,
+      self.page,
+      self.limit,
+    )
+    .await?;
+
+    Ok(ListMyImagesResponse { images })
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for DeleteImage {
+  type Response = DeleteImageResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<DeleteImageResponse, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+
+    let image = LocalImage::read(context.pool(), &self.pictrs_alias)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_image"))?;
+    if image.local_user_id != local_user_view.local_user.id && !local_user_view.person.admin {
+      return Err(LemmyError::from_message("no_image_delete_allowed"));
+    }
+
+    delete_image(
+      context.client(),
+      context.settings(),
+      &image.pictrs_alias,
+      &image.pictrs_delete_token,
+    )
+    .await?;
+    LocalImage::delete(context.pool(), &image.pictrs_alias).await?;
+
+    Ok(DeleteImageResponse { success: true })
+  }
+}
@@ -0,0 +1,23 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListLogins, ListLoginsResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::login_token::LoginToken;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for ListLogins {
+  type Response = ListLoginsResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<ListLoginsResponse, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+
+    let logins = LoginToken::list(context.pool(), local_user_view.local_user.id).await?;
+
+    Ok(ListLoginsResponse { logins })
+  }
+}
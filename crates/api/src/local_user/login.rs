@@ -1,76 +1,88 @@
-use crate::Perform;
-use actix_web::web::Data;
+use actix_web::{
+  http::header::USER_AGENT,
+  web::{Data, Json},
+  HttpRequest,
+};
 use bcrypt::verify;
 use lemmy_api_common::{
   context::LemmyContext,
   person::{Login, LoginResponse},
-  utils::{check_registration_application, check_user_valid},
+  utils::{check_registration_application, check_user_valid, create_login_token},
 };
 use lemmy_db_views::structs::{LocalUserView, SiteView};
-use lemmy_utils::{claims::Claims, error::LemmyError, utils::validation::check_totp_2fa_valid};
+use lemmy_utils::{error::LemmyError, utils::validation::check_totp_2fa_valid};
 
-#[async_trait::async_trait(?Send)]
-impl Perform for Login {
-  type Response = LoginResponse;
+/// Unlike most other api actions this is not implemented with `Perform`, because the ip and
+/// user agent of the request are stored together with the login token.
+#[tracing::instrument(skip(context))]
+pub async fn login(
+  data: Json<Login>,
+  req: HttpRequest,
+  context: Data<LemmyContext>,
+) -> Result<Json<LoginResponse>, LemmyError> {
+  let site_view = SiteView::read_local(context.pool()).await?;
 
-  #[tracing::instrument(skip(context))]
-  async fn perform(&self, context: &Data<LemmyContext>) -> Result<LoginResponse, LemmyError> {
-    let data: &Login = self;
+  // Fetch that username / email
+  let username_or_email = data.username_or_email.clone();
+  let local_user_view = LocalUserView::find_by_email_or_name(context.pool(), &username_or_email)
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_that_username_or_email"))?;
 
-    let site_view = SiteView::read_local(context.pool()).await?;
+  // Verify the password
+  let valid: bool = verify(
+    &data.password,
+    &local_user_view.local_user.password_encrypted,
+  )
+  .unwrap_or(false);
+  if !valid {
+    return Err(LemmyError::from_message("password_incorrect"));
+  }
+  check_user_valid(
+    local_user_view.person.banned,
+    local_user_view.person.ban_expires,
+    local_user_view.person.deleted,
+  )?;
 
-    // Fetch that username / email
-    let username_or_email = data.username_or_email.clone();
-    let local_user_view = LocalUserView::find_by_email_or_name(context.pool(), &username_or_email)
-      .await
-      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_that_username_or_email"))?;
+  // Check if the user's email is verified if email verification is turned on
+  // However, skip checking verification if the user is an admin
+  if !local_user_view.person.admin
+    && site_view.local_site.require_email_verification
+    && !local_user_view.local_user.email_verified
+  {
+    return Err(LemmyError::from_message("email_not_verified"));
+  }
 
-    // Verify the password
-    let valid: bool = verify(
-      &data.password,
-      &local_user_view.local_user.password_encrypted,
-    )
-    .unwrap_or(false);
-    if !valid {
-      return Err(LemmyError::from_message("password_incorrect"));
-    }
-    check_user_valid(
-      local_user_view.person.banned,
-      local_user_view.person.ban_expires,
-      local_user_view.person.deleted,
-    )?;
+  check_registration_application(&local_user_view, &site_view.local_site, context.pool()).await?;
 
-    // Check if the user's email is verified if email verification is turned on
-    // However, skip checking verification if the user is an admin
-    if !local_user_view.person.admin
-      && site_view.local_site.require_email_verification
-      && !local_user_view.local_user.email_verified
-    {
-      return Err(LemmyError::from_message("email_not_verified"));
-    }
+  // Check the totp
+  check_totp_2fa_valid(
+    &local_user_view.local_user.totp_2fa_secret,
+    &data.totp_2fa_token,
+    &site_view.site.name,
+    &local_user_view.person.name,
+  )?;
 
-    check_registration_application(&local_user_view, &site_view.local_site, context.pool()).await?;
+  // Return the jwt
+  let (ip, user_agent) = client_info(&req);
+  let jwt = create_login_token(local_user_view.local_user.id, ip, user_agent, &context).await?;
 
-    // Check the totp
-    check_totp_2fa_valid(
-      &local_user_view.local_user.totp_2fa_secret,
-      &data.totp_2fa_token,
-      &site_view.site.name,
-      &local_user_view.person.name,
-    )?;
+  Ok(Json(LoginResponse {
+    jwt: Some(jwt),
+    verify_email_sent: false,
+    registration_created: false,
+  }))
+}
 
-    // Return the jwt
-    Ok(LoginResponse {
-      jwt: Some(
-        Claims::jwt(
-          local_user_view.local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      ),
-      verify_email_sent: false,
-      registration_created: false,
-    })
-  }
+/// The ip and user agent of the client, which are stored together with a login token.
+pub(crate) fn client_info(req: &HttpRequest) -> (Option<String>, Option<String>) {
+  let ip = req
+    .connection_info()
+    .realip_remote_addr()
+    .map(ToString::to_string);
+  let user_agent = req
+    .headers()
+    .get(USER_AGENT)
+    .and_then(|ua| ua.to_str().ok())
+    .map(ToString::to_string);
+  (ip, user_agent)
 }
@@ -0,0 +1,38 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{Logout, LogoutAll, LogoutResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::login_token::LoginToken;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for Logout {
+  type Response = LogoutResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<LogoutResponse, LemmyError> {
+    // Make sure that the token is valid, so that random strings can't be used to probe the table
+    local_user_view_from_jwt(&self.auth, context).await?;
+
+    LoginToken::invalidate(context.pool(), &self.auth).await?;
+
+    Ok(LogoutResponse {})
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for LogoutAll {
+  type Response = LogoutResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<LogoutResponse, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+
+    LoginToken::invalidate_all(context.pool(), local_user_view.local_user.id).await?;
+
+    Ok(LogoutResponse {})
+  }
+}
@@ -3,10 +3,16 @@ mod ban_person;
 mod block;
 mod change_password;
 mod change_password_after_reset;
+mod follow;
 mod get_captcha;
+mod images;
 mod list_banned;
-mod login;
+mod list_logins;
+pub mod login;
+mod logout;
 mod notifications;
+pub mod oidc;
+mod push_subscription;
 mod report_count;
 mod reset_password;
 mod save_settings;
@@ -0,0 +1,553 @@
+use crate::local_user::login::client_info;
+use actix_web::{
+  web::{Data, Json},
+  HttpRequest,
+};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{LoginResponse, OidcAuthorize, OidcAuthorizeResponse, OidcLogin},
+  utils::{
+    check_registration_application,
+    check_user_valid,
+    create_local_account,
+    create_login_token,
+    local_user_view_from_jwt,
+    registration_login_response,
+    NewLocalAccount,
+  },
+};
+use lemmy_db_schema::source::oidc::{
+  OidcIdentity,
+  OidcIdentityForm,
+  OidcLoginState,
+  OidcLoginStateForm,
+};
+use lemmy_db_views::structs::{LocalUserView, SiteView};
+use lemmy_utils::{
+  error::LemmyError,
+  oidc::{authorization_url, discover, fetch_user_info, LoginSecrets, UserInfo},
+  settings::structs::{OidcProviderConfig, Settings},
+};
+use url::Url;
+
+#[tracing::instrument(skip(context))]
+pub async fn oidc_authorize(
+  data: Json<OidcAuthorize>,
+  context: Data<LemmyContext>,
+) -> Result<Json<OidcAuthorizeResponse>, LemmyError> {
+  let config = provider_config(context.settings(), &data.provider)?;
+  start_login(&data, config, &context).await.map(Json)
+}
+
+/// Like [login](crate::local_user::login::login), this is not implemented with `Perform` because
+/// the ip and user agent are stored together with the login token.
+///
+/// The provider is responsible for checking the password and second factor, so the totp of the
+/// local account isn't required.
+#[tracing::instrument(skip(context))]
+pub async fn oidc_login(
+  data: Json<OidcLogin>,
+  req: HttpRequest,
+  context: Data<LemmyContext>,
+) -> Result<Json<LoginResponse>, LemmyError> {
+  let login_state = OidcLoginState::take(context.pool(), &data.state)
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "oidc_login_expired"))?;
+  let config = provider_config(context.settings(), &login_state.provider)?;
+  finish_login(&data, login_state, config, client_info(&req), &context)
+    .await
+    .map(Json)
+}
+
+/// Stores the secrets of the login, and who wants to link their account if a user is logged in.
+async fn start_login(
+  data: &OidcAuthorize,
+  config: &OidcProviderConfig,
+  context: &LemmyContext,
+) -> Result<OidcAuthorizeResponse, LemmyError> {
+  // The code is passed to the redirect uri, so it must not be possible to send it elsewhere
+  let redirect_uri = Url::parse(&data.redirect_uri)
+    .map_err(|e| LemmyError::from_error_message(e, "invalid_redirect_uri"))?;
+  if redirect_uri.origin().ascii_serialization() != context.settings().get_protocol_and_hostname() {
+    return Err(LemmyError::from_message("invalid_redirect_uri"));
+  }
+  let link_local_user_id = match &data.auth {
+    Some(auth) => Some(local_user_view_from_jwt(auth, context).await?.local_user.id),
+    None => None,
+  };
+
+  let metadata = discover(context.client(), config).await?;
+  let secrets = LoginSecrets::generate()?;
+  let form = OidcLoginStateForm {
+    state: secrets.state.clone(),
+    provider: config.name.clone(),
+    code_verifier: secrets.code_verifier.clone(),
+    redirect_uri: data.redirect_uri.clone(),
+    link_local_user_id,
+  };
+  OidcLoginState::create(context.pool(), &form).await?;
+
+  let authorization_url = authorization_url(&metadata, config, &secrets, &data.redirect_uri);
+  Ok(OidcAuthorizeResponse {
+    authorization_url: authorization_url.into(),
+  })
+}
+
+/// Logs in, registers or links the account, depending on how the login was started.
+async fn finish_login(
+  data: &OidcLogin,
+  login_state: OidcLoginState,
+  config: &OidcProviderConfig,
+  (ip, user_agent): (Option<String>, Option<String>),
+  context: &LemmyContext,
+) -> Result<LoginResponse, LemmyError> {
+  let metadata = discover(context.client(), config).await?;
+  let user_info = fetch_user_info(
+    context.client(),
+    &metadata,
+    config,
+    &data.code,
+    &login_state.code_verifier,
+    &login_state.redirect_uri,
+  )
+  .await?;
+
+  // Only the user who started the login can link the provider account, otherwise a login link
+  // could be used to attach an attacker's provider account to the victim's account
+  match (login_state.link_local_user_id, &data.auth) {
+    (Some(link_local_user_id), Some(auth)) => {
+      let local_user_view = local_user_view_from_jwt(auth, context).await?;
+      if local_user_view.local_user.id != link_local_user_id {
+        return Err(LemmyError::from_message("oidc_link_not_requested"));
+      }
+      let form = OidcIdentityForm {
+        local_user_id: link_local_user_id,
+        provider: config.name.clone(),
+        subject: user_info.sub,
+      };
+      OidcIdentity::create(context.pool(), &form)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "oidc_account_already_linked"))?;
+      return Ok(LoginResponse {
+        jwt: None,
+        registration_created: false,
+        verify_email_sent: false,
+      });
+    }
+    (None, None) => {}
+    _ => return Err(LemmyError::from_message("oidc_link_not_requested")),
+  }
+
+  let site_view = SiteView::read_local(context.pool()).await?;
+  let identity =
+    OidcIdentity::read_for_subject(context.pool(), &config.name, &user_info.sub).await?;
+  let Some(identity) = identity else {
+    return register(
+      data,
+      config,
+      user_info,
+      &site_view,
+      (ip, user_agent),
+      context,
+    )
+    .await;
+  };
+
+  let local_user_view = LocalUserView::read(context.pool(), identity.local_user_id).await?;
+  check_user_valid(
+    local_user_view.person.banned,
+    local_user_view.person.ban_expires,
+    local_user_view.person.deleted,
+  )?;
+  if !local_user_view.person.admin
+    && site_view.local_site.require_email_verification
+    && !local_user_view.local_user.email_verified
+  {
+    return Err(LemmyError::from_message("email_not_verified"));
+  }
+  check_registration_application(&local_user_view, &site_view.local_site, context.pool()).await?;
+
+  let jwt = create_login_token(local_user_view.local_user.id, ip, user_agent, context).await?;
+  Ok(LoginResponse {
+    jwt: Some(jwt),
+    verify_email_sent: false,
+    registration_created: false,
+  })
+}
+
+fn provider_config<'a>(
+  settings: &'a Settings,
+  name: &str,
+) -> Result<&'a OidcProviderConfig, LemmyError> {
+  settings
+    .oidc_providers
+    .iter()
+    .find(|p| p.name == name)
+    .ok_or_else(|| LemmyError::from_message("oidc_provider_not_found"))
+}
+
+/// Creates a new account for the provider account, with the same rules as `Register`. Captchas
+/// aren't necessary, because the user already went through the login of the provider.
+async fn register(
+  data: &OidcLogin,
+  config: &OidcProviderConfig,
+  user_info: UserInfo,
+  site_view: &SiteView,
+  client_info: (Option<String>, Option<String>),
+  context: &LemmyContext,
+) -> Result<LoginResponse, LemmyError> {
+  let username = data
+    .username
+    .clone()
+    .or(user_info.preferred_username)
+    .ok_or_else(|| LemmyError::from_message("oidc_username_required"))?;
+
+  // Existing accounts with the same email need to log in first, and link the provider account
+  // themselves. The account has no usable password, it can be set with a password reset.
+  let account = NewLocalAccount {
+    username,
+    email: user_info.email,
+    email_verified: user_info.email_verified,
+    password: uuid::Uuid::new_v4().to_string(),
+    show_nsfw: data.show_nsfw,
+    answer: data.answer.clone(),
+  };
+  let local_user_view = create_local_account(account, site_view, context).await?;
+
+  let form = OidcIdentityForm {
+    local_user_id: local_user_view.local_user.id,
+    provider: config.name.clone(),
+    subject: user_info.sub,
+  };
+  OidcIdentity::create(context.pool(), &form).await?;
+
+  // Emails which the provider already verified don't need to be verified again
+  let local_site = &site_view.local_site;
+  let require_email_verification =
+    local_site.require_email_verification && !user_info.email_verified;
+  registration_login_response(
+    &local_user_view,
+    local_site,
+    require_email_verification,
+    client_info,
+    context,
+  )
+  .await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{finish_login, start_login};
+  use lemmy_api_common::{
+    context::LemmyContext,
+    person::{LoginResponse, OidcAuthorize, OidcLogin},
+    sensitive::Sensitive,
+    utils::create_login_token,
+  };
+  use lemmy_db_schema::{
+    source::{
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm, LocalSiteUpdateForm},
+      local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitInsertForm},
+      local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
+      oidc::{OidcIdentity, OidcLoginState},
+      person::{Person, PersonInsertForm},
+      registration_application::RegistrationApplication,
+      secret::Secret,
+      site::{Site, SiteInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    RegistrationMode,
+  };
+  use lemmy_db_views::structs::LocalUserView;
+  use lemmy_utils::{
+    error::LemmyError,
+    oidc::mock::{MockProvider, CLIENT_ID, CLIENT_SECRET},
+    rate_limit::{RateLimitCell, RateLimitConfig},
+    settings::structs::OidcProviderConfig,
+  };
+  use reqwest_middleware::ClientBuilder;
+  use serial_test::serial;
+  use url::Url;
+
+  async fn init_context() -> LemmyContext {
+    let pool = build_db_pool_for_tests().await;
+    let secret = Secret::init(&pool).await.unwrap();
+    let client = ClientBuilder::new(reqwest::Client::new()).build();
+    let rate_limit_config = RateLimitConfig::builder().build();
+    let rate_limit_cell = RateLimitCell::new(rate_limit_config, secret.jwt_secret.clone()).await;
+    LemmyContext::create(pool, client, secret, rate_limit_cell.clone())
+  }
+
+  /// Goes through the whole login, as the given user of the mock provider.
+  async fn login(
+    provider: &MockProvider,
+    config: &OidcProviderConfig,
+    name: &str,
+    start_auth: Option<&Sensitive<String>>,
+    data: OidcLogin,
+    context: &LemmyContext,
+  ) -> Result<LoginResponse, LemmyError> {
+    let authorize = OidcAuthorize {
+      provider: config.name.clone(),
+      redirect_uri: format!(
+        "{}/oidc/callback",
+        context.settings().get_protocol_and_hostname()
+      ),
+      auth: start_auth.cloned(),
+    };
+    let response = start_login(&authorize, config, context).await?;
+    let authorization_url = Url::parse(&response.authorization_url)?;
+    let (code, state) = provider.authorize_as(&authorization_url, name);
+    let login_state = OidcLoginState::take(context.pool(), &state).await?;
+    let data = OidcLogin {
+      code,
+      state,
+      ..data
+    };
+    finish_login(&data, login_state, config, (None, None), context).await
+  }
+
+  fn error_message(result: Result<LoginResponse, LemmyError>) -> Option<String> {
+    result.err().and_then(|e| e.message)
+  }
+
+  async fn set_registration_mode(context: &LemmyContext, registration_mode: RegistrationMode) {
+    let form = LocalSiteUpdateForm::builder()
+      .registration_mode(Some(registration_mode))
+      .build();
+    LocalSite::update(context.pool(), &form).await.unwrap();
+  }
+
+  #[actix_web::test]
+  #[serial]
+  async fn test_oidc_register_and_login() {
+    let context = init_context().await;
+    let pool = context.pool();
+    let provider = MockProvider::start().await;
+    let config = OidcProviderConfig {
+      name: "mock".to_string(),
+      display_name: "Mock".to_string(),
+      issuer: provider.issuer.clone(),
+      client_id: CLIENT_ID.to_string(),
+      client_secret: CLIENT_SECRET.to_string(),
+      scopes: "openid email profile".to_string(),
+    };
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_site = Site::create(pool, &site_form).await.unwrap();
+    let local_site_form = LocalSiteInsertForm::builder()
+      .site_id(inserted_site.id)
+      .site_setup(Some(true))
+      .registration_mode(Some(RegistrationMode::Closed))
+      .application_question(Some("Why do you want to join?".to_string()))
+      .build();
+    let inserted_local_site = LocalSite::create(pool, &local_site_form).await.unwrap();
+    let rate_limit_form = LocalSiteRateLimitInsertForm::builder()
+      .local_site_id(inserted_local_site.id)
+      .build();
+    LocalSiteRateLimit::create(pool, &rate_limit_form)
+      .await
+      .unwrap();
+
+    // Logging in with an unknown provider account registers it, which isn't possible while
+    // registrations are closed
+    let result = login(
+      &provider,
+      &config,
+      "mock",
+      None,
+      OidcLogin::default(),
+      &context,
+    )
+    .await;
+    assert_eq!(
+      Some("registration_closed"),
+      error_message(result).as_deref()
+    );
+
+    // The registration questionnaire needs to be answered
+    set_registration_mode(&context, RegistrationMode::RequireApplication).await;
+    let result = login(
+      &provider,
+      &config,
+      "mock",
+      None,
+      OidcLogin::default(),
+      &context,
+    )
+    .await;
+    assert_eq!(
+      Some("registration_application_answer_required"),
+      error_message(result).as_deref()
+    );
+
+    let data = OidcLogin {
+      answer: Some("To test the login".to_string()),
+      ..Default::default()
+    };
+    let response = login(&provider, &config, "mock", None, data, &context)
+      .await
+      .unwrap();
+    assert!(response.registration_created);
+    assert!(response.jwt.is_none());
+
+    let identity = OidcIdentity::read_for_subject(pool, "mock", "mock_subject")
+      .await
+      .unwrap()
+      .unwrap();
+    let local_user_view = LocalUserView::read(pool, identity.local_user_id)
+      .await
+      .unwrap();
+    assert_eq!("mock_user", local_user_view.person.name);
+    assert_eq!(
+      Some("mock@example.com".to_string()),
+      local_user_view.local_user.email
+    );
+    let application = RegistrationApplication::find_by_local_user_id(pool, identity.local_user_id)
+      .await
+      .unwrap();
+    assert_eq!("To test the login", application.answer);
+
+    // Logging in again doesn't work before the application is accepted
+    let result = login(
+      &provider,
+      &config,
+      "mock",
+      None,
+      OidcLogin::default(),
+      &context,
+    )
+    .await;
+    assert_eq!(
+      Some("registration_application_pending"),
+      error_message(result).as_deref()
+    );
+
+    let form = LocalUserUpdateForm::builder()
+      .accepted_application(Some(true))
+      .build();
+    LocalUser::update(pool, identity.local_user_id, &form)
+      .await
+      .unwrap();
+    let response = login(
+      &provider,
+      &config,
+      "mock",
+      None,
+      OidcLogin::default(),
+      &context,
+    )
+    .await
+    .unwrap();
+    assert!(response.jwt.is_some());
+
+    // Open registrations don't need an answer, and log in directly
+    set_registration_mode(&context, RegistrationMode::Open).await;
+    let response = login(
+      &provider,
+      &config,
+      "other",
+      None,
+      OidcLogin::default(),
+      &context,
+    )
+    .await
+    .unwrap();
+    assert!(!response.registration_created);
+    let other_jwt = response.jwt.unwrap();
+    let other_identity = OidcIdentity::read_for_subject(pool, "mock", "other_subject")
+      .await
+      .unwrap()
+      .unwrap();
+
+    let person_form = PersonInsertForm::builder()
+      .name("oidc_linker".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &person_form).await.unwrap();
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("password".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &local_user_form).await.unwrap();
+    let jwt = create_login_token(inserted_local_user.id, None, None, &context)
+      .await
+      .unwrap();
+
+    // A provider account can only be linked by the user who started the login
+    let linked_identity = |name: &str| {
+      let subject = format!("{name}_subject");
+      async move {
+        OidcIdentity::read_for_subject(pool, "mock", &subject)
+          .await
+          .unwrap()
+      }
+    };
+    let data = OidcLogin {
+      auth: Some(jwt.clone()),
+      ..Default::default()
+    };
+    let result = login(&provider, &config, "third", None, data.clone(), &context).await;
+    assert_eq!(
+      Some("oidc_link_not_requested"),
+      error_message(result).as_deref()
+    );
+    let result = login(
+      &provider,
+      &config,
+      "third",
+      Some(&other_jwt),
+      data.clone(),
+      &context,
+    )
+    .await;
+    assert_eq!(
+      Some("oidc_link_not_requested"),
+      error_message(result).as_deref()
+    );
+    let result = login(
+      &provider,
+      &config,
+      "third",
+      Some(&jwt),
+      OidcLogin::default(),
+      &context,
+    )
+    .await;
+    assert_eq!(
+      Some("oidc_link_not_requested"),
+      error_message(result).as_deref()
+    );
+    assert_eq!(None, linked_identity("third").await);
+
+    let response = login(&provider, &config, "third", Some(&jwt), data, &context)
+      .await
+      .unwrap();
+    assert!(response.jwt.is_none());
+    assert_eq!(
+      Some(inserted_local_user.id),
+      linked_identity("third").await.map(|i| i.local_user_id)
+    );
+
+    for local_user_id in [identity.local_user_id, other_identity.local_user_id] {
+      let local_user_view = LocalUserView::read(pool, local_user_id).await.unwrap();
+      Person::delete(pool, local_user_view.person.id)
+        .await
+        .unwrap();
+    }
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    LocalSite::delete(pool).await.unwrap();
+    Site::delete(pool, inserted_site.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+    provider.stop().await;
+  }
+}
@@ -0,0 +1,62 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{DeletePushSubscription, PushSubscriptionResponse, RegisterPushSubscription},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::push_subscription::{PushSubscription, PushSubscriptionForm};
+use lemmy_utils::{error::LemmyError, web_push::validate_subscription};
+
+#[async_trait::async_trait(?Send)]
+impl Perform for RegisterPushSubscription {
+  type Response = PushSubscriptionResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<PushSubscriptionResponse, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+    if context.settings().web_push.is_none() {
+      return Err(LemmyError::from_message("no_web_push_setup"));
+    }
+
+    validate_subscription(&self.endpoint, &self.keys.p256dh, &self.keys.auth)?;
+
+    let form = PushSubscriptionForm {
+      local_user_id: local_user_view.local_user.id,
+      endpoint: self.endpoint.clone(),
+      p256dh: self.keys.p256dh.clone(),
+      auth: self.keys.auth.clone(),
+    };
+    PushSubscription::upsert(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_register_push_subscription"))?
+      .ok_or_else(|| LemmyError::from_message("push_subscription_already_exists"))?;
+
+    Ok(PushSubscriptionResponse {})
+  }
+}
+
+#[async_trait::async_trait(?Send)]
+impl Perform for DeletePushSubscription {
+  type Response = PushSubscriptionResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<PushSubscriptionResponse, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+
+    PushSubscription::delete_for_local_user(
+      context.pool(),
+      local_user_view.local_user.id,
+      &self.endpoint,
+    )
+    .await?;
+
+    Ok(PushSubscriptionResponse {})
+  }
+}
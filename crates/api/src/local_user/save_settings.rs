@@ -3,7 +3,7 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
   person::{LoginResponse, SaveUserSettings},
-  utils::{local_user_view_from_jwt, send_verification_email},
+  utils::{check_also_known_as, local_user_view_from_jwt, send_verification_email},
 };
 use lemmy_db_schema::{
   source::{
@@ -16,14 +16,15 @@ use lemmy_db_schema::{
 };
 use lemmy_db_views::structs::SiteView;
 use lemmy_utils::{
-  claims::Claims,
   error::LemmyError,
   utils::validation::{
     build_totp_2fa,
     generate_totp_2fa_secret,
     is_valid_bio_field,
     is_valid_display_name,
+    is_valid_interface_language,
     is_valid_matrix_id,
+    is_valid_theme,
   },
 };
 
@@ -76,6 +77,20 @@ impl Perform for SaveUserSettings {
       is_valid_matrix_id(matrix_user_id)?;
     }
 
+    if let Some(theme) = &data.theme {
+      is_valid_theme(theme)?;
+    }
+
+    if let Some(interface_language) = &data.interface_language {
+      is_valid_interface_language(interface_language)?;
+    }
+
+    let also_known_as = data
+      .also_known_as
+      .as_deref()
+      .map(check_also_known_as)
+      .transpose()?;
+
     let local_user_id = local_user_view.local_user.id;
     let person_id = local_user_view.person.id;
     let default_listing_type = data.default_listing_type;
@@ -88,6 +103,7 @@ impl Perform for SaveUserSettings {
       .bot_account(data.bot_account)
       .avatar(avatar)
       .banner(banner)
+      .also_known_as(also_known_as)
       .build();
 
     Person::update(context.pool(), person_id, &person_form)
@@ -128,34 +144,27 @@ impl Perform for SaveUserSettings {
       .totp_2fa_secret(totp_2fa_secret)
       .totp_2fa_url(totp_2fa_url)
       .open_links_in_new_tab(data.open_links_in_new_tab)
+      .push_notify_replies(data.push_notify_replies)
+      .push_notify_mentions(data.push_notify_mentions)
+      .push_notify_private_messages(data.push_notify_private_messages)
       .build();
 
     let local_user_res = LocalUser::update(context.pool(), local_user_id, &local_user_form).await;
-    let updated_local_user = match local_user_res {
-      Ok(u) => u,
-      Err(e) => {
-        let err_type = if e.to_string()
-          == "duplicate key value violates unique constraint \"local_user_email_key\""
-        {
-          "email_already_exists"
-        } else {
-          "user_already_exists"
-        };
-
-        return Err(LemmyError::from_error_message(e, err_type));
-      }
-    };
+    if let Err(e) = local_user_res {
+      let err_type = if e.to_string()
+        == "duplicate key value violates unique constraint \"local_user_email_key\""
+      {
+        "email_already_exists"
+      } else {
+        "user_already_exists"
+      };
+
+      return Err(LemmyError::from_error_message(e, err_type));
+    }
 
-    // Return the jwt
+    // The existing jwt stays valid, so return it unchanged
     Ok(LoginResponse {
-      jwt: Some(
-        Claims::jwt(
-          updated_local_user.id.0,
-          &context.secret().jwt_secret,
-          &context.settings().hostname,
-        )?
-        .into(),
-      ),
+      jwt: Some(data.auth.clone()),
       verify_email_sent: false,
       registration_created: false,
     })
@@ -0,0 +1,54 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{GetPostHistory, GetPostHistoryResponse},
+  utils::{
+    check_community_visible,
+    check_private_instance,
+    is_mod_or_admin_opt,
+    local_user_view_from_jwt_opt,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    edit_history::PostEditHistory,
+    local_site::LocalSite,
+    post::Post,
+  },
+  traits::Crud,
+};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for GetPostHistory {
+  type Response = GetPostHistoryResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<GetPostHistoryResponse, LemmyError> {
+    let data: &GetPostHistory = self;
+    let local_user_view = local_user_view_from_jwt_opt(data.auth.as_ref(), context).await;
+    let local_site = LocalSite::read(context.pool()).await?;
+    check_private_instance(&local_user_view, &local_site)?;
+
+    let post = Post::read(context.pool(), data.post_id)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_post"))?;
+    let community = Community::read(context.pool(), post.community_id).await?;
+    check_community_visible(&community, local_user_view.as_ref(), context.pool()).await?;
+
+    // Mods need the history to review reports, for everyone else the community has to allow it
+    // and the post must still be visible
+    let visible = !post.removed && !post.deleted;
+    if !community.edit_history_public || !visible {
+      is_mod_or_admin_opt(context.pool(), local_user_view.as_ref(), Some(community.id)).await?;
+    }
+
+    let history = PostEditHistory::list(context.pool(), post.id).await?;
+    Ok(GetPostHistoryResponse { history })
+  }
+}
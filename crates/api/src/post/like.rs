@@ -8,6 +8,7 @@ use lemmy_api_common::{
     check_community_ban,
     check_community_deleted_or_removed,
     check_downvotes_enabled,
+    check_post_published,
     local_user_view_from_jwt,
     mark_post_as_read,
   },
@@ -37,6 +38,7 @@ impl Perform for CreatePostLike {
     // Check for a community ban
     let post_id = data.post_id;
     let post = Post::read(context.pool(), post_id).await?;
+    check_post_published(&post)?;
 
     check_community_ban(local_user_view.person.id, post.community_id, context.pool()).await?;
     check_community_deleted_or_removed(post.community_id, context.pool()).await?;
@@ -0,0 +1,39 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{ListScheduledPosts, ListScheduledPostsResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_views::post_view::PostQuery;
+use lemmy_utils::error::LemmyError;
+
+/// Lists the scheduled posts of the user, and those in the communities they moderate. Admins can
+/// see all scheduled posts.
+#[async_trait::async_trait(?Send)]
+impl Perform for ListScheduledPosts {
+  type Response = ListScheduledPostsResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListScheduledPostsResponse, LemmyError> {
+    let data: &ListScheduledPosts = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let posts = PostQuery::builder()
+      .pool(context.pool())
+      .local_user(Some(&local_user_view.local_user))
+      .community_id(data.community_id)
+      .scheduled_only(Some(true))
+      .is_mod_or_admin(Some(local_user_view.person.admin))
+      .page(data.page)
+      .limit(data.limit)
+      .build()
+      .list()
+      .await?;
+
+    Ok(ListScheduledPostsResponse { posts })
+  }
+}
@@ -7,6 +7,7 @@ use lemmy_api_common::{
   utils::{
     check_community_ban,
     check_community_deleted_or_removed,
+    check_post_published,
     is_mod_or_admin,
     local_user_view_from_jwt,
   },
@@ -31,6 +32,7 @@ impl Perform for LockPost {
 
     let post_id = data.post_id;
     let orig_post = Post::read(context.pool(), post_id).await?;
+    check_post_published(&orig_post)?;
 
     check_community_ban(
       local_user_view.person.id,
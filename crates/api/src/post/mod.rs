@@ -1,6 +1,9 @@
 mod feature;
 mod get_link_metadata;
+mod history;
 mod like;
+mod list_scheduled;
 mod lock;
 mod mark_read;
 mod save;
+mod vote_poll;
@@ -3,11 +3,11 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
   post::{PostResponse, SavePost},
-  utils::{local_user_view_from_jwt, mark_post_as_read},
+  utils::{check_post_published, local_user_view_from_jwt, mark_post_as_read},
 };
 use lemmy_db_schema::{
-  source::post::{PostSaved, PostSavedForm},
-  traits::Saveable,
+  source::post::{Post, PostSaved, PostSavedForm},
+  traits::{Crud, Saveable},
 };
 use lemmy_db_views::structs::PostView;
 use lemmy_utils::error::LemmyError;
@@ -21,6 +21,9 @@ impl Perform for SavePost {
     let data: &SavePost = self;
     let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
 
+    let post = Post::read(context.pool(), data.post_id).await?;
+    check_post_published(&post)?;
+
     let post_saved_form = PostSavedForm {
       post_id: data.post_id,
       person_id: local_user_view.person.id,
@@ -0,0 +1,65 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{PostPollResponse, VotePostPoll},
+  utils::{check_community_ban, check_community_deleted_or_removed, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    post::Post,
+    post_poll::{PostPoll, PostPollOption, PostPollVote},
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::PostPollView;
+use lemmy_utils::error::LemmyError;
+use std::collections::HashSet;
+
+#[async_trait::async_trait(?Send)]
+impl Perform for VotePostPoll {
+  type Response = PostPollResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<PostPollResponse, LemmyError> {
+    let data: &VotePostPoll = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let person_id = local_user_view.person.id;
+
+    let post = Post::read(context.pool(), data.post_id).await?;
+    check_community_ban(person_id, post.community_id, context.pool()).await?;
+    check_community_deleted_or_removed(post.community_id, context.pool()).await?;
+    if post.scheduled_publish_time.is_some() {
+      return Err(LemmyError::from_message("post_not_published"));
+    }
+
+    let poll = PostPoll::read_for_post(context.pool(), post.id)
+      .await?
+      .ok_or_else(|| LemmyError::from_message("post_has_no_poll"))?;
+    if poll.is_closed() {
+      return Err(LemmyError::from_message("poll_closed"));
+    }
+
+    // Only known options are accepted, and single choice polls take exactly one
+    let options = PostPollOption::list_for_poll(context.pool(), poll.id).await?;
+    let valid_options = !data.option_ids.is_empty()
+      && data.option_ids.iter().collect::<HashSet<_>>().len() == data.option_ids.len()
+      && data
+        .option_ids
+        .iter()
+        .all(|id| options.iter().any(|o| &o.id == id));
+    if !valid_options || (!poll.multiple_choice && data.option_ids.len() != 1) {
+      return Err(LemmyError::from_message("invalid_poll_vote"));
+    }
+
+    PostPollVote::vote(context.pool(), poll.id, person_id, &data.option_ids)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_vote_in_poll"))?
+      .ok_or_else(|| LemmyError::from_message("already_voted_in_poll"))?;
+
+    let poll = PostPollView::read(context.pool(), post.id, Some(person_id))
+      .await?
+      .ok_or_else(|| LemmyError::from_message("post_has_no_poll"))?;
+    Ok(PostPollResponse { poll })
+  }
+}
@@ -0,0 +1,44 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{AssignPostReport, PostReportResponse},
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{source::post_report::PostReport, traits::Reportable};
+use lemmy_db_views::structs::PostReportView;
+use lemmy_db_views_actor::structs::CommunityView;
+use lemmy_utils::error::LemmyError;
+
+/// Assigns a post report to a moderator of the community, or unassigns it
+#[async_trait::async_trait(?Send)]
+impl Perform for AssignPostReport {
+  type Response = PostReportResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<PostReportResponse, LemmyError> {
+    let data: &AssignPostReport = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let report_id = data.report_id;
+    let person_id = local_user_view.person.id;
+    let report = PostReportView::read(context.pool(), report_id, person_id).await?;
+
+    is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
+
+    if let Some(assignee_id) = data.assignee_id {
+      let community_id = report.community.id;
+      if !CommunityView::is_mod_or_admin(context.pool(), assignee_id, community_id).await? {
+        return Err(LemmyError::from_message("invalid_report_assignee"));
+      }
+    }
+
+    PostReport::assign(context.pool(), report_id, data.assignee_id)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_assign_report"))?;
+
+    let post_report_view = PostReportView::read(context.pool(), report_id, person_id).await?;
+
+    Ok(PostReportResponse { post_report_view })
+  }
+}
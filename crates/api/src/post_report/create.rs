@@ -2,6 +2,7 @@ use crate::{check_report_reason, Perform};
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  live_notification::LiveNotification,
   post::{CreatePostReport, PostReportResponse},
   utils::{check_community_ban, local_user_view_from_jwt, send_new_report_email_to_admins},
 };
@@ -50,6 +51,12 @@ impl Perform for CreatePostReport {
 
     let post_report_view = PostReportView::read(context.pool(), report.id, person_id).await?;
 
+    LiveNotification::Report {
+      community_id: Some(post_view.community.id),
+    }
+    .send(context.pool())
+    .await;
+
     // Email the admins
     if local_site.reports_email_admins {
       send_new_report_email_to_admins(
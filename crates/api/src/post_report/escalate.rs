@@ -0,0 +1,47 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{EscalatePostReport, PostReportResponse},
+  utils::{is_mod_or_admin, local_user_view_from_jwt, send_new_report_email_to_admins},
+};
+use lemmy_db_schema::source::{local_site::LocalSite, post_report::PostReport};
+use lemmy_db_views::structs::PostReportView;
+use lemmy_utils::error::LemmyError;
+
+/// Escalates a post report to the admins, and emails them about it
+#[async_trait::async_trait(?Send)]
+impl Perform for EscalatePostReport {
+  type Response = PostReportResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<PostReportResponse, LemmyError> {
+    let data: &EscalatePostReport = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let local_site = LocalSite::read(context.pool()).await?;
+
+    let report_id = data.report_id;
+    let person_id = local_user_view.person.id;
+    let report = PostReportView::read(context.pool(), report_id, person_id).await?;
+
+    is_mod_or_admin(context.pool(), person_id, report.community.id).await?;
+
+    PostReport::escalate(context.pool(), report_id, data.escalated)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_escalate_report"))?;
+
+    if data.escalated && !report.post_report.escalated && local_site.reports_email_admins {
+      send_new_report_email_to_admins(
+        &local_user_view.person.name,
+        &report.post_creator.name,
+        context.pool(),
+        context.settings(),
+      )
+      .await?;
+    }
+
+    let post_report_view = PostReportView::read(context.pool(), report_id, person_id).await?;
+
+    Ok(PostReportResponse { post_report_view })
+  }
+}
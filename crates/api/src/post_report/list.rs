@@ -26,6 +26,7 @@ impl Perform for ListPostReports {
     let admin = local_user_view.person.admin;
     let community_id = data.community_id;
     let unresolved_only = data.unresolved_only;
+    let escalated_only = data.escalated_only;
 
     let page = data.page;
     let limit = data.limit;
@@ -35,6 +36,7 @@ impl Perform for ListPostReports {
       .admin(admin)
       .community_id(community_id)
       .unresolved_only(unresolved_only)
+      .escalated_only(escalated_only)
       .page(page)
       .limit(limit)
       .build()
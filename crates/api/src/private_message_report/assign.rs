@@ -0,0 +1,45 @@
+use crate::Perform;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  private_message::{AssignPrivateMessageReport, PrivateMessageReportResponse},
+  utils::{is_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{person::Person, private_message_report::PrivateMessageReport},
+  traits::{Crud, Reportable},
+};
+use lemmy_db_views::structs::PrivateMessageReportView;
+use lemmy_utils::error::LemmyError;
+
+/// Assigns a private message report to an admin, or unassigns it
+#[async_trait::async_trait(?Send)]
+impl Perform for AssignPrivateMessageReport {
+  type Response = PrivateMessageReportResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<Self::Response, LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&self.auth, context).await?;
+
+    is_admin(&local_user_view)?;
+
+    if let Some(assignee_id) = self.assignee_id {
+      let assignee = Person::read(context.pool(), assignee_id).await?;
+      if !assignee.admin {
+        return Err(LemmyError::from_message("invalid_report_assignee"));
+      }
+    }
+
+    let report_id = self.report_id;
+    PrivateMessageReport::assign(context.pool(), report_id, self.assignee_id)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_assign_report"))?;
+
+    let private_message_report_view =
+      PrivateMessageReportView::read(context.pool(), report_id).await?;
+
+    Ok(PrivateMessageReportResponse {
+      private_message_report_view,
+    })
+  }
+}
@@ -2,6 +2,7 @@ use crate::{check_report_reason, Perform};
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  live_notification::LiveNotification,
   private_message::{CreatePrivateMessageReport, PrivateMessageReportResponse},
   utils::{local_user_view_from_jwt, send_new_report_email_to_admins},
 };
@@ -46,6 +47,10 @@ impl Perform for CreatePrivateMessageReport {
     let private_message_report_view =
       PrivateMessageReportView::read(context.pool(), report.id).await?;
 
+    LiveNotification::Report { community_id: None }
+      .send(context.pool())
+      .await;
+
     // Email the admins
     if local_site.reports_email_admins {
       send_new_report_email_to_admins(
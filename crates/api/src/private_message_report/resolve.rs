@@ -1,11 +1,18 @@
-use crate::Perform;
+use crate::{check_report_reason, Perform};
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
   private_message::{PrivateMessageReportResponse, ResolvePrivateMessageReport},
   utils::{is_admin, local_user_view_from_jwt},
 };
-use lemmy_db_schema::{source::private_message_report::PrivateMessageReport, traits::Reportable};
+use lemmy_db_schema::{
+  source::{
+    local_site::LocalSite,
+    moderator::{ModResolveReport, ModResolveReportForm},
+    private_message_report::PrivateMessageReport,
+  },
+  traits::{Crud, Reportable},
+};
 use lemmy_db_views::structs::PrivateMessageReportView;
 use lemmy_utils::error::LemmyError;
 
@@ -21,8 +28,19 @@ impl Perform for ResolvePrivateMessageReport {
 
     let report_id = self.report_id;
     let person_id = local_user_view.person.id;
+    let reason = self
+      .reason
+      .as_deref()
+      .map(str::trim)
+      .filter(|r| !r.is_empty());
+    if let Some(reason) = reason {
+      let local_site = LocalSite::read(context.pool()).await?;
+      check_report_reason(reason, &local_site)?;
+    }
+
+    let reason = reason.map(ToOwned::to_owned);
     if self.resolved {
-      PrivateMessageReport::resolve(context.pool(), report_id, person_id)
+      PrivateMessageReport::resolve(context.pool(), report_id, person_id, reason.clone())
         .await
         .map_err(|e| LemmyError::from_error_message(e, "couldnt_resolve_report"))?;
     } else {
@@ -31,6 +49,17 @@ impl Perform for ResolvePrivateMessageReport {
         .map_err(|e| LemmyError::from_error_message(e, "couldnt_resolve_report"))?;
     }
 
+    // The modlog only shows the report id, not the private message
+    let form = ModResolveReportForm {
+      mod_person_id: person_id,
+      post_id: None,
+      comment_id: None,
+      private_message_report_id: Some(report_id),
+      resolved: Some(self.resolved),
+      reason,
+    };
+    ModResolveReport::create(context.pool(), &form).await?;
+
     let private_message_report_view =
       PrivateMessageReportView::read(context.pool(), report_id).await?;
 
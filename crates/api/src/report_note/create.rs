@@ -0,0 +1,67 @@
+use crate::{check_report_reason, report_note::check_report_note_access, Perform};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{CreateReportNote, ReportNoteResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::{
+  local_site::LocalSite,
+  report_note::{ReportNote, ReportNoteForm},
+};
+use lemmy_db_views::structs::ReportNoteView;
+use lemmy_utils::error::LemmyError;
+
+/// Adds a note to a report, which the other moderators can see
+#[async_trait::async_trait(?Send)]
+impl Perform for CreateReportNote {
+  type Response = ReportNoteResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<ReportNoteResponse, LemmyError> {
+    let data: &CreateReportNote = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let local_site = LocalSite::read(context.pool()).await?;
+
+    check_report_note_access(
+      data.post_report_id,
+      data.comment_report_id,
+      data.private_message_report_id,
+      &local_user_view,
+      context,
+    )
+    .await?;
+
+    let content = data.content.trim();
+    check_report_reason(content, &local_site)?;
+
+    // Replies are only possible within the same report
+    if let Some(parent_id) = data.parent_id {
+      let parent = ReportNote::read(context.pool(), parent_id)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "invalid_report_note_parent"))?;
+      let same_report = parent.post_report_id == data.post_report_id
+        && parent.comment_report_id == data.comment_report_id
+        && parent.private_message_report_id == data.private_message_report_id;
+      if !same_report {
+        return Err(LemmyError::from_message("invalid_report_note_parent"));
+      }
+    }
+
+    let form = ReportNoteForm {
+      post_report_id: data.post_report_id,
+      comment_report_id: data.comment_report_id,
+      private_message_report_id: data.private_message_report_id,
+      creator_id: local_user_view.person.id,
+      content: content.to_owned(),
+      parent_id: data.parent_id,
+    };
+    let report_note = ReportNote::create(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_report_note"))?;
+
+    let report_note_view = ReportNoteView::read(context.pool(), report_note.id).await?;
+
+    Ok(ReportNoteResponse { report_note_view })
+  }
+}
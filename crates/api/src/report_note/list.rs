@@ -0,0 +1,44 @@
+use crate::{report_note::check_report_note_access, Perform};
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{ListReportNotes, ListReportNotesResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_views::report_note_view::ReportNoteQuery;
+use lemmy_utils::error::LemmyError;
+
+/// Lists the notes of a report
+#[async_trait::async_trait(?Send)]
+impl Perform for ListReportNotes {
+  type Response = ListReportNotesResponse;
+
+  #[tracing::instrument(skip(context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<ListReportNotesResponse, LemmyError> {
+    let data: &ListReportNotes = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    check_report_note_access(
+      data.post_report_id,
+      data.comment_report_id,
+      data.private_message_report_id,
+      &local_user_view,
+      context,
+    )
+    .await?;
+
+    let report_notes = ReportNoteQuery::builder()
+      .pool(context.pool())
+      .post_report_id(data.post_report_id)
+      .comment_report_id(data.comment_report_id)
+      .private_message_report_id(data.private_message_report_id)
+      .build()
+      .list()
+      .await?;
+
+    Ok(ListReportNotesResponse { report_notes })
+  }
+}
@@ -0,0 +1,35 @@
+use actix_web::web::Data;
+use lemmy_api_common::{
+  context::LemmyContext,
+  utils::{is_admin, is_mod_or_admin},
+};
+use lemmy_db_schema::newtypes::{CommentReportId, PostReportId, PrivateMessageReportId};
+use lemmy_db_views::structs::{CommentReportView, LocalUserView, PostReportView};
+use lemmy_utils::error::LemmyError;
+
+mod create;
+mod list;
+
+/// Only the moderators of the community can see the notes on reports of posts and comments.
+/// Reports of private messages are handled by the admins.
+async fn check_report_note_access(
+  post_report_id: Option<PostReportId>,
+  comment_report_id: Option<CommentReportId>,
+  private_message_report_id: Option<PrivateMessageReportId>,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let person_id = local_user_view.person.id;
+  match (post_report_id, comment_report_id, private_message_report_id) {
+    (Some(report_id), None, None) => {
+      let report = PostReportView::read(context.pool(), report_id, person_id).await?;
+      is_mod_or_admin(context.pool(), person_id, report.community.id).await
+    }
+    (None, Some(report_id), None) => {
+      let report = CommentReportView::read(context.pool(), report_id, person_id).await?;
+      is_mod_or_admin(context.pool(), person_id, report.community.id).await
+    }
+    (None, None, Some(_)) => is_admin(local_user_view),
+    _ => Err(LemmyError::from_message("invalid_report_note_report")),
+  }
+}
@@ -3,7 +3,7 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
   site::{GetSiteResponse, LeaveAdmin},
-  utils::{is_admin, local_user_view_from_jwt},
+  utils::{is_admin, local_user_view_from_jwt, site_oidc_providers, site_vapid_public_key},
 };
 use lemmy_db_schema::{
   source::{
@@ -71,6 +71,8 @@ impl Perform for LeaveAdmin {
       discussion_languages,
       taglines,
       custom_emojis,
+      vapid_public_key: site_vapid_public_key(context.settings()),
+      oidc_providers: site_oidc_providers(context.settings()),
     })
   }
 }
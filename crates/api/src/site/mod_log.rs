@@ -1,5 +1,6 @@
 use crate::Perform;
 use actix_web::web::Data;
+use chrono::{DateTime, TimeZone, Utc};
 use lemmy_api_common::{
   context::LemmyContext,
   site::{GetModlog, GetModlogResponse},
@@ -8,28 +9,11 @@ use lemmy_api_common::{
 use lemmy_db_schema::{
   newtypes::{CommunityId, PersonId},
   source::local_site::LocalSite,
+  utils::limit_and_offset,
   ModlogActionType,
 };
-use lemmy_db_views_moderator::structs::{
-  AdminPurgeCommentView,
-  AdminPurgeCommunityView,
-  AdminPurgePersonView,
-  AdminPurgePostView,
-  ModAddCommunityView,
-  ModAddView,
-  ModBanFromCommunityView,
-  ModBanView,
-  ModFeaturePostView,
-  ModHideCommunityView,
-  ModLockPostView,
-  ModRemoveCommentView,
-  ModRemoveCommunityView,
-  ModRemovePostView,
-  ModTransferCommunityView,
-  ModlogListParams,
-};
+use lemmy_db_views_moderator::structs::{ModlogCursor, ModlogEntry, ModlogListParams};
 use lemmy_utils::error::LemmyError;
-use ModlogActionType::*;
 
 #[async_trait::async_trait(?Send)]
 impl Perform for GetModlog {
@@ -44,7 +28,7 @@ impl Perform for GetModlog {
 
     check_private_instance(&local_user_view, &local_site)?;
 
-    let type_ = data.type_.unwrap_or(All);
+    let type_ = data.type_.unwrap_or(ModlogActionType::All);
     let community_id = data.community_id;
 
     let (local_person_id, is_admin) = match local_user_view {
@@ -66,128 +50,39 @@ impl Perform for GetModlog {
     } else {
       data.mod_person_id
     };
-    let other_person_id = data.other_person_id;
+    let cursor = data
+      .page_cursor
+      .as_deref()
+      .map(|c| ModlogCursor::decode(c).ok_or(LemmyError::from_message("invalid_modlog_cursor")))
+      .transpose()?;
     let params = ModlogListParams {
       community_id,
       mod_person_id,
-      other_person_id,
-      page: data.page,
+      other_person_id: data.other_person_id,
+      post_id: data.post_id,
+      comment_id: data.comment_id,
+      since: data.since.map(timestamp_to_datetime).transpose()?,
+      until: data.until.map(timestamp_to_datetime).transpose()?,
+      cursor,
+      page: None,
       limit: data.limit,
       hide_modlog_names,
     };
-    let removed_posts = match type_ {
-      All | ModRemovePost => ModRemovePostView::list(context.pool(), params).await?,
-      _ => Default::default(),
-    };
-
-    let locked_posts = match type_ {
-      All | ModLockPost => ModLockPostView::list(context.pool(), params).await?,
-      _ => Default::default(),
-    };
-
-    let featured_posts = match type_ {
-      All | ModFeaturePost => ModFeaturePostView::list(context.pool(), params).await?,
-      _ => Default::default(),
-    };
-
-    let removed_comments = match type_ {
-      All | ModRemoveComment => ModRemoveCommentView::list(context.pool(), params).await?,
-      _ => Default::default(),
-    };
-
-    let banned_from_community = match type_ {
-      All | ModBanFromCommunity => ModBanFromCommunityView::list(context.pool(), params).await?,
-      _ => Default::default(),
-    };
+    let entries = ModlogEntry::list(context.pool(), type_, params).await?;
 
-    let added_to_community = match type_ {
-      All | ModAddCommunity => ModAddCommunityView::list(context.pool(), params).await?,
-      _ => Default::default(),
-    };
-
-    let transferred_to_community = match type_ {
-      All | ModTransferCommunity => ModTransferCommunityView::list(context.pool(), params).await?,
-      _ => Default::default(),
-    };
-
-    let hidden_communities = match type_ {
-      All | ModHideCommunity if other_person_id.is_none() => {
-        ModHideCommunityView::list(context.pool(), params).await?
-      }
-      _ => Default::default(),
-    };
+    let (limit, _) = limit_and_offset(None, data.limit)?;
+    let next_page = entries
+      .last()
+      .filter(|_| entries.len() as i64 == limit)
+      .map(|e| e.cursor().encode());
 
-    // These arrays are only for the full modlog, when a community isn't given
-    let (
-      banned,
-      added,
-      removed_communities,
-      admin_purged_persons,
-      admin_purged_communities,
-      admin_purged_posts,
-      admin_purged_comments,
-    ) = if data.community_id.is_none() {
-      (
-        match type_ {
-          All | ModBan => ModBanView::list(context.pool(), params).await?,
-          _ => Default::default(),
-        },
-        match type_ {
-          All | ModAdd => ModAddView::list(context.pool(), params).await?,
-          _ => Default::default(),
-        },
-        match type_ {
-          All | ModRemoveCommunity if other_person_id.is_none() => {
-            ModRemoveCommunityView::list(context.pool(), params).await?
-          }
-          _ => Default::default(),
-        },
-        match type_ {
-          All | AdminPurgePerson if other_person_id.is_none() => {
-            AdminPurgePersonView::list(context.pool(), params).await?
-          }
-          _ => Default::default(),
-        },
-        match type_ {
-          All | AdminPurgeCommunity if other_person_id.is_none() => {
-            AdminPurgeCommunityView::list(context.pool(), params).await?
-          }
-          _ => Default::default(),
-        },
-        match type_ {
-          All | AdminPurgePost if other_person_id.is_none() => {
-            AdminPurgePostView::list(context.pool(), params).await?
-          }
-          _ => Default::default(),
-        },
-        match type_ {
-          All | AdminPurgeComment if other_person_id.is_none() => {
-            AdminPurgeCommentView::list(context.pool(), params).await?
-          }
-          _ => Default::default(),
-        },
-      )
-    } else {
-      Default::default()
-    };
-
-    // Return the jwt
-    Ok(GetModlogResponse {
-      removed_posts,
-      locked_posts,
-      featured_posts,
-      removed_comments,
-      removed_communities,
-      banned_from_community,
-      banned,
-      added_to_community,
-      added,
-      transferred_to_community,
-      admin_purged_persons,
-      admin_purged_communities,
-      admin_purged_posts,
-      admin_purged_comments,
-      hidden_communities,
-    })
+    Ok(GetModlogResponse { entries, next_page })
   }
 }
+
+fn timestamp_to_datetime(timestamp: i64) -> Result<DateTime<Utc>, LemmyError> {
+  Utc
+    .timestamp_opt(timestamp, 0)
+    .single()
+    .ok_or(LemmyError::from_message("invalid_modlog_time"))
+}
@@ -4,7 +4,12 @@ use lemmy_api_common::{
   context::LemmyContext,
   request::purge_image_from_pictrs,
   site::{PurgeItemResponse, PurgePerson},
-  utils::{is_admin, local_user_view_from_jwt, purge_image_posts_for_person},
+  utils::{
+    is_admin,
+    local_user_view_from_jwt,
+    purge_image_posts_for_person,
+    purge_local_images_for_person,
+  },
 };
 use lemmy_db_schema::{
   source::{
@@ -51,6 +56,14 @@ impl Perform for PurgePerson {
     )
     .await?;
 
+    purge_local_images_for_person(
+      person_id,
+      context.pool(),
+      context.settings(),
+      context.client(),
+    )
+    .await?;
+
     Person::delete(context.pool(), person_id).await?;
 
     // Mod tables
@@ -0,0 +1,411 @@
+use crate::live_notification::LiveNotification;
+use chrono::Duration;
+use lemmy_db_schema::{
+  aggregates::structs::PersonAggregates,
+  newtypes::{CommunityId, PersonId},
+  source::{
+    comment::{Comment, CommentUpdateForm},
+    comment_report::{CommentReport, CommentReportForm},
+    community_automod_rule::CommunityAutomodRule,
+    moderator::{ModRemoveComment, ModRemoveCommentForm, ModRemovePost, ModRemovePostForm},
+    person::Person,
+    post::{Post, PostUpdateForm},
+    post_report::{PostReport, PostReportForm},
+  },
+  traits::Crud,
+  utils::{naive_now, DbPool},
+  AutomodAction,
+  AutomodRuleType,
+};
+use lemmy_db_views_actor::structs::{CommunityModeratorView, PersonView};
+use lemmy_utils::error::LemmyError;
+use moka::sync::Cache;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tracing::warn;
+use url::Url;
+
+/// Maximum length of the value of an automod rule.
+const AUTOMOD_VALUE_MAX_LENGTH: usize = 500;
+
+/// Number of compiled title regexes which are kept in memory.
+const TITLE_REGEX_CACHE_SIZE: u64 = 1000;
+
+/// The parts of a new or edited post or comment which automod rules are checked against.
+#[derive(Debug, Default)]
+pub struct AutomodContent<'a> {
+  /// Only set for posts.
+  pub title: Option<&'a str>,
+  pub url: Option<&'a Url>,
+  /// Markdown or html, links in it are checked against banned domains.
+  pub body: Option<&'a str>,
+}
+
+/// Makes sure that the value can be used for the given rule type.
+pub fn validate_automod_rule(rule_type: AutomodRuleType, value: &str) -> Result<(), LemmyError> {
+  let valid = value.len() <= AUTOMOD_VALUE_MAX_LENGTH
+    && match rule_type {
+      AutomodRuleType::MinAccountAge => value.parse::<i64>().map(|d| d >= 0).unwrap_or(false),
+      AutomodRuleType::MinScore => value.parse::<i64>().is_ok(),
+      AutomodRuleType::BannedDomain => {
+        !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || c == '/')
+      }
+      AutomodRuleType::TitleRegex => Regex::new(value).is_ok(),
+      AutomodRuleType::RequiredKeyword => keywords(value).next().is_some(),
+    };
+  if valid {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("invalid_automod_rule"))
+  }
+}
+
+/// Checks new or edited content against the automod rules of its community.
+///
+/// Returns an error if any violated rule rejects the content. Otherwise returns the first violated
+/// rule which removes the content, or if there is none the first one which reports it. The
+/// returned rule needs to be applied with [`apply_post_automod`] or [`apply_comment_automod`]
+/// once the content is saved.
+#[tracing::instrument(skip_all)]
+pub async fn check_automod_rules(
+  pool: &DbPool,
+  community_id: CommunityId,
+  creator: &Person,
+  content: &AutomodContent<'_>,
+) -> Result<Option<CommunityAutomodRule>, LemmyError> {
+  let rules = CommunityAutomodRule::list_for_community(pool, community_id).await?;
+  if rules.is_empty() {
+    return Ok(None);
+  }
+  let score = if rules
+    .iter()
+    .any(|r| r.rule_type == AutomodRuleType::MinScore)
+  {
+    let aggregates = PersonAggregates::read(pool, creator.id).await?;
+    aggregates.post_score + aggregates.comment_score
+  } else {
+    0
+  };
+
+  let violated: Vec<_> = rules
+    .into_iter()
+    .filter(|r| violates_rule(r, creator, score, content))
+    .collect();
+  if violated.iter().any(|r| r.action == AutomodAction::Reject) {
+    return Err(LemmyError::from_message("rejected_by_automod"));
+  }
+  let rule = violated
+    .iter()
+    .find(|r| r.action == AutomodAction::Remove)
+    .or_else(|| violated.first())
+    .cloned();
+  Ok(rule)
+}
+
+fn violates_rule(
+  rule: &CommunityAutomodRule,
+  creator: &Person,
+  creator_score: i64,
+  content: &AutomodContent,
+) -> bool {
+  let value = rule.value.as_str();
+  match rule.rule_type {
+    AutomodRuleType::MinAccountAge => value
+      .parse()
+      .map(|days| creator.published > naive_now() - Duration::days(days))
+      .unwrap_or(false),
+    AutomodRuleType::MinScore => value
+      .parse()
+      .map(|min_score: i64| creator_score < min_score)
+      .unwrap_or(false),
+    AutomodRuleType::BannedDomain => {
+      let domain = value.to_lowercase();
+      content
+        .url
+        .into_iter()
+        .cloned()
+        .chain(content.body.into_iter().flat_map(linked_urls))
+        .filter_map(|url| url.host_str().map(str::to_lowercase))
+        .any(|host| host == domain || host.ends_with(&format!(".{domain}")))
+    }
+    AutomodRuleType::TitleRegex => match (content.title, title_regex(value)) {
+      (Some(title), Some(regex)) => regex.is_match(title),
+      _ => false,
+    },
+    AutomodRuleType::RequiredKeyword => content.title.is_some_and(|title| {
+      let title = title.to_lowercase();
+      !keywords(value).any(|k| title.contains(&k.to_lowercase()))
+    }),
+  }
+}
+
+/// Compiles the regex of a rule only the first time it is checked.
+fn title_regex(pattern: &str) -> Option<Regex> {
+  static CACHE: Lazy<Cache<String, Regex>> = Lazy::new(|| Cache::new(TITLE_REGEX_CACHE_SIZE));
+  CACHE.optionally_get_with_by_ref(pattern, || Regex::new(pattern).ok())
+}
+
+fn keywords(value: &str) -> impl Iterator<Item = &str> {
+  value.split(',').map(str::trim).filter(|k| !k.is_empty())
+}
+
+fn linked_urls(text: &str) -> impl Iterator<Item = Url> + '_ {
+  text
+    .split(|c: char| c.is_whitespace() || "()[]<>\"'".contains(c))
+    .filter(|w| w.starts_with("http://") || w.starts_with("https://"))
+    .filter_map(|w| Url::parse(w).ok())
+}
+
+fn automod_reason(rule: &CommunityAutomodRule) -> String {
+  rule
+    .reason
+    .clone()
+    .unwrap_or_else(|| format!("Automod: {}", rule.rule_type))
+}
+
+/// Mod log entries and reports need a person, so they are made in the name of the top mod of the
+/// community, or of an admin if the community has no mods. They are marked as automod, so that they
+/// aren't shown as actions of that person.
+async fn automod_person_id(
+  pool: &DbPool,
+  community_id: CommunityId,
+) -> Result<PersonId, LemmyError> {
+  let mods = CommunityModeratorView::for_community(pool, community_id).await?;
+  if let Some(top_mod) = mods.first() {
+    return Ok(top_mod.moderator.id);
+  }
+  PersonView::admins(pool)
+    .await?
+    .first()
+    .map(|a| a.person.id)
+    .ok_or_else(|| LemmyError::from_message("couldnt_apply_automod"))
+}
+
+/// Removes or reports a new or edited post, depending on the action of the violated rule.
+///
+/// The post is already saved at this point, so failures are only logged.
+#[tracing::instrument(skip_all)]
+pub async fn apply_post_automod(pool: &DbPool, rule: &CommunityAutomodRule, post: &Post) {
+  if let Err(e) = try_apply_post_automod(pool, rule, post).await {
+    warn!("Failed to apply automod to post: {e}");
+  }
+}
+
+async fn try_apply_post_automod(
+  pool: &DbPool,
+  rule: &CommunityAutomodRule,
+  post: &Post,
+) -> Result<(), LemmyError> {
+  let person_id = automod_person_id(pool, post.community_id).await?;
+  let reason = automod_reason(rule);
+  match rule.action {
+    // Content which is already removed isn't removed again on edits
+    AutomodAction::Remove if post.removed => {}
+    AutomodAction::Remove => {
+      let form = PostUpdateForm::builder().removed(Some(true)).build();
+      Post::update(pool, post.id, &form).await?;
+      let form = ModRemovePostForm {
+        mod_person_id: person_id,
+        post_id: post.id,
+        removed: Some(true),
+        reason: Some(reason),
+      };
+      ModRemovePost::create_automod(pool, &form).await?;
+    }
+    AutomodAction::Report => {
+      let form = PostReportForm {
+        creator_id: person_id,
+        post_id: post.id,
+        original_post_name: post.name.clone(),
+        original_post_url: post.url.clone(),
+        original_post_body: post.body.clone(),
+        reason,
+      };
+      // Edits of a post which was already reported don't report it again
+      if PostReport::report_automod(pool, &form).await?.is_some() {
+        LiveNotification::Report {
+          community_id: Some(rule.community_id),
+        }
+        .send(pool)
+        .await;
+      }
+    }
+    AutomodAction::Reject => {}
+  }
+  Ok(())
+}
+
+/// Removes or reports a new or edited comment, depending on the action of the violated rule.
+///
+/// The comment is already saved at this point, so failures are only logged.
+#[tracing::instrument(skip_all)]
+pub async fn apply_comment_automod(pool: &DbPool, rule: &CommunityAutomodRule, comment: &Comment) {
+  if let Err(e) = try_apply_comment_automod(pool, rule, comment).await {
+    warn!("Failed to apply automod to comment: {e}");
+  }
+}
+
+async fn try_apply_comment_automod(
+  pool: &DbPool,
+  rule: &CommunityAutomodRule,
+  comment: &Comment,
+) -> Result<(), LemmyError> {
+  let person_id = automod_person_id(pool, rule.community_id).await?;
+  let reason = automod_reason(rule);
+  match rule.action {
+    AutomodAction::Remove if comment.removed => {}
+    AutomodAction::Remove => {
+      let form = CommentUpdateForm::builder().removed(Some(true)).build();
+      Comment::update(pool, comment.id, &form).await?;
+      let form = ModRemoveCommentForm {
+        mod_person_id: person_id,
+        comment_id: comment.id,
+        removed: Some(true),
+        reason: Some(reason),
+      };
+      ModRemoveComment::create_automod(pool, &form).await?;
+    }
+    AutomodAction::Report => {
+      let form = CommentReportForm {
+        creator_id: person_id,
+        comment_id: comment.id,
+        original_comment_text: comment.content.clone(),
+        reason,
+      };
+      if CommentReport::report_automod(pool, &form).await?.is_some() {
+        LiveNotification::Report {
+          community_id: Some(rule.community_id),
+        }
+        .send(pool)
+        .await;
+      }
+    }
+    AutomodAction::Reject => {}
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+  #![allow(clippy::indexing_slicing)]
+
+  use crate::automod::{validate_automod_rule, violates_rule, AutomodContent};
+  use chrono::Duration;
+  use lemmy_db_schema::{
+    newtypes::{CommunityAutomodRuleId, CommunityId, InstanceId, PersonId},
+    source::{community_automod_rule::CommunityAutomodRule, person::Person},
+    utils::naive_now,
+    AutomodAction,
+    AutomodRuleType,
+  };
+  use url::Url;
+
+  fn rule(rule_type: AutomodRuleType, value: &str) -> CommunityAutomodRule {
+    CommunityAutomodRule {
+      id: CommunityAutomodRuleId::default(),
+      community_id: CommunityId(1),
+      rule_type,
+      value: value.to_string(),
+      action: AutomodAction::Reject,
+      reason: None,
+      published: naive_now(),
+    }
+  }
+
+  fn person(age_days: i64) -> Person {
+    Person {
+      id: PersonId(1),
+      name: "automod".to_string(),
+      display_name: None,
+      avatar: None,
+      banned: false,
+      published: naive_now() - Duration::days(age_days),
+      updated: None,
+      actor_id: Url::parse("http://example.com/u/automod").unwrap().into(),
+      bio: None,
+      local: true,
+      private_key: None,
+      public_key: String::new(),
+      last_refreshed_at: naive_now(),
+      banner: None,
+      deleted: false,
+      inbox_url: Url::parse("http://example.com/u/automod/inbox")
+        .unwrap()
+        .into(),
+      shared_inbox_url: None,
+      matrix_user_id: None,
+      admin: false,
+      bot_account: false,
+      ban_expires: None,
+      instance_id: InstanceId::default(),
+      also_known_as: vec![],
+      moved_to: None,
+    }
+  }
+
+  #[test]
+  fn test_validate_automod_rule() {
+    assert!(validate_automod_rule(AutomodRuleType::MinAccountAge, "7").is_ok());
+    assert!(validate_automod_rule(AutomodRuleType::MinAccountAge, "-1").is_err());
+    assert!(validate_automod_rule(AutomodRuleType::MinScore, "-10").is_ok());
+    assert!(validate_automod_rule(AutomodRuleType::MinScore, "ten").is_err());
+    assert!(validate_automod_rule(AutomodRuleType::BannedDomain, "example.com").is_ok());
+    assert!(validate_automod_rule(AutomodRuleType::BannedDomain, "example.com/path").is_err());
+    assert!(validate_automod_rule(AutomodRuleType::TitleRegex, "(?i)buy now").is_ok());
+    assert!(validate_automod_rule(AutomodRuleType::TitleRegex, "(unclosed").is_err());
+    assert!(validate_automod_rule(AutomodRuleType::RequiredKeyword, "[Q], [D]").is_ok());
+    assert!(validate_automod_rule(AutomodRuleType::RequiredKeyword, " , ").is_err());
+  }
+
+  #[test]
+  fn test_violates_rule() {
+    let new_person = person(1);
+    let old_person = person(30);
+    let content = AutomodContent::default();
+
+    let age = rule(AutomodRuleType::MinAccountAge, "7");
+    assert!(violates_rule(&age, &new_person, 0, &content));
+    assert!(!violates_rule(&age, &old_person, 0, &content));
+
+    let score = rule(AutomodRuleType::MinScore, "10");
+    assert!(violates_rule(&score, &old_person, 9, &content));
+    assert!(!violates_rule(&score, &old_person, 10, &content));
+
+    let url = Url::parse("https://www.Example.com/page").unwrap();
+    let domain = rule(AutomodRuleType::BannedDomain, "example.com");
+    let with_url = AutomodContent {
+      url: Some(&url),
+      ..Default::default()
+    };
+    let with_link = AutomodContent {
+      body: Some("see [here](https://sub.example.com/x) for more"),
+      ..Default::default()
+    };
+    let with_other_link = AutomodContent {
+      body: Some("https://notexample.com"),
+      ..Default::default()
+    };
+    assert!(violates_rule(&domain, &old_person, 0, &with_url));
+    assert!(violates_rule(&domain, &old_person, 0, &with_link));
+    assert!(!violates_rule(&domain, &old_person, 0, &with_other_link));
+
+    let regex = rule(AutomodRuleType::TitleRegex, "(?i)buy now");
+    let spam = AutomodContent {
+      title: Some("BUY NOW cheap"),
+      ..Default::default()
+    };
+    let question = AutomodContent {
+      title: Some("[Question] How does this work?"),
+      ..Default::default()
+    };
+    assert!(violates_rule(&regex, &old_person, 0, &spam));
+    assert!(!violates_rule(&regex, &old_person, 0, &question));
+
+    // Comments have no title, so they can't violate title rules
+    let keyword = rule(AutomodRuleType::RequiredKeyword, "[question], [discussion]");
+    assert!(violates_rule(&keyword, &old_person, 0, &spam));
+    assert!(!violates_rule(&keyword, &old_person, 0, &question));
+    assert!(!violates_rule(&keyword, &old_person, 0, &content));
+  }
+}
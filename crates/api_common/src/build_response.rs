@@ -2,8 +2,17 @@ use crate::{
   comment::CommentResponse,
   community::CommunityResponse,
   context::LemmyContext,
+  live::LiveEvent,
+  live_notification::LiveNotification,
+  person::GetReportCountResponse,
   post::PostResponse,
-  utils::{check_person_block, get_interface_language, is_mod_or_admin, send_email_to_user},
+  utils::{
+    check_person_block,
+    get_interface_language,
+    is_mod_or_admin,
+    send_email_to_user,
+    send_push_to_user,
+  },
 };
 use actix_web::web::Data;
 use lemmy_db_schema::{
@@ -18,7 +27,14 @@ use lemmy_db_schema::{
   },
   traits::Crud,
 };
-use lemmy_db_views::structs::{CommentView, LocalUserView, PostView};
+use lemmy_db_views::structs::{
+  CommentReportView,
+  CommentView,
+  LocalUserView,
+  PostReportView,
+  PostView,
+  PrivateMessageReportView,
+};
 use lemmy_db_views_actor::structs::CommunityView;
 use lemmy_utils::{error::LemmyError, utils::mention::MentionData};
 
@@ -81,6 +97,34 @@ pub async fn build_post_response(
   Ok(PostResponse { post_view })
 }
 
+/// Counts the open reports which the person can resolve. Only admins get the reports of private
+/// messages, and only if no community is given.
+pub async fn build_report_count_response(
+  context: &LemmyContext,
+  person: &Person,
+  community_id: Option<CommunityId>,
+) -> Result<GetReportCountResponse, LemmyError> {
+  let comment_reports =
+    CommentReportView::get_report_count(context.pool(), person.id, person.admin, community_id)
+      .await?;
+
+  let post_reports =
+    PostReportView::get_report_count(context.pool(), person.id, person.admin, community_id).await?;
+
+  let private_message_reports = if person.admin && community_id.is_none() {
+    Some(PrivateMessageReportView::get_report_count(context.pool()).await?)
+  } else {
+    None
+  };
+
+  Ok(GetReportCountResponse {
+    community_id,
+    comment_reports,
+    post_reports,
+    private_message_reports,
+  })
+}
+
 // TODO: this function is a mess and should be split up to handle email seperately
 #[tracing::instrument(skip_all)]
 pub async fn send_local_notifs(
@@ -115,9 +159,18 @@ pub async fn send_local_notifs(
 
       // Allow this to fail softly, since comment edits might re-update or replace it
       // Let the uniqueness handle this fail
-      PersonMention::create(context.pool(), &user_mention_form)
+      let person_mention = PersonMention::create(context.pool(), &user_mention_form)
         .await
         .ok();
+      if let Some(person_mention) = &person_mention {
+        LiveNotification::PersonMention {
+          recipient_id: person_mention.recipient_id,
+          person_mention_id: person_mention.id,
+          comment_id: comment.id,
+        }
+        .send(context.pool())
+        .await;
+      }
 
       // Send an email to those local users that have notifications on
       if do_send_email {
@@ -127,7 +180,15 @@ pub async fn send_local_notifs(
           &lang.notification_mentioned_by_subject(&person.name),
           &lang.notification_mentioned_by_body(&comment.content, &inbox_link, &person.name),
           context.settings(),
-        )
+        );
+        if let Some(person_mention) = person_mention {
+          let event = LiveEvent::PersonMention {
+            person_mention_id: person_mention.id,
+            comment_id: comment.id,
+          };
+          let title = lang.notification_mentioned_by_subject(&person.name);
+          send_push_to_user(&mention_user_view, title, event, context).await;
+        }
       }
     }
   }
@@ -158,9 +219,18 @@ pub async fn send_local_notifs(
 
         // Allow this to fail softly, since comment edits might re-update or replace it
         // Let the uniqueness handle this fail
-        CommentReply::create(context.pool(), &comment_reply_form)
+        let comment_reply = CommentReply::create(context.pool(), &comment_reply_form)
           .await
           .ok();
+        if let Some(comment_reply) = &comment_reply {
+          LiveNotification::CommentReply {
+            recipient_id: comment_reply.recipient_id,
+            comment_reply_id: comment_reply.id,
+            comment_id: comment.id,
+          }
+          .send(context.pool())
+          .await;
+        }
 
         if do_send_email {
           let lang = get_interface_language(&parent_user_view);
@@ -169,7 +239,15 @@ pub async fn send_local_notifs(
             &lang.notification_comment_reply_subject(&person.name),
             &lang.notification_comment_reply_body(&comment.content, &inbox_link, &person.name),
             context.settings(),
-          )
+          );
+          if let Some(comment_reply) = comment_reply {
+            let event = LiveEvent::CommentReply {
+              comment_reply_id: comment_reply.id,
+              comment_id: comment.id,
+            };
+            let title = lang.notification_comment_reply_subject(&person.name);
+            send_push_to_user(&parent_user_view, title, event, context).await;
+          }
         }
       }
     }
@@ -194,9 +272,18 @@ pub async fn send_local_notifs(
 
         // Allow this to fail softly, since comment edits might re-update or replace it
         // Let the uniqueness handle this fail
-        CommentReply::create(context.pool(), &comment_reply_form)
+        let comment_reply = CommentReply::create(context.pool(), &comment_reply_form)
           .await
           .ok();
+        if let Some(comment_reply) = &comment_reply {
+          LiveNotification::CommentReply {
+            recipient_id: comment_reply.recipient_id,
+            comment_reply_id: comment_reply.id,
+            comment_id: comment.id,
+          }
+          .send(context.pool())
+          .await;
+        }
 
         if do_send_email {
           let lang = get_interface_language(&parent_user_view);
@@ -205,7 +292,15 @@ pub async fn send_local_notifs(
             &lang.notification_post_reply_subject(&person.name),
             &lang.notification_post_reply_body(&comment.content, &inbox_link, &person.name),
             context.settings(),
-          )
+          );
+          if let Some(comment_reply) = comment_reply {
+            let event = LiveEvent::CommentReply {
+              comment_reply_id: comment_reply.id,
+              comment_id: comment.id,
+            };
+            let title = lang.notification_post_reply_subject(&person.name);
+            send_push_to_user(&parent_user_view, title, event, context).await;
+          }
         }
       }
     }
@@ -1,6 +1,7 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommentReportId, CommunityId, LanguageId, LocalUserId, PostId},
+  newtypes::{CommentId, CommentReportId, CommunityId, LanguageId, LocalUserId, PersonId, PostId},
+  source::edit_history::CommentEditHistory,
   CommentSortType,
   ListingType,
 };
@@ -131,15 +132,23 @@ pub struct GetComments {
   pub post_id: Option<PostId>,
   pub parent_id: Option<CommentId>,
   pub saved_only: Option<bool>,
+  /// The `next_page` of a previous response, to continue after it instead of using `page`. This
+  /// doesn't work for comment trees, which are fetched with `max_depth`.
+  pub page_cursor: Option<String>,
+  /// Also return the comments as html, with links to communities and users and custom emojis.
+  pub render_html: Option<bool>,
   pub auth: Option<Sensitive<String>>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// The comment list response.
 pub struct GetCommentsResponse {
   pub comments: Vec<CommentView>,
+  /// Pass this as `page_cursor` to get the following comments.
+  pub next_page: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -160,6 +169,7 @@ pub struct CommentReportResponse {
   pub comment_report_view: CommentReportView,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -167,6 +177,30 @@ pub struct CommentReportResponse {
 pub struct ResolveCommentReport {
   pub report_id: CommentReportId,
   pub resolved: bool,
+  /// Why the report was resolved, which is shown in the modlog.
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Assign a comment report to a moderator, so that others know it is being handled.
+pub struct AssignCommentReport {
+  pub report_id: CommentReportId,
+  /// Leave empty to unassign the report.
+  pub assignee_id: Option<PersonId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Escalate a comment report to the instance admins (only doable by mods).
+pub struct EscalateCommentReport {
+  pub report_id: CommentReportId,
+  pub escalated: bool,
   pub auth: Sensitive<String>,
 }
 
@@ -180,6 +214,8 @@ pub struct ListCommentReports {
   pub limit: Option<i64>,
   /// Only shows the unresolved reports
   pub unresolved_only: Option<bool>,
+  /// Only shows the reports which were escalated to the admins
+  pub escalated_only: Option<bool>,
   /// if no community is given, it returns reports for all communities moderated by the auth user
   pub community_id: Option<CommunityId>,
   pub auth: Sensitive<String>,
@@ -192,3 +228,23 @@ pub struct ListCommentReports {
 pub struct ListCommentReportsResponse {
   pub comment_reports: Vec<CommentReportView>,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the previous versions of an edited comment.
+///
+/// Only mods can see them, unless the community made the edit history public.
+pub struct GetCommentHistory {
+  pub comment_id: CommentId,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The previous versions of a comment, oldest first.
+pub struct GetCommentHistoryResponse {
+  pub history: Vec<CommentEditHistory>,
+}
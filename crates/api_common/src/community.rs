@@ -1,11 +1,23 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommunityId, LanguageId, PersonId},
-  source::site::Site,
+  newtypes::{CommunityAutomodRuleId, CommunityId, CommunityPostTagId, LanguageId, PersonId},
+  source::{
+    community_automod_rule::CommunityAutomodRule,
+    community_post_tag::CommunityPostTag,
+    site::Site,
+  },
+  AutomodAction,
+  AutomodRuleType,
+  CommunityVisibility,
   ListingType,
   SortType,
 };
-use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView, PersonView};
+use lemmy_db_views_actor::structs::{
+  CommunityFollowerView,
+  CommunityModeratorView,
+  CommunityView,
+  PersonView,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -33,6 +45,8 @@ pub struct GetCommunityResponse {
   pub site: Option<Site>,
   pub moderators: Vec<CommunityModeratorView>,
   pub discussion_languages: Vec<LanguageId>,
+  /// The tags which can be added to posts in this community.
+  pub post_tags: Vec<CommunityPostTag>,
 }
 
 #[skip_serializing_none]
@@ -55,6 +69,10 @@ pub struct CreateCommunity {
   pub nsfw: Option<bool>,
   /// Whether to restrict posting only to moderators.
   pub posting_restricted_to_mods: Option<bool>,
+  /// Who can follow the community and see its content.
+  pub visibility: Option<CommunityVisibility>,
+  /// Whether everyone can see the edit history of posts and comments, otherwise only mods.
+  pub edit_history_public: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub auth: Sensitive<String>,
 }
@@ -152,6 +170,10 @@ pub struct EditCommunity {
   pub nsfw: Option<bool>,
   /// Whether to restrict posting only to moderators.
   pub posting_restricted_to_mods: Option<bool>,
+  /// Who can follow the community and see its content.
+  pub visibility: Option<CommunityVisibility>,
+  /// Whether everyone can see the edit history of posts and comments, otherwise only mods.
+  pub edit_history_public: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub auth: Sensitive<String>,
 }
@@ -203,6 +225,48 @@ pub struct FollowCommunity {
   pub auth: Sensitive<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List follows which are waiting for approval, in communities you moderate.
+pub struct ListPendingFollows {
+  /// Only show follows of this community.
+  pub community_id: Option<CommunityId>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The pending follows response.
+pub struct ListPendingFollowsResponse {
+  pub items: Vec<CommunityFollowerView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Approve or reject a pending follow (only doable by moderators).
+pub struct ApproveFollow {
+  pub community_id: CommunityId,
+  pub person_id: PersonId,
+  pub approve: bool,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for approving or rejecting a follow.
+pub struct ApproveFollowResponse {
+  pub community_id: CommunityId,
+  pub person_id: PersonId,
+  pub approved: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -232,3 +296,89 @@ pub struct TransferCommunity {
   pub person_id: PersonId,
   pub auth: Sensitive<String>,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Add an automod rule to a community (only doable by moderators).
+pub struct CreateAutomodRule {
+  pub community_id: CommunityId,
+  pub rule_type: AutomodRuleType,
+  /// The parameter of the rule, for example `7` for a minimum account age of 7 days.
+  pub value: String,
+  pub action: AutomodAction,
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete an automod rule (only doable by moderators).
+pub struct DeleteAutomodRule {
+  pub id: CommunityAutomodRuleId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An automod rule response.
+pub struct AutomodRuleResponse {
+  pub rule: CommunityAutomodRule,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the automod rules of a community (only doable by moderators).
+pub struct ListAutomodRules {
+  pub community_id: CommunityId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The automod rules of a community, in the order in which they are checked.
+pub struct ListAutomodRulesResponse {
+  pub rules: Vec<CommunityAutomodRule>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create a tag for the posts of a community (only doable by moderators).
+pub struct CreateCommunityPostTag {
+  pub community_id: CommunityId,
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Rename a post tag (only doable by moderators).
+pub struct EditCommunityPostTag {
+  pub tag_id: CommunityPostTagId,
+  pub name: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a post tag, which also removes it from all posts (only doable by moderators).
+pub struct DeleteCommunityPostTag {
+  pub tag_id: CommunityPostTagId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A post tag response.
+pub struct CommunityPostTagResponse {
+  pub tag: CommunityPostTag,
+}
@@ -0,0 +1,455 @@
+use crate::{
+  context::LemmyContext,
+  image_storage::{
+    filesystem::{image_response, mime_type, process_image},
+    image_storage,
+    ImageParams,
+    UploadedFile,
+  },
+  request::fetch_public_url,
+};
+use actix_web::{
+  http::header::{ContentDisposition, DispositionParam, DispositionType, HeaderMap, CONTENT_TYPE},
+  web::{Bytes, BytesMut},
+  HttpResponse,
+};
+use anyhow::anyhow;
+use futures::StreamExt;
+use image::ImageFormat;
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{
+    community::Community,
+    local_site::LocalSite,
+    person::Person,
+    post::Post,
+    remote_image::{RemoteImage, RemoteImageForm},
+  },
+};
+use lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_db_views_actor::structs::{CommunityView, PersonView};
+use lemmy_utils::{
+  error::LemmyError,
+  settings::{
+    structs::{ImageProxyMode, Settings},
+    SETTINGS,
+  },
+  utils::{markdown::markdown_image_links, validation::is_public_url},
+};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use openssl::{hash::MessageDigest, memcmp, pkey::PKey, sign::Signer};
+use serde::Deserialize;
+use std::{collections::HashMap, net::SocketAddr, time::Duration};
+use url::Url;
+
+/// How long fetched images are kept in memory, in proxy mode.
+const CACHE_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Rewrites links to remote images, so that browsers load them from this instance instead, and
+/// the remote servers don't see the addresses of our users.
+///
+/// Proxied links are signed, otherwise anyone could use the instance to fetch arbitrary urls.
+/// Links to hosts which aren't public are never signed, and the fetch checks the addresses again.
+pub struct ImageProxy<'a> {
+  endpoint: Url,
+  secret: &'a str,
+}
+
+impl<'a> ImageProxy<'a> {
+  /// Returns `None` if the image proxy is turned off.
+  pub fn new(context: &'a LemmyContext) -> Option<Self> {
+    let settings = context.settings();
+    if settings.image_proxy.mode == ImageProxyMode::Off {
+      return None;
+    }
+    let endpoint = Url::parse(&format!(
+      "{}/image_proxy",
+      settings.get_protocol_and_hostname()
+    ))
+    .ok()?;
+    Some(ImageProxy {
+      endpoint,
+      secret: &context.secret().image_proxy_secret,
+    })
+  }
+
+  /// The proxied link for an image, or `None` if it is a local image or not on a public host.
+  pub fn link(&self, url: &Url) -> Option<Url> {
+    if !is_public_url(url) || url.host_str() == self.endpoint.host_str() {
+      return None;
+    }
+    let signature = self.signature(url.as_str()).ok()?;
+    let mut link = self.endpoint.clone();
+    link
+      .query_pairs_mut()
+      .append_pair("url", url.as_str())
+      .append_pair("sig", &signature);
+    Some(link)
+  }
+
+  /// Checks that the link was generated by [ImageProxy::link].
+  pub fn verify(&self, url: &str, signature: &str) -> bool {
+    match self.signature(url) {
+      Ok(expected) => {
+        expected.len() == signature.len() && memcmp::eq(expected.as_bytes(), signature.as_bytes())
+      }
+      Err(_) => false,
+    }
+  }
+
+  /// The HMAC-SHA256 of the url, hex encoded.
+  fn signature(&self, url: &str) -> Result<String, LemmyError> {
+    let key = PKey::hmac(self.secret.as_bytes())?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+    signer.update(url.as_bytes())?;
+    Ok(
+      signer
+        .sign_to_vec()?
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect(),
+    )
+  }
+
+  /// Adds the proxied link under the original link, exactly as it is written in the object.
+  fn add_link(&self, original: &str, links: &mut HashMap<String, String>) {
+    if let Some(link) = Url::parse(original).ok().and_then(|url| self.link(&url)) {
+      links.insert(original.to_string(), link.into());
+    }
+  }
+
+  /// Replaces an image url field with the proxied link.
+  fn proxy_url(&self, url: &mut Option<DbUrl>) {
+    if let Some(link) = url.as_deref().and_then(|url| self.link(url)) {
+      *url = Some(link.into());
+    }
+  }
+
+  fn add_markdown_links(&self, text: Option<&str>, links: &mut HashMap<String, String>) {
+    for link in text.map(markdown_image_links).unwrap_or_default() {
+      self.add_link(&link, links);
+    }
+  }
+
+  /// Post links are only proxied if they point directly to an image.
+  fn proxy_post(&self, post: &mut Post, links: &mut HashMap<String, String>) {
+    self.proxy_url(&mut post.thumbnail_url);
+    if post.url.as_deref().is_some_and(is_image_link) {
+      self.proxy_url(&mut post.url);
+    }
+    self.add_markdown_links(post.body.as_deref(), links);
+  }
+
+  fn proxy_person(&self, person: &mut Person, links: &mut HashMap<String, String>) {
+    self.proxy_url(&mut person.avatar);
+    self.proxy_url(&mut person.banner);
+    self.add_markdown_links(person.bio.as_deref(), links);
+  }
+
+  fn proxy_community(&self, community: &mut Community, links: &mut HashMap<String, String>) {
+    self.proxy_url(&mut community.icon);
+    self.proxy_url(&mut community.banner);
+    self.add_markdown_links(community.description.as_deref(), links);
+  }
+}
+
+fn is_image_link(url: &Url) -> bool {
+  let path = url.path().to_lowercase();
+  [".png", ".jpg", ".jpeg", ".gif", ".webp"]
+    .iter()
+    .any(|extension| path.ends_with(extension))
+}
+
+/// Objects which contain links to images that can be proxied. Image url fields like avatars and
+/// thumbnails are replaced with the proxied links. Markdown sources stay as they are, so that they
+/// can be edited without the proxy links, and their proxied links are returned separately in
+/// `image_proxy_links`.
+pub trait ProxyImageLinks {
+  fn proxy_image_links(&mut self, proxy: &ImageProxy);
+}
+
+/// Fills in the proxied image links of an api response, if the image proxy is turned on.
+pub fn proxy_image_links<T: ProxyImageLinks>(value: &mut T, context: &LemmyContext) {
+  if let Some(proxy) = ImageProxy::new(context) {
+    value.proxy_image_links(&proxy);
+  }
+}
+
+impl<T: ProxyImageLinks> ProxyImageLinks for Vec<T> {
+  fn proxy_image_links(&mut self, proxy: &ImageProxy) {
+    self
+      .iter_mut()
+      .for_each(|value| value.proxy_image_links(proxy));
+  }
+}
+
+impl<T: ProxyImageLinks> ProxyImageLinks for Option<T> {
+  fn proxy_image_links(&mut self, proxy: &ImageProxy) {
+    if let Some(value) = self {
+      value.proxy_image_links(proxy);
+    }
+  }
+}
+
+impl ProxyImageLinks for PostView {
+  fn proxy_image_links(&mut self, proxy: &ImageProxy) {
+    let mut links = HashMap::new();
+    proxy.proxy_post(&mut self.post, &mut links);
+    proxy.proxy_person(&mut self.creator, &mut links);
+    proxy.proxy_community(&mut self.community, &mut links);
+    self.image_proxy_links = Some(links);
+  }
+}
+
+impl ProxyImageLinks for CommentView {
+  fn proxy_image_links(&mut self, proxy: &ImageProxy) {
+    let mut links = HashMap::new();
+    proxy.add_markdown_links(Some(&self.comment.content), &mut links);
+    proxy.proxy_person(&mut self.creator, &mut links);
+    proxy.proxy_post(&mut self.post, &mut links);
+    proxy.proxy_community(&mut self.community, &mut links);
+    self.image_proxy_links = Some(links);
+  }
+}
+
+impl ProxyImageLinks for CommunityView {
+  fn proxy_image_links(&mut self, proxy: &ImageProxy) {
+    let mut links = HashMap::new();
+    proxy.proxy_community(&mut self.community, &mut links);
+    self.image_proxy_links = Some(links);
+  }
+}
+
+impl ProxyImageLinks for PersonView {
+  fn proxy_image_links(&mut self, proxy: &ImageProxy) {
+    let mut links = HashMap::new();
+    proxy.proxy_person(&mut self.person, &mut links);
+    self.image_proxy_links = Some(links);
+  }
+}
+
+/// Query params of `/image_proxy`. The format and thumbnail size work like for local images.
+#[derive(Debug, Deserialize)]
+pub struct ImageProxyParams {
+  pub url: String,
+  pub sig: String,
+  pub format: Option<String>,
+  pub thumbnail: Option<u32>,
+}
+
+/// Responds with a remote image which was linked by [ImageProxy].
+pub async fn serve_proxied_image(
+  params: ImageProxyParams,
+  headers: &HeaderMap,
+  peer_addr: Option<SocketAddr>,
+  context: &LemmyContext,
+) -> Result<HttpResponse, LemmyError> {
+  let proxy =
+    ImageProxy::new(context).ok_or_else(|| LemmyError::from_message("image_proxy_disabled"))?;
+  if !proxy.verify(&params.url, &params.sig) {
+    return Err(LemmyError::from_message("invalid_image_proxy_signature"));
+  }
+  let url = Url::parse(&params.url)?;
+  let image_params = ImageParams {
+    format: params.format,
+    thumbnail: params.thumbnail,
+  };
+  let max_bytes = LocalSite::read(context.pool())
+    .await?
+    .image_upload_max_bytes;
+
+  match context.settings().image_proxy.mode {
+    ImageProxyMode::Store => {
+      let alias = store_remote_image(&url, max_bytes, context).await?;
+      image_storage(context.client(), context.settings())?
+        .serve(&alias, &image_params, headers, peer_addr)
+        .await
+    }
+    _ => {
+      let (format, data) = fetch_remote_image_cached(context.settings(), &url, max_bytes).await?;
+      let (format, data) = process_image(data.to_vec(), format, &image_params).await?;
+      Ok(image_response(format, data))
+    }
+  }
+}
+
+/// Keeps fetched images in memory, up to the configured size.
+async fn fetch_remote_image_cached(
+  settings: &Settings,
+  url: &Url,
+  max_bytes: i64,
+) -> Result<(ImageFormat, Bytes), LemmyError> {
+  static CACHE: Lazy<Cache<Url, (ImageFormat, Bytes)>> = Lazy::new(|| {
+    Cache::builder()
+      .max_capacity(SETTINGS.image_proxy.cache_size_mb * 1024 * 1024)
+      .weigher(|_, (_, data): &(ImageFormat, Bytes)| data.len().try_into().unwrap_or(u32::MAX))
+      .time_to_live(CACHE_DURATION)
+      .build()
+  });
+  // Concurrent requests for the same image wait for a single fetch
+  CACHE
+    .try_get_with(url.clone(), fetch_remote_image(settings, url, max_bytes))
+    .await
+    .map_err(|e| match &e.message {
+      Some(message) => LemmyError::from_message(message),
+      None => anyhow!("{e}").into(),
+    })
+}
+
+/// Copies a remote image into the image storage, unless that was already done before, and
+/// returns the alias of the copy.
+async fn store_remote_image(
+  url: &Url,
+  max_bytes: i64,
+  context: &LemmyContext,
+) -> Result<String, LemmyError> {
+  let link: DbUrl = url.clone().into();
+  if let Some(image) = RemoteImage::read(context.pool(), &link).await? {
+    return Ok(image.pictrs_alias);
+  }
+
+  let (format, data) = fetch_remote_image(context.settings(), url, max_bytes).await?;
+  let extension = format.extensions_str().first().unwrap_or(&"png");
+  let file = UploadedFile {
+    content_disposition: ContentDisposition {
+      disposition: DispositionType::FormData,
+      parameters: vec![
+        DispositionParam::Name("images[]".to_string()),
+        DispositionParam::Filename(format!("image.{extension}")),
+      ],
+    },
+    content_type: mime_type(format).to_string(),
+    data,
+  };
+  let storage = image_storage(context.client(), context.settings())?;
+  let stored = storage
+    .upload(&[file], None)
+    .await?
+    .pop()
+    .ok_or_else(|| LemmyError::from_message("couldnt_store_image"))?;
+
+  let form = RemoteImageForm {
+    link,
+    pictrs_alias: stored.file.clone(),
+  };
+  let image = RemoteImage::create(context.pool(), &form).await?;
+  // Another request stored the same image in the meantime
+  if image.pictrs_alias != stored.file {
+    storage.purge(&stored.file).await.ok();
+  }
+  Ok(image.pictrs_alias)
+}
+
+/// Fetches a remote image, which must not be larger than images which users can upload.
+async fn fetch_remote_image(
+  settings: &Settings,
+  url: &Url,
+  max_bytes: i64,
+) -> Result<(ImageFormat, Bytes), LemmyError> {
+  let response = fetch_public_url(url, settings).await?.error_for_status()?;
+  let is_image = response
+    .headers()
+    .get(CONTENT_TYPE)
+    .and_then(|content_type| content_type.to_str().ok())
+    .is_some_and(|content_type| content_type.starts_with("image/"));
+  if !is_image {
+    return Err(LemmyError::from_message("not_an_image"));
+  }
+
+  let mut data = BytesMut::new();
+  let mut stream = response.bytes_stream();
+  while let Some(chunk) = stream.next().await {
+    data.extend_from_slice(&chunk?);
+    if data.len() as i64 > max_bytes {
+      return Err(LemmyError::from_message("image_too_large"));
+    }
+  }
+
+  let format = image::guess_format(&data)
+    .ok()
+    .filter(|format| {
+      matches!(
+        format,
+        ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP
+      )
+    })
+    .ok_or_else(|| LemmyError::from_message("image_type_not_supported"))?;
+  Ok((format, data.freeze()))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::image_proxy::{is_image_link, ImageProxy};
+  use lemmy_db_schema::newtypes::DbUrl;
+  use std::collections::HashMap;
+  use url::Url;
+
+  #[test]
+  fn test_image_proxy_link() {
+    let proxy = ImageProxy {
+      endpoint: Url::parse("https://lemmy.example/image_proxy").unwrap(),
+      secret: "secret",
+    };
+
+    let remote = Url::parse("https://remote.example/image.png?size=large").unwrap();
+    let link = proxy.link(&remote).unwrap();
+    assert_eq!(Some("lemmy.example"), link.host_str());
+    let params: Vec<_> = link.query_pairs().collect();
+    assert_eq!(("url".into(), remote.as_str().into()), params[0]);
+    assert!(proxy.verify(remote.as_str(), &params[1].1));
+    assert!(!proxy.verify("https://other.example/image.png", &params[1].1));
+    assert!(!proxy.verify(remote.as_str(), "invalid"));
+
+    // Local images don't need a proxy
+    let local = Url::parse("https://lemmy.example/pictrs/image/abc.png").unwrap();
+    assert_eq!(None, proxy.link(&local));
+
+    // Internal services can't be reached through the proxy
+    for internal in [
+      "http://127.0.0.1/image.png",
+      "http://169.254.169.254/latest/meta-data",
+      "http://pictrs:8080/image/original/abc.png",
+    ] {
+      assert_eq!(None, proxy.link(&Url::parse(internal).unwrap()));
+    }
+  }
+
+  #[test]
+  fn test_image_proxy_url() {
+    let proxy = ImageProxy {
+      endpoint: Url::parse("https://lemmy.example/image_proxy").unwrap(),
+      secret: "secret",
+    };
+    let mut avatar: Option<DbUrl> =
+      Some(Url::parse("https://remote.example/a.png").unwrap().into());
+    proxy.proxy_url(&mut avatar);
+    assert_eq!(Some("lemmy.example"), avatar.unwrap().host_str());
+    let mut local: Option<DbUrl> = Some(Url::parse("https://lemmy.example/a.png").unwrap().into());
+    proxy.proxy_url(&mut local);
+    assert_eq!(Some("/a.png"), local.as_deref().map(Url::path));
+  }
+
+  #[test]
+  fn test_image_proxy_markdown_links() {
+    let proxy = ImageProxy {
+      endpoint: Url::parse("https://lemmy.example/image_proxy").unwrap(),
+      secret: "secret",
+    };
+    let mut links = HashMap::new();
+    proxy.add_markdown_links(
+      Some("![a](https://remote.example/a.png) ![b](https://lemmy.example/b.png)"),
+      &mut links,
+    );
+    assert_eq!(1, links.len());
+    let link = Url::parse(&links["https://remote.example/a.png"]).unwrap();
+    assert_eq!(Some("lemmy.example"), link.host_str());
+
+    assert!(is_image_link(
+      &Url::parse("https://remote.example/a.JPG").unwrap()
+    ));
+    assert!(!is_image_link(
+      &Url::parse("https://remote.example/article.html").unwrap()
+    ));
+  }
+}
@@ -0,0 +1,425 @@
+use crate::image_storage::{ImageParams, ImageStorage, StoredImage, UploadedFile};
+use actix_web::{
+  http::header::{CacheControl, CacheDirective, HeaderMap},
+  HttpResponse,
+};
+use image::{io::Limits, DynamicImage, ImageFormat};
+use lemmy_utils::error::LemmyError;
+use std::{
+  io::Cursor,
+  net::SocketAddr,
+  path::{Path, PathBuf},
+};
+use tokio::{fs, task::spawn_blocking};
+use tracing::warn;
+use uuid::Uuid;
+
+/// How long browsers can cache images. They never change, but they can be deleted.
+const CACHE_MAX_AGE_SECONDS: u32 = 7 * 24 * 60 * 60;
+
+/// Images which are wider or higher than this are rejected before they are decoded.
+const MAX_IMAGE_DIMENSION: u32 = 10_000;
+
+/// How much memory the decoder may allocate for a single image.
+const MAX_DECODE_ALLOC_BYTES: u64 = 256 * 1024 * 1024;
+
+/// The subdirectory where thumbnails and converted images are kept.
+const DERIVED_DIRECTORY: &str = "derived";
+
+/// Thumbnails are only generated in these sizes. Requested sizes are rounded up to the next one,
+/// so that arbitrary sizes can't fill up the disk.
+const THUMBNAIL_SIZES: [u32; 6] = [64, 128, 256, 512, 1024, 2048];
+
+/// Maximum number of thumbnails and conversions which are kept for an image. Further ones are
+/// generated for each request.
+const MAX_DERIVED_FILES_PER_IMAGE: usize = 10;
+
+/// Stores images in a local directory, so that small instances don't need to run pictrs.
+///
+/// Uploads are decoded and encoded again, which strips EXIF metadata like the location where a
+/// photo was taken. Jpegs stay jpegs, all other formats are stored as png, and animations are
+/// reduced to their first frame. Thumbnails and other formats are generated on the first request,
+/// and kept on disk next to the original until it is deleted. Thumbnails come in a fixed set of
+/// sizes.
+pub struct FilesystemStorage {
+  directory: PathBuf,
+}
+
+impl FilesystemStorage {
+  pub fn new(directory: impl Into<PathBuf>) -> Self {
+    FilesystemStorage {
+      directory: directory.into(),
+    }
+  }
+
+  /// The path and format of an image. Aliases are checked, so that they can't point outside of
+  /// the directory.
+  fn image_path(&self, alias: &str) -> Result<(PathBuf, ImageFormat), LemmyError> {
+    let (name, extension) = alias
+      .split_once('.')
+      .ok_or_else(|| LemmyError::from_message("couldnt_find_image"))?;
+    let format = output_format(extension)
+      .filter(|_| name.len() == 32 && name.chars().all(|c| c.is_ascii_hexdigit()))
+      .ok_or_else(|| LemmyError::from_message("couldnt_find_image"))?;
+    Ok((self.directory.join(alias), format))
+  }
+
+  fn delete_token_path(&self, alias: &str) -> PathBuf {
+    self.directory.join(format!("{alias}.delete_token"))
+  }
+
+  /// The directory with all thumbnails and conversions of an image.
+  fn derived_directory(&self, alias: &str) -> PathBuf {
+    self.directory.join(DERIVED_DIRECTORY).join(alias)
+  }
+
+  fn derived_path(&self, alias: &str, format: ImageFormat, thumbnail: Option<u32>) -> PathBuf {
+    let size = thumbnail.map_or_else(|| "full".to_string(), |t| t.to_string());
+    self
+      .derived_directory(alias)
+      .join(format!("{size}.{}", extension(format)))
+  }
+}
+
+#[async_trait::async_trait]
+impl ImageStorage for FilesystemStorage {
+  async fn upload(
+    &self,
+    files: &[UploadedFile],
+    _peer_addr: Option<SocketAddr>,
+  ) -> Result<Vec<StoredImage>, LemmyError> {
+    fs::create_dir_all(&self.directory).await?;
+
+    let mut images = vec![];
+    for file in files {
+      let data = file.data.clone();
+      let (format, data) = spawn_blocking(move || strip_metadata(&data)).await??;
+
+      let alias = format!("{}.{}", Uuid::new_v4().simple(), extension(format));
+      let delete_token = Uuid::new_v4().to_string();
+      fs::write(self.directory.join(&alias), data).await?;
+      fs::write(self.delete_token_path(&alias), &delete_token).await?;
+      images.push(StoredImage {
+        file: alias,
+        delete_token,
+      });
+    }
+    Ok(images)
+  }
+
+  async fn serve(
+    &self,
+    alias: &str,
+    params: &ImageParams,
+    _headers: &HeaderMap,
+    _peer_addr: Option<SocketAddr>,
+  ) -> Result<HttpResponse, LemmyError> {
+    let (path, stored_format) = self.image_path(alias)?;
+    let derived = derived_params(stored_format, params)?;
+    let Some((format, thumbnail)) = derived else {
+      let Ok(data) = fs::read(path).await else {
+        return Ok(HttpResponse::NotFound().finish());
+      };
+      return Ok(image_response(stored_format, data));
+    };
+
+    let derived_path = self.derived_path(alias, format, thumbnail);
+    if let Ok(data) = fs::read(&derived_path).await {
+      return Ok(image_response(format, data));
+    }
+    let Ok(data) = fs::read(path).await else {
+      return Ok(HttpResponse::NotFound().finish());
+    };
+    let data = derive_image(data, stored_format, format, thumbnail).await?;
+    // The image can still be served if it couldn't be kept
+    if count_files(&self.derived_directory(alias)).await < MAX_DERIVED_FILES_PER_IMAGE {
+      if let Err(e) = write_derived(&derived_path, &data).await {
+        warn!(
+          "Failed to store derived image {}: {e}",
+          derived_path.display()
+        );
+      }
+    }
+    Ok(image_response(format, data))
+  }
+
+  async fn delete(&self, alias: &str, delete_token: &str) -> Result<(), LemmyError> {
+    self.image_path(alias)?;
+    let expected_token = fs::read_to_string(self.delete_token_path(alias))
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_image"))?;
+    if expected_token != delete_token {
+      return Err(LemmyError::from_message("couldnt_delete_image"));
+    }
+    self.purge(alias).await
+  }
+
+  async fn purge(&self, alias: &str) -> Result<(), LemmyError> {
+    let (path, _) = self.image_path(alias)?;
+    fs::remove_file(path)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_image"))?;
+    // Without the image, the token and the thumbnails are useless anyway
+    fs::remove_file(self.delete_token_path(alias)).await.ok();
+    fs::remove_dir_all(self.derived_directory(alias)).await.ok();
+    Ok(())
+  }
+}
+
+/// The formats in which images can be stored and served.
+fn output_format(extension: &str) -> Option<ImageFormat> {
+  match extension.to_ascii_lowercase().as_str() {
+    "png" => Some(ImageFormat::Png),
+    "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+    "gif" => Some(ImageFormat::Gif),
+    _ => None,
+  }
+}
+
+pub(crate) fn extension(format: ImageFormat) -> &'static str {
+  match format {
+    ImageFormat::Jpeg => "jpg",
+    ImageFormat::Gif => "gif",
+    _ => "png",
+  }
+}
+
+pub(crate) fn mime_type(format: ImageFormat) -> &'static str {
+  match format {
+    ImageFormat::Jpeg => "image/jpeg",
+    ImageFormat::Gif => "image/gif",
+    ImageFormat::WebP => "image/webp",
+    _ => "image/png",
+  }
+}
+
+/// Converts an image to the format and thumbnail size which the params ask for. Images which
+/// don't need to change are returned as they are.
+pub(crate) async fn process_image(
+  data: Vec<u8>,
+  input_format: ImageFormat,
+  params: &ImageParams,
+) -> Result<(ImageFormat, Vec<u8>), LemmyError> {
+  match derived_params(input_format, params)? {
+    Some((format, thumbnail)) => Ok((
+      format,
+      derive_image(data, input_format, format, thumbnail).await?,
+    )),
+    None => Ok((input_format, data)),
+  }
+}
+
+/// The output format and thumbnail size which the params ask for, or `None` if the image can be
+/// served as it is.
+fn derived_params(
+  input_format: ImageFormat,
+  params: &ImageParams,
+) -> Result<Option<(ImageFormat, Option<u32>)>, LemmyError> {
+  let format = match &params.format {
+    Some(format) => {
+      output_format(format).ok_or_else(|| LemmyError::from_message("image_format_not_supported"))?
+    }
+    None => input_format,
+  };
+  // Larger thumbnails than the biggest size are served as the full image
+  let thumbnail = params
+    .thumbnail
+    .and_then(|t| THUMBNAIL_SIZES.into_iter().find(|size| *size >= t));
+  if format == input_format && thumbnail.is_none() {
+    return Ok(None);
+  }
+  // Other formats can only be decoded
+  let format = output_format(extension(format)).unwrap_or(ImageFormat::Png);
+  Ok(Some((format, thumbnail)))
+}
+
+async fn derive_image(
+  data: Vec<u8>,
+  input_format: ImageFormat,
+  format: ImageFormat,
+  thumbnail: Option<u32>,
+) -> Result<Vec<u8>, LemmyError> {
+  spawn_blocking(move || {
+    let mut image = decode(&data, input_format)?;
+    if let Some(size) = thumbnail {
+      // Like pictrs, only scale images down
+      if image.width() > size || image.height() > size {
+        image = image.thumbnail(size, size);
+      }
+    }
+    encode(&image, format)
+  })
+  .await?
+}
+
+/// The number of files in a directory, or 0 if it doesn't exist yet.
+async fn count_files(directory: &Path) -> usize {
+  let Ok(mut entries) = fs::read_dir(directory).await else {
+    return 0;
+  };
+  let mut count = 0;
+  while let Ok(Some(_)) = entries.next_entry().await {
+    count += 1;
+  }
+  count
+}
+
+/// Writes to a temporary file first, so that concurrent requests never read a partial image.
+async fn write_derived(path: &Path, data: &[u8]) -> Result<(), LemmyError> {
+  let directory = path
+    .parent()
+    .ok_or_else(|| LemmyError::from_message("couldnt_find_image"))?;
+  fs::create_dir_all(directory).await?;
+  let temp_path = directory.join(format!("{}.tmp", Uuid::new_v4().simple()));
+  fs::write(&temp_path, data).await?;
+  if let Err(e) = fs::rename(&temp_path, path).await {
+    fs::remove_file(&temp_path).await.ok();
+    return Err(e.into());
+  }
+  Ok(())
+}
+
+pub(crate) fn image_response(format: ImageFormat, data: Vec<u8>) -> HttpResponse {
+  HttpResponse::Ok()
+    .content_type(mime_type(format))
+    .insert_header(CacheControl(vec![
+      CacheDirective::Public,
+      CacheDirective::MaxAge(CACHE_MAX_AGE_SECONDS),
+    ]))
+    .body(data)
+}
+
+/// Encodes the upload again, which leaves out all metadata.
+fn strip_metadata(data: &[u8]) -> Result<(ImageFormat, Vec<u8>), LemmyError> {
+  let input_format = image::guess_format(data)
+    .map_err(|e| LemmyError::from_error_message(e, "image_type_not_supported"))?;
+  let image = decode(data, input_format)?;
+  let format = match input_format {
+    ImageFormat::Jpeg => ImageFormat::Jpeg,
+    _ => ImageFormat::Png,
+  };
+  Ok((format, encode(&image, format)?))
+}
+
+/// Decodes with limits, so that small files with huge dimensions can't exhaust the memory.
+fn decode(data: &[u8], format: ImageFormat) -> Result<DynamicImage, LemmyError> {
+  let mut limits = Limits::default();
+  limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+  limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+  limits.max_alloc = Some(MAX_DECODE_ALLOC_BYTES);
+  let mut reader = image::io::Reader::with_format(Cursor::new(data), format);
+  reader.limits(limits);
+  reader
+    .decode()
+    .map_err(|e| LemmyError::from_error_message(e, "image_type_not_supported"))
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, LemmyError> {
+  // Jpegs have no transparency
+  let image = match format {
+    ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8()),
+    _ => image.clone(),
+  };
+  let mut data = Cursor::new(vec![]);
+  image
+    .write_to(&mut data, format)
+    .map_err(|e| LemmyError::from_error_message(e, "couldnt_encode_image"))?;
+  Ok(data.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::image_storage::{
+    filesystem::{encode, FilesystemStorage, MAX_IMAGE_DIMENSION},
+    ImageParams,
+    ImageStorage,
+    UploadedFile,
+  };
+  use actix_web::{
+    body::to_bytes,
+    http::{
+      header::{ContentDisposition, DispositionParam, DispositionType, HeaderMap, CONTENT_TYPE},
+      StatusCode,
+    },
+  };
+  use image::{DynamicImage, ImageFormat, RgbaImage};
+  use uuid::Uuid;
+
+  fn uploaded_file(data: Vec<u8>) -> UploadedFile {
+    UploadedFile {
+      content_disposition: ContentDisposition {
+        disposition: DispositionType::FormData,
+        parameters: vec![DispositionParam::Name("images[]".to_string())],
+      },
+      content_type: "image/png".to_string(),
+      data: data.into(),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_filesystem_storage() {
+    let directory = std::env::temp_dir().join(format!("lemmy_images_{}", Uuid::new_v4()));
+    let storage = FilesystemStorage::new(&directory);
+    let headers = HeaderMap::new();
+
+    let image = DynamicImage::ImageRgba8(RgbaImage::new(100, 50));
+    let png = encode(&image, ImageFormat::Png).unwrap();
+    let images = storage.upload(&[uploaded_file(png)], None).await.unwrap();
+    assert_eq!(1, images.len());
+    let alias = &images[0].file;
+    assert!(alias.ends_with(".png"));
+
+    // The thumbnail size is rounded up to the next one which is generated
+    let params = ImageParams {
+      format: Some("jpg".to_string()),
+      thumbnail: Some(10),
+    };
+    let res = storage.serve(alias, &params, &headers, None).await.unwrap();
+    assert_eq!(StatusCode::OK, res.status());
+    assert_eq!("image/jpeg", res.headers().get(CONTENT_TYPE).unwrap());
+    let thumbnail = to_bytes(res.into_body()).await.unwrap();
+    let thumbnail = image::load_from_memory_with_format(&thumbnail, ImageFormat::Jpeg).unwrap();
+    assert_eq!((64, 32), (thumbnail.width(), thumbnail.height()));
+    // The thumbnail is kept for the next request
+    let derived_directory = directory.join("derived").join(alias);
+    assert!(derived_directory.join("64.jpg").exists());
+    let res = storage.serve(alias, &params, &headers, None).await.unwrap();
+    assert_eq!(StatusCode::OK, res.status());
+    let params = ImageParams {
+      format: Some("jpg".to_string()),
+      thumbnail: Some(60),
+    };
+    storage.serve(alias, &params, &headers, None).await.unwrap();
+    assert_eq!(1, std::fs::read_dir(&derived_directory).unwrap().count());
+
+    // Files outside of the directory can't be accessed
+    let params = ImageParams {
+      format: None,
+      thumbnail: None,
+    };
+    assert!(storage
+      .serve("../../etc/passwd", &params, &headers, None)
+      .await
+      .is_err());
+    // Only images can be uploaded
+    assert!(storage
+      .upload(&[uploaded_file(b"not an image".to_vec())], None)
+      .await
+      .is_err());
+    // Images with huge dimensions aren't decoded
+    let huge = DynamicImage::ImageRgba8(RgbaImage::new(MAX_IMAGE_DIMENSION + 1, 1));
+    let huge = encode(&huge, ImageFormat::Png).unwrap();
+    assert!(storage.upload(&[uploaded_file(huge)], None).await.is_err());
+
+    assert!(storage.delete(alias, "wrong token").await.is_err());
+    storage
+      .delete(alias, &images[0].delete_token)
+      .await
+      .unwrap();
+    let res = storage.serve(alias, &params, &headers, None).await.unwrap();
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+    assert!(!derived_directory.exists());
+
+    std::fs::remove_dir_all(directory).unwrap();
+  }
+}
@@ -0,0 +1,139 @@
+use crate::image_storage::{filesystem::FilesystemStorage, pictrs::PictrsStorage};
+use actix_web::{
+  http::header::{ContentDisposition, HeaderMap},
+  web::Bytes,
+  HttpResponse,
+};
+use image::ImageFormat;
+use lemmy_utils::{
+  error::LemmyError,
+  settings::structs::{ImageStorageBackend, Settings},
+};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+pub mod filesystem;
+pub mod pictrs;
+
+/// A file of a multipart upload, which was already checked against the upload limits of the site.
+pub struct UploadedFile {
+  /// The field name and file name of the upload.
+  pub content_disposition: ContentDisposition,
+  pub content_type: String,
+  pub data: Bytes,
+}
+
+/// Detects the type of an uploaded file from its content, instead of trusting the type which the
+/// client declared. Only images and the common video formats are recognized.
+pub fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+  if let Ok(format) = image::guess_format(data) {
+    return match format {
+      ImageFormat::Png => Some("image/png"),
+      ImageFormat::Jpeg => Some("image/jpeg"),
+      ImageFormat::Gif => Some("image/gif"),
+      ImageFormat::WebP => Some("image/webp"),
+      ImageFormat::Bmp => Some("image/bmp"),
+      ImageFormat::Tiff => Some("image/tiff"),
+      ImageFormat::Ico => Some("image/x-icon"),
+      ImageFormat::Avif => Some("image/avif"),
+      _ => None,
+    };
+  }
+  match data {
+    // ISO base media files, with the brand after the `ftyp` box type
+    [_, _, _, _, b'f', b't', b'y', b'p', b'q', b't', b' ', b' ', ..] => Some("video/quicktime"),
+    [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some("video/mp4"),
+    // Matroska, which webm is based on
+    [0x1a, 0x45, 0xdf, 0xa3, ..] => Some("video/webm"),
+    _ => None,
+  }
+}
+
+/// An image which was stored, as returned by pictrs for uploads.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StoredImage {
+  /// The alias, ie the file name under `/pictrs/image/`.
+  pub file: String,
+  pub delete_token: String,
+}
+
+/// Query params for images, like `/pictrs/image/{alias}?format=jpg&thumbnail=256`.
+#[derive(Debug, Deserialize)]
+pub struct ImageParams {
+  pub format: Option<String>,
+  pub thumbnail: Option<u32>,
+}
+
+/// A place where uploaded images are stored, and served from. The address of the client is
+/// passed along, so that pictrs can apply its own limits.
+#[async_trait::async_trait]
+pub trait ImageStorage: Send + Sync {
+  /// Stores the files of an upload, and returns them in the same order.
+  async fn upload(
+    &self,
+    files: &[UploadedFile],
+    peer_addr: Option<SocketAddr>,
+  ) -> Result<Vec<StoredImage>, LemmyError>;
+
+  /// Responds with the image, converted to another format or scaled down to a thumbnail if the
+  /// params ask for it. The headers of the client request are for caching.
+  async fn serve(
+    &self,
+    alias: &str,
+    params: &ImageParams,
+    headers: &HeaderMap,
+    peer_addr: Option<SocketAddr>,
+  ) -> Result<HttpResponse, LemmyError>;
+
+  /// Deletes an image with the token which was returned for the upload.
+  async fn delete(&self, alias: &str, delete_token: &str) -> Result<(), LemmyError>;
+
+  /// Deletes an image without the delete token, when an admin purges content.
+  async fn purge(&self, alias: &str) -> Result<(), LemmyError>;
+}
+
+/// The image storage which is selected in the settings.
+pub fn image_storage<'a>(
+  client: &'a ClientWithMiddleware,
+  settings: &Settings,
+) -> Result<Box<dyn ImageStorage + 'a>, LemmyError> {
+  Ok(match settings.image_storage.backend {
+    ImageStorageBackend::Pictrs => Box::new(PictrsStorage::new(client, settings.pictrs_config()?)),
+    ImageStorageBackend::Filesystem => {
+      Box::new(FilesystemStorage::new(&settings.image_storage.directory))
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::image_storage::sniff_mime_type;
+
+  #[test]
+  fn test_sniff_mime_type() {
+    let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    assert_eq!(Some("image/png"), sniff_mime_type(png));
+    assert_eq!(
+      Some("image/jpeg"),
+      sniff_mime_type(b"\xff\xd8\xff\xe0\0\x10JFIF")
+    );
+    assert_eq!(
+      Some("video/mp4"),
+      sniff_mime_type(b"\0\0\0\x20ftypisom\0\0\x02\0")
+    );
+    assert_eq!(
+      Some("video/quicktime"),
+      sniff_mime_type(b"\0\0\0\x14ftypqt  \0\0")
+    );
+    assert_eq!(
+      Some("video/webm"),
+      sniff_mime_type(b"\x1a\x45\xdf\xa3\x9f\x42\x86")
+    );
+    assert_eq!(
+      None,
+      sniff_mime_type(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>")
+    );
+    assert_eq!(None, sniff_mime_type(b"<html>"));
+  }
+}
@@ -0,0 +1,196 @@
+use crate::image_storage::{ImageParams, ImageStorage, StoredImage, UploadedFile};
+use actix_web::{
+  body::BodyStream,
+  http::header::{HeaderMap, HeaderName, ACCEPT_ENCODING, CONTENT_DISPOSITION, CONTENT_TYPE, HOST},
+  HttpResponse,
+};
+use lemmy_utils::{error::LemmyError, settings::structs::PictrsConfig, REQWEST_TIMEOUT};
+use reqwest::StatusCode;
+use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+/// Stores images in a separate pictrs server.
+pub struct PictrsStorage<'a> {
+  client: &'a ClientWithMiddleware,
+  config: PictrsConfig,
+}
+
+#[derive(Deserialize)]
+struct PictrsUploadResponse {
+  msg: String,
+  files: Option<Vec<StoredImage>>,
+}
+
+#[derive(Deserialize)]
+struct PictrsPurgeResponse {
+  msg: String,
+}
+
+impl<'a> PictrsStorage<'a> {
+  pub fn new(client: &'a ClientWithMiddleware, config: PictrsConfig) -> Self {
+    PictrsStorage { client, config }
+  }
+
+  /// Forwards the headers of the client request to pictrs.
+  fn adapt_request(
+    &self,
+    url: String,
+    headers: &HeaderMap,
+    peer_addr: Option<SocketAddr>,
+  ) -> RequestBuilder {
+    // remove accept-encoding header so that pictrs doesnt compress the response
+    const INVALID_HEADERS: &[HeaderName] = &[ACCEPT_ENCODING, HOST];
+
+    let client_request = self.client.get(url).timeout(REQWEST_TIMEOUT);
+    let client_request = headers
+      .iter()
+      .fold(client_request, |client_req, (key, value)| {
+        if INVALID_HEADERS.contains(key) {
+          client_req
+        } else {
+          client_req.header(key, value)
+        }
+      });
+
+    match peer_addr {
+      Some(addr) => client_request.header("X-Forwarded-For", addr.to_string()),
+      None => client_request,
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl ImageStorage for PictrsStorage<'_> {
+  async fn upload(
+    &self,
+    files: &[UploadedFile],
+    peer_addr: Option<SocketAddr>,
+  ) -> Result<Vec<StoredImage>, LemmyError> {
+    // A random boundary, so that it can't appear in the uploaded files
+    let boundary = format!("lemmy-{}", Uuid::new_v4().simple());
+    let mut client_req = self
+      .client
+      .post(format!("{}image", self.config.url))
+      .timeout(REQWEST_TIMEOUT)
+      .header(
+        CONTENT_TYPE,
+        format!("multipart/form-data; boundary={boundary}"),
+      );
+
+    if let Some(addr) = peer_addr {
+      client_req = client_req.header("X-Forwarded-For", addr.to_string())
+    };
+
+    let response: PictrsUploadResponse = client_req
+      .body(encode_multipart(files, &boundary))
+      .send()
+      .await?
+      .json()
+      .await
+      .map_err(LemmyError::from)?;
+
+    if response.msg == "ok" {
+      Ok(response.files.unwrap_or_default())
+    } else {
+      Err(LemmyError::from_message(&response.msg))
+    }
+  }
+
+  async fn serve(
+    &self,
+    alias: &str,
+    params: &ImageParams,
+    headers: &HeaderMap,
+    peer_addr: Option<SocketAddr>,
+  ) -> Result<HttpResponse, LemmyError> {
+    // If there are no query params, the URL is original
+    let url = if params.format.is_none() && params.thumbnail.is_none() {
+      format!("{}image/original/{}", self.config.url, alias)
+    } else {
+      // Take file type from name, or jpg if nothing is given
+      let format = params
+        .format
+        .clone()
+        .unwrap_or_else(|| alias.rsplit('.').next().unwrap_or("jpg").to_string());
+
+      let mut url = format!("{}image/process.{}?src={}", self.config.url, format, alias);
+
+      if let Some(size) = params.thumbnail {
+        url = format!("{url}&thumbnail={size}",);
+      }
+      url
+    };
+
+    let res = self.adapt_request(url, headers, peer_addr).send().await?;
+
+    if res.status() == StatusCode::NOT_FOUND {
+      return Ok(HttpResponse::NotFound().finish());
+    }
+
+    let mut client_res = HttpResponse::build(res.status());
+
+    for (name, value) in res.headers().iter().filter(|(h, _)| *h != "connection") {
+      client_res.insert_header((name.clone(), value.clone()));
+    }
+
+    Ok(client_res.body(BodyStream::new(res.bytes_stream())))
+  }
+
+  async fn delete(&self, alias: &str, delete_token: &str) -> Result<(), LemmyError> {
+    let delete_url = format!("{}image/delete/{}/{}", self.config.url, delete_token, alias);
+    self
+      .client
+      .get(&delete_url)
+      .timeout(REQWEST_TIMEOUT)
+      .send()
+      .await?
+      .error_for_status()
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_delete_image"))?;
+    Ok(())
+  }
+
+  async fn purge(&self, alias: &str) -> Result<(), LemmyError> {
+    let purge_url = format!("{}/internal/purge?alias={}", self.config.url, alias);
+
+    let pictrs_api_key = self
+      .config
+      .api_key
+      .as_ref()
+      .ok_or_else(|| LemmyError::from_message("pictrs_api_key_not_provided"))?;
+    let response = self
+      .client
+      .post(&purge_url)
+      .timeout(REQWEST_TIMEOUT)
+      .header("x-api-token", pictrs_api_key)
+      .send()
+      .await?;
+
+    let response: PictrsPurgeResponse = response.json().await.map_err(LemmyError::from)?;
+
+    if response.msg == "ok" {
+      Ok(())
+    } else {
+      Err(LemmyError::from_message(&response.msg))
+    }
+  }
+}
+
+/// Builds the multipart body which is sent to pictrs.
+fn encode_multipart(files: &[UploadedFile], boundary: &str) -> Vec<u8> {
+  let mut body = vec![];
+  for file in files {
+    body.extend_from_slice(
+      format!(
+        "--{boundary}\r\n{CONTENT_DISPOSITION}: {}\r\n{CONTENT_TYPE}: {}\r\n\r\n",
+        file.content_disposition, file.content_type
+      )
+      .as_bytes(),
+    );
+    body.extend_from_slice(&file.data);
+    body.extend_from_slice(b"\r\n");
+  }
+  body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+  body
+}
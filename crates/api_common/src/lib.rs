@@ -1,10 +1,19 @@
 #[cfg(feature = "full")]
+pub mod automod;
+#[cfg(feature = "full")]
 pub mod build_response;
 pub mod comment;
 pub mod community;
 #[cfg(feature = "full")]
 pub mod context;
 pub mod custom_emoji;
+#[cfg(feature = "full")]
+pub mod image_proxy;
+#[cfg(feature = "full")]
+pub mod image_storage;
+pub mod live;
+#[cfg(feature = "full")]
+pub mod live_notification;
 pub mod person;
 pub mod post;
 pub mod private_message;
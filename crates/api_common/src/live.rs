@@ -0,0 +1,66 @@
+use crate::{person::GetReportCountResponse, sensitive::Sensitive};
+use lemmy_db_schema::newtypes::{
+  CommentId,
+  CommentReplyId,
+  CommunityId,
+  PersonMentionId,
+  PostId,
+  PrivateMessageId,
+};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Opens a stream of server-sent events, which pushes a [LiveEvent] whenever something new arrives
+/// for you.
+pub struct GetLiveEvents {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+#[serde(tag = "type_")]
+/// An event of the live event stream.
+pub enum LiveEvent {
+  /// Someone replied to your post or comment.
+  CommentReply {
+    comment_reply_id: CommentReplyId,
+    comment_id: CommentId,
+  },
+  /// Someone mentioned you in a comment.
+  PersonMention {
+    person_mention_id: PersonMentionId,
+    comment_id: CommentId,
+  },
+  /// You received a private message.
+  PrivateMessage {
+    private_message_id: PrivateMessageId,
+  },
+  /// A report was created in a community you moderate, or on the site if you are an admin.
+  ReportCount(GetReportCountResponse),
+  /// A new post in a community you follow.
+  Post {
+    post_id: PostId,
+    community_id: CommunityId,
+  },
+  /// A new comment in a community you follow.
+  Comment {
+    comment_id: CommentId,
+    post_id: PostId,
+    community_id: CommunityId,
+  },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The payload of a web push notification.
+pub struct PushNotification {
+  /// A short text which can be shown in the notification, in the language of the recipient.
+  pub title: String,
+  pub event: LiveEvent,
+}
@@ -0,0 +1,106 @@
+use lemmy_db_schema::{
+  newtypes::{
+    CommentId,
+    CommentReplyId,
+    CommunityId,
+    PersonId,
+    PersonMentionId,
+    PostId,
+    PrivateMessageId,
+  },
+  utils::{listen, notify, DbPool},
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// The Postgres channel which carries the live notifications between the Lemmy processes.
+const LIVE_NOTIFICATION_CHANNEL: &str = "lemmy_live_notification";
+
+/// How many notifications are kept for event streams which fall behind.
+const LIVE_NOTIFICATION_BUFFER: usize = 1000;
+
+/// Something new which may be pushed to connected clients. These are sent through the database,
+/// so that clients connected to any Lemmy process get them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type_")]
+pub enum LiveNotification {
+  CommentReply {
+    recipient_id: PersonId,
+    comment_reply_id: CommentReplyId,
+    comment_id: CommentId,
+  },
+  PersonMention {
+    recipient_id: PersonId,
+    person_mention_id: PersonMentionId,
+    comment_id: CommentId,
+  },
+  PrivateMessage {
+    recipient_id: PersonId,
+    private_message_id: PrivateMessageId,
+  },
+  /// A report was created. Reports of private messages don't have a community.
+  Report { community_id: Option<CommunityId> },
+  Post {
+    creator_id: PersonId,
+    community_id: CommunityId,
+    post_id: PostId,
+  },
+  Comment {
+    creator_id: PersonId,
+    community_id: CommunityId,
+    post_id: PostId,
+    comment_id: CommentId,
+  },
+}
+
+impl LiveNotification {
+  /// Sends the notification to all Lemmy processes. Failures are only logged, as the change
+  /// which is notified about has already happened.
+  pub async fn send(&self, pool: &DbPool) {
+    let payload = match serde_json::to_string(self) {
+      Ok(payload) => payload,
+      Err(e) => {
+        warn!("Failed to serialize live notification: {e}");
+        return;
+      }
+    };
+    if let Err(e) = notify(pool, LIVE_NOTIFICATION_CHANNEL, &payload).await {
+      warn!("Failed to send live notification: {e}");
+    }
+  }
+}
+
+/// Receives the live notifications of all Lemmy processes, and hands them to the event streams of
+/// this process.
+#[derive(Clone)]
+pub struct LiveNotificationListener {
+  sender: broadcast::Sender<LiveNotification>,
+}
+
+impl LiveNotificationListener {
+  pub fn start(db_url: String) -> Self {
+    let (sender, _) = broadcast::channel(LIVE_NOTIFICATION_BUFFER);
+    let mut payloads = listen(db_url, LIVE_NOTIFICATION_CHANNEL);
+    tokio::spawn({
+      let sender = sender.clone();
+      async move {
+        while let Some(payload) = payloads.recv().await {
+          match serde_json::from_str(&payload) {
+            // Fails only if no event stream is connected
+            Ok(notification) => sender.send(notification).ok(),
+            Err(e) => {
+              warn!("Invalid live notification {payload}: {e}");
+              None
+            }
+          };
+        }
+      }
+    });
+    LiveNotificationListener { sender }
+  }
+
+  pub fn subscribe(&self) -> broadcast::Receiver<LiveNotification> {
+    self.sender.subscribe()
+  }
+}
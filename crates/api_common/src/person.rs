@@ -1,11 +1,28 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommentReplyId, CommunityId, LanguageId, PersonId, PersonMentionId},
+  newtypes::{
+    CommentReplyId,
+    CommentReportId,
+    CommunityId,
+    DbUrl,
+    LanguageId,
+    PersonId,
+    PersonMentionId,
+    PostReportId,
+    PrivateMessageReportId,
+  },
+  source::{
+    comment::Comment,
+    local_image::LocalImage,
+    login_token::LoginToken,
+    post::Post,
+    private_message::PrivateMessage,
+  },
   CommentSortType,
   ListingType,
   SortType,
 };
-use lemmy_db_views::structs::{CommentView, PostView};
+use lemmy_db_views::structs::{CommentView, LocalUserView, PostView, ReportNoteView};
 use lemmy_db_views_actor::structs::{
   CommentReplyView,
   CommunityModeratorView,
@@ -51,6 +68,48 @@ pub struct Register {
   pub answer: Option<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Starts a login with an OpenID Connect provider.
+pub struct OidcAuthorize {
+  /// The name of the provider, as listed in the site response.
+  pub provider: String,
+  /// Where the provider sends the user back to. Needs to be on this instance.
+  pub redirect_uri: String,
+  /// If you are logged in, the provider account is linked to your account instead of logging in.
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The url of the provider where the user needs to be sent to log in.
+pub struct OidcAuthorizeResponse {
+  pub authorization_url: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Finishes a login with an OpenID Connect provider, with the parameters it passed to the
+/// redirect uri.
+///
+/// If no account is linked to the provider account yet, a new one is created. If the login was
+/// started to link the provider account to your account, `auth` needs to be set to that account.
+pub struct OidcLogin {
+  pub code: String,
+  pub state: String,
+  /// The name for a new account. Defaults to the username at the provider.
+  pub username: Option<String>,
+  pub show_nsfw: Option<bool>,
+  /// An answer is mandatory for new accounts if require application is enabled on the server
+  pub answer: Option<String>,
+  pub auth: Option<Sensitive<String>>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -133,6 +192,14 @@ pub struct SaveUserSettings {
   pub auth: Sensitive<String>,
   /// Open links in a new tab
   pub open_links_in_new_tab: Option<bool>,
+  /// Sends push notifications for replies.
+  pub push_notify_replies: Option<bool>,
+  /// Sends push notifications for mentions.
+  pub push_notify_mentions: Option<bool>,
+  /// Sends push notifications for private messages.
+  pub push_notify_private_messages: Option<bool>,
+  /// The actor ids of your other accounts, which are allowed to move to this account.
+  pub also_known_as: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -276,6 +343,35 @@ pub struct BlockPersonResponse {
   pub blocked: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Follow a person.
+pub struct FollowPerson {
+  pub person_id: PersonId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Stop following a person.
+pub struct UnfollowPerson {
+  pub person_id: PersonId,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for following or unfollowing a person.
+pub struct FollowPersonResponse {
+  pub person_view: PersonView,
+  pub followed: bool,
+  /// Remote persons need to accept the follow first.
+  pub pending: bool,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -416,6 +512,50 @@ pub struct GetReportCountResponse {
   pub private_message_reports: Option<i64>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Add an internal note to a report, which only the moderators can see. Exactly one of the report
+/// ids must be given.
+pub struct CreateReportNote {
+  pub post_report_id: Option<PostReportId>,
+  pub comment_report_id: Option<CommentReportId>,
+  pub private_message_report_id: Option<PrivateMessageReportId>,
+  pub content: String,
+  /// Replies to another note of the same report.
+  pub parent_id: Option<i32>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A report note response.
+pub struct ReportNoteResponse {
+  pub report_note_view: ReportNoteView,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the notes of a report. Exactly one of the report ids must be given.
+pub struct ListReportNotes {
+  pub post_report_id: Option<PostReportId>,
+  pub comment_report_id: Option<CommentReportId>,
+  pub private_message_report_id: Option<PrivateMessageReportId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The notes of a report, from oldest to newest.
+pub struct ListReportNotesResponse {
+  pub report_notes: Vec<ReportNoteView>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -447,3 +587,211 @@ pub struct VerifyEmail {
 #[cfg_attr(feature = "full", ts(export))]
 /// A response to verifying your email.
 pub struct VerifyEmailResponse {}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the active login sessions of your account.
+pub struct ListLogins {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A response containing your active login sessions.
+pub struct ListLoginsResponse {
+  pub logins: Vec<LoginToken>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Logout, which invalidates the given auth token.
+pub struct Logout {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Logout from all sessions, which invalidates every auth token issued for your account.
+pub struct LogoutAll {
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response of a logout.
+pub struct LogoutResponse {}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the images which you uploaded.
+pub struct ListMyImages {
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Your uploaded images, newest first.
+pub struct ListMyImagesResponse {
+  pub images: Vec<LocalImage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete one of your uploaded images. Admins can delete the uploads of any user.
+pub struct DeleteImage {
+  pub pictrs_alias: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response of deleting an image.
+pub struct DeleteImageResponse {
+  pub success: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Registers a browser or app for web push notifications. The fields are those of a browser
+/// `PushSubscription`.
+pub struct RegisterPushSubscription {
+  /// The url of the push service.
+  pub endpoint: String,
+  pub keys: PushSubscriptionKeys,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The base64url encoded keys which push notifications are encrypted with.
+pub struct PushSubscriptionKeys {
+  pub p256dh: String,
+  pub auth: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Stops web push notifications to a browser or app.
+pub struct DeletePushSubscription {
+  pub endpoint: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for registering or deleting a push subscription.
+pub struct PushSubscriptionResponse {}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Download the data of your account, one page at a time. Fetch the next page until the posts,
+/// comments, messages, votes and saved items are all empty.
+pub struct ExportUserData {
+  pub page: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A page of the data of your account. It can be passed to [[ImportUserSettings]] on another
+/// instance.
+///
+/// Communities and users are given as `name@instance`, posts and comments by their ActivityPub id.
+/// The followed communities and blocks are only included in the first page.
+pub struct ExportUserDataResponse {
+  pub local_user_view: LocalUserView,
+  pub posts: Vec<Post>,
+  pub comments: Vec<Comment>,
+  pub private_messages: Vec<PrivateMessage>,
+  pub post_votes: Vec<ExportedVote>,
+  pub comment_votes: Vec<ExportedVote>,
+  #[cfg_attr(feature = "full", ts(type = "string[]"))]
+  pub saved_posts: Vec<DbUrl>,
+  #[cfg_attr(feature = "full", ts(type = "string[]"))]
+  pub saved_comments: Vec<DbUrl>,
+  pub followed_communities: Vec<String>,
+  pub blocked_communities: Vec<String>,
+  pub blocked_users: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A vote on a post or comment.
+pub struct ExportedVote {
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub object_id: DbUrl,
+  pub score: i16,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Takes over the settings, followed communities, blocks and saved items from an export of your
+/// data. The whole export can be sent, the posts, comments and messages in it are ignored.
+pub struct ImportUserSettings {
+  /// The settings and profile are taken from here.
+  pub local_user_view: Option<LocalUserView>,
+  #[serde(default)]
+  pub followed_communities: Vec<String>,
+  #[serde(default)]
+  pub blocked_communities: Vec<String>,
+  #[serde(default)]
+  pub blocked_users: Vec<String>,
+  #[serde(default)]
+  #[cfg_attr(feature = "full", ts(type = "string[]"))]
+  pub saved_posts: Vec<DbUrl>,
+  #[serde(default)]
+  #[cfg_attr(feature = "full", ts(type = "string[]"))]
+  pub saved_comments: Vec<DbUrl>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The import response.
+pub struct ImportUserSettingsResponse {
+  /// The communities, users, posts and comments which couldn't be found.
+  pub failed_items: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Moves your account to another one, and tells your followers about it.
+///
+/// The new account needs to list this account in its `also_known_as` first.
+pub struct MoveAccount {
+  /// The new account, like `name@example.com`.
+  pub target: String,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response for moving your account.
+pub struct MoveAccountResponse {
+  pub person_view: PersonView,
+}
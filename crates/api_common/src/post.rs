@@ -1,11 +1,22 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PostId, PostReportId},
+  newtypes::{
+    CommentId,
+    CommunityId,
+    CommunityPostTagId,
+    DbUrl,
+    LanguageId,
+    PersonId,
+    PostId,
+    PostPollOptionId,
+    PostReportId,
+  },
+  source::edit_history::PostEditHistory,
   ListingType,
   PostFeatureType,
   SortType,
 };
-use lemmy_db_views::structs::{PostReportView, PostView};
+use lemmy_db_views::structs::{PostPollView, PostReportView, PostView};
 use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -29,9 +40,28 @@ pub struct CreatePost {
   pub honeypot: Option<String>,
   pub nsfw: Option<bool>,
   pub language_id: Option<LanguageId>,
+  /// Publish the post at this time (unix timestamp) instead of immediately.
+  pub scheduled_publish_time: Option<i64>,
+  /// Attach a poll to the post.
+  pub poll: Option<CreatePostPoll>,
+  /// Tags of the community which apply to the post.
+  pub tag_ids: Option<Vec<CommunityPostTagId>>,
   pub auth: Sensitive<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll which is created together with a post.
+pub struct CreatePostPoll {
+  pub options: Vec<String>,
+  /// Allow voting for more than one option. Defaults to false.
+  pub multiple_choice: Option<bool>,
+  /// Close the poll at this time (unix timestamp).
+  pub end_time: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -47,6 +77,8 @@ pub struct PostResponse {
 pub struct GetPost {
   pub id: Option<PostId>,
   pub comment_id: Option<CommentId>,
+  /// Also return the post body as html, with links to communities and users and custom emojis.
+  pub render_html: Option<bool>,
   pub auth: Option<Sensitive<String>>,
 }
 
@@ -60,6 +92,7 @@ pub struct GetPostResponse {
   pub moderators: Vec<CommunityModeratorView>,
   /// A list of cross-posts, or other times / communities this link has been posted to.
   pub cross_posts: Vec<PostView>,
+  pub poll: Option<PostPollView>,
 }
 
 #[skip_serializing_none]
@@ -74,16 +107,64 @@ pub struct GetPosts {
   pub limit: Option<i64>,
   pub community_id: Option<CommunityId>,
   pub community_name: Option<String>,
+  /// Only list posts which have this tag.
+  pub tag_id: Option<CommunityPostTagId>,
   pub saved_only: Option<bool>,
+  /// The `next_page` of a previous response, to continue after it instead of using `page`.
+  pub page_cursor: Option<String>,
+  /// Also return the post bodies as html, with links to communities and users and custom emojis.
+  pub render_html: Option<bool>,
   pub auth: Option<Sensitive<String>>,
 }
 
+#[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// The post list response.
 pub struct GetPostsResponse {
   pub posts: Vec<PostView>,
+  /// Pass this as `page_cursor` to get the following posts.
+  pub next_page: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the posts which are not published yet. These are your own scheduled posts, as well as
+/// those in the communities you moderate.
+pub struct ListScheduledPosts {
+  pub community_id: Option<CommunityId>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The scheduled posts response.
+pub struct ListScheduledPostsResponse {
+  pub posts: Vec<PostView>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Vote in the poll of a post. Single choice polls take exactly one option.
+pub struct VotePostPoll {
+  pub post_id: PostId,
+  pub option_ids: Vec<PostPollOptionId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The poll response.
+pub struct PostPollResponse {
+  pub poll: PostPollView,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -111,6 +192,10 @@ pub struct EditPost {
   pub body: Option<String>,
   pub nsfw: Option<bool>,
   pub language_id: Option<LanguageId>,
+  /// Change the publish time (unix timestamp) of a post which is still scheduled.
+  pub scheduled_publish_time: Option<i64>,
+  /// Replaces the tags of the post.
+  pub tag_ids: Option<Vec<CommunityPostTagId>>,
   pub auth: Sensitive<String>,
 }
 
@@ -195,6 +280,7 @@ pub struct PostReportResponse {
   pub post_report_view: PostReportView,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -202,6 +288,30 @@ pub struct PostReportResponse {
 pub struct ResolvePostReport {
   pub report_id: PostReportId,
   pub resolved: bool,
+  /// Why the report was resolved, which is shown in the modlog.
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Assign a post report to a moderator, so that others know it is being handled.
+pub struct AssignPostReport {
+  pub report_id: PostReportId,
+  /// Leave empty to unassign the report.
+  pub assignee_id: Option<PersonId>,
+  pub auth: Sensitive<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Escalate a post report to the instance admins (only doable by mods).
+pub struct EscalatePostReport {
+  pub report_id: PostReportId,
+  pub escalated: bool,
   pub auth: Sensitive<String>,
 }
 
@@ -215,6 +325,8 @@ pub struct ListPostReports {
   pub limit: Option<i64>,
   /// Only shows the unresolved reports
   pub unresolved_only: Option<bool>,
+  /// Only shows the reports which were escalated to the admins
+  pub escalated_only: Option<bool>,
   /// if no community is given, it returns reports for all communities moderated by the auth user
   pub community_id: Option<CommunityId>,
   pub auth: Sensitive<String>,
@@ -256,3 +368,23 @@ pub struct SiteMetadata {
   pub(crate) image: Option<DbUrl>,
   pub embed_video_url: Option<DbUrl>,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the previous versions of an edited post.
+///
+/// Only mods can see them, unless the community made the edit history public.
+pub struct GetPostHistory {
+  pub post_id: PostId,
+  pub auth: Option<Sensitive<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The previous versions of a post, oldest first.
+pub struct GetPostHistoryResponse {
+  pub history: Vec<PostEditHistory>,
+}
@@ -92,6 +92,7 @@ pub struct PrivateMessageReportResponse {
   pub private_message_report_view: PrivateMessageReportView,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -99,6 +100,20 @@ pub struct PrivateMessageReportResponse {
 pub struct ResolvePrivateMessageReport {
   pub report_id: PrivateMessageReportId,
   pub resolved: bool,
+  /// Why the report was resolved.
+  pub reason: Option<String>,
+  pub auth: Sensitive<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Assign a private message report to an admin, so that others know it is being handled.
+pub struct AssignPrivateMessageReport {
+  pub report_id: PrivateMessageReportId,
+  /// Leave empty to unassign the report.
+  pub assignee_id: Option<PersonId>,
   pub auth: Sensitive<String>,
 }
 
@@ -1,17 +1,22 @@
-use crate::post::SiteMetadata;
+use crate::{image_storage::image_storage, post::SiteMetadata};
+use anyhow::anyhow;
 use encoding::{all::encodings, DecoderTrap};
-use lemmy_db_schema::newtypes::DbUrl;
+use lemmy_db_schema::{newtypes::DbUrl, source::push_subscription::PushSubscription};
 use lemmy_utils::{
   error::LemmyError,
-  settings::structs::Settings,
+  settings::structs::{ImageStorageBackend, Settings, WebPushConfig},
+  utils::validation::{is_public_ip, is_public_url},
   version::VERSION,
+  web_push::{encrypt_payload, vapid_authorization},
   REQWEST_TIMEOUT,
 };
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use reqwest::{header::LOCATION, redirect::Policy, Client, Response, StatusCode};
 use reqwest_middleware::ClientWithMiddleware;
 use serde::Deserialize;
+use tokio::net::lookup_host;
 use tracing::info;
-use url::Url;
+use url::{Host, Url};
 use webpage::HTML;
 
 /// Fetches the post link html tags (like title, description, image, etc)
@@ -111,17 +116,16 @@ pub(crate) struct PictrsFile {
   delete_token: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
-pub(crate) struct PictrsPurgeResponse {
-  msg: String,
-}
-
 #[tracing::instrument(skip_all)]
 pub(crate) async fn fetch_pictrs(
   client: &ClientWithMiddleware,
   settings: &Settings,
   image_url: &Url,
 ) -> Result<PictrsResponse, LemmyError> {
+  // Remote images are only cached with pictrs
+  if settings.image_storage.backend != ImageStorageBackend::Pictrs {
+    return Err(LemmyError::from_message("images_disabled"));
+  }
   let pictrs_config = settings.pictrs_config()?;
   is_image_content_type(client, image_url).await?;
 
@@ -156,7 +160,6 @@ pub async fn purge_image_from_pictrs(
   settings: &Settings,
   image_url: &Url,
 ) -> Result<(), LemmyError> {
-  let pictrs_config = settings.pictrs_config()?;
   is_image_content_type(client, image_url).await?;
 
   let alias = image_url
@@ -165,24 +168,64 @@ pub async fn purge_image_from_pictrs(
     .next_back()
     .ok_or_else(|| LemmyError::from_message("Image URL missing last path segment"))?;
 
-  let purge_url = format!("{}/internal/purge?alias={}", pictrs_config.url, alias);
+  purge_image_alias(client, settings, alias).await
+}
 
-  let pictrs_api_key = pictrs_config
-    .api_key
-    .ok_or_else(|| LemmyError::from_message("pictrs_api_key_not_provided"))?;
-  let response = client
-    .post(&purge_url)
-    .timeout(REQWEST_TIMEOUT)
-    .header("x-api-token", pictrs_api_key)
+/// Purges an image by its alias, ie the file name under `/pictrs/image/`
+pub async fn purge_image_alias(
+  client: &ClientWithMiddleware,
+  settings: &Settings,
+  alias: &str,
+) -> Result<(), LemmyError> {
+  image_storage(client, settings)?.purge(alias).await
+}
+
+/// Deletes an image which a local user uploaded, using the delete token which was returned for
+/// the upload. Unlike purging, this doesn't need the pictrs api key.
+pub async fn delete_image(
+  client: &ClientWithMiddleware,
+  settings: &Settings,
+  alias: &str,
+  delete_token: &str,
+) -> Result<(), LemmyError> {
+  image_storage(client, settings)?
+    .delete(alias, delete_token)
+    .await
+}
+
+/// How long push services keep a notification for devices which are offline.
+const PUSH_TTL_SECONDS: u32 = 24 * 60 * 60;
+
+/// Delivers an encrypted web push notification to the push service of a subscription. Returns
+/// false if the push service doesn't know the subscription anymore.
+pub(crate) async fn send_push_notification(
+  settings: &Settings,
+  config: &WebPushConfig,
+  subscription: &PushSubscription,
+  payload: &[u8],
+) -> Result<bool, LemmyError> {
+  let endpoint = Url::parse(&subscription.endpoint)?;
+  let body = encrypt_payload(payload, &subscription.p256dh, &subscription.auth)?;
+  let authorization =
+    vapid_authorization(config, &endpoint, &settings.get_protocol_and_hostname())?;
+
+  // The endpoint comes from the user, so it must not reach services of the local network
+  let response = public_client(&endpoint, settings)
+    .await?
+    .post(endpoint)
+    .header("Authorization", authorization)
+    .header("Content-Encoding", "aes128gcm")
+    .header("Content-Type", "application/octet-stream")
+    .header("TTL", PUSH_TTL_SECONDS.to_string())
+    .body(body)
     .send()
     .await?;
 
-  let response: PictrsPurgeResponse = response.json().await.map_err(LemmyError::from)?;
-
-  if response.msg == "ok" {
-    Ok(())
-  } else {
-    Err(LemmyError::from_message(&response.msg))
+  match response.status() {
+    // The subscription expired, or the user revoked it in the browser
+    StatusCode::NOT_FOUND | StatusCode::GONE => Ok(false),
+    status if status.is_success() => Ok(true),
+    status => Err(anyhow!("Push service responded with {status}").into()),
   }
 }
 
@@ -270,6 +313,57 @@ pub fn build_user_agent(settings: &Settings) -> String {
   )
 }
 
+/// How many redirects [fetch_public_url] follows.
+const MAX_REDIRECTS: usize = 5;
+
+/// Builds a client for a request to a url which comes from remote content or from users. It only
+/// connects to public addresses, so that the url can't point at services in the local network of
+/// the server. The domain is resolved once here, so that it can't resolve to another address for
+/// the actual request. Redirects aren't followed, because their targets need the same check.
+pub async fn public_client(url: &Url, settings: &Settings) -> Result<Client, LemmyError> {
+  if !is_public_url(url) {
+    return Err(LemmyError::from_message("url_not_public"));
+  }
+  let mut builder = Client::builder()
+    .user_agent(build_user_agent(settings))
+    .timeout(REQWEST_TIMEOUT)
+    .redirect(Policy::none());
+  if let Some(Host::Domain(domain)) = url.host() {
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs = lookup_host((domain, port))
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "url_not_public"))?
+      .collect::<Vec<_>>();
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public_ip(addr.ip())) {
+      return Err(LemmyError::from_message("url_not_public"));
+    }
+    builder = builder.resolve_to_addrs(domain, &addrs);
+  }
+  Ok(builder.build()?)
+}
+
+/// Fetches a url with [public_client], following redirects only to other public addresses.
+pub async fn fetch_public_url(url: &Url, settings: &Settings) -> Result<Response, LemmyError> {
+  let mut url = url.clone();
+  for _ in 0..=MAX_REDIRECTS {
+    let response = public_client(&url, settings)
+      .await?
+      .get(url.as_str())
+      .send()
+      .await?;
+    if !response.status().is_redirection() {
+      return Ok(response);
+    }
+    let location = response
+      .headers()
+      .get(LOCATION)
+      .and_then(|location| location.to_str().ok())
+      .ok_or_else(|| LemmyError::from_message("invalid_redirect"))?;
+    url = url.join(location)?;
+  }
+  Err(LemmyError::from_message("too_many_redirects"))
+}
+
 #[cfg(test)]
 mod tests {
   use crate::request::{
@@ -1,7 +1,12 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
   newtypes::{CommentId, CommunityId, LanguageId, PersonId, PostId},
-  source::{instance::Instance, language::Language, tagline::Tagline},
+  source::{
+    federation_queue_state::FederationQueueState,
+    instance::Instance,
+    language::Language,
+    tagline::Tagline,
+  },
   ListingType,
   ModlogActionType,
   RegistrationMode,
@@ -24,23 +29,7 @@ use lemmy_db_views_actor::structs::{
   PersonBlockView,
   PersonView,
 };
-use lemmy_db_views_moderator::structs::{
-  AdminPurgeCommentView,
-  AdminPurgeCommunityView,
-  AdminPurgePersonView,
-  AdminPurgePostView,
-  ModAddCommunityView,
-  ModAddView,
-  ModBanFromCommunityView,
-  ModBanView,
-  ModFeaturePostView,
-  ModHideCommunityView,
-  ModLockPostView,
-  ModRemoveCommentView,
-  ModRemoveCommunityView,
-  ModRemovePostView,
-  ModTransferCommunityView,
-};
+use lemmy_db_views_moderator::structs::ModlogEntry;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -108,34 +97,29 @@ pub struct ResolveObjectResponse {
 pub struct GetModlog {
   pub mod_person_id: Option<PersonId>,
   pub community_id: Option<CommunityId>,
-  pub page: Option<i64>,
   pub limit: Option<i64>,
   pub type_: Option<ModlogActionType>,
   pub other_person_id: Option<PersonId>,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  /// Only actions at or after this unix timestamp.
+  pub since: Option<i64>,
+  /// Only actions before this unix timestamp.
+  pub until: Option<i64>,
+  /// The `next_page` of a previous response, to continue after it.
+  pub page_cursor: Option<String>,
   pub auth: Option<Sensitive<String>>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
-/// The modlog fetch response.
-// TODO this should be redone as a list of tagged enums
+/// The modlog fetch response, newest actions first.
 pub struct GetModlogResponse {
-  pub removed_posts: Vec<ModRemovePostView>,
-  pub locked_posts: Vec<ModLockPostView>,
-  pub featured_posts: Vec<ModFeaturePostView>,
-  pub removed_comments: Vec<ModRemoveCommentView>,
-  pub removed_communities: Vec<ModRemoveCommunityView>,
-  pub banned_from_community: Vec<ModBanFromCommunityView>,
-  pub banned: Vec<ModBanView>,
-  pub added_to_community: Vec<ModAddCommunityView>,
-  pub transferred_to_community: Vec<ModTransferCommunityView>,
-  pub added: Vec<ModAddView>,
-  pub admin_purged_persons: Vec<AdminPurgePersonView>,
-  pub admin_purged_communities: Vec<AdminPurgeCommunityView>,
-  pub admin_purged_posts: Vec<AdminPurgePostView>,
-  pub admin_purged_comments: Vec<AdminPurgeCommentView>,
-  pub hidden_communities: Vec<ModHideCommunityView>,
+  pub entries: Vec<ModlogEntry>,
+  /// Pass this as `page_cursor` to get the following entries.
+  pub next_page: Option<String>,
 }
 
 #[skip_serializing_none]
@@ -175,6 +159,13 @@ pub struct CreateSite {
   pub rate_limit_comment_per_second: Option<i32>,
   pub rate_limit_search: Option<i32>,
   pub rate_limit_search_per_second: Option<i32>,
+  pub rate_limit_message_per_user: Option<i32>,
+  pub rate_limit_post_per_user: Option<i32>,
+  pub rate_limit_image_per_user: Option<i32>,
+  pub rate_limit_comment_per_user: Option<i32>,
+  pub rate_limit_search_per_user: Option<i32>,
+  pub rate_limit_import_user_settings: Option<i32>,
+  pub rate_limit_import_user_settings_per_second: Option<i32>,
   pub federation_enabled: Option<bool>,
   pub federation_debug: Option<bool>,
   pub captcha_enabled: Option<bool>,
@@ -245,6 +236,24 @@ pub struct EditSite {
   /// The number of searches allowed in a given time frame.
   pub rate_limit_search: Option<i32>,
   pub rate_limit_search_per_second: Option<i32>,
+  /// The number of messages allowed for a single user in the same time frame, in addition to the
+  /// ip limit.
+  pub rate_limit_message_per_user: Option<i32>,
+  /// The number of posts allowed for a single user in the same time frame, in addition to the
+  /// ip limit.
+  pub rate_limit_post_per_user: Option<i32>,
+  /// The number of image uploads allowed for a single user in the same time frame, in addition to the
+  /// ip limit.
+  pub rate_limit_image_per_user: Option<i32>,
+  /// The number of comments allowed for a single user in the same time frame, in addition to the
+  /// ip limit.
+  pub rate_limit_comment_per_user: Option<i32>,
+  /// The number of searches allowed for a single user in the same time frame, in addition to the
+  /// ip limit.
+  pub rate_limit_search_per_user: Option<i32>,
+  /// The number of user settings imports allowed in a given time frame, per ip and per user.
+  pub rate_limit_import_user_settings: Option<i32>,
+  pub rate_limit_import_user_settings_per_second: Option<i32>,
   /// Whether to enable federation.
   pub federation_enabled: Option<bool>,
   /// Enables federation debugging.
@@ -262,6 +271,12 @@ pub struct EditSite {
   pub registration_mode: Option<RegistrationMode>,
   /// Whether to email admins for new reports.
   pub reports_email_admins: Option<bool>,
+  /// The max size of a single image upload in bytes.
+  pub image_upload_max_bytes: Option<i64>,
+  /// The MIME types which can be uploaded, like `image/png` or `image/*`.
+  pub image_upload_allowed_mime_types: Option<Vec<String>>,
+  /// The max total size of the uploads of a single user in bytes. 0 removes the quota.
+  pub image_upload_quota_bytes: Option<i64>,
   pub auth: Sensitive<String>,
 }
 
@@ -299,6 +314,19 @@ pub struct GetSiteResponse {
   pub taglines: Vec<Tagline>,
   /// A list of custom emojis your site supports.
   pub custom_emojis: Vec<CustomEmojiView>,
+  /// The key for web push subscriptions. Only set if the site sends push notifications.
+  pub vapid_public_key: Option<String>,
+  /// OpenID Connect providers which can be used to register and log in.
+  pub oidc_providers: Vec<OidcProvider>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An OpenID Connect provider which users can log in with.
+pub struct OidcProvider {
+  pub name: String,
+  pub display_name: String,
 }
 
 #[skip_serializing_none]
@@ -346,11 +374,22 @@ pub struct LeaveAdmin {
 #[cfg_attr(feature = "full", ts(export))]
 /// A list of federated instances.
 pub struct FederatedInstances {
-  pub linked: Vec<Instance>,
+  pub linked: Vec<InstanceWithFederationState>,
   pub allowed: Vec<Instance>,
   pub blocked: Vec<Instance>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A federated instance, with statistics about activities sent to it.
+pub struct InstanceWithFederationState {
+  pub instance: Instance,
+  /// Missing if no activity was sent to the instance yet.
+  pub federation_state: Option<FederationQueueState>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -1,46 +1,66 @@
 use crate::{
   context::LemmyContext,
-  request::purge_image_from_pictrs,
+  live::{LiveEvent, PushNotification},
+  person::LoginResponse,
+  post::CreatePostPoll,
+  request::{purge_image_alias, purge_image_from_pictrs, send_push_notification},
   sensitive::Sensitive,
-  site::FederatedInstances,
+  site::{FederatedInstances, InstanceWithFederationState, OidcProvider},
 };
+use activitypub_federation::http_signatures::generate_actor_keypair;
 use anyhow::Context;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use futures::try_join;
 use lemmy_db_schema::{
+  aggregates::structs::PersonAggregates,
   impls::person::is_banned,
-  newtypes::{CommunityId, DbUrl, LocalUserId, PersonId, PostId},
+  newtypes::{CommunityId, CommunityPostTagId, DbUrl, LocalUserId, PersonId, PostId},
   source::{
     comment::{Comment, CommentUpdateForm},
-    community::{Community, CommunityModerator, CommunityUpdateForm},
+    community::{Community, CommunityFollower, CommunityModerator, CommunityUpdateForm},
+    community_post_tag::CommunityPostTag,
+    edit_history::{CommentEditHistory, PostEditHistory},
     email_verification::{EmailVerification, EmailVerificationForm},
     instance::Instance,
+    local_image::LocalImage,
     local_site::LocalSite,
     local_site_rate_limit::LocalSiteRateLimit,
+    local_user::{LocalUser, LocalUserInsertForm},
+    login_token::{LoginToken, LoginTokenCreateForm},
     password_reset_request::PasswordResetRequest,
-    person::{Person, PersonUpdateForm},
+    person::{Person, PersonInsertForm, PersonUpdateForm},
     person_block::PersonBlock,
     post::{Post, PostRead, PostReadForm},
-    registration_application::RegistrationApplication,
+    push_subscription::PushSubscription,
+    registration_application::{RegistrationApplication, RegistrationApplicationInsertForm},
   },
   traits::{Crud, Readable},
-  utils::DbPool,
+  utils::{naive_now, DbPool},
+  CommunityVisibility,
   RegistrationMode,
 };
-use lemmy_db_views::{comment_view::CommentQuery, structs::LocalUserView};
+use lemmy_db_views::{
+  comment_view::CommentQuery,
+  structs::{CommentView, CustomEmojiView, LocalUserView, PostView, SiteView},
+};
 use lemmy_db_views_actor::structs::{
   CommunityModeratorView,
   CommunityPersonBanView,
   CommunityView,
 };
 use lemmy_utils::{
-  claims::Claims,
+  claims::{login_token_validity, Claims},
   email::{send_email, translations::Lang},
   error::LemmyError,
   location_info,
-  rate_limit::RateLimitConfig,
+  rate_limit::{RateLimitConfig, RateLimitedGuard},
   settings::structs::Settings,
-  utils::slurs::build_slur_regex,
+  utils::{
+    markdown::{markdown_to_html_with_context, CustomEmojiImage, MarkdownContext},
+    slurs::{build_slur_regex, check_slurs, check_slurs_opt},
+    validation::{is_valid_actor_name, is_valid_poll_options},
+  },
+  web_push::vapid_public_key,
 };
 use regex::Regex;
 use reqwest_middleware::ClientWithMiddleware;
@@ -149,7 +169,12 @@ pub async fn local_user_view_from_jwt(
     local_user_view.person.deleted,
   )?;
 
+  // Checked before the token, so that legacy tokens from before a logout of all sessions aren't
+  // stored again
   check_validator_time(&local_user_view.local_user.validator_time, &claims)?;
+  validate_login_token(context.pool(), &claims, jwt)
+    .await
+    .map_err(|e| e.with_message("not_logged_in"))?;
 
   Ok(local_user_view)
 }
@@ -162,6 +187,48 @@ pub async fn local_user_view_from_jwt_opt(
   local_user_view_from_jwt(jwt?, context).await.ok()
 }
 
+/// Issues a new login token (jwt) for the user, and stores it so that it can later be listed and
+/// revoked.
+#[tracing::instrument(skip(context))]
+pub async fn create_login_token(
+  local_user_id: LocalUserId,
+  ip: Option<String>,
+  user_agent: Option<String>,
+  context: &LemmyContext,
+) -> Result<Sensitive<String>, LemmyError> {
+  let jwt = Claims::jwt(
+    local_user_id.0,
+    &context.secret().jwt_secret,
+    &context.settings().hostname,
+  )?;
+  let form = LoginTokenCreateForm {
+    token_encrypted: LoginToken::hash_token(&jwt),
+    local_user_id,
+    ip,
+    user_agent,
+    expires: Utc::now() + login_token_validity(),
+  };
+  LoginToken::create(context.pool(), &form).await?;
+  Ok(jwt.into())
+}
+
+/// Checks that the login token is stored and neither expired nor revoked.
+///
+/// Tokens which were issued before login tokens were stored have no expiry. They are stored on
+/// first use instead of logging everyone out on upgrade, and then expire like new tokens.
+pub async fn validate_login_token(
+  pool: &DbPool,
+  claims: &Claims,
+  jwt: &str,
+) -> Result<LoginToken, LemmyError> {
+  let local_user_id = LocalUserId(claims.sub);
+  if claims.exp.is_none() {
+    let expires = Utc::now() + login_token_validity();
+    LoginToken::adopt_legacy(pool, local_user_id, jwt, expires).await?;
+  }
+  Ok(LoginToken::validate(pool, local_user_id, jwt).await?)
+}
+
 /// Checks if user's token was issued before user's password reset.
 pub fn check_validator_time(
   validator_time: &DateTime<Utc>,
@@ -175,6 +242,20 @@ pub fn check_validator_time(
   }
 }
 
+/// Applies the per-user limit of a rate limited action. The middleware only knows the user if the
+/// token is sent in a header, cookie or query param, so actions with their own limit are checked
+/// here instead.
+pub fn check_user_rate_limit(
+  rate_limit: RateLimitedGuard,
+  local_user_view: &LocalUserView,
+) -> Result<(), LemmyError> {
+  if rate_limit.check_user(local_user_view.local_user.id.0) {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("rate_limit_error"))
+  }
+}
+
 pub fn check_user_valid(
   banned: bool,
   ban_expires: Option<DateTime<Utc>>,
@@ -224,6 +305,31 @@ pub async fn check_community_deleted_or_removed(
   }
 }
 
+/// Private communities only let approved followers, mods and admins see their content. Local-only
+/// communities are visible to all logged in users of this instance.
+#[tracing::instrument(skip_all)]
+pub async fn check_community_visible(
+  community: &Community,
+  local_user_view: Option<&LocalUserView>,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  let visible = match (community.visibility, local_user_view) {
+    (CommunityVisibility::Public, _) => true,
+    (_, None) => false,
+    (CommunityVisibility::LocalOnly, Some(_)) => true,
+    (CommunityVisibility::ApprovalRequired, Some(local_user_view)) => {
+      let person_id = local_user_view.person.id;
+      CommunityFollower::is_approved(pool, community.id, person_id).await?
+        || CommunityView::is_mod_or_admin(pool, person_id, community.id).await?
+    }
+  };
+  if visible {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("community_is_private"))
+  }
+}
+
 pub fn check_post_deleted_or_removed(post: &Post) -> Result<(), LemmyError> {
   if post.deleted || post.removed {
     Err(LemmyError::from_message("deleted"))
@@ -232,6 +338,57 @@ pub fn check_post_deleted_or_removed(post: &Post) -> Result<(), LemmyError> {
   }
 }
 
+/// Scheduled posts can't be commented on, voted on or moderated before they are published.
+pub fn check_post_published(post: &Post) -> Result<(), LemmyError> {
+  if post.scheduled_publish_time.is_some() {
+    Err(LemmyError::from_message("post_not_published"))
+  } else {
+    Ok(())
+  }
+}
+
+/// Converts the scheduled publish time of a post from a unix timestamp, and checks that it lies
+/// in the future.
+pub fn check_scheduled_publish_time(
+  scheduled_publish_time: Option<i64>,
+) -> Result<Option<DateTime<Utc>>, LemmyError> {
+  scheduled_publish_time
+    .map(|time| match Utc.timestamp_opt(time, 0).single() {
+      Some(time) if time > naive_now() => Ok(time),
+      _ => Err(LemmyError::from_message("scheduled_publish_time_in_past")),
+    })
+    .transpose()
+}
+
+/// Validates a new poll, and converts its end time.
+pub fn check_post_poll(poll: &CreatePostPoll) -> Result<Option<DateTime<Utc>>, LemmyError> {
+  is_valid_poll_options(&poll.options)?;
+  poll
+    .end_time
+    .map(|time| match Utc.timestamp_opt(time, 0).single() {
+      Some(time) if time > naive_now() => Ok(time),
+      _ => Err(LemmyError::from_message("poll_end_time_in_past")),
+    })
+    .transpose()
+}
+
+/// Checks that all the tags of a post belong to its community.
+pub async fn check_post_tags(
+  tag_ids: &[CommunityPostTagId],
+  community_id: CommunityId,
+  pool: &DbPool,
+) -> Result<(), LemmyError> {
+  let community_tags = CommunityPostTag::list_for_community(pool, community_id).await?;
+  if tag_ids
+    .iter()
+    .all(|tag_id| community_tags.iter().any(|t| &t.id == tag_id))
+  {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("invalid_post_tag"))
+  }
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn check_person_block(
   my_id: PersonId,
@@ -275,10 +432,17 @@ pub async fn build_federated_instances(
   if local_site.federation_enabled {
     // TODO I hate that this requires 3 queries
     let (linked, allowed, blocked) = try_join!(
-      Instance::linked(pool),
+      Instance::linked_with_federation_state(pool),
       Instance::allowlist(pool),
       Instance::blocklist(pool)
     )?;
+    let linked = linked
+      .into_iter()
+      .map(|(instance, federation_state)| InstanceWithFederationState {
+        instance,
+        federation_state,
+      })
+      .collect();
 
     Ok(Some(FederatedInstances {
       linked,
@@ -332,6 +496,82 @@ pub fn send_email_to_user(
   }
 }
 
+/// Sends a web push notification to all browsers and apps of the user, unless they turned off
+/// push notifications of this type.
+pub async fn send_push_to_user(
+  local_user_view: &LocalUserView,
+  title: String,
+  event: LiveEvent,
+  context: &LemmyContext,
+) {
+  let local_user = &local_user_view.local_user;
+  let enabled = match event {
+    LiveEvent::CommentReply { .. } => local_user.push_notify_replies,
+    LiveEvent::PersonMention { .. } => local_user.push_notify_mentions,
+    LiveEvent::PrivateMessage { .. } => local_user.push_notify_private_messages,
+    _ => false,
+  };
+  let Some(config) = &context.settings().web_push else {
+    return;
+  };
+  if local_user_view.person.banned || !enabled {
+    return;
+  }
+
+  let subscriptions =
+    match PushSubscription::list_for_local_user(context.pool(), local_user.id).await {
+      Ok(subscriptions) => subscriptions,
+      Err(e) => {
+        warn!("Failed to read push subscriptions: {e}");
+        return;
+      }
+    };
+  let payload = match serde_json::to_vec(&PushNotification { title, event }) {
+    Ok(payload) => payload,
+    Err(e) => {
+      warn!("Failed to serialize push notification: {e}");
+      return;
+    }
+  };
+  // Push services can be slow, so the notifications are sent in the background
+  for subscription in subscriptions {
+    let context = context.clone();
+    let payload = payload.clone();
+    tokio::spawn(async move {
+      let sent = send_push_notification(context.settings(), config, &subscription, &payload).await;
+      match sent {
+        Ok(true) => {}
+        Ok(false) => {
+          PushSubscription::delete(context.pool(), subscription.id)
+            .await
+            .ok();
+        }
+        Err(e) => warn!("Failed to send push notification: {e}"),
+      }
+    });
+  }
+}
+
+/// The key which clients need to subscribe to push notifications, if they are configured.
+pub fn site_vapid_public_key(settings: &Settings) -> Option<String> {
+  let config = settings.web_push.as_ref()?;
+  vapid_public_key(config)
+    .map_err(|e| warn!("Invalid web push configuration: {e}"))
+    .ok()
+}
+
+/// The OpenID Connect providers which are shown on the login page.
+pub fn site_oidc_providers(settings: &Settings) -> Vec<OidcProvider> {
+  settings
+    .oidc_providers
+    .iter()
+    .map(|p| OidcProvider {
+      name: p.name.clone(),
+      display_name: p.display_name.clone(),
+    })
+    .collect()
+}
+
 pub async fn send_password_reset_email(
   user: &LocalUserView,
   pool: &DbPool,
@@ -414,6 +654,13 @@ pub fn local_site_rate_limit_to_rate_limit_config(
     comment_per_second: l.comment_per_second,
     search: l.search,
     search_per_second: l.search_per_second,
+    message_per_user: l.message_per_user,
+    post_per_user: l.post_per_user,
+    image_per_user: l.image_per_user,
+    comment_per_user: l.comment_per_user,
+    search_per_user: l.search_per_user,
+    import_user_settings: l.import_user_settings,
+    import_user_settings_per_second: l.import_user_settings_per_second,
   }
 }
 
@@ -516,6 +763,159 @@ pub async fn check_registration_application(
   Ok(())
 }
 
+/// The parts of a new local account which differ between the ways to sign up.
+pub struct NewLocalAccount {
+  pub username: String,
+  pub email: Option<String>,
+  /// Set if the email was already verified elsewhere, eg by an OIDC provider.
+  pub email_verified: bool,
+  pub password: String,
+  pub show_nsfw: Option<bool>,
+  pub answer: Option<String>,
+}
+
+/// Creates the person and local user of a new account, along with its registration application.
+///
+/// Checks which only apply to one way to sign up, like the password or captcha, are left to the
+/// caller.
+pub async fn create_local_account(
+  account: NewLocalAccount,
+  site_view: &SiteView,
+  context: &LemmyContext,
+) -> Result<LocalUserView, LemmyError> {
+  let local_site = &site_view.local_site;
+  let require_registration_application =
+    local_site.registration_mode == RegistrationMode::RequireApplication;
+
+  if local_site.registration_mode == RegistrationMode::Closed {
+    return Err(LemmyError::from_message("registration_closed"));
+  }
+
+  if local_site.require_email_verification && account.email.is_none() {
+    return Err(LemmyError::from_message("email_required"));
+  }
+
+  let answer = match (
+    local_site.site_setup && require_registration_application,
+    &account.answer,
+  ) {
+    (true, Some(answer)) => Some(answer.clone()),
+    (true, None) => {
+      return Err(LemmyError::from_message(
+        "registration_application_answer_required",
+      ))
+    }
+    (false, _) => None,
+  };
+
+  let slur_regex = local_site_to_slur_regex(local_site);
+  check_slurs(&account.username, &slur_regex)?;
+  check_slurs_opt(&account.answer, &slur_regex)?;
+
+  let actor_keypair = generate_actor_keypair()?;
+  is_valid_actor_name(&account.username, local_site.actor_name_max_length as usize)?;
+  let actor_id = generate_local_apub_endpoint(
+    EndpointType::Person,
+    &account.username,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+
+  let email = account.email.as_deref().map(str::to_lowercase);
+  if let Some(email) = &email {
+    if LocalUser::is_email_taken(context.pool(), email).await? {
+      return Err(LemmyError::from_message("email_already_exists"));
+    }
+  }
+
+  // We have to create both a person, and local_user
+  let person_form = PersonInsertForm::builder()
+    .name(account.username.clone())
+    .actor_id(Some(actor_id.clone()))
+    .private_key(Some(actor_keypair.private_key))
+    .public_key(actor_keypair.public_key)
+    .inbox_url(Some(generate_inbox_url(&actor_id)?))
+    .shared_inbox_url(Some(generate_shared_inbox_url(&actor_id)?))
+    // If its the initial site setup, they are an admin
+    .admin(Some(!local_site.site_setup))
+    .instance_id(site_view.site.instance_id)
+    .build();
+  let inserted_person = Person::create(context.pool(), &person_form)
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "user_already_exists"))?;
+
+  // Automatically set their application as accepted, if they created this with open registration.
+  // Also fixes a bug which allows users to log in when registrations are changed to closed.
+  let local_user_form = LocalUserInsertForm::builder()
+    .person_id(inserted_person.id)
+    .email(email)
+    .email_verified(Some(account.email_verified))
+    .password_encrypted(account.password)
+    .show_nsfw(account.show_nsfw)
+    .accepted_application(Some(!require_registration_application))
+    .build();
+  let inserted_local_user = LocalUser::create(context.pool(), &local_user_form).await?;
+
+  if let Some(answer) = answer {
+    let form = RegistrationApplicationInsertForm {
+      local_user_id: inserted_local_user.id,
+      answer,
+    };
+    RegistrationApplication::create(context.pool(), &form).await?;
+  }
+
+  // Email the admins
+  if local_site.application_email_admins {
+    send_new_applicant_email_to_admins(&account.username, context.pool(), context.settings())
+      .await?;
+  }
+
+  Ok(LocalUserView {
+    local_user: inserted_local_user,
+    person: inserted_person,
+    counts: PersonAggregates::default(),
+  })
+}
+
+/// The response to a sign up, which either logs the new account in directly, or tells that the
+/// email verification or registration application is still outstanding.
+pub async fn registration_login_response(
+  local_user_view: &LocalUserView,
+  local_site: &LocalSite,
+  require_email_verification: bool,
+  (ip, user_agent): (Option<String>, Option<String>),
+  context: &LemmyContext,
+) -> Result<LoginResponse, LemmyError> {
+  let require_registration_application =
+    local_site.registration_mode == RegistrationMode::RequireApplication;
+  let mut login_response = LoginResponse {
+    jwt: None,
+    registration_created: false,
+    verify_email_sent: false,
+  };
+
+  // Log the user in directly if the site is not setup, or email verification and application aren't required
+  if !local_site.site_setup || (!require_registration_application && !require_email_verification) {
+    login_response.jwt =
+      Some(create_login_token(local_user_view.local_user.id, ip, user_agent, context).await?);
+  } else {
+    if require_email_verification {
+      let email = local_user_view
+        .local_user
+        .email
+        .as_deref()
+        .ok_or_else(|| LemmyError::from_message("email_required"))?;
+      send_verification_email(local_user_view, email, context.pool(), context.settings()).await?;
+      login_response.verify_email_sent = true;
+    }
+
+    if require_registration_application {
+      login_response.registration_created = true;
+    }
+  }
+
+  Ok(login_response)
+}
+
 pub fn check_private_instance_and_federation_enabled(
   local_site: &LocalSite,
 ) -> Result<(), LemmyError> {
@@ -527,6 +927,71 @@ pub fn check_private_instance_and_federation_enabled(
   Ok(())
 }
 
+const MAX_ACCOUNT_ALIASES: usize = 10;
+
+/// Parses the account aliases given in the user settings.
+pub fn check_also_known_as(also_known_as: &[String]) -> Result<Vec<DbUrl>, LemmyError> {
+  if also_known_as.len() > MAX_ACCOUNT_ALIASES {
+    return Err(LemmyError::from_message("too_many_aliases"));
+  }
+  also_known_as
+    .iter()
+    .map(|a| {
+      Url::parse(a)
+        .map(Into::into)
+        .map_err(|e| LemmyError::from_error_message(e, "invalid_url"))
+    })
+    .collect()
+}
+
+/// What is needed to render markdown for this instance, including its custom emojis.
+pub async fn markdown_context(
+  context: &LemmyContext,
+  local_site: &LocalSite,
+) -> Result<MarkdownContext, LemmyError> {
+  let custom_emojis = CustomEmojiView::get_all(context.pool(), local_site.id)
+    .await?
+    .into_iter()
+    .map(|emoji| {
+      let image = CustomEmojiImage {
+        image_url: emoji.custom_emoji.image_url.to_string(),
+        alt_text: emoji.custom_emoji.alt_text,
+      };
+      (emoji.custom_emoji.shortcode, image)
+    })
+    .collect();
+  Ok(MarkdownContext {
+    protocol_and_hostname: context.settings().get_protocol_and_hostname(),
+    hostname: context.settings().hostname.clone(),
+    custom_emojis,
+  })
+}
+
+/// Fills in the `content_html` of the posts, from their markdown body. Images are loaded through
+/// the image proxy, if their links were proxied before.
+pub fn render_posts_html(posts: &mut [PostView], markdown: &MarkdownContext) {
+  for post_view in posts {
+    let image_links = post_view.image_proxy_links.as_ref();
+    post_view.content_html = post_view
+      .post
+      .body
+      .as_deref()
+      .map(|body| markdown_to_html_with_context(body, markdown, image_links));
+  }
+}
+
+/// Fills in the `content_html` of the comments, from their markdown content. Images are loaded
+/// through the image proxy, if their links were proxied before.
+pub fn render_comments_html(comments: &mut [CommentView], markdown: &MarkdownContext) {
+  for comment_view in comments {
+    comment_view.content_html = Some(markdown_to_html_with_context(
+      &comment_view.comment.content,
+      markdown,
+      comment_view.image_proxy_links.as_ref(),
+    ));
+  }
+}
+
 pub async fn purge_image_posts_for_person(
   banned_person_id: PersonId,
   pool: &DbPool,
@@ -550,6 +1015,23 @@ pub async fn purge_image_posts_for_person(
   Ok(())
 }
 
+/// Purges all images which the person uploaded, including those which aren't used anywhere.
+pub async fn purge_local_images_for_person(
+  person_id: PersonId,
+  pool: &DbPool,
+  settings: &Settings,
+  client: &ClientWithMiddleware,
+) -> Result<(), LemmyError> {
+  let images = LocalImage::list_for_person(pool, person_id).await?;
+  for image in images {
+    purge_image_alias(client, settings, &image.pictrs_alias)
+      .await
+      .ok();
+  }
+  LocalImage::delete_for_person(pool, person_id).await?;
+  Ok(())
+}
+
 pub async fn purge_image_posts_for_community(
   banned_community_id: CommunityId,
   pool: &DbPool,
@@ -608,6 +1090,7 @@ pub async fn remove_user_data(
 
   // Purge image posts
   purge_image_posts_for_person(banned_person_id, pool, settings, client).await?;
+  purge_local_images_for_person(banned_person_id, pool, settings, client).await?;
 
   // Communities
   // Remove all communities where they're the top mod
@@ -711,11 +1194,17 @@ pub async fn delete_user_account(
   Comment::permadelete_for_creator(pool, person_id)
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_comment"))?;
+  CommentEditHistory::delete_for_creator(pool, person_id)
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_comment"))?;
 
   // Posts
   Post::permadelete_for_creator(pool, person_id)
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_post"))?;
+  PostEditHistory::delete_for_creator(pool, person_id)
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_post"))?;
 
   // Purge image posts
   purge_image_posts_for_person(person_id, pool, settings, client).await?;
@@ -779,6 +1268,13 @@ pub fn generate_followers_url(actor_id: &DbUrl) -> Result<DbUrl, ParseError> {
   Ok(Url::parse(&format!("{actor_id}/followers"))?.into())
 }
 
+pub fn generate_post_tag_url(
+  community_actor_id: &DbUrl,
+  tag_id: CommunityPostTagId,
+) -> Result<DbUrl, ParseError> {
+  Ok(Url::parse(&format!("{community_actor_id}/tag/{}", tag_id.0))?.into())
+}
+
 pub fn generate_inbox_url(actor_id: &DbUrl) -> Result<DbUrl, ParseError> {
   Ok(Url::parse(&format!("{actor_id}/inbox"))?.into())
 }
@@ -0,0 +1,52 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  automod::validate_automod_rule,
+  community::{AutomodRuleResponse, CreateAutomodRule},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    community_automod_rule::{CommunityAutomodRule, CommunityAutomodRuleForm},
+  },
+  traits::Crud,
+};
+use lemmy_utils::{error::LemmyError, utils::validation::is_valid_body_field};
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateAutomodRule {
+  type Response = AutomodRuleResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<AutomodRuleResponse, LemmyError> {
+    let data: &CreateAutomodRule = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let community_id = data.community_id;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
+    // Rules are checked by the instance of the community
+    let community = Community::read(context.pool(), community_id).await?;
+    if !community.local {
+      return Err(LemmyError::from_message("community_not_local"));
+    }
+
+    let value = data.value.trim().to_string();
+    validate_automod_rule(data.rule_type, &value)?;
+    is_valid_body_field(&data.reason, false)?;
+
+    let form = CommunityAutomodRuleForm {
+      community_id,
+      rule_type: data.rule_type,
+      value,
+      action: data.action,
+      reason: data.reason.clone(),
+    };
+    let rule = CommunityAutomodRule::create(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_automod_rule"))?;
+
+    Ok(AutomodRuleResponse { rule })
+  }
+}
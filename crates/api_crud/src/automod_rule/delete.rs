@@ -0,0 +1,29 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{AutomodRuleResponse, DeleteAutomodRule},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::source::community_automod_rule::CommunityAutomodRule;
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteAutomodRule {
+  type Response = AutomodRuleResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(&self, context: &Data<LemmyContext>) -> Result<AutomodRuleResponse, LemmyError> {
+    let data: &DeleteAutomodRule = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let rule = CommunityAutomodRule::read(context.pool(), data.id)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_automod_rule"))?;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, rule.community_id).await?;
+
+    CommunityAutomodRule::delete(context.pool(), rule.id).await?;
+
+    Ok(AutomodRuleResponse { rule })
+  }
+}
@@ -1,13 +1,18 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
+  automod::{apply_comment_automod, check_automod_rules, AutomodContent},
   build_response::{build_comment_response, send_local_notifs},
   comment::{CommentResponse, CreateComment},
   context::LemmyContext,
+  live_notification::LiveNotification,
   utils::{
     check_community_ban,
     check_community_deleted_or_removed,
+    check_community_visible,
     check_post_deleted_or_removed,
+    check_post_published,
+    check_user_rate_limit,
     generate_local_apub_endpoint,
     get_post,
     local_site_to_slur_regex,
@@ -20,10 +25,12 @@ use lemmy_db_schema::{
     actor_language::CommunityLanguage,
     comment::{Comment, CommentInsertForm, CommentLike, CommentLikeForm, CommentUpdateForm},
     comment_reply::{CommentReply, CommentReplyUpdateForm},
+    community::Community,
     local_site::LocalSite,
     person_mention::{PersonMention, PersonMentionUpdateForm},
   },
   traits::{Crud, Likeable},
+  AutomodAction,
 };
 use lemmy_utils::{
   error::LemmyError,
@@ -43,6 +50,7 @@ impl PerformCrud for CreateComment {
   async fn perform(&self, context: &Data<LemmyContext>) -> Result<CommentResponse, LemmyError> {
     let data: &CreateComment = self;
     let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    check_user_rate_limit(context.settings_updated_channel().comment(), &local_user_view)?;
     let local_site = LocalSite::read(context.pool()).await?;
 
     let content_slurs_removed = remove_slurs(
@@ -59,6 +67,9 @@ impl PerformCrud for CreateComment {
     check_community_ban(local_user_view.person.id, community_id, context.pool()).await?;
     check_community_deleted_or_removed(community_id, context.pool()).await?;
     check_post_deleted_or_removed(&post)?;
+    check_post_published(&post)?;
+    let community = Community::read(context.pool(), community_id).await?;
+    check_community_visible(&community, Some(&local_user_view), context.pool()).await?;
 
     // Check if post is locked, no new comments
     if post.locked {
@@ -95,6 +106,18 @@ impl PerformCrud for CreateComment {
     )
     .await?;
 
+    let automod_content = AutomodContent {
+      body: Some(&content_slurs_removed),
+      ..Default::default()
+    };
+    let automod_rule = check_automod_rules(
+      context.pool(),
+      community_id,
+      &local_user_view.person,
+      &automod_content,
+    )
+    .await?;
+
     let comment_form = CommentInsertForm::builder()
       .content(content_slurs_removed.clone())
       .post_id(data.post_id)
@@ -125,6 +148,10 @@ impl PerformCrud for CreateComment {
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_comment"))?;
 
+    if let Some(rule) = &automod_rule {
+      apply_comment_automod(context.pool(), rule, &updated_comment).await;
+    }
+
     // Scan the comment for user mentions, add those rows
     let mentions = scrape_text_for_mentions(&content_slurs_removed);
     let recipient_ids = send_local_notifs(
@@ -137,6 +164,18 @@ impl PerformCrud for CreateComment {
     )
     .await?;
 
+    // Removed comments are only visible to mods
+    if automod_rule.as_ref().map(|r| r.action) != Some(AutomodAction::Remove) {
+      LiveNotification::Comment {
+        creator_id: local_user_view.person.id,
+        community_id,
+        post_id,
+        comment_id: inserted_comment_id,
+      }
+      .send(context.pool())
+      .await;
+    }
+
     // You like your own comment by default
     let like_form = CommentLikeForm {
       comment_id: inserted_comment.id,
@@ -4,7 +4,7 @@ use lemmy_api_common::{
   build_response::build_comment_response,
   comment::{CommentResponse, GetComment},
   context::LemmyContext,
-  utils::{check_private_instance, local_user_view_from_jwt_opt},
+  utils::{check_community_visible, check_private_instance, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::source::local_site::LocalSite;
 use lemmy_utils::error::LemmyError;
@@ -21,6 +21,14 @@ impl PerformCrud for GetComment {
 
     check_private_instance(&local_user_view, &local_site)?;
 
-    build_comment_response(context, data.id, local_user_view, None, vec![]).await
+    let res =
+      build_comment_response(context, data.id, local_user_view.clone(), None, vec![]).await?;
+    check_community_visible(
+      &res.comment_view.community,
+      local_user_view.as_ref(),
+      context.pool(),
+    )
+    .await?;
+    Ok(res)
   }
 }
@@ -1,6 +1,7 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
+  automod::{apply_comment_automod, check_automod_rules, AutomodContent},
   build_response::{build_comment_response, send_local_notifs},
   comment::{CommentResponse, EditComment},
   context::LemmyContext,
@@ -10,6 +11,7 @@ use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
     comment::{Comment, CommentUpdateForm},
+    edit_history::CommentEditHistory,
     local_site::LocalSite,
   },
   traits::Crud,
@@ -66,6 +68,23 @@ impl PerformCrud for EditComment {
 
     is_valid_body_field(&content_slurs_removed, false)?;
 
+    // The automod rules are checked again against the edited comment
+    let automod_content = AutomodContent {
+      body: Some(
+        content_slurs_removed
+          .as_deref()
+          .unwrap_or(&orig_comment.comment.content),
+      ),
+      ..Default::default()
+    };
+    let automod_rule = check_automod_rules(
+      context.pool(),
+      orig_comment.community.id,
+      &local_user_view.person,
+      &automod_content,
+    )
+    .await?;
+
     let comment_id = data.comment_id;
     let form = CommentUpdateForm::builder()
       .content(content_slurs_removed)
@@ -75,6 +94,10 @@ impl PerformCrud for EditComment {
     let updated_comment = Comment::update(context.pool(), comment_id, &form)
       .await
       .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_comment"))?;
+    CommentEditHistory::record(context.pool(), &orig_comment.comment, &updated_comment).await?;
+    if let Some(rule) = &automod_rule {
+      apply_comment_automod(context.pool(), rule, &updated_comment).await;
+    }
 
     // Do the mentions / recipients
     let updated_comment_content = updated_comment.content.clone();
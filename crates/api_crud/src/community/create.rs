@@ -97,6 +97,8 @@ impl PerformCrud for CreateCommunity {
       .inbox_url(Some(generate_inbox_url(&community_actor_id)?))
       .shared_inbox_url(Some(generate_shared_inbox_url(&community_actor_id)?))
       .posting_restricted_to_mods(data.posting_restricted_to_mods)
+      .visibility(data.visibility)
+      .edit_history_public(data.edit_history_public)
       .instance_id(site_view.site.instance_id)
       .build();
 
@@ -3,6 +3,7 @@ use actix_web::web::Data;
 use lemmy_api_common::{
   community::{ListCommunities, ListCommunitiesResponse},
   context::LemmyContext,
+  image_proxy::proxy_image_links,
   utils::{check_private_instance, is_admin, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::source::local_site::LocalSite;
@@ -31,7 +32,7 @@ impl PerformCrud for ListCommunities {
     let page = data.page;
     let limit = data.limit;
     let local_user = local_user_view.map(|l| l.local_user);
-    let communities = CommunityQuery::builder()
+    let mut communities = CommunityQuery::builder()
       .pool(context.pool())
       .listing_type(listing_type)
       .show_nsfw(show_nsfw)
@@ -43,6 +44,7 @@ impl PerformCrud for ListCommunities {
       .build()
       .list()
       .await?;
+    proxy_image_links(&mut communities, context);
 
     // Return the jwt
     Ok(ListCommunitiesResponse { communities })
@@ -69,6 +69,8 @@ impl PerformCrud for EditCommunity {
       .banner(banner)
       .nsfw(data.nsfw)
       .posting_restricted_to_mods(data.posting_restricted_to_mods)
+      .visibility(data.visibility)
+      .edit_history_public(data.edit_history_public)
       .updated(Some(Some(naive_now())))
       .build();
 
@@ -0,0 +1,73 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{CommunityPostTagResponse, CreateCommunityPostTag},
+  context::LemmyContext,
+  utils::{
+    generate_post_tag_url,
+    is_mod_or_admin,
+    local_site_to_slur_regex,
+    local_user_view_from_jwt,
+  },
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    community_post_tag::{
+      CommunityPostTag,
+      CommunityPostTagInsertForm,
+      CommunityPostTagUpdateForm,
+    },
+    local_site::LocalSite,
+  },
+  traits::Crud,
+};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{slurs::check_slurs, validation::is_valid_post_tag_name},
+};
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for CreateCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<CommunityPostTagResponse, LemmyError> {
+    let data: &CreateCommunityPostTag = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    let local_site = LocalSite::read(context.pool()).await?;
+
+    let community_id = data.community_id;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, community_id).await?;
+    // Tags are defined by the instance of the community
+    let community = Community::read(context.pool(), community_id).await?;
+    if !community.local {
+      return Err(LemmyError::from_message("community_not_local"));
+    }
+
+    let name = data.name.trim().to_string();
+    is_valid_post_tag_name(&name)?;
+    check_slurs(&name, &local_site_to_slur_regex(&local_site))?;
+
+    let form = CommunityPostTagInsertForm::builder()
+      .community_id(community_id)
+      .name(name)
+      .build();
+    let inserted_tag = CommunityPostTag::create(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "post_tag_already_exists"))?;
+
+    let ap_id = generate_post_tag_url(&community.actor_id, inserted_tag.id)?;
+    let form = CommunityPostTagUpdateForm::builder()
+      .ap_id(Some(ap_id))
+      .build();
+    let tag = CommunityPostTag::update(context.pool(), inserted_tag.id, &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_post_tag"))?;
+
+    Ok(CommunityPostTagResponse { tag })
+  }
+}
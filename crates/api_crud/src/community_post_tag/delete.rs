@@ -0,0 +1,32 @@
+use crate::PerformCrud;
+use actix_web::web::Data;
+use lemmy_api_common::{
+  community::{CommunityPostTagResponse, DeleteCommunityPostTag},
+  context::LemmyContext,
+  utils::{is_mod_or_admin, local_user_view_from_jwt},
+};
+use lemmy_db_schema::{source::community_post_tag::CommunityPostTag, traits::Crud};
+use lemmy_utils::error::LemmyError;
+
+#[async_trait::async_trait(?Send)]
+impl PerformCrud for DeleteCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+
+  #[tracing::instrument(skip(self, context))]
+  async fn perform(
+    &self,
+    context: &Data<LemmyContext>,
+  ) -> Result<CommunityPostTagResponse, LemmyError> {
+    let data: &DeleteCommunityPostTag = self;
+    let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+
+    let tag = CommunityPostTag::read(context.pool(), data.tag_id)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_post_tag"))?;
+    is_mod_or_admin(context.pool(), local_user_view.person.id, tag.community_id).await?;
+
+    CommunityPostTag::delete(context.pool(), tag.id).await?;
+
+    Ok(CommunityPostTagResponse { tag })
+  }
+}
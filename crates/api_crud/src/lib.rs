@@ -2,8 +2,10 @@ use actix_web::web::Data;
 use lemmy_api_common::context::LemmyContext;
 use lemmy_utils::error::LemmyError;
 
+mod automod_rule;
 mod comment;
 mod community;
+mod community_post_tag;
 mod custom_emoji;
 mod post;
 mod private_message;
@@ -1,13 +1,20 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
+  automod::{apply_post_automod, check_automod_rules, AutomodContent},
   build_response::build_post_response,
   context::LemmyContext,
+  live_notification::LiveNotification,
   post::{CreatePost, PostResponse},
   request::fetch_site_data,
   utils::{
     check_community_ban,
     check_community_deleted_or_removed,
+    check_community_visible,
+    check_post_poll,
+    check_post_tags,
+    check_scheduled_publish_time,
+    check_user_rate_limit,
     generate_local_apub_endpoint,
     honeypot_check,
     local_site_to_slur_regex,
@@ -21,10 +28,13 @@ use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
     community::Community,
+    community_post_tag::PostTag,
     local_site::LocalSite,
     post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
+    post_poll::{PostPoll, PostPollForm, PostPollOption, PostPollOptionForm},
   },
   traits::{Crud, Likeable},
+  AutomodAction,
 };
 use lemmy_db_views_actor::structs::CommunityView;
 use lemmy_utils::{
@@ -46,6 +56,7 @@ impl PerformCrud for CreatePost {
   async fn perform(&self, context: &Data<LemmyContext>) -> Result<PostResponse, LemmyError> {
     let data: &CreatePost = self;
     let local_user_view = local_user_view_from_jwt(&data.auth, context).await?;
+    check_user_rate_limit(context.settings_updated_channel().post(), &local_user_view)?;
     let local_site = LocalSite::read(context.pool()).await?;
 
     let slur_regex = local_site_to_slur_regex(&local_site);
@@ -64,6 +75,7 @@ impl PerformCrud for CreatePost {
 
     let community_id = data.community_id;
     let community = Community::read(context.pool(), community_id).await?;
+    check_community_visible(&community, Some(&local_user_view), context.pool()).await?;
     if community.posting_restricted_to_mods {
       let community_id = data.community_id;
       let is_mod = CommunityView::is_mod_or_admin(
@@ -76,6 +88,18 @@ impl PerformCrud for CreatePost {
         return Err(LemmyError::from_message("only_mods_can_post_in_community"));
       }
     }
+    let automod_content = AutomodContent {
+      title: Some(&data.name),
+      url: data_url,
+      body: data.body.as_deref(),
+    };
+    let automod_rule = check_automod_rules(
+      context.pool(),
+      community_id,
+      &local_user_view.person,
+      &automod_content,
+    )
+    .await?;
 
     // Fetch post links and pictrs cached image
     let (metadata_res, thumbnail_url) =
@@ -93,6 +117,12 @@ impl PerformCrud for CreatePost {
     CommunityLanguage::is_allowed_community_language(context.pool(), language_id, community_id)
       .await?;
 
+    let scheduled_publish_time = check_scheduled_publish_time(data.scheduled_publish_time)?;
+    let poll_end_time = data.poll.as_ref().map(check_post_poll).transpose()?;
+    if let Some(tag_ids) = &data.tag_ids {
+      check_post_tags(tag_ids, community_id, context.pool()).await?;
+    }
+
     let post_form = PostInsertForm::builder()
       .name(data.name.trim().to_owned())
       .url(url)
@@ -105,6 +135,7 @@ impl PerformCrud for CreatePost {
       .embed_video_url(embed_video_url)
       .language_id(language_id)
       .thumbnail_url(thumbnail_url)
+      .scheduled_publish_time(scheduled_publish_time)
       .build();
 
     let inserted_post = Post::create(context.pool(), &post_form)
@@ -126,6 +157,40 @@ impl PerformCrud for CreatePost {
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_post"))?;
 
+    if let Some(poll) = &data.poll {
+      let poll_form = PostPollForm::builder()
+        .post_id(inserted_post_id)
+        .multiple_choice(poll.multiple_choice)
+        .end_time(poll_end_time.flatten())
+        .build();
+      let inserted_poll = PostPoll::upsert(context.pool(), &poll_form)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_poll"))?;
+      let option_forms = poll
+        .options
+        .iter()
+        .map(|name| {
+          PostPollOptionForm::builder()
+            .poll_id(inserted_poll.id)
+            .name(name.trim().to_owned())
+            .build()
+        })
+        .collect::<Vec<_>>();
+      PostPollOption::upsert(context.pool(), &option_forms)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_poll"))?;
+    }
+
+    if let Some(tag_ids) = data.tag_ids.clone() {
+      PostTag::set(context.pool(), inserted_post_id, tag_ids)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_post"))?;
+    }
+
+    if let Some(rule) = &automod_rule {
+      apply_post_automod(context.pool(), rule, &updated_post).await;
+    }
+
     // They like their own post by default
     let person_id = local_user_view.person.id;
     let post_id = inserted_post.id;
@@ -142,7 +207,24 @@ impl PerformCrud for CreatePost {
     // Mark the post as read
     mark_post_as_read(person_id, post_id, context.pool()).await?;
 
-    if let Some(url) = &updated_post.url {
+    // Scheduled posts are announced once they are published, removed ones not at all
+    let removed = automod_rule.as_ref().map(|r| r.action) == Some(AutomodAction::Remove);
+    if updated_post.scheduled_publish_time.is_none() && !removed {
+      LiveNotification::Post {
+        creator_id: person_id,
+        community_id,
+        post_id,
+      }
+      .send(context.pool())
+      .await;
+    }
+
+    // Scheduled posts aren't visible yet, so the link target can't verify the webmention
+    let webmention_url = updated_post
+      .url
+      .as_ref()
+      .filter(|_| updated_post.scheduled_publish_time.is_none());
+    if let Some(url) = webmention_url {
       let mut webmention =
         Webmention::new::<Url>(updated_post.ap_id.clone().into(), url.clone().into())?;
       webmention.set_checked(true);
@@ -4,7 +4,12 @@ use lemmy_api_common::{
   build_response::build_post_response,
   context::LemmyContext,
   post::{DeletePost, PostResponse},
-  utils::{check_community_ban, check_community_deleted_or_removed, local_user_view_from_jwt},
+  utils::{
+    check_community_ban,
+    check_community_deleted_or_removed,
+    check_post_published,
+    local_user_view_from_jwt,
+  },
 };
 use lemmy_db_schema::{
   source::post::{Post, PostUpdateForm},
@@ -23,6 +28,7 @@ impl PerformCrud for DeletePost {
 
     let post_id = data.post_id;
     let orig_post = Post::read(context.pool(), post_id).await?;
+    check_post_published(&orig_post)?;
 
     // Dont delete it if its already been deleted.
     if orig_post.deleted == data.deleted {
@@ -2,12 +2,16 @@ use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::proxy_image_links,
   post::{GetPost, GetPostResponse},
   utils::{
+    check_community_visible,
     check_private_instance,
     is_mod_or_admin_opt,
     local_user_view_from_jwt_opt,
     mark_post_as_read,
+    markdown_context,
+    render_posts_html,
   },
 };
 use lemmy_db_schema::{
@@ -15,7 +19,10 @@ use lemmy_db_schema::{
   source::{comment::Comment, local_site::LocalSite, post::Post},
   traits::Crud,
 };
-use lemmy_db_views::{post_view::PostQuery, structs::PostView};
+use lemmy_db_views::{
+  post_view::PostQuery,
+  structs::{PostPollView, PostView},
+};
 use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView};
 use lemmy_utils::error::LemmyError;
 
@@ -52,9 +59,15 @@ impl PerformCrud for GetPost {
         .await
         .is_ok();
 
-    let post_view = PostView::read(context.pool(), post_id, person_id, Some(is_mod_or_admin))
+    let mut post_view = PostView::read(context.pool(), post_id, person_id, Some(is_mod_or_admin))
       .await
       .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_post"))?;
+    check_community_visible(
+      &post_view.community,
+      local_user_view.as_ref(),
+      context.pool(),
+    )
+    .await?;
 
     // Mark the post as read
     let post_id = post_view.post.id;
@@ -63,7 +76,7 @@ impl PerformCrud for GetPost {
     }
 
     // Necessary for the sidebar subscribed
-    let community_view = CommunityView::read(
+    let mut community_view = CommunityView::read(
       context.pool(),
       community_id,
       person_id,
@@ -90,7 +103,7 @@ impl PerformCrud for GetPost {
     let moderators = CommunityModeratorView::for_community(context.pool(), community_id).await?;
 
     // Fetch the cross_posts
-    let cross_posts = if let Some(url) = &post_view.post.url {
+    let mut cross_posts = if let Some(url) = &post_view.post.url {
       let mut x_posts = PostQuery::builder()
         .pool(context.pool())
         .url_search(Some(url.inner().as_str().into()))
@@ -105,12 +118,25 @@ impl PerformCrud for GetPost {
       Vec::new()
     };
 
+    let poll = PostPollView::read(context.pool(), post_id, person_id).await?;
+
+    proxy_image_links(&mut post_view, context);
+    proxy_image_links(&mut community_view, context);
+    proxy_image_links(&mut cross_posts, context);
+    if data.render_html.unwrap_or(false) {
+      render_posts_html(
+        std::slice::from_mut(&mut post_view),
+        &markdown_context(context, &local_site).await?,
+      );
+    }
+
     // Return the jwt
     Ok(GetPostResponse {
       post_view,
       community_view,
       moderators,
       cross_posts,
+      poll,
     })
   }
 }
@@ -4,7 +4,7 @@ use lemmy_api_common::{
   build_response::build_post_response,
   context::LemmyContext,
   post::{PostResponse, RemovePost},
-  utils::{check_community_ban, is_mod_or_admin, local_user_view_from_jwt},
+  utils::{check_community_ban, check_post_published, is_mod_or_admin, local_user_view_from_jwt},
 };
 use lemmy_db_schema::{
   source::{
@@ -26,6 +26,7 @@ impl PerformCrud for RemovePost {
 
     let post_id = data.post_id;
     let orig_post = Post::read(context.pool(), post_id).await?;
+    check_post_published(&orig_post)?;
 
     check_community_ban(
       local_user_view.person.id,
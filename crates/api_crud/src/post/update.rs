@@ -1,15 +1,24 @@
 use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
+  automod::{apply_post_automod, check_automod_rules, AutomodContent},
   build_response::build_post_response,
   context::LemmyContext,
   post::{EditPost, PostResponse},
   request::fetch_site_data,
-  utils::{check_community_ban, local_site_to_slur_regex, local_user_view_from_jwt},
+  utils::{
+    check_community_ban,
+    check_post_tags,
+    check_scheduled_publish_time,
+    local_site_to_slur_regex,
+    local_user_view_from_jwt,
+  },
 };
 use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
+    community_post_tag::PostTag,
+    edit_history::PostEditHistory,
     local_site::LocalSite,
     post::{Post, PostUpdateForm},
   },
@@ -74,6 +83,12 @@ impl PerformCrud for EditPost {
       .map(|u| (Some(u.title), Some(u.description), Some(u.embed_video_url)))
       .unwrap_or_default();
 
+    // Only posts which are still waiting to be published can be rescheduled
+    let scheduled_publish_time = check_scheduled_publish_time(data.scheduled_publish_time)?;
+    if scheduled_publish_time.is_some() && orig_post.scheduled_publish_time.is_none() {
+      return Err(LemmyError::from_message("post_already_published"));
+    }
+
     let language_id = self.language_id;
     CommunityLanguage::is_allowed_community_language(
       context.pool(),
@@ -81,6 +96,22 @@ impl PerformCrud for EditPost {
       orig_post.community_id,
     )
     .await?;
+    if let Some(tag_ids) = &data.tag_ids {
+      check_post_tags(tag_ids, orig_post.community_id, context.pool()).await?;
+    }
+    // The automod rules are checked again against the edited post
+    let automod_content = AutomodContent {
+      title: Some(data.name.as_deref().unwrap_or(&orig_post.name)),
+      url: data_url,
+      body: data.body.as_deref().or(orig_post.body.as_deref()),
+    };
+    let automod_rule = check_automod_rules(
+      context.pool(),
+      orig_post.community_id,
+      &local_user_view.person,
+      &automod_content,
+    )
+    .await?;
 
     let post_form = PostUpdateForm::builder()
       .name(data.name.clone())
@@ -92,13 +123,24 @@ impl PerformCrud for EditPost {
       .embed_video_url(embed_video_url)
       .language_id(data.language_id)
       .thumbnail_url(Some(thumbnail_url))
+      .scheduled_publish_time(scheduled_publish_time.map(Some))
       .updated(Some(Some(naive_now())))
       .build();
 
     let post_id = data.post_id;
-    Post::update(context.pool(), post_id, &post_form)
+    let updated_post = Post::update(context.pool(), post_id, &post_form)
       .await
       .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_post"))?;
+    PostEditHistory::record(context.pool(), &orig_post, &updated_post).await?;
+    if let Some(rule) = &automod_rule {
+      apply_post_automod(context.pool(), rule, &updated_post).await;
+    }
+
+    if let Some(tag_ids) = data.tag_ids.clone() {
+      PostTag::set(context.pool(), post_id, tag_ids)
+        .await
+        .map_err(|e| LemmyError::from_error_message(e, "couldnt_create_post"))?;
+    }
 
     build_post_response(
       context,
@@ -2,6 +2,8 @@ use crate::PerformCrud;
 use actix_web::web::Data;
 use lemmy_api_common::{
   context::LemmyContext,
+  live::LiveEvent,
+  live_notification::LiveNotification,
   private_message::{CreatePrivateMessage, PrivateMessageResponse},
   utils::{
     check_person_block,
@@ -10,6 +12,7 @@ use lemmy_api_common::{
     local_site_to_slur_regex,
     local_user_view_from_jwt,
     send_email_to_user,
+    send_push_to_user,
     EndpointType,
   },
 };
@@ -86,6 +89,12 @@ impl PerformCrud for CreatePrivateMessage {
     // Send email to the local recipient, if one exists
     if view.recipient.local {
       let recipient_id = data.recipient_id;
+      LiveNotification::PrivateMessage {
+        recipient_id,
+        private_message_id: inserted_private_message_id,
+      }
+      .send(context.pool())
+      .await;
       let local_recipient = LocalUserView::read_person(context.pool(), recipient_id).await?;
       let lang = get_interface_language(&local_recipient);
       let inbox_link = format!("{}/inbox", context.settings().get_protocol_and_hostname());
@@ -96,6 +105,11 @@ impl PerformCrud for CreatePrivateMessage {
         &lang.notification_private_message_body(inbox_link, &content_slurs_removed, sender_name),
         context.settings(),
       );
+      let event = LiveEvent::PrivateMessage {
+        private_message_id: inserted_private_message_id,
+      };
+      let title = lang.notification_private_message_subject(sender_name);
+      send_push_to_user(&local_recipient, title, event, context).await;
     }
 
     Ok(PrivateMessageResponse {
@@ -114,6 +114,13 @@ impl PerformCrud for CreateSite {
       .comment_per_second(data.rate_limit_comment_per_second)
       .search(data.rate_limit_search)
       .search_per_second(data.rate_limit_search_per_second)
+      .message_per_user(data.rate_limit_message_per_user)
+      .post_per_user(data.rate_limit_post_per_user)
+      .image_per_user(data.rate_limit_image_per_user)
+      .comment_per_user(data.rate_limit_comment_per_user)
+      .search_per_user(data.rate_limit_search_per_user)
+      .import_user_settings(data.rate_limit_import_user_settings)
+      .import_user_settings_per_second(data.rate_limit_import_user_settings_per_second)
       .build();
 
     LocalSiteRateLimit::update(context.pool(), &local_site_rate_limit_form).await?;
@@ -521,6 +528,9 @@ mod tests {
       updated: None,
       registration_mode: site_registration_mode,
       reports_email_admins: false,
+      image_upload_max_bytes: 0,
+      image_upload_allowed_mime_types: vec![],
+      image_upload_quota_bytes: None,
     }
   }
 
@@ -570,6 +580,13 @@ mod tests {
       rate_limit_comment_per_second: None,
       rate_limit_search: None,
       rate_limit_search_per_second: None,
+      rate_limit_message_per_user: None,
+      rate_limit_post_per_user: None,
+      rate_limit_image_per_user: None,
+      rate_limit_comment_per_user: None,
+      rate_limit_search_per_user: None,
+      rate_limit_import_user_settings: None,
+      rate_limit_import_user_settings_per_second: None,
       federation_enabled: site_is_federated,
       federation_debug: None,
       captcha_enabled: None,
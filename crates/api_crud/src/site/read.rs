@@ -4,7 +4,13 @@ use lemmy_api_common::{
   context::LemmyContext,
   sensitive::Sensitive,
   site::{GetSite, GetSiteResponse, MyUserInfo},
-  utils::{check_user_valid, check_validator_time},
+  utils::{
+    check_user_valid,
+    check_validator_time,
+    site_oidc_providers,
+    site_vapid_public_key,
+    validate_login_token,
+  },
 };
 use lemmy_db_schema::{
   newtypes::LocalUserId,
@@ -91,6 +97,8 @@ impl PerformCrud for GetSite {
       discussion_languages,
       taglines,
       custom_emojis,
+      vapid_public_key: site_vapid_public_key(context.settings()),
+      oidc_providers: site_oidc_providers(context.settings()),
     })
   }
 }
@@ -117,6 +125,9 @@ async fn local_user_settings_view_from_jwt_opt(
       .ok()?;
 
       check_validator_time(&local_user_view.local_user.validator_time, &claims).ok()?;
+      validate_login_token(context.pool(), &claims, jwt)
+        .await
+        .ok()?;
 
       Some(local_user_view)
     }
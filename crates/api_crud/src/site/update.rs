@@ -30,6 +30,7 @@ use lemmy_utils::{
     slurs::check_slurs_opt,
     validation::{
       build_and_check_regex,
+      check_mime_type_patterns,
       check_site_visibility_valid,
       is_valid_body_field,
       site_description_length_check,
@@ -94,6 +95,13 @@ impl PerformCrud for EditSite {
       .captcha_enabled(data.captcha_enabled)
       .captcha_difficulty(data.captcha_difficulty.clone())
       .reports_email_admins(data.reports_email_admins)
+      .image_upload_max_bytes(data.image_upload_max_bytes)
+      .image_upload_allowed_mime_types(data.image_upload_allowed_mime_types.clone())
+      .image_upload_quota_bytes(
+        data
+          .image_upload_quota_bytes
+          .map(|quota| (quota > 0).then_some(quota)),
+      )
       .build();
 
     let update_local_site = LocalSite::update(context.pool(), &local_site_form)
@@ -113,6 +121,13 @@ impl PerformCrud for EditSite {
       .comment_per_second(data.rate_limit_comment_per_second)
       .search(data.rate_limit_search)
       .search_per_second(data.rate_limit_search_per_second)
+      .message_per_user(data.rate_limit_message_per_user)
+      .post_per_user(data.rate_limit_post_per_user)
+      .image_per_user(data.rate_limit_image_per_user)
+      .comment_per_user(data.rate_limit_comment_per_user)
+      .search_per_user(data.rate_limit_search_per_user)
+      .import_user_settings(data.rate_limit_import_user_settings)
+      .import_user_settings_per_second(data.rate_limit_import_user_settings_per_second)
       .build();
 
     LocalSiteRateLimit::update(context.pool(), &local_site_rate_limit_form)
@@ -206,6 +221,13 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
   // Ensure that the sidebar has fewer than the max num characters...
   is_valid_body_field(&edit_site.sidebar, false)?;
 
+  if edit_site.image_upload_max_bytes.is_some_and(|max| max <= 0) {
+    return Err(LemmyError::from_message("invalid_image_upload_max_bytes"));
+  }
+  if let Some(mime_types) = &edit_site.image_upload_allowed_mime_types {
+    check_mime_type_patterns(mime_types)?;
+  }
+
   application_question_check(
     &local_site.application_question,
     &edit_site.application_question,
@@ -518,6 +540,9 @@ mod tests {
       updated: None,
       registration_mode: site_registration_mode,
       reports_email_admins: false,
+      image_upload_max_bytes: 0,
+      image_upload_allowed_mime_types: vec![],
+      image_upload_quota_bytes: None,
     }
   }
 
@@ -567,6 +592,13 @@ mod tests {
       rate_limit_comment_per_second: None,
       rate_limit_search: None,
       rate_limit_search_per_second: None,
+      rate_limit_message_per_user: None,
+      rate_limit_post_per_user: None,
+      rate_limit_image_per_user: None,
+      rate_limit_comment_per_user: None,
+      rate_limit_search_per_user: None,
+      rate_limit_import_user_settings: None,
+      rate_limit_import_user_settings_per_second: None,
       federation_enabled: site_is_federated,
       federation_debug: None,
       captcha_enabled: None,
@@ -576,6 +608,9 @@ mod tests {
       taglines: None,
       registration_mode: site_registration_mode,
       reports_email_admins: None,
+      image_upload_max_bytes: None,
+      image_upload_allowed_mime_types: None,
+      image_upload_quota_bytes: None,
       auth: Default::default(),
     }
   }
@@ -1,9 +1,10 @@
 use crate::{
   activities::{
     generate_activity_id,
+    generate_to,
     send_lemmy_activity,
-    verify_is_public,
     verify_person_in_community,
+    verify_visibility,
   },
   activity_lists::AnnouncableActivities,
   insert_activity,
@@ -17,10 +18,11 @@ use crate::{
 };
 use activitypub_federation::{
   config::Data,
-  kinds::{activity::AnnounceType, public},
+  kinds::activity::AnnounceType,
   traits::{ActivityHandler, Actor},
 };
 use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::CommunityVisibility;
 use lemmy_utils::error::LemmyError;
 use serde_json::Value;
 use url::Url;
@@ -58,7 +60,7 @@ impl ActivityHandler for RawAnnouncableActivities {
     activity.receive(data).await?;
 
     // send to community followers
-    if community.local {
+    if community.local && !is_removed_content(self.clone(), data).await? {
       verify_person_in_community(&actor_id, &community, data).await?;
       AnnounceActivity::send(self, &community, data).await?;
     }
@@ -66,6 +68,24 @@ impl ActivityHandler for RawAnnouncableActivities {
   }
 }
 
+/// Posts and comments which were removed when they were received, for example by automod, are
+/// not forwarded to the community followers.
+async fn is_removed_content(
+  activity: RawAnnouncableActivities,
+  data: &Data<LemmyContext>,
+) -> Result<bool, LemmyError> {
+  let removed = match activity.try_into()? {
+    AnnouncableActivities::CreateOrUpdatePost(c) => {
+      c.object.id.dereference_local(data).await?.removed
+    }
+    AnnouncableActivities::CreateOrUpdateComment(c) => {
+      c.object.id.dereference_local(data).await?.removed
+    }
+    _ => false,
+  };
+  Ok(removed)
+}
+
 impl AnnounceActivity {
   pub(crate) fn new(
     object: RawAnnouncableActivities,
@@ -74,9 +94,14 @@ impl AnnounceActivity {
   ) -> Result<AnnounceActivity, LemmyError> {
     Ok(AnnounceActivity {
       actor: community.id().into(),
-      to: vec![public()],
+      to: generate_to(community),
       object: IdOrNestedObject::NestedObject(object),
-      cc: vec![community.followers_url.clone().into()],
+      // Content of non-public communities is already addressed to the followers
+      cc: if community.visibility == CommunityVisibility::Public {
+        vec![community.followers_url.clone().into()]
+      } else {
+        vec![]
+      },
       kind: AnnounceType::Announce,
       id: generate_activity_id(
         &AnnounceType::Announce,
@@ -133,8 +158,9 @@ impl ActivityHandler for AnnounceActivity {
   }
 
   #[tracing::instrument(skip_all)]
-  async fn verify(&self, _context: &Data<Self::DataType>) -> Result<(), LemmyError> {
-    verify_is_public(&self.to, &self.cc)?;
+  async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
+    let community = self.actor.dereference(context).await?;
+    verify_visibility(&self.to, &self.cc, &community)?;
     Ok(())
   }
 
@@ -6,7 +6,7 @@ use crate::{
 };
 use activitypub_federation::{config::Data, traits::Actor};
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::source::person::PersonFollower;
+use lemmy_db_schema::{source::person::PersonFollower, CommunityVisibility};
 use lemmy_utils::error::LemmyError;
 use url::Url;
 
@@ -23,6 +23,7 @@ pub mod update;
 /// Activities are sent to the community itself if it lives on another instance. If the community
 /// is local, the activity is directly wrapped into Announce and sent to community followers.
 /// Activities are also sent to those who follow the actor (with exception of moderation activities).
+/// Nothing is sent for local-only communities, and only public communities send to user followers.
 ///
 /// * `activity` - The activity which is being sent
 /// * `actor` - The user who is sending the activity
@@ -38,11 +39,15 @@ pub(crate) async fn send_activity_in_community(
   is_mod_action: bool,
   context: &Data<LemmyContext>,
 ) -> Result<(), LemmyError> {
+  if community.visibility == CommunityVisibility::LocalOnly {
+    return Ok(());
+  }
+
   // send to any users which are mentioned or affected directly
   let mut inboxes = extra_inboxes;
 
   // send to user followers
-  if !is_mod_action {
+  if !is_mod_action && community.visibility == CommunityVisibility::Public {
     inboxes.extend(
       &mut PersonFollower::list_followers(context.pool(), actor.id)
         .await?
@@ -13,14 +13,17 @@ use activitypub_federation::{
   traits::{ActivityHandler, Actor},
 };
 use lemmy_api_common::{
-  comment::{CommentReportResponse, CreateCommentReport},
+  comment::{CommentReportResponse, CreateCommentReport, ResolveCommentReport},
   context::LemmyContext,
-  post::{CreatePostReport, PostReportResponse},
+  live_notification::LiveNotification,
+  post::{CreatePostReport, PostReportResponse, ResolvePostReport},
   utils::local_user_view_from_jwt,
 };
 use lemmy_db_schema::{
   source::{
     comment_report::{CommentReport, CommentReportForm},
+    community::Community,
+    person::Person,
     post_report::{PostReport, PostReportForm},
   },
   traits::Reportable,
@@ -70,6 +73,63 @@ impl SendActivity for CreateCommentReport {
   }
 }
 
+#[async_trait::async_trait]
+impl SendActivity for ResolvePostReport {
+  type Response = PostReportResponse;
+
+  async fn send_activity(
+    request: &Self,
+    response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let view = &response.post_report_view;
+    if !request.resolved || !needs_forward(&view.post_creator, &view.creator, &view.community) {
+      return Ok(());
+    }
+    Report::forward(
+      ObjectId::from(view.post.ap_id.clone()),
+      &view.creator.clone().into(),
+      ObjectId::from(view.community.actor_id.clone()),
+      view.post_report.reason.clone(),
+      view.post_creator.clone().into(),
+      context,
+    )
+    .await
+  }
+}
+
+#[async_trait::async_trait]
+impl SendActivity for ResolveCommentReport {
+  type Response = CommentReportResponse;
+
+  async fn send_activity(
+    request: &Self,
+    response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let view = &response.comment_report_view;
+    if !request.resolved || !needs_forward(&view.comment_creator, &view.creator, &view.community) {
+      return Ok(());
+    }
+    Report::forward(
+      ObjectId::from(view.comment.ap_id.clone()),
+      &view.creator.clone().into(),
+      ObjectId::from(view.community.actor_id.clone()),
+      view.comment_report.reason.clone(),
+      view.comment_creator.clone().into(),
+      context,
+    )
+    .await
+  }
+}
+
+/// Resolved reports are forwarded to the instance of the reported user, unless it is this
+/// instance, or it already got the report because it hosts the community. The report is sent in the
+/// name of the reporter, so reports of remote users can't be forwarded.
+fn needs_forward(content_creator: &Person, reporter: &Person, community: &Community) -> bool {
+  !content_creator.local && reporter.local && content_creator.instance_id != community.instance_id
+}
+
 impl Report {
   #[tracing::instrument(skip_all)]
   async fn send(
@@ -80,6 +140,34 @@ impl Report {
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
     let community = community_id.dereference_local(context).await?;
+    let inbox = vec![community.shared_inbox_or_inbox()];
+    Report::send_to(object_id, actor, &community, reason, inbox, context).await
+  }
+
+  /// Sends a report which was resolved by a local moderator to the instance of the reported user,
+  /// so that its admins can act on the user. The actor is the user who made the report.
+  #[tracing::instrument(skip_all)]
+  async fn forward(
+    object_id: ObjectId<PostOrComment>,
+    actor: &ApubPerson,
+    community_id: ObjectId<ApubCommunity>,
+    reason: String,
+    content_creator: ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let community = community_id.dereference_local(context).await?;
+    let inbox = vec![content_creator.shared_inbox_or_inbox()];
+    Report::send_to(object_id, actor, &community, reason, inbox, context).await
+  }
+
+  async fn send_to(
+    object_id: ObjectId<PostOrComment>,
+    actor: &ApubPerson,
+    community: &ApubCommunity,
+    reason: String,
+    inbox: Vec<Url>,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
     let kind = FlagType::Flag;
     let id = generate_activity_id(
       kind.clone(),
@@ -95,7 +183,6 @@ impl Report {
       audience: Some(community.id().into()),
     };
 
-    let inbox = vec![community.shared_inbox_or_inbox()];
     send_lemmy_activity(context, report, actor, inbox, false).await
   }
 }
@@ -124,6 +211,7 @@ impl ActivityHandler for Report {
   async fn receive(self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
     insert_activity(&self.id, &self, false, true, context).await?;
     let actor = self.actor.dereference(context).await?;
+    let community = self.community(context).await?;
     match self.object.dereference(context).await? {
       PostOrComment::Post(post) => {
         let report_form = PostReportForm {
@@ -146,6 +234,11 @@ impl ActivityHandler for Report {
         CommentReport::report(context.pool(), &report_form).await?;
       }
     };
+    LiveNotification::Report {
+      community_id: Some(community.id),
+    }
+    .send(context.pool())
+    .await;
     Ok(())
   }
 }
@@ -3,8 +3,9 @@ use crate::{
     check_community_deleted_or_removed,
     community::send_activity_in_community,
     generate_activity_id,
-    verify_is_public,
+    generate_to,
     verify_person_in_community,
+    verify_visibility,
   },
   activity_lists::AnnouncableActivities,
   insert_activity,
@@ -19,15 +20,16 @@ use crate::{
 use activitypub_federation::{
   config::Data,
   fetch::object_id::ObjectId,
-  kinds::public,
   protocol::verification::verify_domains_match,
   traits::{ActivityHandler, Actor, Object},
 };
 use lemmy_api_common::{
+  automod::{apply_comment_automod, check_automod_rules, AutomodContent},
   build_response::send_local_notifs,
   comment::{CommentResponse, CreateComment, EditComment},
   context::LemmyContext,
-  utils::{check_post_deleted_or_removed, is_mod_or_admin},
+  live_notification::LiveNotification,
+  utils::{check_post_deleted_or_removed, check_post_published, is_mod_or_admin},
 };
 use lemmy_db_schema::{
   aggregates::structs::CommentAggregates,
@@ -35,10 +37,12 @@ use lemmy_db_schema::{
   source::{
     comment::{Comment, CommentLike, CommentLikeForm},
     community::Community,
+    edit_history::CommentEditHistory,
     person::Person,
     post::Post,
   },
   traits::{Crud, Likeable},
+  AutomodAction,
 };
 use lemmy_utils::{error::LemmyError, utils::mention::scrape_text_for_mentions};
 use url::Url;
@@ -52,6 +56,10 @@ impl SendActivity for CreateComment {
     response: &Self::Response,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
+    // Comments which were removed by automod are not federated
+    if response.comment_view.comment.removed {
+      return Ok(());
+    }
     CreateOrUpdateNote::send(
       &response.comment_view.comment,
       response.comment_view.creator.id,
@@ -71,6 +79,10 @@ impl SendActivity for EditComment {
     response: &Self::Response,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
+    // Edits which were removed by automod are not federated
+    if response.comment_view.comment.removed {
+      return Ok(());
+    }
     CreateOrUpdateNote::send(
       &response.comment_view.comment,
       response.comment_view.creator.id,
@@ -104,7 +116,7 @@ impl CreateOrUpdateNote {
 
     let create_or_update = CreateOrUpdateNote {
       actor: person.id().into(),
-      to: vec![public()],
+      to: generate_to(&community),
       cc: note.cc.clone(),
       tag: note.tag.clone(),
       object: note,
@@ -152,14 +164,15 @@ impl ActivityHandler for CreateOrUpdateNote {
 
   #[tracing::instrument(skip_all)]
   async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
-    verify_is_public(&self.to, &self.cc)?;
     let post = self.object.get_parents(context).await?.0;
     let community = self.community(context).await?;
+    verify_visibility(&self.to, &self.cc, &community)?;
 
     verify_person_in_community(&self.actor, &community, context).await?;
     verify_domains_match(self.actor.inner(), self.object.id.inner())?;
     check_community_deleted_or_removed(&community)?;
     check_post_deleted_or_removed(&post)?;
+    check_post_published(&post)?;
 
     ApubComment::verify(&self.object, self.actor.inner(), context).await?;
     Ok(())
@@ -172,7 +185,7 @@ impl ActivityHandler for CreateOrUpdateNote {
     // send the activity, not the comment author.
     let existing_comment = self.object.id.dereference_local(context).await.ok();
     if let (Some(distinguished), Some(existing_comment)) =
-      (self.object.distinguished, existing_comment)
+      (self.object.distinguished, existing_comment.as_ref())
     {
       if distinguished != existing_comment.distinguished {
         let creator = self.actor.dereference(context).await?;
@@ -181,7 +194,28 @@ impl ActivityHandler for CreateOrUpdateNote {
       }
     }
 
+    // Automod rules are checked by the instance of the community, for new and edited comments. A
+    // repeated Create of a known comment is not checked again.
+    let is_new = existing_comment.is_none();
+    let community = self.community(context).await?;
+    let automod_rule = if community.local && (is_new || self.kind == CreateOrUpdateType::Update) {
+      let creator = self.actor.dereference(context).await?;
+      let content = AutomodContent {
+        body: Some(&self.object.content),
+        ..Default::default()
+      };
+      check_automod_rules(context.pool(), community.id, &creator, &content).await?
+    } else {
+      None
+    };
     let comment = ApubComment::from_json(self.object, context).await?;
+    // Keep the previous version of edited comments
+    if let Some(existing_comment) = existing_comment {
+      CommentEditHistory::record(context.pool(), &existing_comment, &comment).await?;
+    }
+    if let Some(rule) = &automod_rule {
+      apply_comment_automod(context.pool(), rule, &comment).await;
+    }
 
     // author likes their own comment by default
     let like_form = CommentLikeForm {
@@ -207,6 +241,20 @@ impl ActivityHandler for CreateOrUpdateNote {
     // TODO: for compatibility with other projects, it would be much better to read this from cc or tags
     let mentions = scrape_text_for_mentions(&comment.content);
     send_local_notifs(mentions, &comment.0, &actor, &post, do_send_email, context).await?;
+
+    // Removed comments are only visible to mods
+    let removed =
+      comment.removed || automod_rule.as_ref().map(|r| r.action) == Some(AutomodAction::Remove);
+    if is_new && !removed {
+      LiveNotification::Comment {
+        creator_id: comment.creator_id,
+        community_id: post.community_id,
+        post_id,
+        comment_id: comment.id,
+      }
+      .send(context.pool())
+      .await;
+    }
     Ok(())
   }
 }
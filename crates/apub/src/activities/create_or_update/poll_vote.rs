@@ -0,0 +1,191 @@
+use crate::{
+  activities::{
+    generate_activity_id,
+    send_lemmy_activity,
+    verify_person,
+    verify_person_in_community,
+  },
+  insert_activity,
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::{
+    activities::{
+      create_or_update::{page::CreateOrUpdatePage, poll_vote::CreatePollVote},
+      CreateOrUpdateType,
+    },
+    objects::poll_vote::PollVote,
+  },
+  SendActivity,
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::activity::CreateType,
+  protocol::verification::{verify_domains_match, verify_urls_match},
+  traits::{ActivityHandler, Actor},
+};
+use lemmy_api_common::{
+  context::LemmyContext,
+  post::{PostPollResponse, VotePostPoll},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    person::Person,
+    post::Post,
+    post_poll::{PostPoll, PostPollOption, PostPollVote, PostPollVoteForm},
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_utils::error::LemmyError;
+use tracing::warn;
+use url::Url;
+
+#[async_trait::async_trait]
+impl SendActivity for VotePostPoll {
+  type Response = PostPollResponse;
+
+  async fn send_activity(
+    request: &Self,
+    response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let post = Post::read(context.pool(), request.post_id).await?;
+    // For local polls, other instances only need the new vote counts
+    if post.local {
+      PostPoll::queue_update(context.pool(), response.poll.poll.id).await?;
+      return Ok(());
+    }
+
+    let local_user_view = local_user_view_from_jwt(&request.auth, context).await?;
+    let voter: ApubPerson = local_user_view.person.into();
+    let creator: ApubPerson = Person::read(context.pool(), post.creator_id).await?.into();
+    // Like Mastodon, send one activity for each chosen option
+    for option in response
+      .poll
+      .options
+      .iter()
+      .filter(|o| request.option_ids.contains(&o.id))
+    {
+      CreatePollVote::send(&voter, &creator, &post, option, context).await?;
+    }
+    Ok(())
+  }
+}
+
+impl CreatePollVote {
+  #[tracing::instrument(skip_all)]
+  async fn send(
+    voter: &ApubPerson,
+    creator: &ApubPerson,
+    post: &Post,
+    option: &PostPollOption,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let id = generate_activity_id(
+      CreateType::Create,
+      &context.settings().get_protocol_and_hostname(),
+    )?;
+    let object = PollVote {
+      r#type: Default::default(),
+      id: Url::parse(&format!("{}#votes/{}", voter.actor_id, option.id.0))?,
+      attributed_to: voter.id().into(),
+      to: vec![creator.id()],
+      name: option.name.clone(),
+      in_reply_to: post.ap_id.clone().into(),
+    };
+    let create = CreatePollVote {
+      id,
+      actor: voter.id().into(),
+      to: [creator.id().into()],
+      object,
+      kind: CreateType::Create,
+    };
+    let inbox = vec![creator.shared_inbox_or_inbox()];
+    // Votes in polls are private
+    send_lemmy_activity(context, create, voter, inbox, true).await
+  }
+}
+
+/// How long votes are collected before the new counts of a poll are sent, in seconds
+const POLL_UPDATE_DELAY: i64 = 30;
+
+/// Other instances learn the vote counts of a local poll through an Update of the post. Instead of
+/// sending one for every vote, votes queue an update in the database, and all votes within
+/// [`POLL_UPDATE_DELAY`] are sent with one Update. Called periodically by the scheduled tasks.
+pub async fn send_poll_updates(context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+  let queued_before = naive_now() - chrono::Duration::seconds(POLL_UPDATE_DELAY);
+  for post_id in PostPoll::take_queued_updates(context.pool(), queued_before).await? {
+    let res = match Post::read(context.pool(), post_id).await {
+      Ok(post) => {
+        CreateOrUpdatePage::send(&post, post.creator_id, CreateOrUpdateType::Update, context).await
+      }
+      Err(e) => Err(e.into()),
+    };
+    if let Err(e) = res {
+      warn!("Failed to send poll update for post {}: {e}", post_id.0);
+    }
+  }
+  Ok(())
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for CreatePollVote {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
+    verify_person(&self.actor, context).await?;
+    verify_domains_match(self.actor.inner(), &self.object.id)?;
+    verify_urls_match(self.actor.inner(), self.object.attributed_to.inner())?;
+    // Votes are only counted by the instance of the poll
+    let post = self.object.in_reply_to.dereference_local(context).await?;
+    if !post.local {
+      return Err(LemmyError::from_message("Poll is not local"));
+    }
+    let community: ApubCommunity = Community::read(context.pool(), post.community_id)
+      .await?
+      .into();
+    verify_person_in_community(&self.actor, &community, context).await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
+    insert_activity(&self.id, &self, false, true, context).await?;
+    let person = self.actor.dereference(context).await?;
+    let post = self.object.in_reply_to.dereference_local(context).await?;
+
+    let poll = PostPoll::read_for_post(context.pool(), post.id)
+      .await?
+      .ok_or_else(|| LemmyError::from_message("post_has_no_poll"))?;
+    if poll.is_closed() {
+      return Err(LemmyError::from_message("poll_closed"));
+    }
+    let option = PostPollOption::list_for_poll(context.pool(), poll.id)
+      .await?
+      .into_iter()
+      .find(|o| o.name == self.object.name)
+      .ok_or_else(|| LemmyError::from_message("invalid_poll_vote"))?;
+    let form = PostPollVoteForm {
+      option_id: option.id,
+      person_id: person.id,
+    };
+    if !PostPollVote::add_vote(context.pool(), poll.id, &form).await? {
+      return Err(LemmyError::from_message("already_voted_in_poll"));
+    }
+
+    // Let other instances know about the new vote count
+    PostPoll::queue_update(context.pool(), poll.id).await?;
+    Ok(())
+  }
+}
@@ -3,27 +3,30 @@ use crate::{
     check_community_deleted_or_removed,
     community::send_activity_in_community,
     generate_activity_id,
-    verify_is_public,
+    generate_to,
     verify_mod_action,
     verify_person_in_community,
+    verify_visibility,
   },
   activity_lists::AnnouncableActivities,
   insert_activity,
   objects::{community::ApubCommunity, person::ApubPerson, post::ApubPost},
   protocol::{
     activities::{create_or_update::page::CreateOrUpdatePage, CreateOrUpdateType},
+    objects::page::Attachment,
     InCommunity,
   },
   SendActivity,
 };
 use activitypub_federation::{
   config::Data,
-  kinds::public,
   protocol::verification::{verify_domains_match, verify_urls_match},
   traits::{ActivityHandler, Actor, Object},
 };
 use lemmy_api_common::{
+  automod::{apply_post_automod, check_automod_rules, AutomodContent},
   context::LemmyContext,
+  live_notification::LiveNotification,
   post::{CreatePost, EditPost, PostResponse},
 };
 use lemmy_db_schema::{
@@ -31,10 +34,12 @@ use lemmy_db_schema::{
   newtypes::PersonId,
   source::{
     community::Community,
+    edit_history::PostEditHistory,
     person::Person,
     post::{Post, PostLike, PostLikeForm},
   },
   traits::{Crud, Likeable},
+  AutomodAction,
 };
 use lemmy_utils::error::LemmyError;
 use url::Url;
@@ -48,6 +53,14 @@ impl SendActivity for CreatePost {
     response: &Self::Response,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
+    // Scheduled posts are sent by the scheduled task once they are published
+    if response.post_view.post.scheduled_publish_time.is_some() {
+      return Ok(());
+    }
+    // Posts which were removed by automod are not federated
+    if response.post_view.post.removed {
+      return Ok(());
+    }
     CreateOrUpdatePage::send(
       &response.post_view.post,
       response.post_view.creator.id,
@@ -67,6 +80,14 @@ impl SendActivity for EditPost {
     response: &Self::Response,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
+    // Other instances don't know about the post before it is published
+    if response.post_view.post.scheduled_publish_time.is_some() {
+      return Ok(());
+    }
+    // Edits which were removed by automod are not federated
+    if response.post_view.post.removed {
+      return Ok(());
+    }
     CreateOrUpdatePage::send(
       &response.post_view.post,
       response.post_view.creator.id,
@@ -77,6 +98,14 @@ impl SendActivity for EditPost {
   }
 }
 
+/// Send a scheduled post to the community followers, after it was published.
+pub async fn send_scheduled_post(
+  post: &Post,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  CreateOrUpdatePage::send(post, post.creator_id, CreateOrUpdateType::Create, context).await
+}
+
 impl CreateOrUpdatePage {
   pub(crate) async fn new(
     post: ApubPost,
@@ -91,7 +120,7 @@ impl CreateOrUpdatePage {
     )?;
     Ok(CreateOrUpdatePage {
       actor: actor.id().into(),
-      to: vec![public()],
+      to: generate_to(community),
       object: post.into_json(context).await?,
       cc: vec![community.id()],
       kind,
@@ -144,8 +173,8 @@ impl ActivityHandler for CreateOrUpdatePage {
 
   #[tracing::instrument(skip_all)]
   async fn verify(&self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
-    verify_is_public(&self.to, &self.cc)?;
     let community = self.community(context).await?;
+    verify_visibility(&self.to, &self.cc, &community)?;
     verify_person_in_community(&self.actor, &community, context).await?;
     check_community_deleted_or_removed(&community)?;
 
@@ -179,7 +208,31 @@ impl ActivityHandler for CreateOrUpdatePage {
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
     insert_activity(&self.id, &self, false, false, context).await?;
+    let existing_post = self.object.id.dereference_local(context).await.ok();
+    let is_new = existing_post.is_none();
+    // Automod rules are checked by the instance of the community, for new and edited posts. A
+    // repeated Create of a known post is not checked again.
+    let community = self.community(context).await?;
+    let automod_rule = if community.local && (is_new || self.kind == CreateOrUpdateType::Update) {
+      let creator = self.actor.dereference(context).await?;
+      let url = self.object.attachment.first().cloned().map(Attachment::url);
+      let content = AutomodContent {
+        title: self.object.name.as_deref(),
+        url: url.as_ref(),
+        body: self.object.content.as_deref(),
+      };
+      check_automod_rules(context.pool(), community.id, &creator, &content).await?
+    } else {
+      None
+    };
     let post = ApubPost::from_json(self.object, context).await?;
+    // Keep the previous version of edited posts
+    if let Some(existing_post) = existing_post {
+      PostEditHistory::record(context.pool(), &existing_post, &post).await?;
+    }
+    if let Some(rule) = &automod_rule {
+      apply_post_automod(context.pool(), rule, &post).await;
+    }
 
     // author likes their own post by default
     let like_form = PostLikeForm {
@@ -192,6 +245,18 @@ impl ActivityHandler for CreateOrUpdatePage {
     // Calculate initial hot_rank for post
     PostAggregates::update_hot_rank(context.pool(), post.id).await?;
 
+    let removed =
+      post.removed || automod_rule.as_ref().map(|r| r.action) == Some(AutomodAction::Remove);
+    if is_new && !removed {
+      LiveNotification::Post {
+        creator_id: post.creator_id,
+        community_id: post.community_id,
+        post_id: post.id,
+      }
+      .send(context.pool())
+      .await;
+    }
+
     Ok(())
   }
 }
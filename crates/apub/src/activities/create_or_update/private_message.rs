@@ -15,6 +15,7 @@ use activitypub_federation::{
 };
 use lemmy_api_common::{
   context::LemmyContext,
+  live_notification::LiveNotification,
   private_message::{CreatePrivateMessage, EditPrivateMessage, PrivateMessageResponse},
 };
 use lemmy_db_schema::{
@@ -117,7 +118,15 @@ impl ActivityHandler for CreateOrUpdateChatMessage {
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<Self::DataType>) -> Result<(), LemmyError> {
     insert_activity(&self.id, &self, false, true, context).await?;
-    ApubPrivateMessage::from_json(self.object, context).await?;
+    let private_message = ApubPrivateMessage::from_json(self.object, context).await?;
+    if self.kind == CreateOrUpdateType::Create {
+      LiveNotification::PrivateMessage {
+        recipient_id: private_message.recipient_id,
+        private_message_id: private_message.id,
+      }
+      .send(context.pool())
+      .await;
+    }
     Ok(())
   }
 }
@@ -1,5 +1,6 @@
 use crate::{
   activities::{generate_activity_id, send_lemmy_activity},
+  fetcher::user_or_community::UserOrCommunity,
   insert_activity,
   protocol::activities::following::{accept::AcceptFollow, follow::Follow},
 };
@@ -10,7 +11,10 @@ use activitypub_federation::{
   traits::{ActivityHandler, Actor},
 };
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::{source::community::CommunityFollower, traits::Followable};
+use lemmy_db_schema::{
+  source::{community::CommunityFollower, person::PersonFollower},
+  traits::Followable,
+};
 use lemmy_utils::error::LemmyError;
 use url::Url;
 
@@ -61,12 +65,17 @@ impl ActivityHandler for AcceptFollow {
   #[tracing::instrument(skip_all)]
   async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
     insert_activity(&self.id, &self, false, true, context).await?;
-    let community = self.actor.dereference(context).await?;
+    let actor = self.actor.dereference(context).await?;
     let person = self.object.actor.dereference(context).await?;
     // This will throw an error if no follow was requested
-    let community_id = community.id;
-    let person_id = person.id;
-    CommunityFollower::follow_accepted(context.pool(), community_id, person_id).await?;
+    match actor {
+      UserOrCommunity::User(u) => {
+        PersonFollower::accept_follow(context.pool(), u.id, person.id).await?;
+      }
+      UserOrCommunity::Community(c) => {
+        CommunityFollower::follow_accepted(context.pool(), c.id, person.id).await?;
+      }
+    }
 
     Ok(())
   }
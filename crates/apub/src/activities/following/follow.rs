@@ -32,20 +32,21 @@ use lemmy_db_schema::{
     person::{PersonFollower, PersonFollowerForm},
   },
   traits::{Crud, Followable},
+  CommunityVisibility,
 };
 use lemmy_utils::error::LemmyError;
 use url::Url;
 
 impl Follow {
-  pub(in crate::activities::following) fn new(
+  pub(in crate::activities::following) fn new<T: Actor>(
     actor: &ApubPerson,
-    community: &ApubCommunity,
+    target: &T,
     context: &Data<LemmyContext>,
   ) -> Result<Follow, LemmyError> {
     Ok(Follow {
       actor: actor.id().into(),
-      object: community.id().into(),
-      to: Some([community.id().into()]),
+      object: target.id().into(),
+      to: Some([target.id().into()]),
       kind: FollowType::Follow,
       id: generate_activity_id(
         FollowType::Follow,
@@ -73,6 +74,18 @@ impl Follow {
     let inbox = vec![community.shared_inbox_or_inbox()];
     send_lemmy_activity(context, follow, actor, inbox, true).await
   }
+
+  /// Sends a follow request to a remote person. The pending follow is stored by the API handler.
+  #[tracing::instrument(skip_all)]
+  pub async fn send_to_person(
+    actor: &ApubPerson,
+    person: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let follow = Follow::new(actor, person, context)?;
+    let inbox = vec![person.shared_inbox_or_inbox()];
+    send_lemmy_activity(context, follow, actor, inbox, true).await
+  }
 }
 
 #[async_trait::async_trait]
@@ -94,6 +107,9 @@ impl ActivityHandler for Follow {
     let object = self.object.dereference(context).await?;
     if let UserOrCommunity::Community(c) = object {
       verify_person_in_community(&self.actor, &c, context).await?;
+      if c.visibility == CommunityVisibility::LocalOnly {
+        return Err(LemmyError::from_message("community_is_local_only"));
+      }
     }
     if let Some(to) = &self.to {
       verify_urls_match(to[0].inner(), self.object.inner())?;
@@ -116,12 +132,18 @@ impl ActivityHandler for Follow {
         PersonFollower::follow(context.pool(), &form).await?;
       }
       UserOrCommunity::Community(c) => {
+        // Follows of private communities stay pending until a mod approves them, only then the
+        // accept activity is sent
+        let pending = c.visibility == CommunityVisibility::ApprovalRequired;
         let form = CommunityFollowerForm {
           community_id: c.id,
           person_id: actor.id,
-          pending: false,
+          pending,
         };
         CommunityFollower::follow(context.pool(), &form).await?;
+        if pending {
+          return Ok(());
+        }
       }
     }
 
@@ -140,6 +162,11 @@ impl SendActivity for BlockCommunity {
   ) -> Result<(), LemmyError> {
     let local_user_view = local_user_view_from_jwt(&request.auth, context).await?;
     let community = Community::read(context.pool(), request.community_id).await?;
-    UndoFollow::send(&local_user_view.person.into(), &community.into(), context).await
+    UndoFollow::send(
+      &local_user_view.person.into(),
+      &ApubCommunity::from(community),
+      context,
+    )
+    .await
   }
 }
@@ -1,19 +1,30 @@
 use crate::{
-  objects::community::ApubCommunity,
-  protocol::activities::following::{follow::Follow, undo_follow::UndoFollow},
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::activities::following::{
+    accept::AcceptFollow,
+    follow::Follow,
+    reject::RejectFollow,
+    undo_follow::UndoFollow,
+  },
   SendActivity,
 };
 use activitypub_federation::config::Data;
 use lemmy_api_common::{
-  community::{CommunityResponse, FollowCommunity},
+  community::{ApproveFollow, ApproveFollowResponse, CommunityResponse, FollowCommunity},
   context::LemmyContext,
+  person::{FollowPerson, FollowPersonResponse, UnfollowPerson},
   utils::local_user_view_from_jwt,
 };
-use lemmy_db_schema::{source::community::Community, traits::Crud};
+use lemmy_db_schema::{
+  source::{community::Community, person::Person},
+  traits::Crud,
+};
 use lemmy_utils::error::LemmyError;
 
 pub mod accept;
 pub mod follow;
+pub mod move_person;
+pub mod reject;
 pub mod undo_follow;
 
 #[async_trait::async_trait]
@@ -39,3 +50,74 @@ impl SendActivity for FollowCommunity {
     }
   }
 }
+
+#[async_trait::async_trait]
+impl SendActivity for FollowPerson {
+  type Response = FollowPersonResponse;
+
+  async fn send_activity(
+    request: &Self,
+    _response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&request.auth, context).await?;
+    let person: ApubPerson = Person::read(context.pool(), request.person_id)
+      .await?
+      .into();
+    if person.local {
+      Ok(())
+    } else {
+      Follow::send_to_person(&local_user_view.person.into(), &person, context).await
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl SendActivity for UnfollowPerson {
+  type Response = FollowPersonResponse;
+
+  async fn send_activity(
+    request: &Self,
+    _response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let local_user_view = local_user_view_from_jwt(&request.auth, context).await?;
+    let person: ApubPerson = Person::read(context.pool(), request.person_id)
+      .await?
+      .into();
+    if person.local {
+      Ok(())
+    } else {
+      UndoFollow::send(&local_user_view.person.into(), &person, context).await
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl SendActivity for ApproveFollow {
+  type Response = ApproveFollowResponse;
+
+  async fn send_activity(
+    request: &Self,
+    response: &Self::Response,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let person: ApubPerson = Person::read(context.pool(), request.person_id)
+      .await?
+      .into();
+    if person.local {
+      return Ok(());
+    }
+    let community: ApubCommunity = Community::read(context.pool(), request.community_id)
+      .await?
+      .into();
+    // The received Follow may already be pruned from the activity table, so rebuild it from the
+    // stored actor ids. Remote platforms match it by actor and object.
+    let follow = Follow::new(&person, &community, context)?;
+    if response.approved {
+      AcceptFollow::send(follow, context).await
+    } else {
+      RejectFollow::send(follow, context).await
+    }
+  }
+}
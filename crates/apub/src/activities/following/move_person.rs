@@ -0,0 +1,158 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity, verify_person},
+  insert_activity,
+  objects::{community::ApubCommunity, person::ApubPerson},
+  protocol::{
+    activities::following::{follow::Follow, move_person::MovePerson},
+    objects::person::Person,
+  },
+};
+use activitypub_federation::{
+  config::Data,
+  fetch::fetch_object_http,
+  kinds::activity::MoveType,
+  protocol::verification::{verify_domains_match, verify_urls_match},
+  traits::{ActivityHandler, Actor, Object},
+};
+use itertools::Itertools;
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  source::{
+    community::CommunityFollower,
+    person::{Person as DbPerson, PersonFollower, PersonFollowerForm, PersonUpdateForm},
+  },
+  traits::{Crud, Followable},
+};
+use lemmy_db_views_actor::structs::CommunityFollowerView;
+use lemmy_utils::error::LemmyError;
+use url::Url;
+
+impl MovePerson {
+  /// Tells the followers of the person, and the remote communities which it follows, about the
+  /// move.
+  #[tracing::instrument(skip_all)]
+  pub async fn send(
+    actor: &ApubPerson,
+    target: &ApubPerson,
+    context: &Data<LemmyContext>,
+  ) -> Result<(), LemmyError> {
+    let move_ = MovePerson {
+      actor: actor.id().into(),
+      object: actor.id().into(),
+      target: target.id().into(),
+      kind: MoveType::Move,
+      id: generate_activity_id(
+        MoveType::Move,
+        &context.settings().get_protocol_and_hostname(),
+      )?,
+    };
+
+    let follower_inboxes = PersonFollower::list_followers(context.pool(), actor.id)
+      .await?
+      .into_iter()
+      .filter(|p| !p.local)
+      .map(|p| ApubPerson::from(p).shared_inbox_or_inbox());
+    let community_inboxes = CommunityFollowerView::for_person(context.pool(), actor.id)
+      .await?
+      .into_iter()
+      .filter(|f| !f.community.local)
+      .map(|f| ApubCommunity::from(f.community).shared_inbox_or_inbox());
+    let inboxes = follower_inboxes.chain(community_inboxes).unique().collect();
+    send_lemmy_activity(context, move_, actor, inboxes, true).await
+  }
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for MovePerson {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+    verify_urls_match(self.actor.inner(), self.object.inner())?;
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    verify_person(&self.actor, context).await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<LemmyContext>) -> Result<(), LemmyError> {
+    insert_activity(&self.id, &self, false, true, context).await?;
+    let old = self.object.dereference(context).await?;
+    let target = self.target.dereference(context).await?;
+    let target = refetch_person(target, context).await?;
+    move_person(&old, &target, context).await
+  }
+}
+
+/// Fetches the current version of a remote person. The alias for a move is usually added just
+/// before, so the stored version is likely outdated.
+pub(crate) async fn refetch_person(
+  person: ApubPerson,
+  context: &Data<LemmyContext>,
+) -> Result<ApubPerson, LemmyError> {
+  if person.local {
+    return Ok(person);
+  }
+  let id = person.actor_id.inner().clone();
+  let json: Person = fetch_object_http(&id, context).await?;
+  ApubPerson::verify(&json, &id, context).await?;
+  ApubPerson::from_json(json, context).await
+}
+
+/// Marks the old account as moved, and moves its follows of local communities and its local
+/// followers over to the new account.
+pub(crate) async fn move_person(
+  old: &ApubPerson,
+  target: &ApubPerson,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  if old.actor_id == target.actor_id {
+    return Err(LemmyError::from_message("cant_move_to_same_account"));
+  }
+  // The new account needs to confirm that it belongs to the same person
+  if !target.also_known_as.contains(&old.actor_id) {
+    return Err(LemmyError::from_message("move_target_missing_alias"));
+  }
+
+  let form = PersonUpdateForm::builder()
+    .moved_to(Some(Some(target.actor_id.clone())))
+    .build();
+  DbPerson::update(context.pool(), old.id, &form).await?;
+
+  CommunityFollower::transfer_local_follows(context.pool(), old.id, target.id).await?;
+
+  let local_followers = PersonFollower::list_followers(context.pool(), old.id)
+    .await?
+    .into_iter()
+    .filter(|p| p.local);
+  for follower in local_followers {
+    let follower = ApubPerson::from(follower);
+    // Follows of remote persons stay pending until they are accepted
+    let form = PersonFollowerForm {
+      person_id: target.id,
+      follower_id: follower.id,
+      pending: !target.local,
+    };
+    PersonFollower::follow(context.pool(), &form).await?;
+    if !target.local {
+      Follow::send_to_person(&follower, target, context).await?;
+    }
+
+    let form = PersonFollowerForm {
+      person_id: old.id,
+      follower_id: follower.id,
+      pending: false,
+    };
+    PersonFollower::unfollow(context.pool(), &form).await?;
+  }
+  Ok(())
+}
@@ -2,7 +2,7 @@ use crate::{
   activities::{generate_activity_id, send_lemmy_activity, verify_person},
   fetcher::user_or_community::UserOrCommunity,
   insert_activity,
-  objects::{community::ApubCommunity, person::ApubPerson},
+  objects::person::ApubPerson,
   protocol::activities::following::{follow::Follow, undo_follow::UndoFollow},
 };
 use activitypub_federation::{
@@ -24,15 +24,15 @@ use url::Url;
 
 impl UndoFollow {
   #[tracing::instrument(skip_all)]
-  pub async fn send(
+  pub async fn send<T: Actor>(
     actor: &ApubPerson,
-    community: &ApubCommunity,
+    target: &T,
     context: &Data<LemmyContext>,
   ) -> Result<(), LemmyError> {
-    let object = Follow::new(actor, community, context)?;
+    let object = Follow::new(actor, target, context)?;
     let undo = UndoFollow {
       actor: actor.id().into(),
-      to: Some([community.id().into()]),
+      to: Some([target.id().into()]),
       object,
       kind: UndoType::Undo,
       id: generate_activity_id(
@@ -40,7 +40,7 @@ impl UndoFollow {
         &context.settings().get_protocol_and_hostname(),
       )?,
     };
-    let inbox = vec![community.shared_inbox_or_inbox()];
+    let inbox = vec![target.shared_inbox_or_inbox()];
     send_lemmy_activity(context, undo, actor, inbox, true).await
   }
 }
@@ -1,10 +1,11 @@
 use crate::{
   insert_activity,
   objects::{community::ApubCommunity, person::ApubPerson},
+  send_queue::queue_activity,
+  GetActorType,
   CONTEXT,
 };
 use activitypub_federation::{
-  activity_queue::send_activity,
   config::Data,
   fetch::object_id::ObjectId,
   kinds::public,
@@ -13,7 +14,7 @@ use activitypub_federation::{
 };
 use anyhow::anyhow;
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::{newtypes::CommunityId, source::community::Community};
+use lemmy_db_schema::{newtypes::CommunityId, source::community::Community, CommunityVisibility};
 use lemmy_db_views_actor::structs::{CommunityPersonBanView, CommunityView};
 use lemmy_utils::error::LemmyError;
 use serde::Serialize;
@@ -106,6 +107,37 @@ pub(crate) fn verify_is_public(to: &[Url], cc: &[Url]) -> Result<(), LemmyError>
   Ok(())
 }
 
+/// Posts and comments of public communities are public, those of other communities are only
+/// addressed to the community followers.
+pub(crate) fn generate_to(community: &Community) -> Vec<Url> {
+  if community.visibility == CommunityVisibility::Public {
+    vec![public()]
+  } else {
+    vec![community.followers_url.clone().into()]
+  }
+}
+
+/// Checks the addressing of content in the community, see [generate_to].
+pub(crate) fn verify_visibility(
+  to: &[Url],
+  cc: &[Url],
+  community: &Community,
+) -> Result<(), LemmyError> {
+  match community.visibility {
+    CommunityVisibility::Public => verify_is_public(to, cc),
+    CommunityVisibility::ApprovalRequired => {
+      let followers: Url = community.followers_url.clone().into();
+      if ![to, cc].iter().any(|set| set.contains(&followers)) {
+        return Err(LemmyError::from_message(
+          "Object is not addressed to followers",
+        ));
+      }
+      Ok(())
+    }
+    CommunityVisibility::LocalOnly => Err(LemmyError::from_message("Community is local only")),
+  }
+}
+
 pub(crate) fn verify_community_matches<T>(
   a: &ObjectId<ApubCommunity>,
   b: T,
@@ -155,14 +187,14 @@ async fn send_lemmy_activity<Activity, ActorT>(
 ) -> Result<(), LemmyError>
 where
   Activity: ActivityHandler + Serialize + Send + Sync + Clone,
-  ActorT: Actor,
+  ActorT: Actor + GetActorType,
   Activity: ActivityHandler<Error = LemmyError>,
 {
   info!("Sending activity {}", activity.id().to_string());
   let activity = WithContext::new(activity, CONTEXT.deref().clone());
 
-  insert_activity(activity.id(), &activity, true, sensitive, data).await?;
-  send_activity(activity, actor, inbox, data).await?;
+  let inserted = insert_activity(activity.id(), &activity, true, sensitive, data).await?;
+  queue_activity(&inserted, actor.actor_type(), actor.id(), inbox, data).await?;
 
   Ok(())
 }
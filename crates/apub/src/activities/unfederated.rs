@@ -1,21 +1,35 @@
 use crate::SendActivity;
 use lemmy_api_common::{
   comment::{
+    AssignCommentReport,
     CommentReportResponse,
     CommentResponse,
     DistinguishComment,
+    EscalateCommentReport,
     GetComment,
+    GetCommentHistory,
+    GetCommentHistoryResponse,
     ListCommentReports,
     ListCommentReportsResponse,
-    ResolveCommentReport,
     SaveComment,
   },
   community::{
+    AutomodRuleResponse,
+    CommunityPostTagResponse,
     CommunityResponse,
+    CreateAutomodRule,
     CreateCommunity,
+    CreateCommunityPostTag,
+    DeleteAutomodRule,
+    DeleteCommunityPostTag,
+    EditCommunityPostTag,
     GetCommunityResponse,
+    ListAutomodRules,
+    ListAutomodRulesResponse,
     ListCommunities,
     ListCommunitiesResponse,
+    ListPendingFollows,
+    ListPendingFollowsResponse,
     TransferCommunity,
   },
   custom_emoji::{
@@ -33,6 +47,10 @@ use lemmy_api_common::{
     BlockPersonResponse,
     ChangePassword,
     CommentReplyResponse,
+    CreateReportNote,
+    DeleteImage,
+    DeleteImageResponse,
+    DeletePushSubscription,
     GetBannedPersons,
     GetCaptcha,
     GetCaptchaResponse,
@@ -44,8 +62,16 @@ use lemmy_api_common::{
     GetReportCountResponse,
     GetUnreadCount,
     GetUnreadCountResponse,
-    Login,
+    ListLogins,
+    ListLoginsResponse,
+    ListMyImages,
+    ListMyImagesResponse,
+    ListReportNotes,
+    ListReportNotesResponse,
     LoginResponse,
+    Logout,
+    LogoutAll,
+    LogoutResponse,
     MarkAllAsRead,
     MarkCommentReplyAsRead,
     MarkPersonMentionAsRead,
@@ -53,25 +79,34 @@ use lemmy_api_common::{
     PasswordReset,
     PasswordResetResponse,
     PersonMentionResponse,
+    PushSubscriptionResponse,
     Register,
+    RegisterPushSubscription,
+    ReportNoteResponse,
     SaveUserSettings,
     VerifyEmail,
     VerifyEmailResponse,
   },
   post::{
+    AssignPostReport,
+    EscalatePostReport,
     GetPost,
+    GetPostHistory,
+    GetPostHistoryResponse,
     GetPostResponse,
     GetSiteMetadata,
     GetSiteMetadataResponse,
     ListPostReports,
     ListPostReportsResponse,
+    ListScheduledPosts,
+    ListScheduledPostsResponse,
     MarkPostAsRead,
     PostReportResponse,
     PostResponse,
-    ResolvePostReport,
     SavePost,
   },
   private_message::{
+    AssignPrivateMessageReport,
     CreatePrivateMessageReport,
     GetPrivateMessages,
     ListPrivateMessageReports,
@@ -131,6 +166,34 @@ impl SendActivity for ListCommunities {
   type Response = ListCommunitiesResponse;
 }
 
+impl SendActivity for CreateAutomodRule {
+  type Response = AutomodRuleResponse;
+}
+
+impl SendActivity for DeleteAutomodRule {
+  type Response = AutomodRuleResponse;
+}
+
+impl SendActivity for ListAutomodRules {
+  type Response = ListAutomodRulesResponse;
+}
+
+impl SendActivity for CreateCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+}
+
+impl SendActivity for EditCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+}
+
+impl SendActivity for DeleteCommunityPostTag {
+  type Response = CommunityPostTagResponse;
+}
+
+impl SendActivity for ListPendingFollows {
+  type Response = ListPendingFollowsResponse;
+}
+
 impl SendActivity for CreateCommunity {
   type Response = CommunityResponse;
 }
@@ -143,8 +206,32 @@ impl SendActivity for GetComment {
   type Response = CommentResponse;
 }
 
-impl SendActivity for Login {
-  type Response = LoginResponse;
+impl SendActivity for ListLogins {
+  type Response = ListLoginsResponse;
+}
+
+impl SendActivity for Logout {
+  type Response = LogoutResponse;
+}
+
+impl SendActivity for LogoutAll {
+  type Response = LogoutResponse;
+}
+
+impl SendActivity for RegisterPushSubscription {
+  type Response = PushSubscriptionResponse;
+}
+
+impl SendActivity for DeletePushSubscription {
+  type Response = PushSubscriptionResponse;
+}
+
+impl SendActivity for ListMyImages {
+  type Response = ListMyImagesResponse;
+}
+
+impl SendActivity for DeleteImage {
+  type Response = DeleteImageResponse;
 }
 
 impl SendActivity for GetCaptcha {
@@ -235,6 +322,10 @@ impl SendActivity for ResolvePrivateMessageReport {
   type Response = PrivateMessageReportResponse;
 }
 
+impl SendActivity for AssignPrivateMessageReport {
+  type Response = PrivateMessageReportResponse;
+}
+
 impl SendActivity for ListPrivateMessageReports {
   type Response = ListPrivateMessageReportsResponse;
 }
@@ -279,7 +370,19 @@ impl SendActivity for ListPostReports {
   type Response = ListPostReportsResponse;
 }
 
-impl SendActivity for ResolvePostReport {
+impl SendActivity for ListScheduledPosts {
+  type Response = ListScheduledPostsResponse;
+}
+
+impl SendActivity for GetPostHistory {
+  type Response = GetPostHistoryResponse;
+}
+
+impl SendActivity for AssignPostReport {
+  type Response = PostReportResponse;
+}
+
+impl SendActivity for EscalatePostReport {
   type Response = PostReportResponse;
 }
 
@@ -295,14 +398,30 @@ impl SendActivity for DistinguishComment {
   type Response = CommentResponse;
 }
 
+impl SendActivity for GetCommentHistory {
+  type Response = GetCommentHistoryResponse;
+}
+
 impl SendActivity for ListCommentReports {
   type Response = ListCommentReportsResponse;
 }
 
-impl SendActivity for ResolveCommentReport {
+impl SendActivity for AssignCommentReport {
   type Response = CommentReportResponse;
 }
 
+impl SendActivity for EscalateCommentReport {
+  type Response = CommentReportResponse;
+}
+
+impl SendActivity for CreateReportNote {
+  type Response = ReportNoteResponse;
+}
+
+impl SendActivity for ListReportNotes {
+  type Response = ListReportNotesResponse;
+}
+
 impl SendActivity for CreateCustomEmoji {
   type Response = CustomEmojiResponse;
 }
@@ -15,9 +15,16 @@ use crate::{
         chat_message::CreateOrUpdateChatMessage,
         note::CreateOrUpdateNote,
         page::CreateOrUpdatePage,
+        poll_vote::CreatePollVote,
       },
       deletion::{delete::Delete, delete_user::DeleteUser, undo_delete::UndoDelete},
-      following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+      following::{
+        accept::AcceptFollow,
+        follow::Follow,
+        move_person::MovePerson,
+        reject::RejectFollow,
+        undo_follow::UndoFollow,
+      },
       voting::{undo_vote::UndoVote, vote::Vote},
     },
     objects::page::Page,
@@ -48,6 +55,7 @@ pub enum SharedInboxActivities {
 pub enum GroupInboxActivities {
   Follow(Follow),
   UndoFollow(UndoFollow),
+  MovePerson(MovePerson),
   Report(Report),
   // This is a catch-all and needs to be last
   AnnouncableActivities(RawAnnouncableActivities),
@@ -57,9 +65,13 @@ pub enum GroupInboxActivities {
 #[serde(untagged)]
 #[enum_delegate::implement(ActivityHandler)]
 pub enum PersonInboxActivities {
+  // Needs to be before any other Create activity, as poll votes are also notes
+  CreatePollVote(CreatePollVote),
   Follow(Follow),
   AcceptFollow(AcceptFollow),
+  RejectFollow(RejectFollow),
   UndoFollow(UndoFollow),
+  MovePerson(MovePerson),
   CreateOrUpdatePrivateMessage(CreateOrUpdateChatMessage),
   Delete(Delete),
   UndoDelete(UndoDelete),
@@ -168,6 +180,13 @@ mod tests {
     .unwrap();
     test_json::<PersonInboxActivitiesWithAnnouncable>("assets/mastodon/activities/follow.json")
       .unwrap();
+    let vote =
+      test_json::<PersonInboxActivities>("assets/mastodon/activities/create_poll_vote.json")
+        .unwrap();
+    assert!(matches!(
+      vote.inner(),
+      PersonInboxActivities::CreatePollVote(_)
+    ));
   }
 
   #[test]
@@ -8,13 +8,21 @@ use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   comment::{GetComments, GetCommentsResponse},
   context::LemmyContext,
-  utils::{check_private_instance, local_user_view_from_jwt_opt},
+  image_proxy::proxy_image_links,
+  utils::{
+    check_private_instance,
+    is_mod_or_admin_opt,
+    local_user_view_from_jwt_opt,
+    markdown_context,
+    render_comments_html,
+  },
 };
 use lemmy_db_schema::{
-  source::{comment::Comment, community::Community, local_site::LocalSite},
+  source::{comment::Comment, community::Community, local_site::LocalSite, post::Post},
   traits::Crud,
+  utils::limit_and_offset,
 };
-use lemmy_db_views::comment_view::CommentQuery;
+use lemmy_db_views::comment_view::{CommentCursor, CommentQuery};
 use lemmy_utils::error::LemmyError;
 
 #[tracing::instrument(skip(context))]
@@ -38,6 +46,11 @@ pub async fn list_comments(
   let page = data.page;
   let limit = data.limit;
   let parent_id = data.parent_id;
+  let page_cursor = data
+    .page_cursor
+    .as_deref()
+    .map(|c| CommentCursor::decode(c).ok_or(LemmyError::from_message("invalid_page_cursor")))
+    .transpose()?;
 
   let listing_type = listing_type_with_default(data.type_, &local_site, community_id)?;
 
@@ -50,8 +63,18 @@ pub async fn list_comments(
 
   let parent_path_cloned = parent_path.clone();
   let post_id = data.post_id;
+
+  // Mods of a private community can see its comments without following it
+  let mod_community_id = match (community_id, post_id) {
+    (None, Some(post_id)) => Some(Post::read(context.pool(), post_id).await?.community_id),
+    _ => community_id,
+  };
+  let is_mod_or_admin =
+    is_mod_or_admin_opt(context.pool(), local_user_view.as_ref(), mod_community_id)
+      .await
+      .is_ok();
   let local_user = local_user_view.map(|l| l.local_user);
-  let comments = CommentQuery::builder()
+  let mut comments = CommentQuery::builder()
     .pool(context.pool())
     .listing_type(Some(listing_type))
     .sort(sort)
@@ -61,12 +84,35 @@ pub async fn list_comments(
     .parent_path(parent_path_cloned)
     .post_id(post_id)
     .local_user(local_user.as_ref())
+    .is_mod_or_admin(Some(is_mod_or_admin))
     .page(page)
     .limit(limit)
+    .page_cursor(page_cursor)
     .build()
     .list()
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_get_comments"))?;
 
-  Ok(Json(GetCommentsResponse { comments }))
+  // Comment trees aren't paged, otherwise a full page means that there may be more comments
+  let next_page = if max_depth.is_none() {
+    let (limit, _) = limit_and_offset(None, limit)?;
+    comments
+      .last()
+      .filter(|_| comments.len() as i64 == limit)
+      .map(|c| CommentCursor::from(c).encode())
+  } else {
+    None
+  };
+  proxy_image_links(&mut comments, &context);
+  if data.render_html.unwrap_or(false) {
+    render_comments_html(
+      &mut comments,
+      &markdown_context(&context, &local_site).await?,
+    );
+  }
+
+  Ok(Json(GetCommentsResponse {
+    comments,
+    next_page,
+  }))
 }
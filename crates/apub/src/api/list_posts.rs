@@ -7,11 +7,21 @@ use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::proxy_image_links,
   post::{GetPosts, GetPostsResponse},
-  utils::{check_private_instance, is_mod_or_admin_opt, local_user_view_from_jwt_opt},
+  utils::{
+    check_private_instance,
+    is_mod_or_admin_opt,
+    local_user_view_from_jwt_opt,
+    markdown_context,
+    render_posts_html,
+  },
 };
-use lemmy_db_schema::source::{community::Community, local_site::LocalSite};
-use lemmy_db_views::post_view::PostQuery;
+use lemmy_db_schema::{
+  source::{community::Community, local_site::LocalSite},
+  utils::limit_and_offset,
+};
+use lemmy_db_views::post_view::{PostCursor, PostQuery};
 use lemmy_utils::error::LemmyError;
 
 #[tracing::instrument(skip(context))]
@@ -35,6 +45,11 @@ pub async fn list_posts(
     data.community_id
   };
   let saved_only = data.saved_only;
+  let page_cursor = data
+    .page_cursor
+    .as_deref()
+    .map(|c| PostCursor::decode(c).ok_or(LemmyError::from_message("invalid_page_cursor")))
+    .transpose()?;
 
   let listing_type = listing_type_with_default(data.type_, &local_site, community_id)?;
 
@@ -42,20 +57,33 @@ pub async fn list_posts(
     .await
     .is_ok();
 
-  let posts = PostQuery::builder()
+  let mut posts = PostQuery::builder()
     .pool(context.pool())
     .local_user(local_user_view.map(|l| l.local_user).as_ref())
     .listing_type(Some(listing_type))
     .sort(sort)
     .community_id(community_id)
+    .tag_id(data.tag_id)
     .saved_only(saved_only)
     .page(page)
     .limit(limit)
+    .page_cursor(page_cursor)
     .is_mod_or_admin(Some(is_mod_or_admin))
     .build()
     .list()
     .await
     .map_err(|e| LemmyError::from_error_message(e, "couldnt_get_posts"))?;
 
-  Ok(Json(GetPostsResponse { posts }))
+  // A full page means that there may be more posts after it
+  let (limit, _) = limit_and_offset(None, limit)?;
+  let next_page = posts
+    .last()
+    .filter(|_| posts.len() as i64 == limit)
+    .map(|p| PostCursor::from(p).encode());
+  proxy_image_links(&mut posts, &context);
+  if data.render_html.unwrap_or(false) {
+    render_posts_html(&mut posts, &markdown_context(&context, &local_site).await?);
+  }
+
+  Ok(Json(GetPostsResponse { posts, next_page }))
 }
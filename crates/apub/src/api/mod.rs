@@ -3,10 +3,12 @@ use lemmy_utils::error::LemmyError;
 
 pub mod list_comments;
 pub mod list_posts;
+pub mod move_account;
 pub mod read_community;
 pub mod read_person;
 pub mod resolve_object;
 pub mod search;
+pub mod user_settings_backup;
 
 /// Returns default listing type, depending if the query is for frontpage or community.
 fn listing_type_with_default(
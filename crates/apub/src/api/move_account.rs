@@ -0,0 +1,41 @@
+use crate::{
+  activities::following::move_person::{move_person, refetch_person},
+  fetcher::resolve_actor_identifier,
+  objects::person::ApubPerson,
+  protocol::activities::following::move_person::MovePerson,
+};
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{MoveAccount, MoveAccountResponse},
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::source::person::Person;
+use lemmy_db_views_actor::structs::PersonView;
+use lemmy_utils::error::LemmyError;
+
+#[tracing::instrument(skip(context))]
+pub async fn move_account(
+  data: Json<MoveAccount>,
+  context: Data<LemmyContext>,
+) -> Result<Json<MoveAccountResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person: ApubPerson = local_user_view.person.clone().into();
+
+  let target = resolve_actor_identifier::<ApubPerson, Person>(
+    &data.target,
+    &context,
+    &Some(local_user_view),
+    false,
+  )
+  .await
+  .map_err(|e| e.with_message("couldnt_find_person"))?;
+  let target = refetch_person(target, &context).await?;
+
+  move_person(&person, &target, &context).await?;
+  MovePerson::send(&person, &target, &context).await?;
+
+  let person_view = PersonView::read(context.pool(), person.id).await?;
+  Ok(Json(MoveAccountResponse { person_view }))
+}
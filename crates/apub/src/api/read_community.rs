@@ -4,11 +4,13 @@ use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   community::{GetCommunity, GetCommunityResponse},
   context::LemmyContext,
+  image_proxy::proxy_image_links,
   utils::{check_private_instance, is_mod_or_admin_opt, local_user_view_from_jwt_opt},
 };
 use lemmy_db_schema::source::{
   actor_language::CommunityLanguage,
   community::Community,
+  community_post_tag::CommunityPostTag,
   local_site::LocalSite,
   site::Site,
 };
@@ -47,7 +49,7 @@ pub async fn read_community(
       .await
       .is_ok();
 
-  let community_view = CommunityView::read(
+  let mut community_view = CommunityView::read(
     context.pool(),
     community_id,
     person_id,
@@ -55,6 +57,7 @@ pub async fn read_community(
   )
   .await
   .map_err(|e| LemmyError::from_error_message(e, "couldnt_find_community"))?;
+  proxy_image_links(&mut community_view, &context);
 
   let moderators = CommunityModeratorView::for_community(context.pool(), community_id)
     .await
@@ -72,11 +75,13 @@ pub async fn read_community(
 
   let community_id = community_view.community.id;
   let discussion_languages = CommunityLanguage::read(context.pool(), community_id).await?;
+  let post_tags = CommunityPostTag::list_for_community(context.pool(), community_id).await?;
 
   Ok(Json(GetCommunityResponse {
     community_view,
     site,
     moderators,
     discussion_languages,
+    post_tags,
   }))
 }
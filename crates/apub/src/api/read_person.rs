@@ -3,6 +3,7 @@ use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::proxy_image_links,
   person::{GetPersonDetails, GetPersonDetailsResponse},
   utils::{check_private_instance, is_admin, local_user_view_from_jwt_opt},
 };
@@ -48,7 +49,7 @@ pub async fn read_person(
 
   // You don't need to return settings for the user, since this comes back with GetSite
   // `my_user`
-  let person_view = PersonView::read(context.pool(), person_details_id).await?;
+  let mut person_view = PersonView::read(context.pool(), person_details_id).await?;
 
   let sort = data.sort;
   let page = data.page;
@@ -70,7 +71,7 @@ pub async fn read_person(
 
   // If its saved only, you don't care what creator it was
   // Or, if its not saved, then you only want it for that specific creator
-  let posts = if !saved_only.unwrap_or(false) {
+  let mut posts = if !saved_only.unwrap_or(false) {
     posts_query
       .creator_id(Some(person_details_id))
       .build()
@@ -92,7 +93,7 @@ pub async fn read_person(
 
   // If its saved only, you don't care what creator it was
   // Or, if its not saved, then you only want it for that specific creator
-  let comments = if !saved_only.unwrap_or(false) {
+  let mut comments = if !saved_only.unwrap_or(false) {
     comments_query
       .creator_id(Some(person_details_id))
       .build()
@@ -104,6 +105,10 @@ pub async fn read_person(
 
   let moderates = CommunityModeratorView::for_person(context.pool(), person_details_id).await?;
 
+  proxy_image_links(&mut person_view, &context);
+  proxy_image_links(&mut posts, &context);
+  proxy_image_links(&mut comments, &context);
+
   // Return the jwt
   Ok(Json(GetPersonDetailsResponse {
     person_view,
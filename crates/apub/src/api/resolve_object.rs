@@ -4,6 +4,7 @@ use actix_web::web::{Json, Query};
 use diesel::NotFound;
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::proxy_image_links,
   site::{ResolveObject, ResolveObjectResponse},
   utils::{check_private_instance, local_user_view_from_jwt},
 };
@@ -25,9 +26,14 @@ pub async fn resolve_object(
   let res = search_query_to_object_id(&data.q, &context)
     .await
     .map_err(|e| e.with_message("couldnt_find_object"))?;
-  convert_response(res, person_id, context.pool())
+  let mut res = convert_response(res, person_id, context.pool())
     .await
-    .map_err(|e| e.with_message("couldnt_find_object"))
+    .map_err(|e| e.with_message("couldnt_find_object"))?;
+  proxy_image_links(&mut res.comment, &context);
+  proxy_image_links(&mut res.post, &context);
+  proxy_image_links(&mut res.community, &context);
+  proxy_image_links(&mut res.person, &context);
+  Ok(res)
 }
 
 async fn convert_response(
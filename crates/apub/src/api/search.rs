@@ -3,6 +3,7 @@ use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
+  image_proxy::proxy_image_links,
   site::{Search, SearchResponse},
   utils::{check_private_instance, is_admin, local_user_view_from_jwt_opt},
 };
@@ -198,6 +199,11 @@ pub async fn search(
     }
   };
 
+  proxy_image_links(&mut posts, &context);
+  proxy_image_links(&mut comments, &context);
+  proxy_image_links(&mut communities, &context);
+  proxy_image_links(&mut users, &context);
+
   // Return the jwt
   Ok(Json(SearchResponse {
     type_: search_type,
@@ -0,0 +1,348 @@
+use crate::{
+  fetcher::resolve_actor_identifier,
+  objects::{comment::ApubComment, community::ApubCommunity, person::ApubPerson, post::ApubPost},
+  protocol::activities::following::follow::Follow,
+};
+use activitypub_federation::{config::Data, fetch::object_id::ObjectId};
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{
+    ExportUserData,
+    ExportUserDataResponse,
+    ExportedVote,
+    ImportUserSettings,
+    ImportUserSettingsResponse,
+  },
+  utils::{
+    check_community_ban,
+    check_community_deleted_or_removed,
+    check_user_rate_limit,
+    local_site_to_slur_regex,
+    local_user_view_from_jwt,
+  },
+};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{
+    comment::{Comment, CommentLike, CommentSaved, CommentSavedForm},
+    community::{Community, CommunityFollower, CommunityFollowerForm},
+    community_block::{CommunityBlock, CommunityBlockForm},
+    local_site::LocalSite,
+    local_user::{LocalUser, LocalUserUpdateForm},
+    person::{Person, PersonUpdateForm},
+    person_block::{PersonBlock, PersonBlockForm},
+    post::{Post, PostLike, PostSaved, PostSavedForm},
+    private_message::PrivateMessage,
+  },
+  traits::{Blockable, Crud, Followable, Saveable},
+  utils::limit_and_offset_unlimited,
+  CommunityVisibility,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::{CommunityBlockView, CommunityFollowerView, PersonBlockView};
+use lemmy_utils::{
+  error::LemmyError,
+  utils::{
+    slurs::check_slurs,
+    validation::{
+      is_valid_bio_field,
+      is_valid_display_name,
+      is_valid_interface_language,
+      is_valid_matrix_id,
+      is_valid_theme,
+    },
+  },
+};
+use tracing::warn;
+
+/// Maximum number of follows, blocks and saved items which can be imported at once. Each of them
+/// may need to be fetched from another instance.
+const MAX_IMPORT_ITEMS: usize = 100;
+
+/// Maximum number of posts, comments, messages, votes and saved items each in a page of the export.
+const EXPORT_PAGE_SIZE: i64 = 1000;
+
+#[tracing::instrument(skip(context))]
+pub async fn export_user_data(
+  data: Query<ExportUserData>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ExportUserDataResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let person_id = local_user_view.person.id;
+  let pool = context.pool();
+  let page = data.page.unwrap_or(1);
+  if page < 1 {
+    return Err(LemmyError::from_message("invalid_page"));
+  }
+  let (limit, offset) = limit_and_offset_unlimited(Some(page), Some(EXPORT_PAGE_SIZE));
+
+  let posts = Post::list_for_creator(pool, person_id, limit, offset).await?;
+  let comments = Comment::list_for_creator(pool, person_id, limit, offset).await?;
+  let private_messages = PrivateMessage::list_for_person(pool, person_id, limit, offset).await?;
+  let post_votes = PostLike::list_for_person(pool, person_id, limit, offset)
+    .await?
+    .into_iter()
+    .map(|(object_id, score)| ExportedVote { object_id, score })
+    .collect();
+  let comment_votes = CommentLike::list_for_person(pool, person_id, limit, offset)
+    .await?
+    .into_iter()
+    .map(|(object_id, score)| ExportedVote { object_id, score })
+    .collect();
+  let saved_posts = PostSaved::list_for_person(pool, person_id, limit, offset).await?;
+  let saved_comments = CommentSaved::list_for_person(pool, person_id, limit, offset).await?;
+
+  let (mut followed_communities, mut blocked_communities, mut blocked_users) =
+    (vec![], vec![], vec![]);
+  if page == 1 {
+    followed_communities = CommunityFollowerView::for_person(pool, person_id)
+      .await?
+      .into_iter()
+      .map(|f| actor_identifier(&f.community.name, &f.community.actor_id))
+      .collect();
+    blocked_communities = CommunityBlockView::for_person(pool, person_id)
+      .await?
+      .into_iter()
+      .map(|b| actor_identifier(&b.community.name, &b.community.actor_id))
+      .collect();
+    blocked_users = PersonBlockView::for_person(pool, person_id)
+      .await?
+      .into_iter()
+      .map(|b| actor_identifier(&b.target.name, &b.target.actor_id))
+      .collect();
+  }
+
+  Ok(Json(ExportUserDataResponse {
+    local_user_view,
+    posts,
+    comments,
+    private_messages,
+    post_votes,
+    comment_votes,
+    saved_posts,
+    saved_comments,
+    followed_communities,
+    blocked_communities,
+    blocked_users,
+  }))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn import_user_settings(
+  data: Json<ImportUserSettings>,
+  context: Data<LemmyContext>,
+) -> Result<Json<ImportUserSettingsResponse>, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  check_user_rate_limit(
+    context.settings_updated_channel().import_user_settings(),
+    &local_user_view,
+  )?;
+
+  let item_count = data.followed_communities.len()
+    + data.blocked_communities.len()
+    + data.blocked_users.len()
+    + data.saved_posts.len()
+    + data.saved_comments.len();
+  if item_count > MAX_IMPORT_ITEMS {
+    return Err(LemmyError::from_message("too_many_items"));
+  }
+
+  if let Some(imported) = &data.local_user_view {
+    import_settings(imported, &local_user_view, &context).await?;
+  }
+
+  let person_id = local_user_view.person.id;
+  let mut failed_items = vec![];
+
+  for identifier in &data.followed_communities {
+    if let Err(e) = follow_community(identifier, &local_user_view, &context).await {
+      warn!("Failed to follow community {identifier}: {e}");
+      failed_items.push(identifier.clone());
+    }
+  }
+
+  for identifier in &data.blocked_communities {
+    let community = resolve_actor_identifier::<ApubCommunity, Community>(
+      identifier,
+      &context,
+      &Some(local_user_view.clone()),
+      false,
+    )
+    .await;
+    let blocked = match community {
+      Ok(c) => {
+        let form = CommunityBlockForm {
+          person_id,
+          community_id: c.id,
+        };
+        CommunityBlock::block(context.pool(), &form).await.is_ok()
+      }
+      Err(_) => false,
+    };
+    if !blocked {
+      failed_items.push(identifier.clone());
+    }
+  }
+
+  for identifier in &data.blocked_users {
+    let target = resolve_actor_identifier::<ApubPerson, Person>(
+      identifier,
+      &context,
+      &Some(local_user_view.clone()),
+      false,
+    )
+    .await;
+    let blocked = match target {
+      // Admins can't be blocked
+      Ok(t) if !t.admin => {
+        let form = PersonBlockForm {
+          person_id,
+          target_id: t.id,
+        };
+        PersonBlock::block(context.pool(), &form).await.is_ok()
+      }
+      _ => false,
+    };
+    if !blocked {
+      failed_items.push(identifier.clone());
+    }
+  }
+
+  for ap_id in &data.saved_posts {
+    let post: Result<ApubPost, _> = ObjectId::from(ap_id.clone()).dereference(&context).await;
+    let saved = match post {
+      Ok(p) => {
+        let form = PostSavedForm {
+          post_id: p.id,
+          person_id,
+        };
+        PostSaved::save(context.pool(), &form).await.is_ok()
+      }
+      Err(_) => false,
+    };
+    if !saved {
+      failed_items.push(ap_id.to_string());
+    }
+  }
+
+  for ap_id in &data.saved_comments {
+    let comment: Result<ApubComment, _> = ObjectId::from(ap_id.clone()).dereference(&context).await;
+    let saved = match comment {
+      Ok(c) => {
+        let form = CommentSavedForm {
+          comment_id: c.id,
+          person_id,
+        };
+        CommentSaved::save(context.pool(), &form).await.is_ok()
+      }
+      Err(_) => false,
+    };
+    if !saved {
+      failed_items.push(ap_id.to_string());
+    }
+  }
+
+  Ok(Json(ImportUserSettingsResponse { failed_items }))
+}
+
+/// Builds an identifier like `name@example.com`, which can be passed to
+/// [[resolve_actor_identifier]].
+fn actor_identifier(name: &str, actor_id: &DbUrl) -> String {
+  format!("{}@{}", name, actor_id.domain().unwrap_or_default())
+}
+
+/// Takes over the profile and settings of the exported account.
+async fn import_settings(
+  imported: &LocalUserView,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  // The export can be edited, so it gets the same checks as SaveUserSettings
+  let local_site = LocalSite::read(context.pool()).await?;
+  let slur_regex = local_site_to_slur_regex(&local_site);
+  let person = &imported.person;
+  if let Some(display_name) = &person.display_name {
+    is_valid_display_name(
+      display_name.trim(),
+      local_site.actor_name_max_length as usize,
+    )?;
+    check_slurs(display_name, &slur_regex)?;
+  }
+  if let Some(bio) = &person.bio {
+    is_valid_bio_field(bio)?;
+    check_slurs(bio, &slur_regex)?;
+  }
+  if let Some(matrix_user_id) = &person.matrix_user_id {
+    is_valid_matrix_id(matrix_user_id)?;
+  }
+  is_valid_theme(&imported.local_user.theme)?;
+  is_valid_interface_language(&imported.local_user.interface_language)?;
+
+  let person_form = PersonUpdateForm::builder()
+    .display_name(Some(imported.person.display_name.clone()))
+    .bio(Some(imported.person.bio.clone()))
+    .matrix_user_id(Some(imported.person.matrix_user_id.clone()))
+    .bot_account(Some(imported.person.bot_account))
+    .build();
+  Person::update(context.pool(), local_user_view.person.id, &person_form)
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "user_already_exists"))?;
+
+  let settings = &imported.local_user;
+  let local_user_form = LocalUserUpdateForm::builder()
+    .show_nsfw(Some(settings.show_nsfw))
+    .theme(Some(settings.theme.clone()))
+    .default_sort_type(Some(settings.default_sort_type))
+    .default_listing_type(Some(settings.default_listing_type))
+    .interface_language(Some(settings.interface_language.clone()))
+    .show_avatars(Some(settings.show_avatars))
+    .send_notifications_to_email(Some(settings.send_notifications_to_email))
+    .show_scores(Some(settings.show_scores))
+    .show_bot_accounts(Some(settings.show_bot_accounts))
+    .show_read_posts(Some(settings.show_read_posts))
+    .show_new_post_notifs(Some(settings.show_new_post_notifs))
+    .open_links_in_new_tab(Some(settings.open_links_in_new_tab))
+    .build();
+  LocalUser::update(
+    context.pool(),
+    local_user_view.local_user.id,
+    &local_user_form,
+  )
+  .await
+  .map_err(|e| LemmyError::from_error_message(e, "couldnt_update_user"))?;
+  Ok(())
+}
+
+/// Follows a community the same way as the `FollowCommunity` API, fetching it first if necessary.
+async fn follow_community(
+  identifier: &str,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> Result<(), LemmyError> {
+  let community: ApubCommunity = resolve_actor_identifier::<ApubCommunity, Community>(
+    identifier,
+    context,
+    &Some(local_user_view.clone()),
+    false,
+  )
+  .await?;
+  let person_id = local_user_view.person.id;
+  if community.local {
+    check_community_ban(person_id, community.id, context.pool()).await?;
+    check_community_deleted_or_removed(community.id, context.pool()).await?;
+
+    let form = CommunityFollowerForm {
+      community_id: community.id,
+      person_id,
+      pending: community.visibility == CommunityVisibility::ApprovalRequired,
+    };
+    CommunityFollower::follow(context.pool(), &form)
+      .await
+      .map_err(|e| LemmyError::from_error_message(e, "community_follower_already_exists"))?;
+    Ok(())
+  } else {
+    let person: ApubPerson = local_user_view.person.clone().into();
+    Follow::send(&person, &community, context).await
+  }
+}
@@ -10,7 +10,7 @@ use activitypub_federation::{
 };
 use futures::future::{join_all, try_join_all};
 use lemmy_api_common::{context::LemmyContext, utils::generate_featured_url};
-use lemmy_db_schema::{source::post::Post, utils::FETCH_LIMIT_MAX};
+use lemmy_db_schema::{source::post::Post, utils::FETCH_LIMIT_MAX, CommunityVisibility};
 use lemmy_utils::error::LemmyError;
 use url::Url;
 
@@ -28,9 +28,13 @@ impl Collection for ApubCommunityFeatured {
     owner: &Self::Owner,
     data: &Data<Self::DataType>,
   ) -> Result<Self::Kind, Self::Error> {
+    let posts = if owner.visibility == CommunityVisibility::Public {
+      Post::list_featured_for_community(data.pool(), owner.id).await?
+    } else {
+      vec![]
+    };
     let ordered_items = try_join_all(
-      Post::list_featured_for_community(data.pool(), owner.id)
-        .await?
+      posts
         .into_iter()
         .map(ApubPost::from)
         .map(|p| p.into_json(data)),
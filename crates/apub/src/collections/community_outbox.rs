@@ -22,6 +22,7 @@ use lemmy_db_schema::{
   source::{person::Person, post::Post},
   traits::Crud,
   utils::FETCH_LIMIT_MAX,
+  CommunityVisibility,
 };
 use lemmy_utils::error::LemmyError;
 use url::Url;
@@ -41,11 +42,16 @@ impl Collection for ApubCommunityOutbox {
     owner: &Self::Owner,
     data: &Data<Self::DataType>,
   ) -> Result<Self::Kind, LemmyError> {
-    let post_list: Vec<ApubPost> = Post::list_for_community(data.pool(), owner.id)
-      .await?
-      .into_iter()
-      .map(Into::into)
-      .collect();
+    // Content of private communities is only delivered to approved followers
+    let post_list: Vec<ApubPost> = if owner.visibility == CommunityVisibility::Public {
+      Post::list_for_community(data.pool(), owner.id)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect()
+    } else {
+      vec![]
+    };
     let mut ordered_items = vec![];
     for post in post_list {
       let person = Person::read(data.pool(), post.creator_id).await?.into();
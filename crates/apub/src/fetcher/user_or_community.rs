@@ -1,6 +1,7 @@
 use crate::{
   objects::{community::ApubCommunity, person::ApubPerson},
   protocol::objects::{group::Group, person::Person},
+  GetActorType,
 };
 use activitypub_federation::{
   config::Data,
@@ -8,6 +9,7 @@ use activitypub_federation::{
 };
 use chrono::{DateTime, Utc};
 use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::ActorType;
 use lemmy_utils::error::LemmyError;
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -119,3 +121,12 @@ impl Actor for UserOrCommunity {
     unimplemented!()
   }
 }
+
+impl GetActorType for UserOrCommunity {
+  fn actor_type(&self) -> ActorType {
+    match self {
+      UserOrCommunity::User(p) => p.actor_type(),
+      UserOrCommunity::Community(p) => p.actor_type(),
+    }
+  }
+}
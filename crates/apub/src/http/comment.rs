@@ -1,11 +1,20 @@
 use crate::{
-  http::{create_apub_response, create_apub_tombstone_response, err_object_not_local},
+  http::{
+    check_community_public,
+    create_apub_response,
+    create_apub_tombstone_response,
+    err_object_not_local,
+  },
   objects::comment::ApubComment,
 };
 use activitypub_federation::{config::Data, traits::Object};
 use actix_web::{web::Path, HttpResponse};
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::{newtypes::CommentId, source::comment::Comment, traits::Crud};
+use lemmy_db_schema::{
+  newtypes::CommentId,
+  source::{comment::Comment, post::Post},
+  traits::Crud,
+};
 use lemmy_utils::error::LemmyError;
 use serde::Deserialize;
 
@@ -25,6 +34,8 @@ pub(crate) async fn get_apub_comment(
   if !comment.local {
     return Err(err_object_not_local());
   }
+  let post = Post::read(context.pool(), comment.post_id).await?;
+  check_community_public(post.community_id, &context).await?;
 
   if !comment.deleted && !comment.removed {
     create_apub_response(&comment.into_json(&context).await?)
@@ -17,7 +17,7 @@ use activitypub_federation::{
 };
 use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::{source::community::Community, traits::ApubActor};
+use lemmy_db_schema::{source::community::Community, traits::ApubActor, CommunityVisibility};
 use lemmy_utils::error::LemmyError;
 use serde::Deserialize;
 
@@ -36,6 +36,9 @@ pub(crate) async fn get_apub_community_http(
     Community::read_from_name(context.pool(), &info.community_name, true)
       .await?
       .into();
+  if community.visibility == CommunityVisibility::LocalOnly {
+    return Err(LemmyError::from_message("community_is_local_only"));
+  }
 
   if !community.deleted && !community.removed {
     let apub = community.into_json(&context).await?;
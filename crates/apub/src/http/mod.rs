@@ -13,7 +13,12 @@ use activitypub_federation::{
 use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
 use http::StatusCode;
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::source::activity::Activity;
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::{activity::Activity, community::Community},
+  traits::Crud,
+  CommunityVisibility,
+};
 use lemmy_utils::error::{LemmyError, LemmyResult};
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
@@ -68,6 +73,18 @@ fn err_object_not_local() -> LemmyError {
   LemmyError::from_message("Object not local, fetch it from original instance")
 }
 
+/// Content of private and local-only communities can't be fetched over federation.
+async fn check_community_public(
+  community_id: CommunityId,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let community = Community::read(context.pool(), community_id).await?;
+  if community.visibility != CommunityVisibility::Public {
+    return Err(LemmyError::from_message("community_is_private"));
+  }
+  Ok(())
+}
+
 #[derive(Deserialize)]
 pub struct ActivityQuery {
   type_: String,
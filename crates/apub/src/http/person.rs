@@ -3,7 +3,7 @@ use crate::{
   fetcher::user_or_community::UserOrCommunity,
   http::{create_apub_response, create_apub_tombstone_response},
   objects::person::ApubPerson,
-  protocol::collections::empty_outbox::EmptyOutbox,
+  protocol::collections::{empty_outbox::EmptyOutbox, person_followers::PersonFollowers},
 };
 use activitypub_federation::{
   actix_web::inbox::receive_activity,
@@ -65,3 +65,14 @@ pub(crate) async fn get_apub_person_outbox(
   let outbox = EmptyOutbox::new(outbox_id)?;
   create_apub_response(&outbox)
 }
+
+/// Returns an empty followers collection, only populating the size (for privacy).
+#[tracing::instrument(skip_all)]
+pub(crate) async fn get_apub_person_followers(
+  info: web::Path<PersonQuery>,
+  context: Data<LemmyContext>,
+) -> Result<HttpResponse, LemmyError> {
+  let person = Person::read_from_name(context.pool(), &info.user_name, false).await?;
+  let followers = PersonFollowers::new(person, &context).await?;
+  create_apub_response(&followers)
+}
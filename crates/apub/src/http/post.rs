@@ -1,5 +1,10 @@
 use crate::{
-  http::{create_apub_response, create_apub_tombstone_response, err_object_not_local},
+  http::{
+    check_community_public,
+    create_apub_response,
+    create_apub_tombstone_response,
+    err_object_not_local,
+  },
   objects::post::ApubPost,
 };
 use activitypub_federation::{config::Data, traits::Object};
@@ -25,6 +30,11 @@ pub(crate) async fn get_apub_post(
   if !post.local {
     return Err(err_object_not_local());
   }
+  // Scheduled posts are not federated until they are published
+  if post.scheduled_publish_time.is_some() {
+    return Err(LemmyError::from_message("post_not_published"));
+  }
+  check_community_public(post.community_id, &context).await?;
 
   if !post.deleted && !post.removed {
     create_apub_response(&post.into_json(&context).await?)
@@ -9,7 +9,7 @@ use crate::http::{
     get_apub_community_outbox,
   },
   get_activity,
-  person::{get_apub_person_http, get_apub_person_outbox, person_inbox},
+  person::{get_apub_person_followers, get_apub_person_http, get_apub_person_outbox, person_inbox},
   post::get_apub_post,
   shared_inbox,
   site::{get_apub_site_http, get_apub_site_inbox, get_apub_site_outbox},
@@ -49,6 +49,10 @@ pub fn config(cfg: &mut web::ServiceConfig) {
       "/u/{user_name}/outbox",
       web::get().to(get_apub_person_outbox),
     )
+    .route(
+      "/u/{user_name}/followers",
+      web::get().to(get_apub_person_followers),
+    )
     .route("/post/{post_id}", web::get().to(get_apub_post))
     .route("/comment/{comment_id}", web::get().to(get_apub_comment))
     .route("/activities/{type_}/{id}", web::get().to(get_activity));
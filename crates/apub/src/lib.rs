@@ -11,6 +11,7 @@ use lemmy_db_schema::{
   },
   traits::Crud,
   utils::DbPool,
+  ActorType,
 };
 use lemmy_utils::error::{LemmyError, LemmyResult};
 use moka::future::Cache;
@@ -28,6 +29,7 @@ pub mod http;
 pub(crate) mod mentions;
 pub mod objects;
 pub mod protocol;
+pub mod send_queue;
 
 pub const FEDERATION_HTTP_FETCH_LIMIT: u32 = 50;
 /// All incoming and outgoing federation actions read the blocklist/allowlist and slur filters
@@ -183,20 +185,28 @@ async fn insert_activity<T>(
   local: bool,
   sensitive: bool,
   data: &Data<LemmyContext>,
-) -> Result<(), LemmyError>
+) -> Result<Activity, LemmyError>
 where
   T: Serialize,
 {
-  let ap_id = ap_id.clone().into();
+  if !local {
+    if let Some(domain) = ap_id.domain() {
+      Instance::update_last_received_activity(data.pool(), domain).await?;
+    }
+  }
   let form = ActivityInsertForm {
-    ap_id,
+    ap_id: ap_id.clone().into(),
     data: serde_json::to_value(activity)?,
     local: Some(local),
     sensitive: Some(sensitive),
     updated: None,
   };
-  Activity::create(data.pool(), &form).await?;
-  Ok(())
+  Ok(Activity::create(data.pool(), &form).await?)
+}
+
+/// The type of a local actor, so that its private key can be looked up when sending activities.
+pub(crate) trait GetActorType {
+  fn actor_type(&self) -> ActorType;
 }
 
 #[async_trait::async_trait]
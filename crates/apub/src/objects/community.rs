@@ -7,6 +7,7 @@ use crate::{
     ImageObject,
     Source,
   },
+  GetActorType,
 };
 use activitypub_federation::{
   config::Data,
@@ -24,6 +25,8 @@ use lemmy_db_schema::{
     community::{Community, CommunityUpdateForm},
   },
   traits::{ApubActor, Crud},
+  ActorType,
+  CommunityVisibility,
 };
 use lemmy_db_views_actor::structs::CommunityFollowerView;
 use lemmy_utils::{
@@ -107,6 +110,8 @@ impl Object for ApubCommunity {
       published: Some(convert_datetime(self.published)),
       updated: self.updated.map(convert_datetime),
       posting_restricted_to_mods: Some(self.posting_restricted_to_mods),
+      manually_approves_followers: Some(self.visibility == CommunityVisibility::ApprovalRequired),
+      edit_history_public: Some(self.edit_history_public),
       attributed_to: Some(generate_moderators_url(&self.actor_id)?.into()),
     };
     Ok(group)
@@ -177,6 +182,12 @@ impl Actor for ApubCommunity {
   }
 }
 
+impl GetActorType for ApubCommunity {
+  fn actor_type(&self) -> ActorType {
+    ActorType::Community
+  }
+}
+
 impl ApubCommunity {
   /// For a given community, returns the inboxes of all followers.
   #[tracing::instrument(skip_all)]
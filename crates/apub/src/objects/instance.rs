@@ -7,6 +7,7 @@ use crate::{
     ImageObject,
     Source,
   },
+  GetActorType,
 };
 use activitypub_federation::{
   config::Data,
@@ -26,6 +27,7 @@ use lemmy_db_schema::{
   },
   traits::Crud,
   utils::{naive_now, DbPool},
+  ActorType,
 };
 use lemmy_utils::{
   error::LemmyError,
@@ -169,6 +171,12 @@ impl Actor for ApubSite {
   }
 }
 
+impl GetActorType for ApubSite {
+  fn actor_type(&self) -> ActorType {
+    ActorType::Site
+  }
+}
+
 /// Try to fetch the instance actor (to make things like instance rules available).
 pub(in crate::objects) async fn fetch_instance_actor_for_object<T: Into<Url> + Clone>(
   object_id: &T,
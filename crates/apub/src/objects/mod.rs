@@ -96,10 +96,11 @@ pub(crate) mod tests {
     let secret = Secret {
       id: 0,
       jwt_secret: String::new(),
+      image_proxy_secret: String::new(),
     };
 
     let rate_limit_config = RateLimitConfig::builder().build();
-    let rate_limit_cell = RateLimitCell::new(rate_limit_config).await;
+    let rate_limit_cell = RateLimitCell::new(rate_limit_config, secret.jwt_secret.clone()).await;
 
     let context = LemmyContext::create(pool, client, secret, rate_limit_cell.clone());
     let config = FederationConfig::builder()
@@ -10,6 +10,7 @@ use crate::{
     ImageObject,
     Source,
   },
+  GetActorType,
 };
 use activitypub_federation::{
   config::Data,
@@ -19,12 +20,13 @@ use activitypub_federation::{
 use chrono::{DateTime, Utc};
 use lemmy_api_common::{
   context::LemmyContext,
-  utils::{generate_outbox_url, local_site_opt_to_slur_regex},
+  utils::{generate_followers_url, generate_outbox_url, local_site_opt_to_slur_regex},
 };
 use lemmy_db_schema::{
   source::person::{Person as DbPerson, PersonInsertForm, PersonUpdateForm},
   traits::{ApubActor, Crud},
   utils::naive_now,
+  ActorType,
 };
 use lemmy_utils::{
   error::LemmyError,
@@ -102,12 +104,15 @@ impl Object for ApubPerson {
       matrix_user_id: self.matrix_user_id.clone(),
       published: Some(convert_datetime(self.published)),
       outbox: generate_outbox_url(&self.actor_id)?.into(),
+      followers: Some(generate_followers_url(&self.actor_id)?.into()),
       endpoints: self.shared_inbox_url.clone().map(|s| Endpoints {
         shared_inbox: s.into(),
       }),
       public_key: self.public_key(),
       updated: self.updated.map(convert_datetime),
       inbox: self.inbox_url.clone().into(),
+      also_known_as: self.also_known_as.iter().cloned().map(Into::into).collect(),
+      moved_to: self.moved_to.clone().map(Into::into),
     };
     Ok(person)
   }
@@ -164,6 +169,8 @@ impl Object for ApubPerson {
       shared_inbox_url: person.endpoints.map(|e| e.shared_inbox.into()),
       matrix_user_id: person.matrix_user_id,
       instance_id,
+      also_known_as: Some(person.also_known_as.into_iter().map(Into::into).collect()),
+      moved_to: person.moved_to.map(Into::into),
     };
     let person = DbPerson::upsert(context.pool(), &person_form).await?;
 
@@ -193,6 +200,12 @@ impl Actor for ApubPerson {
   }
 }
 
+impl GetActorType for ApubPerson {
+  fn actor_type(&self) -> ActorType {
+    ActorType::Person
+  }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
   use super::*;
@@ -1,11 +1,11 @@
 use crate::{
-  activities::{verify_is_public, verify_person_in_community},
+  activities::{generate_to, verify_person_in_community, verify_visibility},
   check_apub_id_valid_with_strictness,
   local_site_data_cached,
-  objects::{read_from_string_or_source_opt, verify_is_remote_object},
+  objects::{community::ApubCommunity, read_from_string_or_source_opt, verify_is_remote_object},
   protocol::{
     objects::{
-      page::{Attachment, AttributedTo, Page, PageType},
+      page::{Attachment, AttributedTo, Hashtag, HashtagOrValue, Page, PageType, PollOption},
       LanguageTag,
     },
     ImageObject,
@@ -15,12 +15,12 @@ use crate::{
 };
 use activitypub_federation::{
   config::Data,
-  kinds::public,
   protocol::{values::MediaTypeMarkdownOrHtml, verification::verify_domains_match},
   traits::Object,
 };
 use anyhow::anyhow;
 use chrono::{DateTime, Utc};
+use diesel::result::{DatabaseErrorKind, Error::DatabaseError};
 use html2md::parse_html;
 use lemmy_api_common::{
   context::LemmyContext,
@@ -29,12 +29,15 @@ use lemmy_api_common::{
 };
 use lemmy_db_schema::{
   self,
+  newtypes::CommunityPostTagId,
   source::{
     community::Community,
+    community_post_tag::{CommunityPostTag, CommunityPostTagInsertForm, PostTag},
     local_site::LocalSite,
     moderator::{ModLockPost, ModLockPostForm},
     person::Person,
     post::{Post, PostInsertForm, PostUpdateForm},
+    post_poll::{PostPoll, PostPollForm, PostPollOption, PostPollOptionForm},
   },
   traits::Crud,
 };
@@ -44,9 +47,11 @@ use lemmy_utils::{
     markdown::markdown_to_html,
     slurs::{check_slurs_opt, remove_slurs},
     time::convert_datetime,
+    validation::is_valid_post_tag_name,
   },
 };
 use std::ops::Deref;
+use tracing::warn;
 use url::Url;
 
 const MAX_TITLE_LENGTH: usize = 200;
@@ -106,12 +111,37 @@ impl Object for ApubPost {
     let community_id = self.community_id;
     let community = Community::read(context.pool(), community_id).await?;
     let language = LanguageTag::new_single(self.language_id, context.pool()).await?;
+    let poll = PostPoll::read_for_post(context.pool(), self.id).await?;
+    let poll_options = match &poll {
+      Some(poll) => Some(
+        PostPollOption::list_for_poll(context.pool(), poll.id)
+          .await?
+          .into_iter()
+          .map(|o| PollOption::new(o.name, o.vote_count))
+          .collect::<Vec<_>>(),
+      ),
+      None => None,
+    };
+    let multiple_choice = poll.as_ref().map(|p| p.multiple_choice).unwrap_or(false);
+    let tag = CommunityPostTag::list_for_post(context.pool(), self.id)
+      .await?
+      .into_iter()
+      .map(|t| HashtagOrValue::Hashtag(Hashtag::new(t)))
+      .collect();
 
     let page = Page {
-      kind: PageType::Page,
+      kind: if poll.is_some() {
+        PageType::Question
+      } else {
+        PageType::Page
+      },
       id: self.ap_id.clone().into(),
       attributed_to: AttributedTo::Lemmy(creator.actor_id.into()),
-      to: vec![community.actor_id.clone().into(), public()],
+      to: [
+        vec![community.actor_id.clone().into()],
+        generate_to(&community),
+      ]
+      .concat(),
       cc: vec![],
       name: Some(self.name.clone()),
       content: self.body.as_ref().map(|b| markdown_to_html(b)),
@@ -126,6 +156,15 @@ impl Object for ApubPost {
       updated: self.updated.map(convert_datetime),
       audience: Some(community.actor_id.into()),
       in_reply_to: None,
+      one_of: poll_options.clone().filter(|_| !multiple_choice),
+      any_of: poll_options.filter(|_| multiple_choice),
+      end_time: poll.as_ref().and_then(|p| p.end_time).map(Into::into),
+      closed: poll
+        .as_ref()
+        .filter(|p| p.is_closed())
+        .and_then(|p| p.end_time)
+        .map(Into::into),
+      tag,
     };
     Ok(page)
   }
@@ -152,7 +191,7 @@ impl Object for ApubPost {
     check_slurs_opt(&page.name, slur_regex)?;
 
     verify_domains_match(page.creator()?.inner(), page.id.inner())?;
-    verify_is_public(&page.to, &page.cc)?;
+    verify_visibility(&page.to, &page.cc, &community)?;
     Ok(())
   }
 
@@ -181,7 +220,22 @@ impl Object for ApubPost {
     // read existing, local post if any (for generating mod log)
     let old_post = page.id.dereference_local(context).await;
 
-    let form = if !page.is_mod_action(context).await? {
+    // Vote counts are taken over from the origin instance. Mod actions may come from a different
+    // instance, so the poll is only updated by the author.
+    let is_mod_action = page.is_mod_action(context).await?;
+    let poll = page
+      .poll_options()
+      .filter(|_| !is_mod_action)
+      .map(|(options, multiple_choice)| (options.clone(), multiple_choice));
+    let poll_end_time = page.end_time.or(page.closed);
+    // Like the poll, tags are chosen by the author
+    let tags = if is_mod_action {
+      None
+    } else {
+      Some(receive_tags(&page.tag, &community, context).await?)
+    };
+
+    let form = if !is_mod_action {
       let first_attachment = page.attachment.into_iter().map(Attachment::url).next();
       let url = if first_attachment.is_some() {
         first_attachment
@@ -246,6 +300,7 @@ impl Object for ApubPost {
         language_id,
         featured_community: None,
         featured_local: None,
+        scheduled_publish_time: None,
       }
     } else {
       // if is mod action, only update locked/stickied fields, nothing else
@@ -261,6 +316,30 @@ impl Object for ApubPost {
 
     let post = Post::create(context.pool(), &form).await?;
 
+    if let Some((options, multiple_choice)) = poll {
+      let poll_form = PostPollForm::builder()
+        .post_id(post.id)
+        .multiple_choice(Some(multiple_choice))
+        .end_time(poll_end_time.map(Into::into))
+        .build();
+      let poll = PostPoll::upsert(context.pool(), &poll_form).await?;
+      let option_forms = options
+        .iter()
+        .map(|o| {
+          PostPollOptionForm::builder()
+            .poll_id(poll.id)
+            .name(o.name.clone())
+            .vote_count(Some(o.replies.total_items))
+            .build()
+        })
+        .collect::<Vec<_>>();
+      PostPollOption::upsert(context.pool(), &option_forms).await?;
+    }
+
+    if let Some(tags) = tags {
+      PostTag::set(context.pool(), post.id, tags).await?;
+    }
+
     // write mod log entry for lock
     if Page::is_locked_changed(&old_post, &page.comments_enabled) {
       let form = ModLockPostForm {
@@ -275,6 +354,57 @@ impl Object for ApubPost {
   }
 }
 
+/// Finds the tags of a received post. Tags are defined by the instance of the community, so
+/// hashtags from elsewhere are ignored. Those of remote communities are stored when they are first
+/// seen, while those of local communities must exist already.
+async fn receive_tags(
+  tags: &[HashtagOrValue],
+  community: &ApubCommunity,
+  context: &Data<LemmyContext>,
+) -> Result<Vec<CommunityPostTagId>, LemmyError> {
+  let mut tag_ids = vec![];
+  for tag in tags {
+    let HashtagOrValue::Hashtag(hashtag) = tag else {
+      continue;
+    };
+    if hashtag.href.domain() != community.actor_id.inner().domain()
+      || is_valid_post_tag_name(hashtag.tag_name()).is_err()
+    {
+      continue;
+    }
+    let existing =
+      CommunityPostTag::read_from_apub_id(context.pool(), hashtag.href.clone()).await?;
+    let tag = match existing {
+      Some(t) if t.community_id != community.id => continue,
+      Some(t) if community.local => t,
+      None if community.local => continue,
+      _ => {
+        let form = CommunityPostTagInsertForm::builder()
+          .community_id(community.id)
+          .name(hashtag.tag_name().to_string())
+          .ap_id(Some(hashtag.href.clone().into()))
+          .build();
+        // The community might have replaced a tag with another one of the same name. The post is
+        // still accepted, only without the tag.
+        match CommunityPostTag::upsert(context.pool(), &form).await {
+          Ok(tag) => tag,
+          Err(DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            warn!(
+              "Tag {} conflicts with another tag of community {}",
+              hashtag.href,
+              community.actor_id.inner()
+            );
+            continue;
+          }
+          Err(e) => return Err(e.into()),
+        }
+      }
+    };
+    tag_ids.push(tag.id);
+  }
+  Ok(tag_ids)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -310,6 +440,13 @@ mod tests {
     assert!(!post.featured_community);
     assert_eq!(context.request_count(), 0);
 
+    let tags = CommunityPostTag::list_for_post(context.pool(), post.id)
+      .await
+      .unwrap();
+    assert_eq!(1, tags.len());
+    assert_eq!("Meta", tags[0].name);
+    assert_eq!(community.id, tags[0].community_id);
+
     Post::delete(context.pool(), post.id).await.unwrap();
     Person::delete(context.pool(), person.id).await.unwrap();
     Community::delete(context.pool(), community.id)
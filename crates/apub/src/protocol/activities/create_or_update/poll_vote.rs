@@ -0,0 +1,20 @@
+use crate::{objects::person::ApubPerson, protocol::objects::poll_vote::PollVote};
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::CreateType,
+  protocol::helpers::deserialize_one,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePollVote {
+  pub(crate) id: Url,
+  pub(crate) actor: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "deserialize_one")]
+  pub(crate) to: [ObjectId<ApubPerson>; 1],
+  pub(crate) object: PollVote,
+  #[serde(rename = "type")]
+  pub(crate) kind: CreateType,
+}
@@ -1,5 +1,6 @@
 use crate::{
-  objects::{community::ApubCommunity, person::ApubPerson},
+  fetcher::user_or_community::UserOrCommunity,
+  objects::person::ApubPerson,
   protocol::activities::following::follow::Follow,
 };
 use activitypub_federation::{
@@ -13,7 +14,7 @@ use url::Url;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AcceptFollow {
-  pub(crate) actor: ObjectId<ApubCommunity>,
+  pub(crate) actor: ObjectId<UserOrCommunity>,
   /// Optional, for compatibility with platforms that always expect recipient field
   #[serde(deserialize_with = "deserialize_skip_error", default)]
   pub(crate) to: Option<[ObjectId<ApubPerson>; 1]>,
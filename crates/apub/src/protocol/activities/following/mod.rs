@@ -1,11 +1,19 @@
 pub(crate) mod accept;
 pub mod follow;
+pub mod move_person;
+pub(crate) mod reject;
 pub mod undo_follow;
 
 #[cfg(test)]
 mod tests {
   use crate::protocol::{
-    activities::following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+    activities::following::{
+      accept::AcceptFollow,
+      follow::Follow,
+      move_person::MovePerson,
+      reject::RejectFollow,
+      undo_follow::UndoFollow,
+    },
     tests::test_parse_lemmy_item,
   };
 
@@ -13,7 +21,10 @@ mod tests {
   fn test_parse_lemmy_accept_follow() {
     test_parse_lemmy_item::<Follow>("assets/lemmy/activities/following/follow.json").unwrap();
     test_parse_lemmy_item::<AcceptFollow>("assets/lemmy/activities/following/accept.json").unwrap();
+    test_parse_lemmy_item::<RejectFollow>("assets/lemmy/activities/following/reject.json").unwrap();
     test_parse_lemmy_item::<UndoFollow>("assets/lemmy/activities/following/undo_follow.json")
       .unwrap();
+    test_parse_lemmy_item::<MovePerson>("assets/lemmy/activities/following/move_person.json")
+      .unwrap();
   }
 }
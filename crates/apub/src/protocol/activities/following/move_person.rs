@@ -0,0 +1,19 @@
+use crate::objects::person::ApubPerson;
+use activitypub_federation::{fetch::object_id::ObjectId, kinds::activity::MoveType};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent to the followers of an account which moved to another account, in the same format as
+/// Mastodon uses for account migration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovePerson {
+  pub(crate) actor: ObjectId<ApubPerson>,
+  /// The account which moved, always the same as the actor
+  pub(crate) object: ObjectId<ApubPerson>,
+  /// The account which it moved to
+  pub(crate) target: ObjectId<ApubPerson>,
+  #[serde(rename = "type")]
+  pub(crate) kind: MoveType,
+  pub(crate) id: Url,
+}
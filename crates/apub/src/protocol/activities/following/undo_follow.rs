@@ -1,4 +1,8 @@
-use crate::{objects::person::ApubPerson, protocol::activities::following::follow::Follow};
+use crate::{
+  fetcher::user_or_community::UserOrCommunity,
+  objects::person::ApubPerson,
+  protocol::activities::following::follow::Follow,
+};
 use activitypub_federation::{
   fetch::object_id::ObjectId,
   kinds::activity::UndoType,
@@ -13,7 +17,7 @@ pub struct UndoFollow {
   pub(crate) actor: ObjectId<ApubPerson>,
   /// Optional, for compatibility with platforms that always expect recipient field
   #[serde(deserialize_with = "deserialize_skip_error", default)]
-  pub(crate) to: Option<[ObjectId<ApubPerson>; 1]>,
+  pub(crate) to: Option<[ObjectId<UserOrCommunity>; 1]>,
   pub(crate) object: Follow,
   #[serde(rename = "type")]
   pub(crate) kind: UndoType,
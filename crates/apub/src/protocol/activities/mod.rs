@@ -21,7 +21,7 @@ mod tests {
       community::announce::AnnounceActivity,
       create_or_update::{note::CreateOrUpdateNote, page::CreateOrUpdatePage},
       deletion::delete::Delete,
-      following::{follow::Follow, undo_follow::UndoFollow},
+      following::{follow::Follow, move_person::MovePerson, undo_follow::UndoFollow},
       voting::{undo_vote::UndoVote, vote::Vote},
     },
     tests::test_json,
@@ -45,6 +45,7 @@ mod tests {
     test_json::<Delete>("assets/mastodon/activities/delete.json").unwrap();
     test_json::<Follow>("assets/mastodon/activities/follow.json").unwrap();
     test_json::<UndoFollow>("assets/mastodon/activities/undo_follow.json").unwrap();
+    test_json::<MovePerson>("assets/mastodon/activities/move.json").unwrap();
     test_json::<Vote>("assets/mastodon/activities/like_page.json").unwrap();
     test_json::<UndoVote>("assets/mastodon/activities/undo_like_page.json").unwrap();
   }
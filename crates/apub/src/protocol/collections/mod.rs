@@ -3,6 +3,7 @@ pub(crate) mod group_featured;
 pub(crate) mod group_followers;
 pub(crate) mod group_moderators;
 pub(crate) mod group_outbox;
+pub(crate) mod person_followers;
 
 #[cfg(test)]
 mod tests {
@@ -13,6 +14,7 @@ mod tests {
       group_followers::GroupFollowers,
       group_moderators::GroupModerators,
       group_outbox::GroupOutbox,
+      person_followers::PersonFollowers,
     },
     tests::{test_json, test_parse_lemmy_item},
   };
@@ -29,6 +31,8 @@ mod tests {
     test_parse_lemmy_item::<GroupModerators>("assets/lemmy/collections/group_moderators.json")
       .unwrap();
     test_parse_lemmy_item::<EmptyOutbox>("assets/lemmy/collections/person_outbox.json").unwrap();
+    test_parse_lemmy_item::<PersonFollowers>("assets/lemmy/collections/person_followers.json")
+      .unwrap();
   }
 
   #[test]
@@ -0,0 +1,31 @@
+use activitypub_federation::kinds::collection::CollectionType;
+use lemmy_api_common::{context::LemmyContext, utils::generate_followers_url};
+use lemmy_db_schema::source::person::{Person, PersonFollower};
+use lemmy_utils::error::LemmyError;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PersonFollowers {
+  id: Url,
+  r#type: CollectionType,
+  total_items: i32,
+  items: Vec<()>,
+}
+
+impl PersonFollowers {
+  pub(crate) async fn new(
+    person: Person,
+    context: &LemmyContext,
+  ) -> Result<PersonFollowers, LemmyError> {
+    let person_followers = PersonFollower::count_followers(context.pool(), person.id).await?;
+
+    Ok(PersonFollowers {
+      id: generate_followers_url(&person.actor_id)?.into(),
+      r#type: CollectionType::Collection,
+      total_items: person_followers as i32,
+      items: vec![],
+    })
+  }
+}
@@ -28,6 +28,7 @@ use lemmy_db_schema::{
   newtypes::InstanceId,
   source::community::{CommunityInsertForm, CommunityUpdateForm},
   utils::naive_now,
+  CommunityVisibility,
 };
 use lemmy_utils::{
   error::LemmyError,
@@ -65,6 +66,10 @@ pub struct Group {
   pub(crate) attributed_to: Option<CollectionId<ApubCommunityModerators>>,
   // lemmy extension
   pub(crate) posting_restricted_to_mods: Option<bool>,
+  /// Follows need to be approved by a mod, and only followers can see the content
+  pub(crate) manually_approves_followers: Option<bool>,
+  // lemmy extension
+  pub(crate) edit_history_public: Option<bool>,
   pub(crate) outbox: CollectionId<ApubCommunityOutbox>,
   pub(crate) endpoints: Option<Endpoints>,
   pub(crate) featured: Option<CollectionId<ApubCommunityFeatured>>,
@@ -93,7 +98,18 @@ impl Group {
     Ok(())
   }
 
+  /// Local only communities are never federated, so a remote community is either public or
+  /// requires approval.
+  fn visibility(&self) -> CommunityVisibility {
+    if self.manually_approves_followers.unwrap_or(false) {
+      CommunityVisibility::ApprovalRequired
+    } else {
+      CommunityVisibility::Public
+    }
+  }
+
   pub(crate) fn into_insert_form(self, instance_id: InstanceId) -> CommunityInsertForm {
+    let visibility = self.visibility();
     CommunityInsertForm {
       name: self.preferred_username.clone(),
       title: self.name.unwrap_or(self.preferred_username),
@@ -118,10 +134,13 @@ impl Group {
       posting_restricted_to_mods: self.posting_restricted_to_mods,
       instance_id,
       featured_url: self.featured.map(Into::into),
+      visibility: Some(visibility),
+      edit_history_public: self.edit_history_public,
     }
   }
 
   pub(crate) fn into_update_form(self) -> CommunityUpdateForm {
+    let visibility = self.visibility();
     CommunityUpdateForm {
       title: Some(self.name.unwrap_or(self.preferred_username)),
       description: Some(read_from_string_or_source_opt(
@@ -148,6 +167,8 @@ impl Group {
       moderators_url: self.attributed_to.map(Into::into),
       posting_restricted_to_mods: self.posting_restricted_to_mods,
       featured_url: self.featured.map(Into::into),
+      visibility: Some(visibility),
+      edit_history_public: self.edit_history_public,
     }
   }
 }
@@ -14,6 +14,7 @@ pub(crate) mod instance;
 pub(crate) mod note;
 pub(crate) mod page;
 pub(crate) mod person;
+pub(crate) mod poll_vote;
 pub(crate) mod tombstone;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -137,6 +138,7 @@ mod tests {
     test_json::<Person>("assets/mastodon/objects/person.json").unwrap();
     test_json::<Note>("assets/mastodon/objects/note.json").unwrap();
     test_json::<Page>("assets/mastodon/objects/page.json").unwrap();
+    test_json::<Page>("assets/mastodon/objects/question.json").unwrap();
   }
 
   #[test]
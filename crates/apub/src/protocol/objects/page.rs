@@ -8,8 +8,9 @@ use activitypub_federation::{
   config::Data,
   fetch::object_id::ObjectId,
   kinds::{
+    collection::CollectionType,
     link::LinkType,
-    object::{DocumentType, ImageType},
+    object::{DocumentType, ImageType, NoteType},
   },
   protocol::{
     helpers::{deserialize_one_or_many, deserialize_skip_error},
@@ -20,9 +21,10 @@ use activitypub_federation::{
 use chrono::{DateTime, FixedOffset};
 use itertools::Itertools;
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::newtypes::DbUrl;
+use lemmy_db_schema::{newtypes::DbUrl, source::community_post_tag::CommunityPostTag};
 use lemmy_utils::error::LemmyError;
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use serde_with::skip_serializing_none;
 use url::Url;
 
@@ -33,6 +35,7 @@ pub enum PageType {
   Note,
   Video,
   Event,
+  Question,
 }
 
 #[skip_serializing_none]
@@ -67,6 +70,59 @@ pub struct Page {
   pub(crate) updated: Option<DateTime<FixedOffset>>,
   pub(crate) language: Option<LanguageTag>,
   pub(crate) audience: Option<ObjectId<ApubCommunity>>,
+  /// Options of a single choice poll, only present if the type is Question
+  pub(crate) one_of: Option<Vec<PollOption>>,
+  /// Options of a multiple choice poll, only present if the type is Question
+  pub(crate) any_of: Option<Vec<PollOption>>,
+  pub(crate) end_time: Option<DateTime<FixedOffset>>,
+  /// Mastodon sends the time when the poll was closed, others only a boolean
+  #[serde(deserialize_with = "deserialize_skip_error", default)]
+  pub(crate) closed: Option<DateTime<FixedOffset>>,
+  /// The tags of the community which apply to the post. Other software also puts mentions and
+  /// its own hashtags here.
+  #[serde(default)]
+  pub(crate) tag: Vec<HashtagOrValue>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum HashtagType {
+  Hashtag,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum HashtagOrValue {
+  Hashtag(Hashtag),
+  Value(Value),
+}
+
+/// A post tag of a community, identified by its `href`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Hashtag {
+  #[serde(rename = "type")]
+  pub(crate) kind: HashtagType,
+  pub(crate) href: Url,
+  pub(crate) name: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PollOption {
+  #[serde(rename = "type")]
+  pub(crate) kind: NoteType,
+  pub(crate) name: String,
+  #[serde(default)]
+  pub(crate) replies: PollOptionReplies,
+}
+
+/// Only the number of votes is federated, not the individual votes.
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PollOptionReplies {
+  #[serde(rename = "type")]
+  pub(crate) kind: CollectionType,
+  pub(crate) total_items: i32,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -154,6 +210,15 @@ impl Page {
     false
   }
 
+  /// Returns the poll options, and whether multiple of them can be chosen.
+  pub(crate) fn poll_options(&self) -> Option<(&Vec<PollOption>, bool)> {
+    match (&self.one_of, &self.any_of) {
+      (Some(options), _) => Some((options, false)),
+      (None, Some(options)) => Some((options, true)),
+      (None, None) => None,
+    }
+  }
+
   pub(crate) fn creator(&self) -> Result<ObjectId<ApubPerson>, LemmyError> {
     match &self.attributed_to {
       AttributedTo::Lemmy(l) => Ok(l.clone()),
@@ -166,6 +231,34 @@ impl Page {
   }
 }
 
+impl PollOption {
+  pub(crate) fn new(name: String, vote_count: i32) -> PollOption {
+    PollOption {
+      kind: Default::default(),
+      name,
+      replies: PollOptionReplies {
+        kind: Default::default(),
+        total_items: vote_count,
+      },
+    }
+  }
+}
+
+impl Hashtag {
+  pub(crate) fn new(tag: CommunityPostTag) -> Hashtag {
+    Hashtag {
+      kind: HashtagType::Hashtag,
+      href: tag.ap_id.into(),
+      name: format!("#{}", tag.name),
+    }
+  }
+
+  /// The tag name without the leading `#`.
+  pub(crate) fn tag_name(&self) -> &str {
+    self.name.strip_prefix('#').unwrap_or(&self.name)
+  }
+}
+
 impl Attachment {
   pub(crate) fn new(url: DbUrl) -> Attachment {
     Attachment::Link(Link {
@@ -242,10 +335,26 @@ where
 
 #[cfg(test)]
 mod tests {
-  use crate::protocol::{objects::page::Page, tests::test_parse_lemmy_item};
+  use crate::protocol::{
+    objects::page::{Page, PageType},
+    tests::{test_json, test_parse_lemmy_item},
+  };
 
   #[test]
   fn test_not_parsing_note_as_page() {
     assert!(test_parse_lemmy_item::<Page>("assets/lemmy/objects/note.json").is_err());
   }
+
+  #[test]
+  fn test_parse_poll() {
+    let page = test_json::<Page>("assets/mastodon/objects/question.json").unwrap();
+    let page = page.inner();
+    assert_eq!(page.kind, PageType::Question);
+    let (options, multiple_choice) = page.poll_options().unwrap();
+    assert!(!multiple_choice);
+    assert_eq!(options.len(), 2);
+    assert_eq!(options[0].name, "Thinkpad");
+    assert_eq!(options[0].replies.total_items, 2);
+    assert!(page.end_time.is_some());
+  }
 }
@@ -30,6 +30,8 @@ pub struct Person {
   pub(crate) inbox: Url,
   /// mandatory field in activitypub, lemmy currently serves an empty outbox
   pub(crate) outbox: Url,
+  /// only contains the number of followers
+  pub(crate) followers: Option<Url>,
   pub(crate) public_key: PublicKey,
 
   /// displayname
@@ -45,4 +47,9 @@ pub struct Person {
   pub(crate) endpoints: Option<Endpoints>,
   pub(crate) published: Option<DateTime<FixedOffset>>,
   pub(crate) updated: Option<DateTime<FixedOffset>>,
+  /// other accounts of the person, which are allowed to move to this account
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub(crate) also_known_as: Vec<Url>,
+  /// the account which the person moved to
+  pub(crate) moved_to: Option<ObjectId<ApubPerson>>,
 }
@@ -0,0 +1,22 @@
+use crate::objects::{person::ApubPerson, post::ApubPost};
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::object::NoteType,
+  protocol::helpers::deserialize_one_or_many,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// A vote in a poll, in the format used by Mastodon. It is a reply to the poll without content,
+/// and the name of the chosen option.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollVote {
+  pub(crate) r#type: NoteType,
+  pub(crate) id: Url,
+  pub(crate) attributed_to: ObjectId<ApubPerson>,
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) name: String,
+  pub(crate) in_reply_to: ObjectId<ApubPost>,
+}
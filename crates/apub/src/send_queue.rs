@@ -0,0 +1,548 @@
+//! Outgoing activities are stored in the database and delivered in the background, with one
+//! worker per remote instance. Each worker keeps a cursor in `federation_queue_state` which points
+//! to the last delivered activity, so that deliveries resume where they stopped after a restart.
+//! Failed deliveries are retried with exponential backoff, without blocking other instances.
+//! Activities which can never be delivered, for example because the remote instance rejected
+//! them, are skipped instead. Activities for instances which are marked as dead are skipped too.
+//!
+//! The activity queue of `activitypub_federation` keeps activities only in memory and doesn't
+//! report the outcome of deliveries, and the library doesn't expose its request signing. So
+//! requests are signed here the same way as the library does, with the same crates.
+
+use crate::{check_apub_id_valid, local_site_data_cached};
+use activitypub_federation::{config::FederationConfig, FEDERATION_CONTENT_TYPE};
+use anyhow::anyhow;
+use chrono::Utc;
+use http_signature_normalization_reqwest::prelude::{Config, SignExt};
+use itertools::Itertools;
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::{
+  newtypes::{DbUrl, InstanceId},
+  source::{
+    activity::Activity,
+    community::Community,
+    federation_queue_state::{FederationQueueState, FederationQueueStateForm},
+    instance::Instance,
+    person::Person,
+    sent_activity::{SentActivity, SentActivityForm},
+    site::Site,
+  },
+  traits::{ApubActor, Crud},
+  ActorType,
+};
+use lemmy_utils::{error::LemmyError, REQWEST_TIMEOUT};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use openssl::{
+  hash::MessageDigest,
+  pkey::{PKey, Private},
+  sign::Signer,
+};
+use reqwest::{
+  header::{HeaderMap, HeaderName, HeaderValue},
+  StatusCode,
+};
+use sha2::{Digest, Sha256};
+use std::{
+  collections::HashMap,
+  sync::Arc,
+  time::{Duration, Instant},
+};
+use tokio::{
+  sync::{watch, Semaphore, SemaphorePermit},
+  task::JoinHandle,
+  time::sleep,
+};
+use tracing::{debug, warn};
+use url::Url;
+
+/// How often to check for newly queued activities
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How often to check for newly discovered instances which need a worker
+const INSTANCE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Number of queued activities which a worker reads from the database at once
+const BATCH_SIZE: i64 = 100;
+/// Delay before the first retry of a failed delivery, it doubles with each further failure
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(10);
+/// Upper limit for the delay between retries
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Options which must match those of the [FederationConfig], as the queue sends activities
+/// itself instead of using the activity queue of the library.
+#[derive(Clone, Copy)]
+pub struct SendQueueOptions {
+  /// Allows sending to http and localhost inboxes, and checks for new instances more often.
+  pub debug: bool,
+  /// Signs requests in a way that Mastodon also understands.
+  pub http_signature_compat: bool,
+  /// Maximum number of deliveries in flight at the same time, 0 for no limit.
+  pub worker_count: usize,
+  /// Maximum number of retries of failed deliveries in flight at the same time, 0 for no limit.
+  pub retry_count: usize,
+}
+
+/// Limits the concurrent deliveries of all instance workers together.
+struct DeliveryLimits {
+  deliveries: Option<Semaphore>,
+  retries: Option<Semaphore>,
+}
+
+impl DeliveryLimits {
+  fn new(options: SendQueueOptions) -> Self {
+    let semaphore = |count: usize| (count > 0).then(|| Semaphore::new(count));
+    DeliveryLimits {
+      deliveries: semaphore(options.worker_count),
+      retries: semaphore(options.retry_count),
+    }
+  }
+
+  /// Waits until another delivery or retry may be started.
+  async fn acquire(&self, retry: bool) -> Option<SemaphorePermit<'_>> {
+    let semaphore = if retry {
+      &self.retries
+    } else {
+      &self.deliveries
+    };
+    // The semaphores are never closed
+    semaphore.as_ref()?.acquire().await.ok()
+  }
+}
+
+/// Stores an activity for delivery to the given inboxes. Inboxes on the local instance are
+/// skipped.
+pub(crate) async fn queue_activity(
+  activity: &Activity,
+  actor_type: ActorType,
+  actor_apub_id: Url,
+  inboxes: Vec<Url>,
+  context: &LemmyContext,
+) -> Result<(), LemmyError> {
+  let local_domain = context.settings().get_hostname_without_port()?;
+  let send_inboxes: Vec<Option<DbUrl>> = inboxes
+    .into_iter()
+    .unique()
+    .filter(|inbox| inbox.domain() != Some(local_domain.as_str()))
+    .map(|inbox| Some(inbox.into()))
+    .collect();
+  if send_inboxes.is_empty() {
+    return Ok(());
+  }
+
+  let form = SentActivityForm {
+    activity_id: activity.id,
+    send_inboxes,
+    actor_type,
+    actor_apub_id: actor_apub_id.into(),
+  };
+  SentActivity::create(context.pool(), form).await?;
+  Ok(())
+}
+
+/// Starts a background task which delivers queued activities to all known remote instances.
+pub fn start_send_queue(
+  federation_config: FederationConfig<LemmyContext>,
+  options: SendQueueOptions,
+) -> Result<JoinHandle<()>, LemmyError> {
+  let local_domain = federation_config
+    .to_request_data()
+    .settings()
+    .get_hostname_without_port()?;
+  let limits = Arc::new(DeliveryLimits::new(options));
+  Ok(tokio::spawn(async move {
+    let context = federation_config.to_request_data();
+    let (latest_id_sender, latest_id) = watch::channel(0);
+    let mut workers = HashMap::<InstanceId, JoinHandle<()>>::new();
+    let mut last_instance_refresh = None;
+    loop {
+      match SentActivity::read_max_id(context.pool()).await {
+        Ok(max_id) => {
+          latest_id_sender.send_replace(max_id.unwrap_or(0));
+        }
+        Err(e) => warn!("Failed to read queued activities: {e}"),
+      }
+
+      let refresh_interval = if options.debug {
+        POLL_INTERVAL
+      } else {
+        INSTANCE_REFRESH_INTERVAL
+      };
+      let refresh_due = last_instance_refresh
+        .map(|t: tokio::time::Instant| t.elapsed() > refresh_interval)
+        .unwrap_or(true);
+      if refresh_due {
+        last_instance_refresh = Some(tokio::time::Instant::now());
+        match Instance::linked(context.pool()).await {
+          Ok(instances) => {
+            for instance in instances {
+              if instance.domain == local_domain || workers.contains_key(&instance.id) {
+                continue;
+              }
+              let worker = InstanceWorker {
+                instance: instance.clone(),
+                federation_config: federation_config.clone(),
+                options,
+                limits: limits.clone(),
+                latest_id: latest_id.clone(),
+              };
+              workers.insert(instance.id, tokio::spawn(worker.run()));
+            }
+          }
+          Err(e) => warn!("Failed to read instances for sending activities: {e}"),
+        }
+      }
+
+      sleep(POLL_INTERVAL).await;
+    }
+  }))
+}
+
+struct InstanceWorker {
+  instance: Instance,
+  federation_config: FederationConfig<LemmyContext>,
+  options: SendQueueOptions,
+  limits: Arc<DeliveryLimits>,
+  /// Id of the most recently queued activity
+  latest_id: watch::Receiver<i32>,
+}
+
+impl InstanceWorker {
+  async fn run(mut self) {
+    let context = self.federation_config.to_request_data();
+    let mut state = loop {
+      match self.initial_state(&context).await {
+        Ok(state) => break state,
+        Err(e) => {
+          warn!(
+            "Failed to read queue state for {}: {e}",
+            self.instance.domain
+          );
+          sleep(POLL_INTERVAL).await;
+        }
+      }
+    };
+    loop {
+      // Wait until there is something new to send
+      while *self.latest_id.borrow() <= state.last_successful_id {
+        if self.latest_id.changed().await.is_err() {
+          return;
+        }
+      }
+      if let Err(e) = self.send_batch(&mut state, &context).await {
+        warn!("Failed to send activities to {}: {e}", self.instance.domain);
+        sleep(POLL_INTERVAL).await;
+      }
+    }
+  }
+
+  /// Reads the stored queue state. If this instance didn't have any, start sending from the
+  /// first activity which is addressed to it. Workers for new instances are only spawned
+  /// periodically, so activities may have been queued for the instance before that.
+  async fn initial_state(
+    &self,
+    context: &LemmyContext,
+  ) -> Result<FederationQueueStateForm, LemmyError> {
+    let state = FederationQueueState::read(context.pool(), self.instance.id).await?;
+    Ok(match state {
+      Some(state) => FederationQueueStateForm {
+        instance_id: state.instance_id,
+        last_successful_id: state.last_successful_id,
+        fail_count: state.fail_count,
+        last_retry: state.last_retry,
+        last_successful_send: state.last_successful_send,
+        inbox_latency_ms: state.inbox_latency_ms,
+      },
+      None => {
+        // Read the newest id first, so that activities which are queued in between are still
+        // found by the second query
+        let latest_id = SentActivity::read_max_id(context.pool()).await?;
+        let first_id =
+          SentActivity::read_first_id_for_domain(context.pool(), &self.instance.domain).await?;
+        let form = FederationQueueStateForm {
+          instance_id: self.instance.id,
+          last_successful_id: first_id
+            .map(|first_id| first_id - 1)
+            .or(latest_id)
+            .unwrap_or(0),
+          fail_count: 0,
+          last_retry: None,
+          last_successful_send: None,
+          inbox_latency_ms: None,
+        };
+        FederationQueueState::upsert(context.pool(), &form).await?;
+        form
+      }
+    })
+  }
+
+  /// Delivers the next batch of queued activities, retrying each failed delivery until it
+  /// succeeds or the instance is marked as dead. Activities which fail permanently are skipped.
+  async fn send_batch(
+    &self,
+    state: &mut FederationQueueStateForm,
+    context: &LemmyContext,
+  ) -> Result<(), LemmyError> {
+    let pool = context.pool();
+    let activities = SentActivity::read_after(pool, state.last_successful_id, BATCH_SIZE).await?;
+    let mut dead = self.is_dead(context).await?;
+    for sent in activities {
+      let inboxes = if dead {
+        vec![]
+      } else {
+        self.inboxes_for_instance(&sent, context).await?
+      };
+      let needs_delivery = !inboxes.is_empty();
+      if needs_delivery {
+        loop {
+          if state.fail_count > 0 {
+            sleep_until_retry(state).await;
+            dead = self.is_dead(context).await?;
+            if dead {
+              debug!(
+                "Skipping activities for dead instance {}",
+                self.instance.domain
+              );
+              break;
+            }
+          }
+          let permit = self.limits.acquire(state.fail_count > 0).await;
+          let result = deliver(&sent, &inboxes, self.options, context).await;
+          drop(permit);
+          match result {
+            Ok(latency) => {
+              state.last_successful_send = Some(Utc::now());
+              state.inbox_latency_ms = Some(i32::try_from(latency.as_millis()).unwrap_or(i32::MAX));
+              break;
+            }
+            Err(DeliveryError::Permanent(e)) => {
+              warn!(
+                "Skipping activity {} for {}, it can't be delivered: {e}",
+                sent.activity_id, self.instance.domain
+              );
+              break;
+            }
+            Err(DeliveryError::Temporary(e)) => {
+              state.fail_count += 1;
+              state.last_retry = Some(Utc::now());
+              FederationQueueState::upsert(pool, state).await?;
+              warn!(
+                "Failed to deliver activity {} to {} ({} failures): {e}",
+                sent.activity_id, self.instance.domain, state.fail_count
+              );
+            }
+          }
+        }
+        if !dead {
+          state.fail_count = 0;
+          state.last_retry = None;
+        }
+      }
+      state.last_successful_id = sent.id;
+      if needs_delivery {
+        FederationQueueState::upsert(pool, state).await?;
+      }
+    }
+    // Also store progress over activities which didn't need to be sent to this instance
+    FederationQueueState::upsert(pool, state).await?;
+    Ok(())
+  }
+
+  /// Dead instances don't get any activities, they are marked by a scheduled task.
+  async fn is_dead(&self, context: &LemmyContext) -> Result<bool, LemmyError> {
+    Ok(Instance::read(context.pool(), self.instance.id).await?.dead)
+  }
+
+  /// Inboxes of the activity which belong to this instance, if the instance isn't blocked.
+  /// Outside of debug mode, http and localhost inboxes are left out, like the library does.
+  async fn inboxes_for_instance(
+    &self,
+    sent: &SentActivity,
+    context: &LemmyContext,
+  ) -> Result<Vec<Url>, LemmyError> {
+    let local_site_data = local_site_data_cached(context.pool()).await?;
+    Ok(
+      sent
+        .send_inboxes
+        .iter()
+        .flatten()
+        .map(|inbox| inbox.inner().clone())
+        .filter(|inbox| inbox.domain() == Some(self.instance.domain.as_str()))
+        .filter(|inbox| check_apub_id_valid(inbox, &local_site_data).is_ok())
+        .filter(|inbox| {
+          self.options.debug || (inbox.scheme() == "https" && inbox.domain() != Some("localhost"))
+        })
+        .collect(),
+    )
+  }
+}
+
+/// Waits until the next retry is due, based on the number of previous failures.
+async fn sleep_until_retry(state: &FederationQueueStateForm) {
+  let exponent = u32::try_from(state.fail_count - 1).unwrap_or(0).min(16);
+  let delay = (INITIAL_RETRY_DELAY * 2_u32.pow(exponent)).min(MAX_RETRY_DELAY);
+  let elapsed = state
+    .last_retry
+    .and_then(|last_retry| (Utc::now() - last_retry).to_std().ok())
+    .unwrap_or_default();
+  if let Some(remaining) = delay.checked_sub(elapsed) {
+    sleep(remaining).await;
+  }
+}
+
+/// Why an activity couldn't be delivered.
+enum DeliveryError {
+  /// Retrying won't help, for example because the activity or its actor were deleted.
+  Permanent(LemmyError),
+  /// The remote instance may accept the activity later.
+  Temporary(LemmyError),
+}
+
+impl<T: Into<LemmyError>> From<T> for DeliveryError {
+  /// Network and database errors are usually temporary.
+  fn from(e: T) -> Self {
+    DeliveryError::Temporary(e.into())
+  }
+}
+
+/// Signs the activity and sends it to each of the inboxes. Rejections by the remote instance (4xx
+/// status other than 429) count as delivered, because retrying wouldn't change the result.
+/// Returns how long the slowest inbox took to respond.
+async fn deliver(
+  sent: &SentActivity,
+  inboxes: &[Url],
+  options: SendQueueOptions,
+  context: &LemmyContext,
+) -> Result<Duration, DeliveryError> {
+  let activity = match Activity::read(context.pool(), sent.activity_id).await {
+    Ok(activity) => activity,
+    Err(diesel::result::Error::NotFound) => {
+      return Err(DeliveryError::Permanent(
+        anyhow!("activity was deleted").into(),
+      ))
+    }
+    Err(e) => return Err(e.into()),
+  };
+  let body = serde_json::to_vec(&activity.data).map_err(|e| DeliveryError::Permanent(e.into()))?;
+  let private_key = actor_private_key(sent.actor_type, &sent.actor_apub_id, context)
+    .await?
+    .ok_or_else(|| {
+      let actor = &sent.actor_apub_id;
+      DeliveryError::Permanent(anyhow!("actor {actor} has no private key").into())
+    })?;
+  let mut config = Config::new().set_expiration(Duration::from_secs(60 * 60));
+  if options.http_signature_compat {
+    config = config.mastodon_compat();
+  }
+  let mut latency = Duration::ZERO;
+
+  for inbox in inboxes {
+    let headers = request_headers(inbox).map_err(DeliveryError::Permanent)?;
+    let request_builder = context
+      .client()
+      .post(inbox.as_str())
+      .timeout(REQWEST_TIMEOUT)
+      .headers(headers);
+    let request = request_builder
+      .signature_with_digest(
+        config.clone(),
+        main_key_id(sent.actor_apub_id.inner()),
+        Sha256::new(),
+        body.clone(),
+        {
+          let private_key = private_key.clone();
+          move |signing_string| {
+            let mut signer = Signer::new(MessageDigest::sha256(), &private_key)?;
+            signer.update(signing_string.as_bytes())?;
+            Ok(base64::encode(signer.sign_to_vec()?)) as Result<_, anyhow::Error>
+          }
+        },
+      )
+      .await
+      .map_err(|e| DeliveryError::Permanent(e.into()))?;
+
+    let start = Instant::now();
+    let response = context.client().execute(request).await?;
+    latency = latency.max(start.elapsed());
+    let status = response.status();
+    if status.is_success() {
+      debug!("Activity {} delivered to {inbox}", activity.ap_id);
+    } else if status.is_client_error() && status != StatusCode::TOO_MANY_REQUESTS {
+      debug!(
+        "Activity {} was rejected by {inbox}: {status}",
+        activity.ap_id
+      );
+    } else {
+      return Err(DeliveryError::Temporary(
+        anyhow!("{inbox} responded with {status}").into(),
+      ));
+    }
+  }
+  Ok(latency)
+}
+
+/// Id of the actor's public key, as used in the `keyId` field of http signatures.
+fn main_key_id(actor_id: &Url) -> String {
+  let mut key_id = actor_id.clone();
+  key_id.set_fragment(Some("main-key"));
+  key_id.to_string()
+}
+
+fn request_headers(inbox: &Url) -> Result<HeaderMap, LemmyError> {
+  let mut host = inbox
+    .domain()
+    .ok_or_else(|| anyhow!("inbox {inbox} has no domain"))?
+    .to_string();
+  if let Some(port) = inbox.port() {
+    host = format!("{host}:{port}");
+  }
+  let mut headers = HeaderMap::new();
+  headers.insert(
+    HeaderName::from_static("content-type"),
+    HeaderValue::from_static(FEDERATION_CONTENT_TYPE),
+  );
+  headers.insert(
+    HeaderName::from_static("host"),
+    HeaderValue::from_str(&host)?,
+  );
+  headers.insert(
+    HeaderName::from_static("date"),
+    HeaderValue::from_str(&Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string())?,
+  );
+  Ok(headers)
+}
+
+/// Private keys are cached, because parsing them is relatively expensive and each activity
+/// needs to be signed once for every instance it is sent to. Returns `None` if the actor doesn't
+/// exist anymore or has no private key.
+async fn actor_private_key(
+  actor_type: ActorType,
+  actor_apub_id: &DbUrl,
+  context: &LemmyContext,
+) -> Result<Option<PKey<Private>>, LemmyError> {
+  static CACHE: Lazy<Cache<Url, Option<PKey<Private>>>> = Lazy::new(|| {
+    Cache::builder()
+      .max_capacity(1000)
+      .time_to_live(Duration::from_secs(60 * 60))
+      .build()
+  });
+  CACHE
+    .try_get_with(actor_apub_id.inner().clone(), async {
+      let pool = context.pool();
+      let private_key = match actor_type {
+        ActorType::Site => Site::read_from_apub_id(pool, actor_apub_id)
+          .await?
+          .and_then(|s| s.private_key),
+        ActorType::Community => Community::read_from_apub_id(pool, actor_apub_id)
+          .await?
+          .and_then(|c| c.private_key),
+        ActorType::Person => Person::read_from_apub_id(pool, actor_apub_id)
+          .await?
+          .and_then(|p| p.private_key),
+      };
+      let private_key = private_key
+        .map(|private_key| PKey::private_key_from_pem(private_key.as_bytes()))
+        .transpose()?;
+      Ok::<_, LemmyError>(private_key)
+    })
+    .await
+    .map_err(|e| anyhow!("{e}").into())
+}
@@ -1,6 +1,16 @@
 use crate::{
   newtypes::{CommentId, DbUrl, PersonId},
-  schema::comment::dsl::{ap_id, comment, content, creator_id, deleted, path, removed, updated},
+  schema::comment::dsl::{
+    ap_id,
+    comment,
+    content,
+    creator_id,
+    deleted,
+    path,
+    published,
+    removed,
+    updated,
+  },
   source::comment::{
     Comment,
     CommentInsertForm,
@@ -18,12 +28,32 @@ use diesel::{
   result::Error,
   ExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use diesel_ltree::Ltree;
 use url::Url;
 
 impl Comment {
+  /// Lists one page of the comments of a person, for the export of their data.
+  pub async fn list_for_creator(
+    pool: &DbPool,
+    for_creator_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment
+      .filter(creator_id.eq(for_creator_id))
+      .order_by(published.asc())
+      .then_order_by(crate::schema::comment::id)
+      .limit(limit)
+      .offset(offset)
+      .select(Self::as_select())
+      .load::<Self>(conn)
+      .await
+  }
+
   pub async fn permadelete_for_creator(
     pool: &DbPool,
     for_creator_id: PersonId,
@@ -36,6 +66,7 @@ impl Comment {
         deleted.eq(true),
         updated.eq(naive_now()),
       ))
+      .returning(Self::as_returning())
       .get_results::<Self>(conn)
       .await
   }
@@ -48,6 +79,7 @@ impl Comment {
     let conn = &mut get_conn(pool).await?;
     diesel::update(comment.filter(creator_id.eq(for_creator_id)))
       .set((removed.eq(new_removed), updated.eq(naive_now())))
+      .returning(Self::as_returning())
       .get_results::<Self>(conn)
       .await
   }
@@ -65,6 +97,7 @@ impl Comment {
       .on_conflict(ap_id)
       .do_update()
       .set(comment_form)
+      .returning(Self::as_returning())
       .get_result::<Self>(conn)
       .await;
 
@@ -83,6 +116,7 @@ impl Comment {
 
       let updated_comment = diesel::update(comment.find(comment_id))
         .set(path.eq(ltree))
+        .returning(Self::as_returning())
         .get_result::<Self>(conn)
         .await;
 
@@ -129,6 +163,7 @@ where ca.comment_id = c.id"
     Ok(
       comment
         .filter(ap_id.eq(object_id))
+        .select(Comment::as_select())
         .first::<Comment>(conn)
         .await
         .ok()
@@ -155,7 +190,11 @@ impl Crud for Comment {
   type IdType = CommentId;
   async fn read(pool: &DbPool, comment_id: CommentId) -> Result<Self, Error> {
     let conn = &mut get_conn(pool).await?;
-    comment.find(comment_id).first::<Self>(conn).await
+    comment
+      .find(comment_id)
+      .select(Self::as_select())
+      .first::<Self>(conn)
+      .await
   }
 
   async fn delete(pool: &DbPool, comment_id: CommentId) -> Result<usize, Error> {
@@ -176,6 +215,7 @@ impl Crud for Comment {
     let conn = &mut get_conn(pool).await?;
     diesel::update(comment.find(comment_id))
       .set(comment_form)
+      .returning(Self::as_returning())
       .get_result::<Self>(conn)
       .await
   }
@@ -213,6 +253,28 @@ impl Likeable for CommentLike {
   }
 }
 
+impl CommentLike {
+  /// Lists one page of the ap_ids of comments which a person voted on, with the score.
+  pub async fn list_for_person(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<(DbUrl, i16)>, Error> {
+    use crate::schema::comment_like::dsl::{comment_like, id, person_id, score};
+    let conn = &mut get_conn(pool).await?;
+    comment_like
+      .inner_join(comment)
+      .filter(person_id.eq(for_person_id))
+      .order_by(id)
+      .limit(limit)
+      .offset(offset)
+      .select((ap_id, score))
+      .load(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Saveable for CommentSaved {
   type Form = CommentSavedForm;
@@ -240,6 +302,28 @@ impl Saveable for CommentSaved {
   }
 }
 
+impl CommentSaved {
+  /// Lists one page of the ap_ids of comments which a person saved.
+  pub async fn list_for_person(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<DbUrl>, Error> {
+    use crate::schema::comment_saved::dsl::{comment_saved, id, person_id};
+    let conn = &mut get_conn(pool).await?;
+    comment_saved
+      .inner_join(comment)
+      .filter(person_id.eq(for_person_id))
+      .order_by(id)
+      .limit(limit)
+      .offset(offset)
+      .select(ap_id)
+      .load(conn)
+      .await
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use crate::{
@@ -1,6 +1,17 @@
 use crate::{
   newtypes::{CommentReportId, PersonId},
-  schema::comment_report::dsl::{comment_report, resolved, resolver_id, updated},
+  schema::comment_report::dsl::{
+    assignee_id,
+    automod,
+    comment_id,
+    comment_report,
+    creator_id,
+    escalated,
+    resolution_reason,
+    resolved,
+    resolver_id,
+    updated,
+  },
   source::comment_report::{CommentReport, CommentReportForm},
   traits::Reportable,
   utils::{get_conn, naive_now, DbPool},
@@ -9,6 +20,7 @@ use diesel::{
   dsl::{insert_into, update},
   result::Error,
   ExpressionMethods,
+  OptionalExtension,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
@@ -38,12 +50,14 @@ impl Reportable for CommentReport {
     pool: &DbPool,
     report_id_: Self::IdType,
     by_resolver_id: PersonId,
+    reason: Option<String>,
   ) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
     update(comment_report.find(report_id_))
       .set((
         resolved.eq(true),
         resolver_id.eq(by_resolver_id),
+        resolution_reason.eq(reason),
         updated.eq(naive_now()),
       ))
       .execute(conn)
@@ -65,9 +79,58 @@ impl Reportable for CommentReport {
       .set((
         resolved.eq(false),
         resolver_id.eq(by_resolver_id),
+        resolution_reason.eq(None::<String>),
         updated.eq(naive_now()),
       ))
       .execute(conn)
       .await
   }
+
+  /// assign a comment report to a moderator, or unassign it
+  ///
+  /// * `conn` - the postgres connection
+  /// * `report_id` - the id of the report to assign
+  /// * `to_assignee_id` - the id of the moderator who handles the report
+  async fn assign(
+    pool: &DbPool,
+    report_id_: Self::IdType,
+    to_assignee_id: Option<PersonId>,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(comment_report.find(report_id_))
+      .set((assignee_id.eq(to_assignee_id), updated.eq(naive_now())))
+      .execute(conn)
+      .await
+  }
+}
+
+impl CommentReport {
+  /// Creates a report by automod, unless the comment was already reported in the name of the same
+  /// person.
+  pub async fn report_automod(
+    pool: &DbPool,
+    form: &CommentReportForm,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(comment_report)
+      .values((form, automod.eq(true)))
+      .on_conflict((comment_id, creator_id))
+      .do_nothing()
+      .get_result::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// Hands the report over to the instance admins, or takes it back.
+  pub async fn escalate(
+    pool: &DbPool,
+    report_id: CommentReportId,
+    escalate: bool,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(comment_report.find(report_id))
+      .set((escalated.eq(escalate), updated.eq(naive_now())))
+      .execute(conn)
+      .await
+  }
 }
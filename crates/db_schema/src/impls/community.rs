@@ -1,6 +1,6 @@
 use crate::{
   newtypes::{CommunityId, DbUrl, PersonId},
-  schema::{community, instance},
+  schema::{community, community_follower, instance},
   source::{
     actor_language::CommunityLanguage,
     community::{
@@ -19,7 +19,15 @@ use crate::{
   utils::{functions::lower, get_conn, DbPool},
   SubscribedType,
 };
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel::{
+  dsl::{exists, insert_into, select},
+  result::Error,
+  upsert::excluded,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  QueryDsl,
+  SelectableHelper,
+};
 use diesel_async::RunQueryDsl;
 
 #[async_trait]
@@ -31,6 +39,7 @@ impl Crud for Community {
     let conn = &mut get_conn(pool).await?;
     community::table
       .find(community_id)
+      .select(Self::as_select())
       .first::<Self>(conn)
       .await
   }
@@ -55,6 +64,7 @@ impl Crud for Community {
       .on_conflict(community::actor_id)
       .do_update()
       .set(form)
+      .returning(Self::as_returning())
       .get_result::<Self>(conn)
       .await?;
 
@@ -74,6 +84,7 @@ impl Crud for Community {
     let conn = &mut get_conn(pool).await?;
     diesel::update(community::table.find(community_id))
       .set(form)
+      .returning(Self::as_returning())
       .get_result::<Self>(conn)
       .await
   }
@@ -126,6 +137,7 @@ impl Community {
     let conn = &mut get_conn(pool).await?;
     let res = community::table
       .filter(moderators_url.eq(url))
+      .select(Self::as_select())
       .first::<Self>(conn)
       .await;
     if let Ok(c) = res {
@@ -133,6 +145,7 @@ impl Community {
     }
     let res = community::table
       .filter(featured_url.eq(url))
+      .select(Self::as_select())
       .first::<Self>(conn)
       .await;
     if let Ok(c) = res {
@@ -228,19 +241,121 @@ impl CommunityFollower {
       None => SubscribedType::NotSubscribed,
     }
   }
+
+  /// Whether the person follows the community, and the follow isn't pending anymore.
+  pub async fn is_approved(
+    pool: &DbPool,
+    community_id: CommunityId,
+    person_id: PersonId,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      community_follower::table
+        .filter(community_follower::community_id.eq(community_id))
+        .filter(community_follower::person_id.eq(person_id))
+        .filter(community_follower::pending.eq(false)),
+    ))
+    .get_result(conn)
+    .await
+  }
+
+  /// Removes a follow which is still pending, when a mod rejects it. Approved follows are kept.
+  pub async fn delete_pending(
+    pool: &DbPool,
+    community_id: CommunityId,
+    person_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      community_follower::table
+        .filter(community_follower::community_id.eq(community_id))
+        .filter(community_follower::person_id.eq(person_id))
+        .filter(community_follower::pending.eq(true)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// The communities which the person follows, without the follows which are still pending.
+  pub async fn list_approved_community_ids(
+    pool: &DbPool,
+    person_id: PersonId,
+  ) -> Result<Vec<CommunityId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_follower::table
+      .filter(community_follower::person_id.eq(person_id))
+      .filter(community_follower::pending.eq(false))
+      .select(community_follower::community_id)
+      .load(conn)
+      .await
+  }
+
+  /// Moves the follows of local communities from one person to another, used when an account
+  /// migrates. Pending follows stay pending. Returns the number of moved follows.
+  pub async fn transfer_local_follows(
+    pool: &DbPool,
+    from_person_id: PersonId,
+    to_person_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let follows: Vec<(CommunityId, bool)> = community_follower::table
+            .inner_join(community::table)
+            .filter(community_follower::person_id.eq(from_person_id))
+            .filter(community::local.eq(true))
+            .select((
+              community_follower::community_id,
+              community_follower::pending,
+            ))
+            .load(conn)
+            .await?;
+          let community_ids = follows.iter().map(|f| f.0).collect::<Vec<_>>();
+          let forms = follows
+            .into_iter()
+            .map(|(community_id, pending)| CommunityFollowerForm {
+              community_id,
+              person_id: to_person_id,
+              pending,
+            })
+            .collect::<Vec<_>>();
+          insert_into(community_follower::table)
+            .values(forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          diesel::delete(
+            community_follower::table
+              .filter(community_follower::person_id.eq(from_person_id))
+              .filter(community_follower::community_id.eq_any(community_ids)),
+          )
+          .execute(conn)
+          .await
+        }) as _
+      })
+      .await
+  }
 }
 
 #[async_trait]
 impl Followable for CommunityFollower {
   type Form = CommunityFollowerForm;
   async fn follow(pool: &DbPool, form: &CommunityFollowerForm) -> Result<Self, Error> {
-    use crate::schema::community_follower::dsl::{community_follower, community_id, person_id};
+    use crate::schema::community_follower::dsl::{
+      community_follower,
+      community_id,
+      pending,
+      person_id,
+    };
     let conn = &mut get_conn(pool).await?;
+    // Following again must not reset an approved follow to pending
     insert_into(community_follower)
       .values(form)
       .on_conflict((community_id, person_id))
       .do_update()
-      .set(form)
+      .set(pending.eq(pending.and(excluded(pending))))
       .get_result::<Self>(conn)
       .await
   }
@@ -285,6 +400,7 @@ impl ApubActor for Community {
     Ok(
       community::table
         .filter(community::actor_id.eq(object_id))
+        .select(Community::as_select())
         .first::<Community>(conn)
         .await
         .ok()
@@ -307,7 +423,7 @@ impl ApubActor for Community {
         .filter(community::deleted.eq(false))
         .filter(community::removed.eq(false));
     }
-    q.first::<Self>(conn).await
+    q.select(Self::as_select()).first::<Self>(conn).await
   }
 
   async fn read_from_name_and_domain(
@@ -320,7 +436,7 @@ impl ApubActor for Community {
       .inner_join(instance::table)
       .filter(lower(community::name).eq(community_name.to_lowercase()))
       .filter(instance::domain.eq(for_domain))
-      .select(community::all_columns)
+      .select(Community::as_select())
       .first::<Self>(conn)
       .await
   }
@@ -346,6 +462,7 @@ mod tests {
     },
     traits::{Bannable, Crud, Followable, Joinable},
     utils::build_db_pool_for_tests,
+    CommunityVisibility,
   };
   use serial_test::serial;
 
@@ -400,6 +517,8 @@ mod tests {
       hidden: false,
       posting_restricted_to_mods: false,
       instance_id: inserted_instance.id,
+      visibility: CommunityVisibility::Public,
+      edit_history_public: false,
     };
 
     let community_follower_form = CommunityFollowerForm {
@@ -411,6 +530,48 @@ mod tests {
     let inserted_community_follower = CommunityFollower::follow(pool, &community_follower_form)
       .await
       .unwrap();
+    let is_approved =
+      CommunityFollower::is_approved(pool, inserted_community.id, inserted_person.id)
+        .await
+        .unwrap();
+    assert!(is_approved);
+
+    // Following again doesn't reset the approved follow to pending
+    let pending_form = CommunityFollowerForm {
+      pending: true,
+      ..community_follower_form.clone()
+    };
+    let refollowed = CommunityFollower::follow(pool, &pending_form)
+      .await
+      .unwrap();
+    assert!(!refollowed.pending);
+
+    // Rejecting a follow doesn't remove it once it is approved
+    let rejected =
+      CommunityFollower::delete_pending(pool, inserted_community.id, inserted_person.id)
+        .await
+        .unwrap();
+    assert_eq!(0, rejected);
+
+    let new_moved_person = PersonInsertForm::builder()
+      .name("bobbee_moved".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let moved_person = Person::create(pool, &new_moved_person).await.unwrap();
+    let transferred =
+      CommunityFollower::transfer_local_follows(pool, inserted_person.id, moved_person.id)
+        .await
+        .unwrap();
+    assert_eq!(1, transferred);
+    let is_approved = CommunityFollower::is_approved(pool, inserted_community.id, moved_person.id)
+      .await
+      .unwrap();
+    assert!(is_approved);
+    // Move the follow back, so that it can be checked below
+    CommunityFollower::transfer_local_follows(pool, moved_person.id, inserted_person.id)
+      .await
+      .unwrap();
 
     let expected_community_follower = CommunityFollower {
       id: inserted_community_follower.id,
@@ -476,6 +637,7 @@ mod tests {
       .await
       .unwrap();
     Person::delete(pool, inserted_person.id).await.unwrap();
+    Person::delete(pool, moved_person.id).await.unwrap();
     Instance::delete(pool, inserted_instance.id).await.unwrap();
 
     assert_eq!(expected_community, read_community);
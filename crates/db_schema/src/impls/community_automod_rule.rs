@@ -0,0 +1,121 @@
+use crate::{
+  newtypes::{CommunityAutomodRuleId, CommunityId},
+  schema::community_automod_rule,
+  source::community_automod_rule::{CommunityAutomodRule, CommunityAutomodRuleForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl CommunityAutomodRule {
+  pub async fn create(pool: &DbPool, form: &CommunityAutomodRuleForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_automod_rule::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &DbPool, rule_id: CommunityAutomodRuleId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_automod_rule::table
+      .find(rule_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete(pool: &DbPool, rule_id: CommunityAutomodRuleId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(community_automod_rule::table.find(rule_id))
+      .execute(conn)
+      .await
+  }
+
+  /// Lists the rules of a community, in the order in which they are checked.
+  pub async fn list_for_community(
+    pool: &DbPool,
+    community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_automod_rule::table
+      .filter(community_automod_rule::community_id.eq(community_id))
+      .order_by(community_automod_rule::id)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_automod_rule::{CommunityAutomodRule, CommunityAutomodRuleForm},
+      instance::Instance,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    AutomodAction,
+    AutomodRuleType,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_automod_rule() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_automod".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let forms = [
+      (
+        AutomodRuleType::BannedDomain,
+        "example.com",
+        AutomodAction::Reject,
+      ),
+      (AutomodRuleType::MinAccountAge, "7", AutomodAction::Report),
+    ]
+    .map(|(rule_type, value, action)| CommunityAutomodRuleForm {
+      community_id: inserted_community.id,
+      rule_type,
+      value: value.to_string(),
+      action,
+      reason: None,
+    });
+    let first = CommunityAutomodRule::create(pool, &forms[0]).await.unwrap();
+    let second = CommunityAutomodRule::create(pool, &forms[1]).await.unwrap();
+    assert_eq!(AutomodRuleType::BannedDomain, first.rule_type);
+    assert_eq!(AutomodAction::Report, second.action);
+
+    let read = CommunityAutomodRule::read(pool, first.id).await.unwrap();
+    assert_eq!(first, read);
+
+    let rules = CommunityAutomodRule::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![first.clone(), second], rules);
+
+    let deleted = CommunityAutomodRule::delete(pool, first.id).await.unwrap();
+    assert_eq!(1, deleted);
+
+    // Rules are removed together with their community
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    let rules = CommunityAutomodRule::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert!(rules.is_empty());
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -0,0 +1,267 @@
+use crate::{
+  newtypes::{CommunityId, CommunityPostTagId, DbUrl, PostId},
+  schema::{community_post_tag, post_tag},
+  source::community_post_tag::{
+    CommunityPostTag,
+    CommunityPostTagInsertForm,
+    CommunityPostTagUpdateForm,
+    PostTag,
+  },
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+use url::Url;
+
+#[async_trait]
+impl Crud for CommunityPostTag {
+  type InsertForm = CommunityPostTagInsertForm;
+  type UpdateForm = CommunityPostTagUpdateForm;
+  type IdType = CommunityPostTagId;
+  async fn read(pool: &DbPool, tag_id: CommunityPostTagId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_post_tag::table
+      .find(tag_id)
+      .first::<Self>(conn)
+      .await
+  }
+
+  async fn create(pool: &DbPool, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_post_tag::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &DbPool,
+    tag_id: CommunityPostTagId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_post_tag::table.find(tag_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn delete(pool: &DbPool, tag_id: CommunityPostTagId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(community_post_tag::table.find(tag_id))
+      .execute(conn)
+      .await
+  }
+}
+
+impl CommunityPostTag {
+  /// Creates a tag which was received from another instance, or renames it if it is known already.
+  pub async fn upsert(pool: &DbPool, form: &CommunityPostTagInsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_post_tag::table)
+      .values(form)
+      .on_conflict(community_post_tag::ap_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read_from_apub_id(pool: &DbPool, object_id: Url) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let object_id: DbUrl = object_id.into();
+    community_post_tag::table
+      .filter(community_post_tag::ap_id.eq(object_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn list_for_community(
+    pool: &DbPool,
+    community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_post_tag::table
+      .filter(community_post_tag::community_id.eq(community_id))
+      .order_by(community_post_tag::name)
+      .load::<Self>(conn)
+      .await
+  }
+
+  pub async fn list_for_post(pool: &DbPool, post_id: PostId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_tag::table
+      .inner_join(community_post_tag::table)
+      .filter(post_tag::post_id.eq(post_id))
+      .select(community_post_tag::all_columns)
+      .order_by(community_post_tag::name)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+impl PostTag {
+  /// Replaces the tags of a post. The tags must belong to the community of the post, which needs to
+  /// be checked beforehand.
+  pub async fn set(
+    pool: &DbPool,
+    for_post_id: PostId,
+    tag_ids: Vec<CommunityPostTagId>,
+  ) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          diesel::delete(post_tag::table.filter(post_tag::post_id.eq(for_post_id)))
+            .execute(conn)
+            .await?;
+          let forms = tag_ids
+            .into_iter()
+            .map(|tag_id| PostTag {
+              post_id: for_post_id,
+              tag_id,
+            })
+            .collect::<Vec<_>>();
+          insert_into(post_tag::table)
+            .values(forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          Ok(())
+        }) as _
+      })
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_post_tag::{
+        CommunityPostTag,
+        CommunityPostTagInsertForm,
+        CommunityPostTagUpdateForm,
+        PostTag,
+      },
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_post_tags() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("post_tag_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_post_tag".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A tagged post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let question_form = CommunityPostTagInsertForm::builder()
+      .community_id(inserted_community.id)
+      .name("Question".into())
+      .build();
+    let question = CommunityPostTag::create(pool, &question_form)
+      .await
+      .unwrap();
+    let news_form = CommunityPostTagInsertForm::builder()
+      .community_id(inserted_community.id)
+      .name("News".into())
+      .build();
+    let news = CommunityPostTag::create(pool, &news_form).await.unwrap();
+
+    // Tag names are unique within a community
+    assert!(CommunityPostTag::create(pool, &news_form).await.is_err());
+
+    let ap_id: Url = "https://my_domain.tld/c/test_community_post_tag/tag/1"
+      .parse()
+      .unwrap();
+    let update_form = CommunityPostTagUpdateForm::builder()
+      .ap_id(Some(ap_id.clone().into()))
+      .build();
+    let question = CommunityPostTag::update(pool, question.id, &update_form)
+      .await
+      .unwrap();
+    let read_question = CommunityPostTag::read_from_apub_id(pool, ap_id.clone())
+      .await
+      .unwrap();
+    assert_eq!(Some(question.clone()), read_question);
+
+    // Receiving a known tag again only renames it
+    let remote_form = CommunityPostTagInsertForm::builder()
+      .community_id(inserted_community.id)
+      .name("Questions".into())
+      .ap_id(Some(ap_id.into()))
+      .build();
+    let question = CommunityPostTag::upsert(pool, &remote_form).await.unwrap();
+    assert_eq!("Questions", question.name);
+
+    let tags = CommunityPostTag::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![news.clone(), question.clone()], tags);
+
+    PostTag::set(pool, inserted_post.id, vec![question.id, news.id])
+      .await
+      .unwrap();
+    let post_tags = CommunityPostTag::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![news.clone(), question.clone()], post_tags);
+
+    PostTag::set(pool, inserted_post.id, vec![question.id])
+      .await
+      .unwrap();
+    let post_tags = CommunityPostTag::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![question.clone()], post_tags);
+
+    // Deleting a tag removes it from the posts
+    let deleted = CommunityPostTag::delete(pool, question.id).await.unwrap();
+    assert_eq!(1, deleted);
+    let post_tags = CommunityPostTag::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert!(post_tags.is_empty());
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
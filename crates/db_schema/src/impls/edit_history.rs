@@ -0,0 +1,290 @@
+use crate::{
+  newtypes::{CommentId, PersonId, PostId},
+  schema::{comment, comment_edit_history, post, post_edit_history},
+  source::{
+    comment::Comment,
+    edit_history::{
+      CommentEditHistory,
+      CommentEditHistoryForm,
+      PostEditHistory,
+      PostEditHistoryForm,
+    },
+    post::Post,
+  },
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  dsl::{delete, insert_into},
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+/// Maximum number of previous versions which are kept per post or comment. Storing more removes
+/// the oldest ones.
+const MAX_EDIT_HISTORY_VERSIONS: i64 = 20;
+
+impl PostEditHistory {
+  /// Stores the previous version of an edited post. Nothing is stored if the title, url and body
+  /// didn't change.
+  pub async fn record(
+    pool: &DbPool,
+    previous: &Post,
+    updated: &Post,
+  ) -> Result<Option<Self>, Error> {
+    if previous.name == updated.name && previous.url == updated.url && previous.body == updated.body
+    {
+      return Ok(None);
+    }
+    let conn = &mut get_conn(pool).await?;
+    let form = PostEditHistoryForm {
+      post_id: previous.id,
+      name: previous.name.clone(),
+      url: previous.url.clone(),
+      body: previous.body.clone(),
+    };
+    let version = insert_into(post_edit_history::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await?;
+
+    // Edits can be federated without limit, so only the newest versions are kept
+    let evicted: Vec<i32> = post_edit_history::table
+      .filter(post_edit_history::post_id.eq(previous.id))
+      .order_by(post_edit_history::published.desc())
+      .then_order_by(post_edit_history::id.desc())
+      .offset(MAX_EDIT_HISTORY_VERSIONS)
+      .select(post_edit_history::id)
+      .load(conn)
+      .await?;
+    if !evicted.is_empty() {
+      delete(post_edit_history::table.filter(post_edit_history::id.eq_any(evicted)))
+        .execute(conn)
+        .await?;
+    }
+    Ok(Some(version))
+  }
+
+  /// The previous versions of a post, oldest first.
+  pub async fn list(pool: &DbPool, for_post_id: PostId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_edit_history::table
+      .filter(post_edit_history::post_id.eq(for_post_id))
+      .order_by((post_edit_history::published, post_edit_history::id))
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Removes the previous versions of all posts by a person, used when their account is deleted.
+  pub async fn delete_for_creator(pool: &DbPool, for_creator_id: PersonId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let creator_posts = post::table
+      .filter(post::creator_id.eq(for_creator_id))
+      .select(post::id);
+    delete(post_edit_history::table.filter(post_edit_history::post_id.eq_any(creator_posts)))
+      .execute(conn)
+      .await
+  }
+}
+
+impl CommentEditHistory {
+  /// Stores the previous version of an edited comment, if the content changed.
+  pub async fn record(
+    pool: &DbPool,
+    previous: &Comment,
+    updated: &Comment,
+  ) -> Result<Option<Self>, Error> {
+    if previous.content == updated.content {
+      return Ok(None);
+    }
+    let conn = &mut get_conn(pool).await?;
+    let form = CommentEditHistoryForm {
+      comment_id: previous.id,
+      content: previous.content.clone(),
+    };
+    let version = insert_into(comment_edit_history::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await?;
+
+    let evicted: Vec<i32> = comment_edit_history::table
+      .filter(comment_edit_history::comment_id.eq(previous.id))
+      .order_by(comment_edit_history::published.desc())
+      .then_order_by(comment_edit_history::id.desc())
+      .offset(MAX_EDIT_HISTORY_VERSIONS)
+      .select(comment_edit_history::id)
+      .load(conn)
+      .await?;
+    if !evicted.is_empty() {
+      delete(comment_edit_history::table.filter(comment_edit_history::id.eq_any(evicted)))
+        .execute(conn)
+        .await?;
+    }
+    Ok(Some(version))
+  }
+
+  /// The previous versions of a comment, oldest first.
+  pub async fn list(pool: &DbPool, for_comment_id: CommentId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment_edit_history::table
+      .filter(comment_edit_history::comment_id.eq(for_comment_id))
+      .order_by((comment_edit_history::published, comment_edit_history::id))
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Removes the previous versions of all comments by a person, used when their account is
+  /// deleted.
+  pub async fn delete_for_creator(pool: &DbPool, for_creator_id: PersonId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let creator_comments = comment::table
+      .filter(comment::creator_id.eq(for_creator_id))
+      .select(comment::id);
+    delete(
+      comment_edit_history::table.filter(comment_edit_history::comment_id.eq_any(creator_comments)),
+    )
+    .execute(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::MAX_EDIT_HISTORY_VERSIONS;
+  use crate::{
+    source::{
+      comment::{Comment, CommentInsertForm, CommentUpdateForm},
+      community::{Community, CommunityInsertForm},
+      edit_history::{CommentEditHistory, PostEditHistory},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm, PostUpdateForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_edit_history() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("edit_history_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_edit_history".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("Original title".into())
+      .body(Some("Original body".into()))
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let new_comment = CommentInsertForm::builder()
+      .content("Original comment".into())
+      .creator_id(inserted_person.id)
+      .post_id(inserted_post.id)
+      .build();
+    let inserted_comment = Comment::create(pool, &new_comment, None).await.unwrap();
+
+    // Edits which don't change the content aren't stored
+    let unchanged = PostEditHistory::record(pool, &inserted_post, &inserted_post)
+      .await
+      .unwrap();
+    assert_eq!(None, unchanged);
+
+    let post_form = PostUpdateForm::builder()
+      .name(Some("Edited title".into()))
+      .build();
+    let updated_post = Post::update(pool, inserted_post.id, &post_form)
+      .await
+      .unwrap();
+    PostEditHistory::record(pool, &inserted_post, &updated_post)
+      .await
+      .unwrap();
+    let post_history = PostEditHistory::list(pool, inserted_post.id).await.unwrap();
+    assert_eq!(1, post_history.len());
+    assert_eq!("Original title", post_history[0].name);
+    assert_eq!(Some("Original body".to_string()), post_history[0].body);
+
+    let comment_form = CommentUpdateForm::builder()
+      .content(Some("Edited comment".into()))
+      .build();
+    let updated_comment = Comment::update(pool, inserted_comment.id, &comment_form)
+      .await
+      .unwrap();
+    CommentEditHistory::record(pool, &inserted_comment, &updated_comment)
+      .await
+      .unwrap();
+    let comment_history = CommentEditHistory::list(pool, inserted_comment.id)
+      .await
+      .unwrap();
+    assert_eq!(1, comment_history.len());
+    assert_eq!("Original comment", comment_history[0].content);
+
+    // Only the newest versions are kept
+    for _ in 0..MAX_EDIT_HISTORY_VERSIONS {
+      CommentEditHistory::record(pool, &updated_comment, &inserted_comment)
+        .await
+        .unwrap();
+    }
+    let comment_history = CommentEditHistory::list(pool, inserted_comment.id)
+      .await
+      .unwrap();
+    assert_eq!(MAX_EDIT_HISTORY_VERSIONS, comment_history.len() as i64);
+    assert_eq!("Edited comment", comment_history[0].content);
+
+    // Deleting the account also removes the old versions
+    let deleted_posts = PostEditHistory::delete_for_creator(pool, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(1, deleted_posts);
+    let deleted_comments = CommentEditHistory::delete_for_creator(pool, inserted_person.id)
+      .await
+      .unwrap();
+    assert_eq!(MAX_EDIT_HISTORY_VERSIONS, deleted_comments as i64);
+    assert!(PostEditHistory::list(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .is_empty());
+    PostEditHistory::record(pool, &inserted_post, &updated_post)
+      .await
+      .unwrap();
+    CommentEditHistory::record(pool, &inserted_comment, &updated_comment)
+      .await
+      .unwrap();
+
+    // The history is removed together with the post
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    let post_history = PostEditHistory::list(pool, inserted_post.id).await.unwrap();
+    assert!(post_history.is_empty());
+    let comment_history = CommentEditHistory::list(pool, inserted_comment.id)
+      .await
+      .unwrap();
+    assert!(comment_history.is_empty());
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
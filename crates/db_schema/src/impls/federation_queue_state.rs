@@ -0,0 +1,97 @@
+use crate::{
+  newtypes::InstanceId,
+  schema::federation_queue_state::dsl::{federation_queue_state, instance_id},
+  source::federation_queue_state::{FederationQueueState, FederationQueueStateForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl FederationQueueState {
+  pub async fn read(pool: &DbPool, for_instance_id: InstanceId) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    federation_queue_state
+      .filter(instance_id.eq(for_instance_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn upsert(pool: &DbPool, form: &FederationQueueStateForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(federation_queue_state)
+      .values(form)
+      .on_conflict(instance_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      federation_queue_state::{FederationQueueState, FederationQueueStateForm},
+      instance::Instance,
+    },
+    utils::build_db_pool_for_tests,
+  };
+  use chrono::Utc;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_federation_queue_state() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "queue_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let read = FederationQueueState::read(pool, inserted_instance.id)
+      .await
+      .unwrap();
+    assert_eq!(None, read);
+
+    let mut form = FederationQueueStateForm {
+      instance_id: inserted_instance.id,
+      last_successful_id: 5,
+      fail_count: 0,
+      last_retry: None,
+      last_successful_send: None,
+      inbox_latency_ms: None,
+    };
+    let inserted = FederationQueueState::upsert(pool, &form).await.unwrap();
+
+    form.last_successful_id = 6;
+    form.fail_count = 2;
+    form.last_retry = Some(Utc::now());
+    form.inbox_latency_ms = Some(120);
+    let updated = FederationQueueState::upsert(pool, &form).await.unwrap();
+    assert_eq!(inserted.id, updated.id);
+    assert_eq!(6, updated.last_successful_id);
+    assert_eq!(2, updated.fail_count);
+    assert_eq!(Some(120), updated.inbox_latency_ms);
+
+    let read = FederationQueueState::read(pool, inserted_instance.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(updated.clone()), read);
+
+    Instance::update_last_received_activity(pool, "queue_domain.tld")
+      .await
+      .unwrap();
+    let linked = Instance::linked_with_federation_state(pool).await.unwrap();
+    let (linked_instance, linked_state) = linked
+      .into_iter()
+      .find(|(i, _)| i.id == inserted_instance.id)
+      .unwrap();
+    assert!(linked_instance.last_received_activity.is_some());
+    assert!(!linked_instance.dead);
+    assert_eq!(Some(updated), linked_state);
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
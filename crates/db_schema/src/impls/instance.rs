@@ -1,10 +1,19 @@
 use crate::{
   newtypes::InstanceId,
-  schema::{federation_allowlist, federation_blocklist, instance},
-  source::instance::{Instance, InstanceForm},
+  schema::{federation_allowlist, federation_blocklist, federation_queue_state, instance},
+  source::{
+    federation_queue_state::FederationQueueState,
+    instance::{Instance, InstanceForm},
+  },
   utils::{get_conn, naive_now, DbPool},
 };
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel::{
+  dsl::insert_into,
+  result::Error,
+  ExpressionMethods,
+  NullableExpressionMethods,
+  QueryDsl,
+};
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 
 impl Instance {
@@ -46,6 +55,25 @@ impl Instance {
     let conn = &mut get_conn(pool).await?;
     Self::read_or_create_with_conn(conn, domain).await
   }
+  pub async fn read(pool: &DbPool, instance_id: InstanceId) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table.find(instance_id).first::<Self>(conn).await
+  }
+
+  /// Records that an activity from the given domain was received, which also means that the
+  /// instance is alive.
+  pub async fn update_last_received_activity(pool: &DbPool, domain: &str) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(instance::table.filter(instance::domain.eq(domain)))
+      .set((
+        instance::last_received_activity.eq(naive_now()),
+        instance::dead.eq(false),
+      ))
+      .execute(conn)
+      .await?;
+    Ok(())
+  }
+
   pub async fn delete(pool: &DbPool, instance_id: InstanceId) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
     diesel::delete(instance::table.find(instance_id))
@@ -84,4 +112,21 @@ impl Instance {
       .get_results(conn)
       .await
   }
+
+  /// Linked instances, together with the state of their outgoing activity queue.
+  pub async fn linked_with_federation_state(
+    pool: &DbPool,
+  ) -> Result<Vec<(Self, Option<FederationQueueState>)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .left_join(federation_blocklist::table)
+      .left_join(federation_queue_state::table)
+      .filter(federation_blocklist::id.is_null())
+      .select((
+        instance::all_columns,
+        federation_queue_state::all_columns.nullable(),
+      ))
+      .get_results(conn)
+      .await
+  }
 }
@@ -0,0 +1,186 @@
+use crate::{
+  newtypes::{LocalUserId, PersonId},
+  schema::{local_image, local_user},
+  source::local_image::{LocalImage, LocalImageForm},
+  utils::{get_conn, limit_and_offset, DbPool},
+};
+use diesel::{
+  delete,
+  dsl::sql,
+  insert_into,
+  result::Error,
+  sql_types::BigInt,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl LocalImage {
+  pub async fn create(pool: &DbPool, form: &LocalImageForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(local_image::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &DbPool, alias: &str) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    local_image::table.find(alias).first::<Self>(conn).await
+  }
+
+  /// The uploads of a user, newest first.
+  pub async fn list_for_local_user(
+    pool: &DbPool,
+    for_local_user_id: LocalUserId,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    local_image::table
+      .filter(local_image::local_user_id.eq(for_local_user_id))
+      .order_by(local_image::published.desc())
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// The total size in bytes of all uploads of a user, for checking the storage quota.
+  pub async fn total_size_for_local_user(
+    pool: &DbPool,
+    for_local_user_id: LocalUserId,
+  ) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    // Postgres sums bigints as numeric, which diesel can't load without extra features
+    local_image::table
+      .filter(local_image::local_user_id.eq(for_local_user_id))
+      .select(sql::<BigInt>(
+        "coalesce(sum(local_image.file_size), 0)::bigint",
+      ))
+      .first::<i64>(conn)
+      .await
+  }
+
+  /// All uploads of a person, for purging them.
+  pub async fn list_for_person(pool: &DbPool, for_person_id: PersonId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    local_image::table
+      .inner_join(local_user::table)
+      .filter(local_user::person_id.eq(for_person_id))
+      .select(local_image::all_columns)
+      .load::<Self>(conn)
+      .await
+  }
+
+  pub async fn delete(pool: &DbPool, alias: &str) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(local_image::table.find(alias)).execute(conn).await
+  }
+
+  pub async fn delete_for_person(pool: &DbPool, for_person_id: PersonId) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let local_user_ids = local_user::table
+      .filter(local_user::person_id.eq(for_person_id))
+      .select(local_user::id);
+    delete(local_image::table.filter(local_image::local_user_id.eq_any(local_user_ids)))
+      .execute(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      instance::Instance,
+      local_image::{LocalImage, LocalImageForm},
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_local_image() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let mut local_users = vec![];
+    for name in ["local_image_a", "local_image_b"] {
+      let new_person = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      let inserted_person = Person::create(pool, &new_person).await.unwrap();
+      let new_local_user = LocalUserInsertForm::builder()
+        .person_id(inserted_person.id)
+        .password_encrypted("pass".to_string())
+        .build();
+      local_users.push(LocalUser::create(pool, &new_local_user).await.unwrap());
+    }
+
+    let form = |local_user: &LocalUser, alias: &str| LocalImageForm {
+      local_user_id: local_user.id,
+      pictrs_alias: alias.to_string(),
+      pictrs_delete_token: "token".to_string(),
+      file_size: 100,
+    };
+    let first = LocalImage::create(pool, &form(&local_users[0], "first.png"))
+      .await
+      .unwrap();
+    LocalImage::create(pool, &form(&local_users[0], "second.png"))
+      .await
+      .unwrap();
+    LocalImage::create(pool, &form(&local_users[1], "other.png"))
+      .await
+      .unwrap();
+
+    let read = LocalImage::read(pool, "first.png").await.unwrap();
+    assert_eq!(first, read);
+
+    let images = LocalImage::list_for_local_user(pool, local_users[0].id, None, None)
+      .await
+      .unwrap();
+    assert_eq!(2, images.len());
+    let images = LocalImage::list_for_local_user(pool, local_users[0].id, Some(2), Some(1))
+      .await
+      .unwrap();
+    assert_eq!(1, images.len());
+
+    let total_size = LocalImage::total_size_for_local_user(pool, local_users[0].id)
+      .await
+      .unwrap();
+    assert_eq!(200, total_size);
+
+    let images = LocalImage::list_for_person(pool, local_users[1].person_id)
+      .await
+      .unwrap();
+    assert_eq!(1, images.len());
+    assert_eq!("other.png", images[0].pictrs_alias);
+
+    let num_deleted = LocalImage::delete(pool, "second.png").await.unwrap();
+    assert_eq!(1, num_deleted);
+    let num_deleted = LocalImage::delete_for_person(pool, local_users[0].person_id)
+      .await
+      .unwrap();
+    assert_eq!(1, num_deleted);
+    assert!(LocalImage::read(pool, "first.png").await.is_err());
+    // The uploads of other users aren't affected
+    assert!(LocalImage::read(pool, "other.png").await.is_ok());
+
+    for local_user in local_users {
+      LocalUser::delete(pool, local_user.id).await.unwrap();
+      Person::delete(pool, local_user.person_id).await.unwrap();
+    }
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -51,6 +51,13 @@ impl LocalSiteRateLimitUpdateForm {
       && self.comment_per_second.is_none()
       && self.search.is_none()
       && self.search_per_second.is_none()
+      && self.message_per_user.is_none()
+      && self.post_per_user.is_none()
+      && self.image_per_user.is_none()
+      && self.comment_per_user.is_none()
+      && self.search_per_user.is_none()
+      && self.import_user_settings.is_none()
+      && self.import_user_settings_per_second.is_none()
       && self.updated.is_none()
   }
 }
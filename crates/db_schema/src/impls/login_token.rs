@@ -0,0 +1,250 @@
+use crate::{
+  impls::password_reset_request::bytes_to_hex,
+  newtypes::LocalUserId,
+  schema::{
+    local_user,
+    login_token::dsl::{expires, last_used, local_user_id, login_token, token_encrypted},
+  },
+  source::login_token::{LoginToken, LoginTokenCreateForm},
+  utils::{get_conn, naive_now, DbPool},
+};
+use chrono::{DateTime, Duration, Utc};
+use diesel::{dsl::now, insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use sha2::{Digest, Sha256};
+
+/// The last use time of a token is only updated this often, so that api calls don't write to the
+/// database.
+fn last_used_update_interval() -> Duration {
+  Duration::hours(1)
+}
+
+impl LoginToken {
+  pub async fn create(pool: &DbPool, form: &LoginTokenCreateForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    // Tokens issued for the same user within the same second are identical
+    insert_into(login_token)
+      .values(form)
+      .on_conflict(token_encrypted)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Stores a token which was issued before tokens were stored, so that it stays valid until
+  /// `expires`. Does nothing if the token is already known, so revoked and expired tokens can't
+  /// be brought back this way.
+  pub async fn adopt_legacy(
+    pool: &DbPool,
+    for_local_user_id: LocalUserId,
+    token: &str,
+    expires_at: DateTime<Utc>,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let form = LoginTokenCreateForm {
+      token_encrypted: Self::hash_token(token),
+      local_user_id: for_local_user_id,
+      ip: None,
+      user_agent: None,
+      expires: expires_at,
+    };
+    insert_into(login_token)
+      .values(form)
+      .on_conflict(token_encrypted)
+      .do_nothing()
+      .execute(conn)
+      .await
+  }
+
+  /// Checks that the token was issued for the given user, and is neither expired nor revoked.
+  /// Also updates the last use time of the token, if it wasn't updated recently.
+  pub async fn validate(
+    pool: &DbPool,
+    for_local_user_id: LocalUserId,
+    token: &str,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let valid = login_token
+      .filter(token_encrypted.eq(Self::hash_token(token)))
+      .filter(local_user_id.eq(for_local_user_id))
+      .filter(expires.gt(now))
+      .first::<Self>(conn)
+      .await?;
+    if valid.last_used > naive_now() - last_used_update_interval() {
+      return Ok(valid);
+    }
+    diesel::update(login_token.find(valid.id))
+      .set(last_used.eq(naive_now()))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// All sessions of a user which are still valid, most recently used first.
+  pub async fn list(pool: &DbPool, for_local_user_id: LocalUserId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    login_token
+      .filter(local_user_id.eq(for_local_user_id))
+      .filter(expires.gt(now))
+      .order_by(last_used.desc())
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Revokes a single token, ie logs out of one session. The row is kept as expired, so that a
+  /// revoked legacy token can't be adopted again.
+  pub async fn invalidate(pool: &DbPool, token: &str) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      login_token
+        .filter(token_encrypted.eq(Self::hash_token(token)))
+        .filter(expires.gt(now)),
+    )
+    .set(expires.eq(naive_now()))
+    .execute(conn)
+    .await
+  }
+
+  /// Revokes all tokens of a user, ie logs out of every session.
+  ///
+  /// Legacy tokens which weren't used since the upgrade aren't stored yet, so the validator time of
+  /// the user is moved forward as well, which rejects every token issued before now.
+  pub async fn invalidate_all(
+    pool: &DbPool,
+    for_local_user_id: LocalUserId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(local_user::table.find(for_local_user_id))
+      .set(local_user::validator_time.eq(naive_now()))
+      .execute(conn)
+      .await?;
+    diesel::update(
+      login_token
+        .filter(local_user_id.eq(for_local_user_id))
+        .filter(expires.gt(now)),
+    )
+    .set(expires.eq(naive_now()))
+    .execute(conn)
+    .await
+  }
+
+  /// Tokens are only stored as hash, so that a database leak doesn't allow logging in as users.
+  pub fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token);
+    bytes_to_hex(hasher.finalize().to_vec())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      login_token::{LoginToken, LoginTokenCreateForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use chrono::{Duration, Utc};
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_login_token() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("login_token_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_local_user = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("pass".to_string())
+      .build();
+
+    let inserted_local_user = LocalUser::create(pool, &new_local_user).await.unwrap();
+
+    let form = |token: &str, expires| LoginTokenCreateForm {
+      token_encrypted: LoginToken::hash_token(token),
+      local_user_id: inserted_local_user.id,
+      ip: Some("127.0.0.1".to_string()),
+      user_agent: Some("Lemmy test".to_string()),
+      expires,
+    };
+    let valid_token = LoginToken::create(pool, &form("valid", Utc::now() + Duration::days(1)))
+      .await
+      .unwrap();
+    LoginToken::create(pool, &form("expired", Utc::now() - Duration::days(1)))
+      .await
+      .unwrap();
+    LoginToken::create(pool, &form("other", Utc::now() + Duration::days(1)))
+      .await
+      .unwrap();
+
+    let validated = LoginToken::validate(pool, inserted_local_user.id, "valid")
+      .await
+      .unwrap();
+    assert_eq!(valid_token.id, validated.id);
+    assert!(validated.last_used >= valid_token.last_used);
+    assert!(
+      LoginToken::validate(pool, inserted_local_user.id, "expired")
+        .await
+        .is_err()
+    );
+    assert!(
+      LoginToken::validate(pool, inserted_local_user.id, "unknown")
+        .await
+        .is_err()
+    );
+
+    let logins = LoginToken::list(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    assert_eq!(2, logins.len());
+
+    LoginToken::invalidate(pool, "valid").await.unwrap();
+    assert!(LoginToken::validate(pool, inserted_local_user.id, "valid")
+      .await
+      .is_err());
+    assert!(LoginToken::validate(pool, inserted_local_user.id, "other")
+      .await
+      .is_ok());
+
+    // Legacy tokens are stored on first use, but revoked ones stay revoked
+    let expires = Utc::now() + Duration::days(1);
+    LoginToken::adopt_legacy(pool, inserted_local_user.id, "legacy", expires)
+      .await
+      .unwrap();
+    assert!(LoginToken::validate(pool, inserted_local_user.id, "legacy")
+      .await
+      .is_ok());
+    LoginToken::invalidate(pool, "legacy").await.unwrap();
+    LoginToken::adopt_legacy(pool, inserted_local_user.id, "legacy", expires)
+      .await
+      .unwrap();
+    assert!(LoginToken::validate(pool, inserted_local_user.id, "legacy")
+      .await
+      .is_err());
+
+    let num_invalidated = LoginToken::invalidate_all(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    assert_eq!(1, num_invalidated);
+    let updated_local_user = LocalUser::read(pool, inserted_local_user.id).await.unwrap();
+    assert!(updated_local_user.validator_time > inserted_local_user.validator_time);
+
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -28,13 +28,15 @@ use crate::{
     ModRemoveCommunityForm,
     ModRemovePost,
     ModRemovePostForm,
+    ModResolveReport,
+    ModResolveReportForm,
     ModTransferCommunity,
     ModTransferCommunityForm,
   },
   traits::Crud,
   utils::{get_conn, DbPool},
 };
-use diesel::{dsl::insert_into, result::Error, QueryDsl};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 
 #[async_trait]
@@ -67,6 +69,18 @@ impl Crud for ModRemovePost {
   }
 }
 
+impl ModRemovePost {
+  /// Creates the mod log entry of a removal by automod.
+  pub async fn create_automod(pool: &DbPool, form: &ModRemovePostForm) -> Result<Self, Error> {
+    use crate::schema::mod_remove_post::dsl::{automod, mod_remove_post};
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_remove_post)
+      .values((form, automod.eq(true)))
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Crud for ModLockPost {
   type InsertForm = ModLockPostForm;
@@ -157,6 +171,48 @@ impl Crud for ModRemoveComment {
   }
 }
 
+impl ModRemoveComment {
+  /// Creates the mod log entry of a removal by automod.
+  pub async fn create_automod(pool: &DbPool, form: &ModRemoveCommentForm) -> Result<Self, Error> {
+    use crate::schema::mod_remove_comment::dsl::{automod, mod_remove_comment};
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_remove_comment)
+      .values((form, automod.eq(true)))
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[async_trait]
+impl Crud for ModResolveReport {
+  type InsertForm = ModResolveReportForm;
+  type UpdateForm = ModResolveReportForm;
+  type IdType = i32;
+  async fn read(pool: &DbPool, from_id: i32) -> Result<Self, Error> {
+    use crate::schema::mod_resolve_report::dsl::mod_resolve_report;
+    let conn = &mut get_conn(pool).await?;
+    mod_resolve_report.find(from_id).first::<Self>(conn).await
+  }
+
+  async fn create(pool: &DbPool, form: &ModResolveReportForm) -> Result<Self, Error> {
+    use crate::schema::mod_resolve_report::dsl::mod_resolve_report;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(mod_resolve_report)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(pool: &DbPool, from_id: i32, form: &ModResolveReportForm) -> Result<Self, Error> {
+    use crate::schema::mod_resolve_report::dsl::mod_resolve_report;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(mod_resolve_report.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Crud for ModRemoveCommunity {
   type InsertForm = ModRemoveCommunityForm;
@@ -535,6 +591,8 @@ mod tests {
         ModRemoveCommunityForm,
         ModRemovePost,
         ModRemovePostForm,
+        ModResolveReport,
+        ModResolveReportForm,
       },
       person::{Person, PersonInsertForm},
       post::{Post, PostInsertForm},
@@ -616,6 +674,7 @@ mod tests {
       reason: None,
       removed: true,
       when_: inserted_mod_remove_post.when_,
+      automod: false,
     };
 
     // lock post
@@ -683,6 +742,34 @@ mod tests {
       reason: None,
       removed: true,
       when_: inserted_mod_remove_comment.when_,
+      automod: false,
+    };
+
+    // resolve report
+
+    let mod_resolve_report_form = ModResolveReportForm {
+      mod_person_id: inserted_mod.id,
+      post_id: Some(inserted_post.id),
+      comment_id: Some(inserted_comment.id),
+      private_message_report_id: None,
+      resolved: None,
+      reason: Some("spam".into()),
+    };
+    let inserted_mod_resolve_report = ModResolveReport::create(pool, &mod_resolve_report_form)
+      .await
+      .unwrap();
+    let read_mod_resolve_report = ModResolveReport::read(pool, inserted_mod_resolve_report.id)
+      .await
+      .unwrap();
+    let expected_mod_resolve_report = ModResolveReport {
+      id: inserted_mod_resolve_report.id,
+      mod_person_id: inserted_mod.id,
+      post_id: Some(inserted_post.id),
+      comment_id: Some(inserted_comment.id),
+      resolved: true,
+      reason: Some("spam".into()),
+      when_: inserted_mod_resolve_report.when_,
+      private_message_report_id: None,
     };
 
     // community
@@ -815,6 +902,7 @@ mod tests {
     assert_eq!(expected_mod_lock_post, read_mod_lock_post);
     assert_eq!(expected_mod_feature_post, read_mod_feature_post);
     assert_eq!(expected_mod_remove_comment, read_mod_remove_comment);
+    assert_eq!(expected_mod_resolve_report, read_mod_resolve_report);
     assert_eq!(expected_mod_remove_community, read_mod_remove_community);
     assert_eq!(expected_mod_ban_from_community, read_mod_ban_from_community);
     assert_eq!(expected_mod_ban, read_mod_ban);
@@ -0,0 +1,159 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::{oidc_identity, oidc_login_state},
+  source::oidc::{OidcIdentity, OidcIdentityForm, OidcLoginState, OidcLoginStateForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  delete,
+  dsl::{insert_into, now, IntervalDsl},
+  result::Error,
+  sql_types::Timestamptz,
+  ExpressionMethods,
+  IntoSql,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl OidcIdentity {
+  pub async fn create(pool: &DbPool, form: &OidcIdentityForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(oidc_identity::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// The identity with the given subject at the provider, if it is linked to any local user.
+  pub async fn read_for_subject(
+    pool: &DbPool,
+    for_provider: &str,
+    for_subject: &str,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    oidc_identity::table
+      .filter(oidc_identity::provider.eq(for_provider))
+      .filter(oidc_identity::subject.eq(for_subject))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  pub async fn list_for_local_user(
+    pool: &DbPool,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    oidc_identity::table
+      .filter(oidc_identity::local_user_id.eq(for_local_user_id))
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+impl OidcLoginState {
+  pub async fn create(pool: &DbPool, form: &OidcLoginStateForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(oidc_login_state::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Removes and returns the login with the given state. Each login can only be finished once,
+  /// and only within ten minutes after it was started.
+  pub async fn take(pool: &DbPool, for_state: &str) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(
+      oidc_login_state::table
+        .filter(oidc_login_state::state.eq(for_state))
+        .filter(oidc_login_state::published.gt(now.into_sql::<Timestamptz>() - 10.minutes())),
+    )
+    .get_result::<Self>(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      oidc::{OidcIdentity, OidcIdentityForm, OidcLoginState, OidcLoginStateForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_oidc() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("oidc_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let new_local_user = LocalUserInsertForm::builder()
+      .person_id(inserted_person.id)
+      .password_encrypted("pass".to_string())
+      .build();
+    let inserted_local_user = LocalUser::create(pool, &new_local_user).await.unwrap();
+
+    let form = OidcIdentityForm {
+      local_user_id: inserted_local_user.id,
+      provider: "mock".to_string(),
+      subject: "1234".to_string(),
+    };
+    let inserted_identity = OidcIdentity::create(pool, &form).await.unwrap();
+    // The same account can't be linked twice
+    assert!(OidcIdentity::create(pool, &form).await.is_err());
+
+    let read_identity = OidcIdentity::read_for_subject(pool, "mock", "1234")
+      .await
+      .unwrap();
+    assert_eq!(Some(inserted_identity.clone()), read_identity);
+    let other_provider = OidcIdentity::read_for_subject(pool, "other", "1234")
+      .await
+      .unwrap();
+    assert_eq!(None, other_provider);
+    let identities = OidcIdentity::list_for_local_user(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_identity], identities);
+
+    let form = OidcLoginStateForm {
+      state: "random_state".to_string(),
+      provider: "mock".to_string(),
+      code_verifier: "verifier".to_string(),
+      redirect_uri: "https://my_domain.tld/oidc/callback".to_string(),
+      link_local_user_id: Some(inserted_local_user.id),
+    };
+    let inserted_state = OidcLoginState::create(pool, &form).await.unwrap();
+    let taken_state = OidcLoginState::take(pool, "random_state").await.unwrap();
+    assert_eq!(inserted_state, taken_state);
+    // A login can only be finished once
+    assert!(OidcLoginState::take(pool, "random_state").await.is_err());
+
+    LocalUser::delete(pool, inserted_local_user.id)
+      .await
+      .unwrap();
+    // Linked identities are removed together with the user
+    let read_identity = OidcIdentity::read_for_subject(pool, "mock", "1234")
+      .await
+      .unwrap();
+    assert_eq!(None, read_identity);
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
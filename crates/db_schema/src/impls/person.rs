@@ -11,9 +11,17 @@ use crate::{
   traits::{ApubActor, Crud, Followable},
   utils::{functions::lower, get_conn, naive_now, DbPool},
 };
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, JoinOnDsl, QueryDsl};
-use diesel_async::RunQueryDsl;use chrono::{DateTime, Utc};
-
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::insert_into,
+  result::Error,
+  upsert::excluded,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
 
 #[async_trait]
 impl Crud for Person {
@@ -155,13 +163,14 @@ impl ApubActor for Person {
 impl Followable for PersonFollower {
   type Form = PersonFollowerForm;
   async fn follow(pool: &DbPool, form: &PersonFollowerForm) -> Result<Self, Error> {
-    use crate::schema::person_follower::dsl::{follower_id, person_follower, person_id};
+    use crate::schema::person_follower::dsl::{follower_id, pending, person_follower, person_id};
     let conn = &mut get_conn(pool).await?;
+    // Following again must not reset an approved follow to pending
     insert_into(person_follower)
       .values(form)
       .on_conflict((follower_id, person_id))
       .do_update()
-      .set(form)
+      .set(pending.eq(pending.and(excluded(pending))))
       .get_result::<Self>(conn)
       .await
   }
@@ -194,6 +203,50 @@ impl PersonFollower {
       .load(conn)
       .await
   }
+
+  pub async fn count_followers(pool: &DbPool, for_person_id: PersonId) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    person_follower::table
+      .filter(person_follower::person_id.eq(for_person_id))
+      .filter(person_follower::pending.eq(false))
+      .count()
+      .get_result(conn)
+      .await
+  }
+
+  /// Marks a pending follow of a remote person as accepted. Fails if no follow was requested.
+  pub async fn accept_follow(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    for_follower_id: PersonId,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(
+      person_follower::table
+        .filter(person_follower::person_id.eq(for_person_id))
+        .filter(person_follower::follower_id.eq(for_follower_id)),
+    )
+    .set(person_follower::pending.eq(false))
+    .get_result::<Self>(conn)
+    .await
+  }
+
+  /// Removes a pending follow of a remote person, when it was rejected. Approved follows are kept.
+  pub async fn delete_pending(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    for_follower_id: PersonId,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::delete(
+      person_follower::table
+        .filter(person_follower::person_id.eq(for_person_id))
+        .filter(person_follower::follower_id.eq(for_follower_id))
+        .filter(person_follower::pending.eq(true)),
+    )
+    .execute(conn)
+    .await
+  }
 }
 
 #[cfg(test)]
@@ -248,6 +301,8 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      also_known_as: vec![],
+      moved_to: None,
     };
 
     let read_person = Person::read(pool, inserted_person.id).await.unwrap();
@@ -294,14 +349,38 @@ mod tests {
       follower_id: person_2.id,
       pending: false,
     };
-    let person_follower = PersonFollower::follow(pool, &follow_form).await.unwrap();
+    let pending_follow_form = PersonFollowerForm {
+      pending: true,
+      ..follow_form.clone()
+    };
+    let pending_follower = PersonFollower::follow(pool, &pending_follow_form)
+      .await
+      .unwrap();
+    assert!(pending_follower.pending);
+    let follower_count = PersonFollower::count_followers(pool, person_1.id)
+      .await
+      .unwrap();
+    assert_eq!(0, follower_count);
+
+    let person_follower = PersonFollower::accept_follow(pool, person_1.id, person_2.id)
+      .await
+      .unwrap();
     assert_eq!(person_1.id, person_follower.person_id);
     assert_eq!(person_2.id, person_follower.follower_id);
     assert!(!person_follower.pending);
+    let follower_count = PersonFollower::count_followers(pool, person_1.id)
+      .await
+      .unwrap();
+    assert_eq!(1, follower_count);
 
     let followers = PersonFollower::list_followers(pool, person_1.id)
       .await
       .unwrap();
+    // A rejection doesn't remove an approved follow
+    let rejected = PersonFollower::delete_pending(pool, person_1.id, person_2.id)
+      .await
+      .unwrap();
+    assert_eq!(0, rejected);
     assert_eq!(vec![person_2], followers);
 
     let unfollow = PersonFollower::unfollow(pool, &follow_form).await.unwrap();
@@ -1,19 +1,23 @@
 use crate::{
   newtypes::{CommunityId, DbUrl, PersonId, PostId},
-  schema::post::dsl::{
-    ap_id,
-    body,
-    community_id,
-    creator_id,
-    deleted,
-    featured_community,
-    name,
-    post,
-    published,
-    removed,
-    thumbnail_url,
-    updated,
-    url,
+  schema::{
+    post::dsl::{
+      ap_id,
+      body,
+      community_id,
+      creator_id,
+      deleted,
+      featured_community,
+      name,
+      post,
+      published,
+      removed,
+      scheduled_publish_time,
+      thumbnail_url,
+      updated,
+      url,
+    },
+    post_aggregates,
   },
   source::post::{
     Post,
@@ -30,7 +34,15 @@ use crate::{
   utils::{get_conn, naive_now, DbPool, DELETED_REPLACEMENT_TEXT, FETCH_LIMIT_MAX},
 };
 use ::url::Url;
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl, TextExpressionMethods};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::insert_into,
+  result::Error,
+  ExpressionMethods,
+  QueryDsl,
+  SelectableHelper,
+  TextExpressionMethods,
+};
 use diesel_async::RunQueryDsl;
 
 #[async_trait]
@@ -40,7 +52,11 @@ impl Crud for Post {
   type IdType = PostId;
   async fn read(pool: &DbPool, post_id: PostId) -> Result<Self, Error> {
     let conn = &mut get_conn(pool).await?;
-    post.find(post_id).first::<Self>(conn).await
+    post
+      .find(post_id)
+      .select(Self::as_select())
+      .first::<Self>(conn)
+      .await
   }
 
   async fn delete(pool: &DbPool, post_id: PostId) -> Result<usize, Error> {
@@ -55,6 +71,7 @@ impl Crud for Post {
       .on_conflict(ap_id)
       .do_update()
       .set(form)
+      .returning(Self::as_returning())
       .get_result::<Self>(conn)
       .await
   }
@@ -67,12 +84,32 @@ impl Crud for Post {
     let conn = &mut get_conn(pool).await?;
     diesel::update(post.find(post_id))
       .set(new_post)
+      .returning(Self::as_returning())
       .get_result::<Self>(conn)
       .await
   }
 }
 
 impl Post {
+  /// Lists one page of the posts of a person, for the export of their data.
+  pub async fn list_for_creator(
+    pool: &DbPool,
+    for_creator_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post
+      .filter(creator_id.eq(for_creator_id))
+      .order_by(published.asc())
+      .then_order_by(crate::schema::post::id)
+      .limit(limit)
+      .offset(offset)
+      .select(Self::as_select())
+      .load::<Self>(conn)
+      .await
+  }
+
   pub async fn list_for_community(
     pool: &DbPool,
     the_community_id: CommunityId,
@@ -82,9 +119,11 @@ impl Post {
       .filter(community_id.eq(the_community_id))
       .filter(deleted.eq(false))
       .filter(removed.eq(false))
+      .filter(scheduled_publish_time.is_null())
       .then_order_by(featured_community.desc())
       .then_order_by(published.desc())
       .limit(FETCH_LIMIT_MAX)
+      .select(Self::as_select())
       .load::<Self>(conn)
       .await
   }
@@ -101,10 +140,49 @@ impl Post {
       .filter(featured_community.eq(true))
       .then_order_by(published.desc())
       .limit(FETCH_LIMIT_MAX)
+      .select(Self::as_select())
       .load::<Self>(conn)
       .await
   }
 
+  /// Publishes the scheduled posts which are due, and returns them. The published time of the
+  /// post and its aggregates is reset, so that it ranks like a new post. Removed posts are
+  /// published as well, so that they don't stay scheduled once they are restored.
+  pub async fn publish_scheduled(pool: &DbPool) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let now = naive_now();
+          let posts = diesel::update(
+            post
+              .filter(scheduled_publish_time.le(now))
+              .filter(deleted.eq(false)),
+          )
+          .set((
+            scheduled_publish_time.eq(None::<DateTime<Utc>>),
+            published.eq(now),
+          ))
+          .returning(Self::as_returning())
+          .get_results::<Self>(conn)
+          .await?;
+
+          let post_ids: Vec<PostId> = posts.iter().map(|p| p.id).collect();
+          diesel::update(post_aggregates::table.filter(post_aggregates::post_id.eq_any(post_ids)))
+            .set((
+              post_aggregates::published.eq(now),
+              post_aggregates::newest_comment_time.eq(now),
+              post_aggregates::newest_comment_time_necro.eq(now),
+            ))
+            .execute(conn)
+            .await?;
+          Ok(posts)
+        }) as _
+      })
+      .await
+  }
+
   pub async fn permadelete_for_creator(
     pool: &DbPool,
     for_creator_id: PersonId,
@@ -119,6 +197,7 @@ impl Post {
         deleted.eq(true),
         updated.eq(naive_now()),
       ))
+      .returning(Self::as_returning())
       .get_results::<Self>(conn)
       .await
   }
@@ -140,6 +219,7 @@ impl Post {
 
     update
       .set((removed.eq(new_removed), updated.eq(naive_now())))
+      .returning(Self::as_returning())
       .get_results::<Self>(conn)
       .await
   }
@@ -154,6 +234,7 @@ impl Post {
     Ok(
       post
         .filter(ap_id.eq(object_id))
+        .select(Post::as_select())
         .first::<Post>(conn)
         .await
         .ok()
@@ -171,6 +252,7 @@ impl Post {
     post
       .filter(creator_id.eq(for_creator_id))
       .filter(url.like(pictrs_search))
+      .select(Self::as_select())
       .load::<Self>(conn)
       .await
   }
@@ -192,6 +274,7 @@ impl Post {
       url.eq::<Option<String>>(None),
       thumbnail_url.eq::<Option<String>>(None),
     ))
+    .returning(Self::as_returning())
     .get_results::<Self>(conn)
     .await
   }
@@ -205,6 +288,7 @@ impl Post {
     post
       .filter(community_id.eq(for_community_id))
       .filter(url.like(pictrs_search))
+      .select(Self::as_select())
       .load::<Self>(conn)
       .await
   }
@@ -226,6 +310,7 @@ impl Post {
       url.eq::<Option<String>>(None),
       thumbnail_url.eq::<Option<String>>(None),
     ))
+    .returning(Self::as_returning())
     .get_results::<Self>(conn)
     .await
   }
@@ -259,6 +344,28 @@ impl Likeable for PostLike {
   }
 }
 
+impl PostLike {
+  /// Lists one page of the ap_ids of posts which a person voted on, with the score.
+  pub async fn list_for_person(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<(DbUrl, i16)>, Error> {
+    use crate::schema::post_like::dsl::{id, person_id, post_like, score};
+    let conn = &mut get_conn(pool).await?;
+    post_like
+      .inner_join(post)
+      .filter(person_id.eq(for_person_id))
+      .order_by(id)
+      .limit(limit)
+      .offset(offset)
+      .select((ap_id, score))
+      .load(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Saveable for PostSaved {
   type Form = PostSavedForm;
@@ -286,6 +393,28 @@ impl Saveable for PostSaved {
   }
 }
 
+impl PostSaved {
+  /// Lists one page of the ap_ids of posts which a person saved.
+  pub async fn list_for_person(
+    pool: &DbPool,
+    for_person_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<DbUrl>, Error> {
+    use crate::schema::post_saved::dsl::{id, person_id, post_saved};
+    let conn = &mut get_conn(pool).await?;
+    post_saved
+      .inner_join(post)
+      .filter(person_id.eq(for_person_id))
+      .order_by(id)
+      .limit(limit)
+      .offset(offset)
+      .select(ap_id)
+      .load(conn)
+      .await
+  }
+}
+
 #[async_trait]
 impl Readable for PostRead {
   type Form = PostReadForm;
@@ -317,6 +446,7 @@ impl Readable for PostRead {
 #[cfg(test)]
 mod tests {
   use crate::{
+    newtypes::PostId,
     source::{
       community::{Community, CommunityInsertForm},
       instance::Instance,
@@ -334,8 +464,9 @@ mod tests {
       },
     },
     traits::{Crud, Likeable, Readable, Saveable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, naive_now},
   };
+  use chrono::Duration;
   use serial_test::serial;
 
   #[tokio::test]
@@ -394,6 +525,7 @@ mod tests {
       language_id: Default::default(),
       featured_community: false,
       featured_local: false,
+      scheduled_publish_time: None,
     };
 
     // Post Like
@@ -445,6 +577,24 @@ mod tests {
 
     let read_post = Post::read(pool, inserted_post.id).await.unwrap();
 
+    // The data export of the person
+    let creator_posts = Post::list_for_creator(pool, inserted_person.id, 10, 0)
+      .await
+      .unwrap();
+    assert_eq!(vec![read_post.clone()], creator_posts);
+    let post_votes = PostLike::list_for_person(pool, inserted_person.id, 10, 0)
+      .await
+      .unwrap();
+    assert_eq!(vec![(inserted_post.ap_id.clone(), 1)], post_votes);
+    let saved_posts = PostSaved::list_for_person(pool, inserted_person.id, 10, 0)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_post.ap_id.clone()], saved_posts);
+    let next_page = Post::list_for_creator(pool, inserted_person.id, 10, 10)
+      .await
+      .unwrap();
+    assert!(next_page.is_empty());
+
     let new_post_update = PostUpdateForm::builder()
       .name(Some("A test post".into()))
       .build();
@@ -477,4 +627,72 @@ mod tests {
     assert_eq!(1, read_removed);
     assert_eq!(1, num_deleted);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_publish_scheduled() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("scheduler".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_scheduled".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let due_form = PostInsertForm::builder()
+      .name("due post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .scheduled_publish_time(Some(naive_now() - Duration::minutes(1)))
+      .build();
+    let due_post = Post::create(pool, &due_form).await.unwrap();
+
+    let future_form = PostInsertForm::builder()
+      .name("future post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .scheduled_publish_time(Some(naive_now() + Duration::days(1)))
+      .build();
+    let future_post = Post::create(pool, &future_form).await.unwrap();
+
+    let removed_form = PostInsertForm::builder()
+      .name("removed post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .removed(Some(true))
+      .scheduled_publish_time(Some(naive_now() - Duration::minutes(1)))
+      .build();
+    let removed_post = Post::create(pool, &removed_form).await.unwrap();
+
+    let published = Post::publish_scheduled(pool).await.unwrap();
+    let read_future_post = Post::read(pool, future_post.id).await.unwrap();
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+
+    let published_ids: Vec<PostId> = published.iter().map(|p| p.id).collect();
+    assert_eq!(2, published_ids.len());
+    assert!(published_ids.contains(&due_post.id));
+    assert!(published_ids.contains(&removed_post.id));
+    assert!(published.iter().all(|p| p.scheduled_publish_time.is_none()));
+    assert!(read_future_post.scheduled_publish_time.is_some());
+  }
 }
@@ -0,0 +1,369 @@
+use crate::{
+  newtypes::{PersonId, PostId, PostPollId, PostPollOptionId},
+  schema::{post_poll, post_poll_option, post_poll_update, post_poll_vote, post_poll_voter},
+  source::post_poll::{
+    PostPoll,
+    PostPollForm,
+    PostPollOption,
+    PostPollOptionForm,
+    PostPollVote,
+    PostPollVoteForm,
+  },
+  utils::{get_conn, naive_now, DbPool},
+};
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl::{exists, select},
+  insert_into,
+  result::Error,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+impl PostPoll {
+  pub async fn upsert(pool: &DbPool, form: &PostPollForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_poll::table)
+      .values(form)
+      .on_conflict(post_poll::post_id)
+      .do_update()
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read_for_post(pool: &DbPool, post_id: PostId) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_poll::table
+      .filter(post_poll::post_id.eq(post_id))
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// A poll is closed once its end time has passed.
+  pub fn is_closed(&self) -> bool {
+    self.end_time.map(|t| t < naive_now()).unwrap_or(false)
+  }
+
+  /// Queues an update of the vote counts of a local poll for other instances. If one is queued
+  /// already, the new votes are sent along with it.
+  pub async fn queue_update(pool: &DbPool, poll_id: PostPollId) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_poll_update::table)
+      .values(post_poll_update::poll_id.eq(poll_id))
+      .on_conflict_do_nothing()
+      .execute(conn)
+      .await?;
+    Ok(())
+  }
+
+  /// Removes the updates which were queued before the given time, and returns the posts of their
+  /// polls.
+  pub async fn take_queued_updates(
+    pool: &DbPool,
+    queued_before: DateTime<Utc>,
+  ) -> Result<Vec<PostId>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let poll_ids =
+      diesel::delete(post_poll_update::table.filter(post_poll_update::published.lt(queued_before)))
+        .returning(post_poll_update::poll_id)
+        .get_results::<PostPollId>(conn)
+        .await?;
+    post_poll::table
+      .filter(post_poll::id.eq_any(poll_ids))
+      .select(post_poll::post_id)
+      .load::<PostId>(conn)
+      .await
+  }
+}
+
+impl PostPollOption {
+  /// Creates the options of a poll. Existing options with the same name only get their vote count
+  /// updated, if one is given.
+  pub async fn upsert(pool: &DbPool, forms: &[PostPollOptionForm]) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut options = Vec::with_capacity(forms.len());
+    for form in forms {
+      let option = insert_into(post_poll_option::table)
+        .values(form)
+        .on_conflict((post_poll_option::poll_id, post_poll_option::name))
+        .do_update()
+        .set(form)
+        .get_result::<Self>(conn)
+        .await?;
+      options.push(option);
+    }
+    Ok(options)
+  }
+
+  pub async fn list_for_poll(pool: &DbPool, poll_id: PostPollId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_poll_option::table
+      .filter(post_poll_option::poll_id.eq(poll_id))
+      .order_by(post_poll_option::id)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+impl PostPollVote {
+  /// Stores the chosen options of a person in a poll. Returns `None` without storing anything if
+  /// the person already voted in this poll.
+  pub async fn vote(
+    pool: &DbPool,
+    poll_id: PostPollId,
+    person_id: PersonId,
+    option_ids: &[PostPollOptionId],
+  ) -> Result<Option<Vec<Self>>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let forms = option_ids
+      .iter()
+      .map(|option_id| PostPollVoteForm {
+        option_id: *option_id,
+        person_id,
+      })
+      .collect::<Vec<_>>();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let inserted = insert_into(post_poll_voter::table)
+            .values((
+              post_poll_voter::poll_id.eq(poll_id),
+              post_poll_voter::person_id.eq(person_id),
+            ))
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          if inserted == 0 {
+            return Ok(None);
+          }
+          insert_into(post_poll_vote::table)
+            .values(forms)
+            .get_results::<Self>(conn)
+            .await
+            .map(Some)
+        }) as _
+      })
+      .await
+  }
+
+  /// Adds a single option to the votes of a person. Remote instances send the votes for multiple
+  /// choice polls one option at a time. Returns whether the vote was stored, which is not the case
+  /// for repeated votes, or further options in a single choice poll.
+  pub async fn add_vote(
+    pool: &DbPool,
+    poll_id: PostPollId,
+    form: &PostPollVoteForm,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let form = form.clone();
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let multiple_choice = post_poll::table
+            .find(poll_id)
+            .select(post_poll::multiple_choice)
+            .first::<bool>(conn)
+            .await?;
+          let new_voter = insert_into(post_poll_voter::table)
+            .values((
+              post_poll_voter::poll_id.eq(poll_id),
+              post_poll_voter::person_id.eq(form.person_id),
+            ))
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?
+            > 0;
+          if !new_voter && !multiple_choice {
+            return Ok(false);
+          }
+          let inserted = insert_into(post_poll_vote::table)
+            .values(form)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          Ok(inserted > 0)
+        }) as _
+      })
+      .await
+  }
+
+  pub async fn has_voted(
+    pool: &DbPool,
+    poll_id: PostPollId,
+    person_id: PersonId,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    select(exists(
+      post_poll_vote::table
+        .inner_join(post_poll_option::table)
+        .filter(post_poll_option::poll_id.eq(poll_id))
+        .filter(post_poll_vote::person_id.eq(person_id)),
+    ))
+    .get_result(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_poll::{
+        PostPoll,
+        PostPollForm,
+        PostPollOption,
+        PostPollOptionForm,
+        PostPollVote,
+        PostPollVoteForm,
+      },
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, naive_now},
+  };
+  use chrono::Duration;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_poll() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("poll_voter".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("test_community_poll".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A poll".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let poll_form = PostPollForm::builder().post_id(inserted_post.id).build();
+    let poll = PostPoll::upsert(pool, &poll_form).await.unwrap();
+    assert!(!poll.multiple_choice);
+    assert!(!poll.is_closed());
+
+    let option_forms = ["yes", "no"].map(|name| {
+      PostPollOptionForm::builder()
+        .poll_id(poll.id)
+        .name(name.to_string())
+        .build()
+    });
+    let options = PostPollOption::upsert(pool, &option_forms).await.unwrap();
+    assert_eq!(2, options.len());
+
+    let has_voted = PostPollVote::has_voted(pool, poll.id, inserted_person.id)
+      .await
+      .unwrap();
+    assert!(!has_voted);
+
+    let votes = PostPollVote::vote(pool, poll.id, inserted_person.id, &[options[0].id])
+      .await
+      .unwrap();
+    assert_eq!(Some(1), votes.map(|v| v.len()));
+    let has_voted = PostPollVote::has_voted(pool, poll.id, inserted_person.id)
+      .await
+      .unwrap();
+    assert!(has_voted);
+
+    // A second vote in the same poll isn't stored, even for another option
+    let second_vote = PostPollVote::vote(pool, poll.id, inserted_person.id, &[options[1].id])
+      .await
+      .unwrap();
+    assert_eq!(None, second_vote);
+
+    // Remote votes arrive one option at a time, further options only count in multiple choice
+    // polls
+    let vote_form = PostPollVoteForm {
+      option_id: options[1].id,
+      person_id: inserted_person.id,
+    };
+    let added = PostPollVote::add_vote(pool, poll.id, &vote_form)
+      .await
+      .unwrap();
+    assert!(!added);
+
+    let multiple_choice_form = PostPollForm::builder()
+      .post_id(inserted_post.id)
+      .multiple_choice(Some(true))
+      .build();
+    let poll = PostPoll::upsert(pool, &multiple_choice_form).await.unwrap();
+    let added = PostPollVote::add_vote(pool, poll.id, &vote_form)
+      .await
+      .unwrap();
+    assert!(added);
+    let repeated = PostPollVote::add_vote(pool, poll.id, &vote_form)
+      .await
+      .unwrap();
+    assert!(!repeated);
+
+    // The vote count is updated by a trigger
+    let options = PostPollOption::list_for_poll(pool, poll.id).await.unwrap();
+    assert_eq!(1, options[0].vote_count);
+    assert_eq!(1, options[1].vote_count);
+
+    // Updates of the vote counts are combined until they are sent
+    PostPoll::queue_update(pool, poll.id).await.unwrap();
+    PostPoll::queue_update(pool, poll.id).await.unwrap();
+    let not_due = PostPoll::take_queued_updates(pool, naive_now() - Duration::minutes(1))
+      .await
+      .unwrap();
+    assert!(not_due.is_empty());
+    let due = PostPoll::take_queued_updates(pool, naive_now() + Duration::minutes(1))
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_post.id], due);
+    let sent = PostPoll::take_queued_updates(pool, naive_now() + Duration::minutes(1))
+      .await
+      .unwrap();
+    assert!(sent.is_empty());
+
+    // Remote instances report their own totals
+    let remote_form = PostPollOptionForm::builder()
+      .poll_id(poll.id)
+      .name("no".to_string())
+      .vote_count(Some(5))
+      .build();
+    let updated = PostPollOption::upsert(pool, &[remote_form]).await.unwrap();
+    assert_eq!(options[1].id, updated[0].id);
+    assert_eq!(5, updated[0].vote_count);
+
+    let read_poll = PostPoll::read_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(Some(poll), read_poll);
+
+    Post::delete(pool, inserted_post.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
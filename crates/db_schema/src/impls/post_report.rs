@@ -1,6 +1,17 @@
 use crate::{
   newtypes::{PersonId, PostReportId},
-  schema::post_report::dsl::{post_report, resolved, resolver_id, updated},
+  schema::post_report::dsl::{
+    assignee_id,
+    automod,
+    creator_id,
+    escalated,
+    post_id,
+    post_report,
+    resolution_reason,
+    resolved,
+    resolver_id,
+    updated,
+  },
   source::post_report::{PostReport, PostReportForm},
   traits::Reportable,
   utils::{get_conn, naive_now, DbPool},
@@ -9,6 +20,7 @@ use diesel::{
   dsl::{insert_into, update},
   result::Error,
   ExpressionMethods,
+  OptionalExtension,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
@@ -30,12 +42,14 @@ impl Reportable for PostReport {
     pool: &DbPool,
     report_id: Self::IdType,
     by_resolver_id: PersonId,
+    reason: Option<String>,
   ) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
     update(post_report.find(report_id))
       .set((
         resolved.eq(true),
         resolver_id.eq(by_resolver_id),
+        resolution_reason.eq(reason),
         updated.eq(naive_now()),
       ))
       .execute(conn)
@@ -52,9 +66,50 @@ impl Reportable for PostReport {
       .set((
         resolved.eq(false),
         resolver_id.eq(by_resolver_id),
+        resolution_reason.eq(None::<String>),
         updated.eq(naive_now()),
       ))
       .execute(conn)
       .await
   }
+
+  async fn assign(
+    pool: &DbPool,
+    report_id: Self::IdType,
+    to_assignee_id: Option<PersonId>,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(post_report.find(report_id))
+      .set((assignee_id.eq(to_assignee_id), updated.eq(naive_now())))
+      .execute(conn)
+      .await
+  }
+}
+
+impl PostReport {
+  /// Creates a report by automod, unless the post was already reported in the name of the same
+  /// person.
+  pub async fn report_automod(pool: &DbPool, form: &PostReportForm) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(post_report)
+      .values((form, automod.eq(true)))
+      .on_conflict((post_id, creator_id))
+      .do_nothing()
+      .get_result::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// Hands the report over to the instance admins, or takes it back.
+  pub async fn escalate(
+    pool: &DbPool,
+    report_id: PostReportId,
+    escalate: bool,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(post_report.find(report_id))
+      .set((escalated.eq(escalate), updated.eq(naive_now())))
+      .execute(conn)
+      .await
+  }
 }
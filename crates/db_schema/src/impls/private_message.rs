@@ -1,11 +1,18 @@
 use crate::{
   newtypes::{DbUrl, PersonId, PrivateMessageId},
-  schema::private_message::dsl::{ap_id, private_message, read, recipient_id},
+  schema::private_message::dsl::{
+    ap_id,
+    creator_id,
+    private_message,
+    published,
+    read,
+    recipient_id,
+  },
   source::private_message::{PrivateMessage, PrivateMessageInsertForm, PrivateMessageUpdateForm},
   traits::Crud,
   utils::{get_conn, DbPool},
 };
-use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel::{dsl::insert_into, result::Error, BoolExpressionMethods, ExpressionMethods, QueryDsl};
 use diesel_async::RunQueryDsl;
 use lemmy_utils::error::LemmyError;
 use url::Url;
@@ -69,6 +76,24 @@ impl PrivateMessage {
     .await
   }
 
+  /// Lists one page of the messages which a person sent or received, for the export of their data.
+  pub async fn list_for_person(
+    pool: &DbPool,
+    person_id: PersonId,
+    limit: i64,
+    offset: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    private_message
+      .filter(creator_id.eq(person_id).or(recipient_id.eq(person_id)))
+      .order_by(published.asc())
+      .then_order_by(crate::schema::private_message::id)
+      .limit(limit)
+      .offset(offset)
+      .load::<Self>(conn)
+      .await
+  }
+
   pub async fn read_from_apub_id(
     pool: &DbPool,
     object_id: Url,
@@ -1,6 +1,13 @@
 use crate::{
   newtypes::{PersonId, PrivateMessageReportId},
-  schema::private_message_report::dsl::{private_message_report, resolved, resolver_id, updated},
+  schema::private_message_report::dsl::{
+    assignee_id,
+    private_message_report,
+    resolution_reason,
+    resolved,
+    resolver_id,
+    updated,
+  },
   source::private_message_report::{PrivateMessageReport, PrivateMessageReportForm},
   traits::Reportable,
   utils::{get_conn, naive_now, DbPool},
@@ -30,12 +37,14 @@ impl Reportable for PrivateMessageReport {
     pool: &DbPool,
     report_id: Self::IdType,
     by_resolver_id: PersonId,
+    reason: Option<String>,
   ) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
     update(private_message_report.find(report_id))
       .set((
         resolved.eq(true),
         resolver_id.eq(by_resolver_id),
+        resolution_reason.eq(reason),
         updated.eq(naive_now()),
       ))
       .execute(conn)
@@ -52,9 +61,22 @@ impl Reportable for PrivateMessageReport {
       .set((
         resolved.eq(false),
         resolver_id.eq(by_resolver_id),
+        resolution_reason.eq(None::<String>),
         updated.eq(naive_now()),
       ))
       .execute(conn)
       .await
   }
+
+  async fn assign(
+    pool: &DbPool,
+    report_id: Self::IdType,
+    to_assignee_id: Option<PersonId>,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    update(private_message_report.find(report_id))
+      .set((assignee_id.eq(to_assignee_id), updated.eq(naive_now())))
+      .execute(conn)
+      .await
+  }
 }
@@ -0,0 +1,196 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::push_subscription::dsl::{endpoint, id, local_user_id, published, push_subscription},
+  source::push_subscription::{PushSubscription, PushSubscriptionForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{
+  delete,
+  dsl::now,
+  insert_into,
+  query_dsl::methods,
+  result::Error,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+
+/// Maximum number of push subscriptions of a user. Registering more removes the oldest ones.
+const MAX_PUSH_SUBSCRIPTIONS_PER_USER: i64 = 10;
+
+impl PushSubscription {
+  /// Registers a subscription, or updates the keys if the user already registered the endpoint.
+  /// Returns `None` if the endpoint belongs to another user, who has to remove it first.
+  pub async fn upsert(pool: &DbPool, form: &PushSubscriptionForm) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let upsert = insert_into(push_subscription)
+      .values(form)
+      .on_conflict(endpoint)
+      .do_update()
+      // Registering again counts as new, so that endpoints which are in use aren't evicted
+      .set((form, published.eq(now)));
+    // Only updates the subscription if it belongs to the same user
+    let subscription = methods::FilterDsl::filter(upsert, local_user_id.eq(form.local_user_id))
+      .get_result::<Self>(conn)
+      .await
+      .optional()?;
+
+    // Browsers register new endpoints over time, so the oldest ones are evicted
+    let evicted: Vec<i32> = push_subscription
+      .filter(local_user_id.eq(form.local_user_id))
+      .order_by(published.desc())
+      .then_order_by(id.desc())
+      .offset(MAX_PUSH_SUBSCRIPTIONS_PER_USER)
+      .select(id)
+      .load(conn)
+      .await?;
+    if !evicted.is_empty() {
+      delete(push_subscription.filter(id.eq_any(evicted)))
+        .execute(conn)
+        .await?;
+    }
+    Ok(subscription)
+  }
+
+  pub async fn list_for_local_user(
+    pool: &DbPool,
+    for_local_user_id: LocalUserId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    push_subscription
+      .filter(local_user_id.eq(for_local_user_id))
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Removes a subscription of the user, for example on logout.
+  pub async fn delete_for_local_user(
+    pool: &DbPool,
+    for_local_user_id: LocalUserId,
+    for_endpoint: &str,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(
+      push_subscription
+        .filter(local_user_id.eq(for_local_user_id))
+        .filter(endpoint.eq(for_endpoint)),
+    )
+    .execute(conn)
+    .await
+  }
+
+  /// Removes a subscription which the push service doesn't know anymore.
+  pub async fn delete(pool: &DbPool, subscription_id: i32) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(push_subscription.find(subscription_id))
+      .execute(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::MAX_PUSH_SUBSCRIPTIONS_PER_USER;
+  use crate::{
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      push_subscription::{PushSubscription, PushSubscriptionForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_push_subscription() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let mut local_user_ids = vec![];
+    for name in ["push_subscription_a", "push_subscription_b"] {
+      let new_person = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      let inserted_person = Person::create(pool, &new_person).await.unwrap();
+      let new_local_user = LocalUserInsertForm::builder()
+        .person_id(inserted_person.id)
+        .password_encrypted("pass".to_string())
+        .build();
+      let inserted_local_user = LocalUser::create(pool, &new_local_user).await.unwrap();
+      local_user_ids.push(inserted_local_user.id);
+    }
+
+    let form = |local_user_id, endpoint: &str| PushSubscriptionForm {
+      local_user_id,
+      endpoint: endpoint.to_string(),
+      p256dh: "p256dh".to_string(),
+      auth: "auth".to_string(),
+    };
+    let first = PushSubscription::upsert(pool, &form(local_user_ids[0], "https://push.tld/1"))
+      .await
+      .unwrap()
+      .unwrap();
+    PushSubscription::upsert(pool, &form(local_user_ids[0], "https://push.tld/2"))
+      .await
+      .unwrap();
+
+    // Registering again only updates the keys
+    let updated = PushSubscription::upsert(pool, &form(local_user_ids[0], "https://push.tld/1"))
+      .await
+      .unwrap();
+    assert_eq!(Some(first.id), updated.map(|s| s.id));
+
+    // The endpoint of another user can't be taken over
+    let taken = PushSubscription::upsert(pool, &form(local_user_ids[1], "https://push.tld/1"))
+      .await
+      .unwrap();
+    assert_eq!(None, taken);
+
+    let subscriptions = PushSubscription::list_for_local_user(pool, local_user_ids[0])
+      .await
+      .unwrap();
+    assert_eq!(2, subscriptions.len());
+    let subscriptions = PushSubscription::list_for_local_user(pool, local_user_ids[1])
+      .await
+      .unwrap();
+    assert!(subscriptions.is_empty());
+
+    // Only the newest subscriptions are kept
+    for i in 0..=MAX_PUSH_SUBSCRIPTIONS_PER_USER {
+      let endpoint = format!("https://push.tld/b/{i}");
+      PushSubscription::upsert(pool, &form(local_user_ids[1], &endpoint))
+        .await
+        .unwrap();
+    }
+    let subscriptions = PushSubscription::list_for_local_user(pool, local_user_ids[1])
+      .await
+      .unwrap();
+    assert_eq!(MAX_PUSH_SUBSCRIPTIONS_PER_USER, subscriptions.len() as i64);
+    assert!(!subscriptions
+      .iter()
+      .any(|s| s.endpoint == "https://push.tld/b/0"));
+
+    // Only the own subscriptions can be removed
+    let num_deleted =
+      PushSubscription::delete_for_local_user(pool, local_user_ids[1], "https://push.tld/1")
+        .await
+        .unwrap();
+    assert_eq!(0, num_deleted);
+    let num_deleted = PushSubscription::delete(pool, first.id).await.unwrap();
+    assert_eq!(1, num_deleted);
+
+    for local_user_id in local_user_ids {
+      LocalUser::delete(pool, local_user_id).await.unwrap();
+    }
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
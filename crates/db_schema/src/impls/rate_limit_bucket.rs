@@ -0,0 +1,127 @@
+use crate::{
+  schema::rate_limit_bucket::dsl::{action, key, last_checked, rate_limit_bucket, tokens},
+  source::rate_limit_bucket::RateLimitBucket,
+  utils::{get_conn, naive_now, DbPool},
+};
+use chrono::Duration;
+use diesel::{
+  delete,
+  insert_into,
+  result::Error,
+  upsert::excluded,
+  ExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_utils::rate_limit::rate_limiter::PersistedBucket;
+
+/// Postgres allows at most 65535 bind parameters per query, and each row uses four.
+const INSERT_CHUNK_SIZE: usize = 10_000;
+
+impl RateLimitBucket {
+  pub async fn read_all(pool: &DbPool) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    rate_limit_bucket.load::<Self>(conn).await
+  }
+
+  /// Stores the given buckets, overwriting stored buckets with the same key and action. Buckets
+  /// which are not given are kept, as they may belong to another lemmy process.
+  pub async fn upsert_all(pool: &DbPool, buckets: Vec<Self>) -> Result<(), Error> {
+    let conn = &mut get_conn(pool).await?;
+    for chunk in buckets.chunks(INSERT_CHUNK_SIZE) {
+      insert_into(rate_limit_bucket)
+        .values(chunk)
+        .on_conflict((key, action))
+        .do_update()
+        .set((
+          tokens.eq(excluded(tokens)),
+          last_checked.eq(excluded(last_checked)),
+        ))
+        .execute(conn)
+        .await?;
+    }
+    Ok(())
+  }
+
+  /// Removes buckets which weren't used for the given duration, like the in-memory ones.
+  pub async fn delete_older_than(pool: &DbPool, duration: Duration) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(rate_limit_bucket.filter(last_checked.lt(naive_now() - duration)))
+      .execute(conn)
+      .await
+  }
+}
+
+impl From<PersistedBucket> for RateLimitBucket {
+  fn from(bucket: PersistedBucket) -> Self {
+    RateLimitBucket {
+      key: bucket.key,
+      action: bucket.action,
+      tokens: bucket.tokens,
+      last_checked: bucket.last_checked,
+    }
+  }
+}
+
+impl From<RateLimitBucket> for PersistedBucket {
+  fn from(bucket: RateLimitBucket) -> Self {
+    PersistedBucket {
+      key: bucket.key,
+      action: bucket.action,
+      tokens: bucket.tokens,
+      last_checked: bucket.last_checked,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{source::rate_limit_bucket::RateLimitBucket, utils::build_db_pool_for_tests};
+  use chrono::{DurationRound, Utc};
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_rate_limit_bucket() {
+    let pool = &build_db_pool_for_tests().await;
+
+    // Postgres stores timestamps with microsecond precision
+    let now = Utc::now()
+      .duration_trunc(chrono::Duration::seconds(1))
+      .unwrap();
+    let bucket = |key: &str, tokens| RateLimitBucket {
+      key: key.to_string(),
+      action: "Post".to_string(),
+      tokens,
+      last_checked: now,
+    };
+
+    RateLimitBucket::upsert_all(
+      pool,
+      vec![bucket("ipv4:1.2.3.4", 2.0), bucket("ipv4:5.6.7.8", 1.0)],
+    )
+    .await
+    .unwrap();
+    // Buckets which are saved by another process are kept
+    RateLimitBucket::upsert_all(
+      pool,
+      vec![bucket("ipv4:1.2.3.4", 3.5), bucket("user:1", 0.5)],
+    )
+    .await
+    .unwrap();
+    let expected = vec![
+      bucket("ipv4:1.2.3.4", 3.5),
+      bucket("ipv4:5.6.7.8", 1.0),
+      bucket("user:1", 0.5),
+    ];
+
+    let mut read = RateLimitBucket::read_all(pool).await.unwrap();
+    read.sort_by(|a, b| a.key.cmp(&b.key));
+    assert_eq!(expected, read);
+
+    RateLimitBucket::delete_older_than(pool, chrono::Duration::seconds(-1))
+      .await
+      .unwrap();
+    assert!(RateLimitBucket::read_all(pool).await.unwrap().is_empty());
+  }
+}
@@ -0,0 +1,77 @@
+use crate::{
+  newtypes::DbUrl,
+  schema::remote_image,
+  source::remote_image::{RemoteImage, RemoteImageForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{delete, insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl RemoteImage {
+  /// Stores the copy of a remote image. If another request already stored a copy at the same
+  /// time, that one is kept.
+  pub async fn create(pool: &DbPool, form: &RemoteImageForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(remote_image::table)
+      .values(form)
+      .on_conflict(remote_image::link)
+      .do_update()
+      .set(remote_image::link.eq(remote_image::link))
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &DbPool, link: &DbUrl) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    remote_image::table
+      .find(link)
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// Forgets the copy, for example after it was purged from the image storage.
+  pub async fn delete(pool: &DbPool, link: &DbUrl) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    delete(remote_image::table.find(link)).execute(conn).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    source::remote_image::{RemoteImage, RemoteImageForm},
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_remote_image() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let link = Url::parse("https://example.com/image.png").unwrap().into();
+    assert_eq!(None, RemoteImage::read(pool, &link).await.unwrap());
+
+    let form = RemoteImageForm {
+      link: link.clone(),
+      pictrs_alias: "first.png".to_string(),
+    };
+    let inserted = RemoteImage::create(pool, &form).await.unwrap();
+    let form = RemoteImageForm {
+      link: link.clone(),
+      pictrs_alias: "second.png".to_string(),
+    };
+    // The first copy is kept
+    let second = RemoteImage::create(pool, &form).await.unwrap();
+    assert_eq!(inserted, second);
+    assert_eq!("first.png", second.pictrs_alias);
+
+    let read = RemoteImage::read(pool, &link).await.unwrap();
+    assert_eq!(Some(inserted), read);
+
+    let deleted = RemoteImage::delete(pool, &link).await.unwrap();
+    assert_eq!(1, deleted);
+  }
+}
@@ -0,0 +1,22 @@
+use crate::{
+  schema::report_note,
+  source::report_note::{ReportNote, ReportNoteForm},
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl ReportNote {
+  pub async fn create(pool: &DbPool, form: &ReportNoteForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(report_note::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  pub async fn read(pool: &DbPool, note_id: i32) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    report_note::table.find(note_id).first::<Self>(conn).await
+  }
+}
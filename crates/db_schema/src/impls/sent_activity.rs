@@ -0,0 +1,139 @@
+use crate::{
+  schema::sent_activity::dsl::{id, send_inboxes, sent_activity},
+  source::sent_activity::{SentActivity, SentActivityForm},
+  utils::{functions::array_to_string, get_conn, DbPool},
+};
+use diesel::{
+  insert_into,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  OptionalExtension,
+  QueryDsl,
+  TextExpressionMethods,
+};
+use diesel_async::RunQueryDsl;
+
+impl SentActivity {
+  pub async fn create(pool: &DbPool, form: SentActivityForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(sent_activity)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// The next activities which were queued after the given id, oldest first.
+  pub async fn read_after(pool: &DbPool, after_id: i32, limit: i64) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    sent_activity
+      .filter(id.gt(after_id))
+      .order_by(id.asc())
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+  }
+
+  /// Id of the most recently queued activity, if any.
+  pub async fn read_max_id(pool: &DbPool) -> Result<Option<i32>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    sent_activity
+      .select(id)
+      .order_by(id.desc())
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Id of the oldest queued activity which has an inbox on the given domain, if any. Inboxes on
+  /// other domains with a similar name may also match.
+  pub async fn read_first_id_for_domain(pool: &DbPool, domain: &str) -> Result<Option<i32>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let inboxes = array_to_string(send_inboxes, " ");
+    sent_activity
+      .select(id)
+      .filter(
+        inboxes
+          .like(format!("%://{domain}/%"))
+          .or(inboxes.like(format!("%://{domain}:%"))),
+      )
+      .order_by(id.asc())
+      .first(conn)
+      .await
+      .optional()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    newtypes::DbUrl,
+    source::{
+      activity::{Activity, ActivityInsertForm},
+      sent_activity::{SentActivity, SentActivityForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    ActorType,
+  };
+  use serde_json::json;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_sent_activity() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let ap_id: DbUrl = Url::parse("https://my_domain.tld/activities/follow/1")
+      .unwrap()
+      .into();
+    let activity_form = ActivityInsertForm {
+      ap_id: ap_id.clone(),
+      data: json!({"id": ap_id.to_string(), "type": "Follow"}),
+      local: Some(true),
+      sensitive: Some(false),
+      updated: None,
+    };
+    let inserted_activity = Activity::create(pool, &activity_form).await.unwrap();
+
+    let previous_max_id = SentActivity::read_max_id(pool).await.unwrap().unwrap_or(0);
+    let inbox: DbUrl = Url::parse("https://other_domain.tld/inbox").unwrap().into();
+    let form = SentActivityForm {
+      activity_id: inserted_activity.id,
+      send_inboxes: vec![Some(inbox)],
+      actor_type: ActorType::Person,
+      actor_apub_id: Url::parse("https://my_domain.tld/u/sender").unwrap().into(),
+    };
+    let inserted_sent_activity = SentActivity::create(pool, form).await.unwrap();
+
+    assert_eq!(
+      Some(inserted_sent_activity.id),
+      SentActivity::read_max_id(pool).await.unwrap()
+    );
+
+    let first_id = SentActivity::read_first_id_for_domain(pool, "other_domain.tld")
+      .await
+      .unwrap();
+    assert!(first_id.is_some_and(|first_id| first_id <= inserted_sent_activity.id));
+    let first_id = SentActivity::read_first_id_for_domain(pool, "unknown_domain.tld")
+      .await
+      .unwrap();
+    assert_eq!(None, first_id);
+    let after = SentActivity::read_after(pool, previous_max_id, 10)
+      .await
+      .unwrap();
+    assert_eq!(vec![inserted_sent_activity], after);
+    let after = SentActivity::read_after(pool, after[0].id, 10)
+      .await
+      .unwrap();
+    assert!(after.is_empty());
+
+    // Deleting the activity also removes it from the queue
+    Activity::delete(pool, inserted_activity.id).await.unwrap();
+    let after = SentActivity::read_after(pool, previous_max_id, 10)
+      .await
+      .unwrap();
+    assert!(after.is_empty());
+  }
+}
@@ -66,6 +66,8 @@ pub enum SortType {
   TopThreeMonths,
   TopSixMonths,
   TopNineMonths,
+  /// Ranks search results by how well they match the query. Behaves like Hot without a search term.
+  Relevance,
 }
 
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy)]
@@ -77,6 +79,8 @@ pub enum CommentSortType {
   Top,
   New,
   Old,
+  /// Full text search rank, see SortType::Relevance.
+  Relevance,
 }
 
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
@@ -95,6 +99,8 @@ pub enum ListingType {
   Local,
   /// Content only from communities you've subscribed to.
   Subscribed,
+  /// Content only from users you follow.
+  FollowedUsers,
 }
 
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
@@ -115,6 +121,67 @@ pub enum RegistrationMode {
   Open,
 }
 
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::CommunityVisibilityEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// Who can see the content of a community, and whether following it needs approval.
+pub enum CommunityVisibility {
+  #[default]
+  /// Visible to everyone, and federated.
+  Public,
+  /// Follows need to be approved by a mod, and only followers can see posts and comments.
+  ApprovalRequired,
+  /// Only visible to logged in users of this instance, and not federated.
+  LocalOnly,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::AutomodRuleTypeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// The condition checked by an automod rule. The rule is violated if the condition doesn't hold.
+pub enum AutomodRuleType {
+  /// The creator account must be at least this many days old.
+  MinAccountAge,
+  /// The creator must have at least this combined post and comment score.
+  MinScore,
+  /// Links to this domain, or any of its subdomains, are not allowed.
+  BannedDomain,
+  /// Post titles must not match this regex.
+  TitleRegex,
+  /// Post titles must contain one of these comma separated keywords, for example `[Question]`.
+  RequiredKeyword,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(DbEnum, TS))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::AutomodActionEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+#[cfg_attr(feature = "full", ts(export))]
+/// What happens to content which violates an automod rule.
+pub enum AutomodAction {
+  /// Refuse to create the content.
+  Reject,
+  /// Create the content, but remove it right away.
+  Remove,
+  /// Create the content, and report it to the mods.
+  Report,
+}
+
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -159,6 +226,7 @@ pub enum ModlogActionType {
   AdminPurgeCommunity,
   AdminPurgePost,
   AdminPurgeComment,
+  ModResolveReport,
 }
 
 #[derive(
@@ -174,3 +242,17 @@ pub enum PostFeatureType {
   /// Features to the top of the community.
   Community,
 }
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(DbEnum))]
+#[cfg_attr(
+  feature = "full",
+  ExistingTypePath = "crate::schema::sql_types::ActorTypeEnum"
+)]
+#[cfg_attr(feature = "full", DbValueStyle = "verbatim")]
+/// The type of local actor which sends an activity.
+pub enum ActorType {
+  Site,
+  Community,
+  Person,
+}
@@ -1,6 +1,22 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "actor_type_enum"))]
+    pub struct ActorTypeEnum;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "automod_action_enum"))]
+    pub struct AutomodActionEnum;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "automod_rule_type_enum"))]
+    pub struct AutomodRuleTypeEnum;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "community_visibility_enum"))]
+    pub struct CommunityVisibilityEnum;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "listing_type_enum"))]
     pub struct ListingTypeEnum;
@@ -76,6 +92,7 @@ diesel::table! {
 diesel::table! {
     use diesel::sql_types::*;
     use diesel_ltree::sql_types::Ltree;
+    use diesel_full_text_search::Tsvector;
 
     comment (id) {
         id -> Int4,
@@ -92,6 +109,7 @@ diesel::table! {
         path -> Ltree,
         distinguished -> Bool,
         language_id -> Int4,
+        search_vector -> Tsvector,
     }
 }
 
@@ -108,6 +126,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    comment_edit_history (id) {
+        id -> Int4,
+        comment_id -> Int4,
+        content -> Text,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     comment_like (id) {
         id -> Int4,
@@ -140,6 +167,10 @@ diesel::table! {
         resolver_id -> Nullable<Int4>,
         published -> Timestamptz,
         updated -> Nullable<Timestamptz>,
+        assignee_id -> Nullable<Int4>,
+        escalated -> Bool,
+        resolution_reason -> Nullable<Text>,
+        automod -> Bool,
     }
 }
 
@@ -153,6 +184,10 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use diesel_full_text_search::Tsvector;
+    use super::sql_types::CommunityVisibilityEnum;
+
     community (id) {
         id -> Int4,
         #[max_length = 255]
@@ -186,6 +221,9 @@ diesel::table! {
         moderators_url -> Nullable<Varchar>,
         #[max_length = 255]
         featured_url -> Nullable<Varchar>,
+        search_vector -> Tsvector,
+        visibility -> CommunityVisibilityEnum,
+        edit_history_public -> Bool,
     }
 }
 
@@ -205,6 +243,22 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AutomodRuleTypeEnum;
+    use super::sql_types::AutomodActionEnum;
+
+    community_automod_rule (id) {
+        id -> Int4,
+        community_id -> Int4,
+        rule_type -> AutomodRuleTypeEnum,
+        value -> Text,
+        action -> AutomodActionEnum,
+        reason -> Nullable<Text>,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     community_block (id) {
         id -> Int4,
@@ -251,6 +305,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    community_post_tag (id) {
+        id -> Int4,
+        #[max_length = 255]
+        ap_id -> Varchar,
+        community_id -> Int4,
+        #[max_length = 50]
+        name -> Varchar,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     custom_emoji (id) {
         id -> Int4,
@@ -302,6 +369,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    federation_queue_state (id) {
+        id -> Int4,
+        instance_id -> Int4,
+        last_successful_id -> Int4,
+        fail_count -> Int4,
+        last_retry -> Nullable<Timestamptz>,
+        last_successful_send -> Nullable<Timestamptz>,
+        inbox_latency_ms -> Nullable<Int4>,
+    }
+}
+
 diesel::table! {
     instance (id) {
         id -> Int4,
@@ -313,6 +392,8 @@ diesel::table! {
         software -> Nullable<Varchar>,
         #[max_length = 255]
         version -> Nullable<Varchar>,
+        last_received_activity -> Nullable<Timestamptz>,
+        dead -> Bool,
     }
 }
 
@@ -325,6 +406,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    local_image (pictrs_alias) {
+        local_user_id -> Int4,
+        pictrs_alias -> Text,
+        pictrs_delete_token -> Text,
+        published -> Timestamptz,
+        file_size -> Int8,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ListingTypeEnum;
@@ -355,6 +446,9 @@ diesel::table! {
         updated -> Nullable<Timestamptz>,
         registration_mode -> RegistrationModeEnum,
         reports_email_admins -> Bool,
+        image_upload_max_bytes -> Int8,
+        image_upload_allowed_mime_types -> Array<Text>,
+        image_upload_quota_bytes -> Nullable<Int8>,
     }
 }
 
@@ -376,6 +470,13 @@ diesel::table! {
         search_per_second -> Int4,
         published -> Timestamptz,
         updated -> Nullable<Timestamptz>,
+        message_per_user -> Int4,
+        post_per_user -> Int4,
+        image_per_user -> Int4,
+        comment_per_user -> Int4,
+        search_per_user -> Int4,
+        import_user_settings -> Int4,
+        import_user_settings_per_second -> Int4,
     }
 }
 
@@ -407,6 +508,9 @@ diesel::table! {
         totp_2fa_secret -> Nullable<Text>,
         totp_2fa_url -> Nullable<Text>,
         open_links_in_new_tab -> Bool,
+        push_notify_replies -> Bool,
+        push_notify_mentions -> Bool,
+        push_notify_private_messages -> Bool,
     }
 }
 
@@ -418,6 +522,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    login_token (id) {
+        id -> Int4,
+        token_encrypted -> Text,
+        local_user_id -> Int4,
+        ip -> Nullable<Text>,
+        user_agent -> Nullable<Text>,
+        published -> Timestamptz,
+        last_used -> Timestamptz,
+        expires -> Timestamptz,
+    }
+}
+
 diesel::table! {
     mod_add (id) {
         id -> Int4,
@@ -504,6 +621,7 @@ diesel::table! {
         reason -> Nullable<Text>,
         removed -> Bool,
         when_ -> Timestamptz,
+        automod -> Bool,
     }
 }
 
@@ -527,6 +645,20 @@ diesel::table! {
         reason -> Nullable<Text>,
         removed -> Bool,
         when_ -> Timestamptz,
+        automod -> Bool,
+    }
+}
+
+diesel::table! {
+    mod_resolve_report (id) {
+        id -> Int4,
+        mod_person_id -> Int4,
+        post_id -> Nullable<Int4>,
+        comment_id -> Nullable<Int4>,
+        resolved -> Bool,
+        reason -> Nullable<Text>,
+        when_ -> Timestamptz,
+        private_message_report_id -> Nullable<Int4>,
     }
 }
 
@@ -540,6 +672,30 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    oidc_identity (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        #[max_length = 255]
+        provider -> Varchar,
+        subject -> Text,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    oidc_login_state (id) {
+        id -> Int4,
+        state -> Text,
+        #[max_length = 255]
+        provider -> Varchar,
+        code_verifier -> Text,
+        redirect_uri -> Text,
+        published -> Timestamptz,
+        link_local_user_id -> Nullable<Int4>,
+    }
+}
+
 diesel::table! {
     password_reset_request (id) {
         id -> Int4,
@@ -578,6 +734,9 @@ diesel::table! {
         bot_account -> Bool,
         ban_expires -> Nullable<Timestamptz>,
         instance_id -> Int4,
+        also_known_as -> Array<Text>,
+        #[max_length = 255]
+        moved_to -> Nullable<Varchar>,
     }
 }
 
@@ -640,6 +799,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use diesel_full_text_search::Tsvector;
+
     post (id) {
         id -> Int4,
         #[max_length = 200]
@@ -665,6 +827,8 @@ diesel::table! {
         language_id -> Int4,
         featured_community -> Bool,
         featured_local -> Bool,
+        search_vector -> Tsvector,
+        scheduled_publish_time -> Nullable<Timestamptz>,
     }
 }
 
@@ -686,6 +850,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_edit_history (id) {
+        id -> Int4,
+        post_id -> Int4,
+        #[max_length = 200]
+        name -> Varchar,
+        #[max_length = 512]
+        url -> Nullable<Varchar>,
+        body -> Nullable<Text>,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     post_like (id) {
         id -> Int4,
@@ -696,6 +873,49 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_poll (id) {
+        id -> Int4,
+        post_id -> Int4,
+        multiple_choice -> Bool,
+        end_time -> Nullable<Timestamptz>,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    post_poll_option (id) {
+        id -> Int4,
+        poll_id -> Int4,
+        name -> Text,
+        vote_count -> Int4,
+    }
+}
+
+diesel::table! {
+    post_poll_update (poll_id) {
+        poll_id -> Int4,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    post_poll_vote (id) {
+        id -> Int4,
+        option_id -> Int4,
+        person_id -> Int4,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    post_poll_voter (poll_id, person_id) {
+        poll_id -> Int4,
+        person_id -> Int4,
+        published -> Timestamptz,
+    }
+}
+
 diesel::table! {
     post_read (id) {
         id -> Int4,
@@ -719,6 +939,10 @@ diesel::table! {
         resolver_id -> Nullable<Int4>,
         published -> Timestamptz,
         updated -> Nullable<Timestamptz>,
+        assignee_id -> Nullable<Int4>,
+        escalated -> Bool,
+        resolution_reason -> Nullable<Text>,
+        automod -> Bool,
     }
 }
 
@@ -731,6 +955,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    post_tag (post_id, tag_id) {
+        post_id -> Int4,
+        tag_id -> Int4,
+    }
+}
+
 diesel::table! {
     private_message (id) {
         id -> Int4,
@@ -758,6 +989,28 @@ diesel::table! {
         resolver_id -> Nullable<Int4>,
         published -> Timestamptz,
         updated -> Nullable<Timestamptz>,
+        assignee_id -> Nullable<Int4>,
+        resolution_reason -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    push_subscription (id) {
+        id -> Int4,
+        local_user_id -> Int4,
+        endpoint -> Text,
+        p256dh -> Text,
+        auth -> Text,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    rate_limit_bucket (key, action) {
+        key -> Text,
+        action -> Text,
+        tokens -> Float4,
+        last_checked -> Timestamptz,
     }
 }
 
@@ -772,10 +1025,46 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    remote_image (link) {
+        link -> Text,
+        pictrs_alias -> Text,
+        published -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    report_note (id) {
+        id -> Int4,
+        post_report_id -> Nullable<Int4>,
+        comment_report_id -> Nullable<Int4>,
+        private_message_report_id -> Nullable<Int4>,
+        creator_id -> Int4,
+        content -> Text,
+        published -> Timestamptz,
+        parent_id -> Nullable<Int4>,
+    }
+}
+
 diesel::table! {
     secret (id) {
         id -> Int4,
         jwt_secret -> Varchar,
+        image_proxy_secret -> Varchar,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ActorTypeEnum;
+
+    sent_activity (id) {
+        id -> Int4,
+        activity_id -> Int4,
+        send_inboxes -> Array<Nullable<Text>>,
+        actor_type -> ActorTypeEnum,
+        actor_apub_id -> Text,
+        published -> Timestamptz,
     }
 }
 
@@ -845,6 +1134,7 @@ diesel::joinable!(comment -> language (language_id));
 diesel::joinable!(comment -> person (creator_id));
 diesel::joinable!(comment -> post (post_id));
 diesel::joinable!(comment_aggregates -> comment (comment_id));
+diesel::joinable!(comment_edit_history -> comment (comment_id));
 diesel::joinable!(comment_like -> comment (comment_id));
 diesel::joinable!(comment_like -> person (person_id));
 diesel::joinable!(comment_like -> post (post_id));
@@ -855,6 +1145,7 @@ diesel::joinable!(comment_saved -> comment (comment_id));
 diesel::joinable!(comment_saved -> person (person_id));
 diesel::joinable!(community -> instance (instance_id));
 diesel::joinable!(community_aggregates -> community (community_id));
+diesel::joinable!(community_automod_rule -> community (community_id));
 diesel::joinable!(community_block -> community (community_id));
 diesel::joinable!(community_block -> person (person_id));
 diesel::joinable!(community_follower -> community (community_id));
@@ -865,16 +1156,20 @@ diesel::joinable!(community_moderator -> community (community_id));
 diesel::joinable!(community_moderator -> person (person_id));
 diesel::joinable!(community_person_ban -> community (community_id));
 diesel::joinable!(community_person_ban -> person (person_id));
+diesel::joinable!(community_post_tag -> community (community_id));
 diesel::joinable!(custom_emoji -> local_site (local_site_id));
 diesel::joinable!(custom_emoji_keyword -> custom_emoji (custom_emoji_id));
 diesel::joinable!(email_verification -> local_user (local_user_id));
 diesel::joinable!(federation_allowlist -> instance (instance_id));
 diesel::joinable!(federation_blocklist -> instance (instance_id));
+diesel::joinable!(federation_queue_state -> instance (instance_id));
+diesel::joinable!(local_image -> local_user (local_user_id));
 diesel::joinable!(local_site -> site (site_id));
 diesel::joinable!(local_site_rate_limit -> local_site (local_site_id));
 diesel::joinable!(local_user -> person (person_id));
 diesel::joinable!(local_user_language -> language (language_id));
 diesel::joinable!(local_user_language -> local_user (local_user_id));
+diesel::joinable!(login_token -> local_user (local_user_id));
 diesel::joinable!(mod_add_community -> community (community_id));
 diesel::joinable!(mod_ban_from_community -> community (community_id));
 diesel::joinable!(mod_feature_post -> person (mod_person_id));
@@ -889,7 +1184,12 @@ diesel::joinable!(mod_remove_community -> community (community_id));
 diesel::joinable!(mod_remove_community -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> person (mod_person_id));
 diesel::joinable!(mod_remove_post -> post (post_id));
+diesel::joinable!(mod_resolve_report -> person (mod_person_id));
+diesel::joinable!(mod_resolve_report -> post (post_id));
+diesel::joinable!(mod_resolve_report -> private_message_report (private_message_report_id));
 diesel::joinable!(mod_transfer_community -> community (community_id));
+diesel::joinable!(oidc_identity -> local_user (local_user_id));
+diesel::joinable!(oidc_login_state -> local_user (link_local_user_id));
 diesel::joinable!(password_reset_request -> local_user (local_user_id));
 diesel::joinable!(person -> instance (instance_id));
 diesel::joinable!(person_aggregates -> person (person_id));
@@ -902,16 +1202,29 @@ diesel::joinable!(post -> community (community_id));
 diesel::joinable!(post -> language (language_id));
 diesel::joinable!(post -> person (creator_id));
 diesel::joinable!(post_aggregates -> post (post_id));
+diesel::joinable!(post_edit_history -> post (post_id));
 diesel::joinable!(post_like -> person (person_id));
 diesel::joinable!(post_like -> post (post_id));
+diesel::joinable!(post_poll -> post (post_id));
+diesel::joinable!(post_poll_option -> post_poll (poll_id));
+diesel::joinable!(post_poll_update -> post_poll (poll_id));
+diesel::joinable!(post_poll_vote -> person (person_id));
+diesel::joinable!(post_poll_vote -> post_poll_option (option_id));
+diesel::joinable!(post_poll_voter -> person (person_id));
+diesel::joinable!(post_poll_voter -> post_poll (poll_id));
 diesel::joinable!(post_read -> person (person_id));
 diesel::joinable!(post_read -> post (post_id));
 diesel::joinable!(post_report -> post (post_id));
 diesel::joinable!(post_saved -> person (person_id));
 diesel::joinable!(post_saved -> post (post_id));
+diesel::joinable!(post_tag -> community_post_tag (tag_id));
+diesel::joinable!(post_tag -> post (post_id));
 diesel::joinable!(private_message_report -> private_message (private_message_id));
+diesel::joinable!(push_subscription -> local_user (local_user_id));
 diesel::joinable!(registration_application -> local_user (local_user_id));
 diesel::joinable!(registration_application -> person (admin_id));
+diesel::joinable!(report_note -> person (creator_id));
+diesel::joinable!(sent_activity -> activity (activity_id));
 diesel::joinable!(site -> instance (instance_id));
 diesel::joinable!(site_aggregates -> site (site_id));
 diesel::joinable!(site_language -> language (language_id));
@@ -927,28 +1240,34 @@ diesel::allow_tables_to_appear_in_same_query!(
     captcha_answer,
     comment,
     comment_aggregates,
+    comment_edit_history,
     comment_like,
     comment_reply,
     comment_report,
     comment_saved,
     community,
     community_aggregates,
+    community_automod_rule,
     community_block,
     community_follower,
     community_language,
     community_moderator,
     community_person_ban,
+    community_post_tag,
     custom_emoji,
     custom_emoji_keyword,
     email_verification,
     federation_allowlist,
     federation_blocklist,
+    federation_queue_state,
     instance,
     language,
+    local_image,
     local_site,
     local_site_rate_limit,
     local_user,
     local_user_language,
+    login_token,
     mod_add,
     mod_add_community,
     mod_ban,
@@ -959,7 +1278,10 @@ diesel::allow_tables_to_appear_in_same_query!(
     mod_remove_comment,
     mod_remove_community,
     mod_remove_post,
+    mod_resolve_report,
     mod_transfer_community,
+    oidc_identity,
+    oidc_login_state,
     password_reset_request,
     person,
     person_aggregates,
@@ -970,14 +1292,26 @@ diesel::allow_tables_to_appear_in_same_query!(
     person_post_aggregates,
     post,
     post_aggregates,
+    post_edit_history,
     post_like,
+    post_poll,
+    post_poll_option,
+    post_poll_update,
+    post_poll_vote,
+    post_poll_voter,
     post_read,
     post_report,
     post_saved,
+    post_tag,
     private_message,
     private_message_report,
+    push_subscription,
+    rate_limit_bucket,
     registration_application,
+    remote_image,
+    report_note,
     secret,
+    sent_activity,
     site,
     site_aggregates,
     site_language,
@@ -1,11 +1,11 @@
 use crate::newtypes::{CommentId, CommentReportId, PersonId};
 #[cfg(feature = "full")]
 use crate::schema::comment_report;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
-use chrono::{DateTime, Utc};
 
 #[skip_serializing_none]
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone)]
@@ -24,6 +24,14 @@ pub struct CommentReport {
   pub resolver_id: Option<PersonId>,
   pub published: DateTime<Utc>,
   pub updated: Option<DateTime<Utc>>,
+  /// The moderator who claimed the report.
+  pub assignee_id: Option<PersonId>,
+  /// Whether the moderators asked the instance admins to handle the report.
+  pub escalated: bool,
+  /// Why the report was resolved.
+  pub resolution_reason: Option<String>,
+  /// Whether the report was made by automod, in the name of a moderator of the community.
+  pub automod: bool,
 }
 
 #[derive(Clone)]
@@ -3,6 +3,7 @@ use crate::schema::{community, community_follower, community_moderator, communit
 use crate::{
   newtypes::{CommunityId, DbUrl, InstanceId, PersonId},
   source::placeholder_apub_url,
+  CommunityVisibility,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -13,7 +14,7 @@ use chrono::{DateTime, Utc};
 
 #[skip_serializing_none]
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
-#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
 #[cfg_attr(feature = "full", diesel(table_name = community))]
 #[cfg_attr(feature = "full", ts(export))]
 /// A community.
@@ -63,6 +64,9 @@ pub struct Community {
   /// Url where featured posts collection is served over Activitypub
   #[serde(skip)]
   pub featured_url: Option<DbUrl>,
+  pub visibility: CommunityVisibility,
+  /// Whether everyone can see the edit history of posts and comments, otherwise only mods.
+  pub edit_history_public: bool,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -96,6 +100,8 @@ pub struct CommunityInsertForm {
   pub posting_restricted_to_mods: Option<bool>,
   #[builder(!default)]
   pub instance_id: InstanceId,
+  pub visibility: Option<CommunityVisibility>,
+  pub edit_history_public: Option<bool>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -124,6 +130,8 @@ pub struct CommunityUpdateForm {
   pub featured_url: Option<DbUrl>,
   pub hidden: Option<bool>,
   pub posting_restricted_to_mods: Option<bool>,
+  pub visibility: Option<CommunityVisibility>,
+  pub edit_history_public: Option<bool>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
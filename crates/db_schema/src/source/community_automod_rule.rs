@@ -0,0 +1,41 @@
+#[cfg(feature = "full")]
+use crate::schema::community_automod_rule;
+use crate::{
+  newtypes::{CommunityAutomodRuleId, CommunityId},
+  AutomodAction,
+  AutomodRuleType,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_automod_rule))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A rule which is automatically checked for new posts and comments in a community.
+pub struct CommunityAutomodRule {
+  pub id: CommunityAutomodRuleId,
+  pub community_id: CommunityId,
+  pub rule_type: AutomodRuleType,
+  /// The parameter of the rule, for example the number of days or the banned domain.
+  pub value: String,
+  pub action: AutomodAction,
+  /// Used for the mod log entry or the report.
+  pub reason: Option<String>,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = community_automod_rule))]
+pub struct CommunityAutomodRuleForm {
+  pub community_id: CommunityId,
+  pub rule_type: AutomodRuleType,
+  pub value: String,
+  pub action: AutomodAction,
+  pub reason: Option<String>,
+}
@@ -0,0 +1,57 @@
+use crate::newtypes::{CommunityId, CommunityPostTagId, DbUrl, PostId};
+#[cfg(feature = "full")]
+use crate::schema::{community_post_tag, post_tag};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use typed_builder::TypedBuilder;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = community_post_tag))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A tag which the mods of a community defined, to categorise its posts.
+pub struct CommunityPostTag {
+  pub id: CommunityPostTagId,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub ap_id: DbUrl,
+  pub community_id: CommunityId,
+  pub name: String,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_post_tag))]
+pub struct CommunityPostTagInsertForm {
+  #[builder(!default)]
+  pub community_id: CommunityId,
+  #[builder(!default)]
+  pub name: String,
+  /// Only set for remote tags, local ones get their ap_id after they are inserted.
+  pub ap_id: Option<DbUrl>,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_post_tag))]
+pub struct CommunityPostTagUpdateForm {
+  pub name: Option<String>,
+  pub ap_id: Option<DbUrl>,
+  pub updated: Option<Option<DateTime<Utc>>>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_tag))]
+/// Links a post to one of the tags of its community.
+pub struct PostTag {
+  pub post_id: PostId,
+  pub tag_id: CommunityPostTagId,
+}
@@ -0,0 +1,56 @@
+use crate::newtypes::{CommentId, DbUrl, PostId};
+#[cfg(feature = "full")]
+use crate::schema::{comment_edit_history, post_edit_history};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = post_edit_history))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A previous version of an edited post.
+pub struct PostEditHistory {
+  pub id: i32,
+  pub post_id: PostId,
+  pub name: String,
+  #[cfg_attr(feature = "full", ts(type = "string"))]
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+  /// When this version was replaced by an edit.
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_edit_history))]
+pub struct PostEditHistoryForm {
+  pub post_id: PostId,
+  pub name: String,
+  pub url: Option<DbUrl>,
+  pub body: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = comment_edit_history))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A previous version of an edited comment.
+pub struct CommentEditHistory {
+  pub id: i32,
+  pub comment_id: CommentId,
+  pub content: String,
+  /// When this version was replaced by an edit.
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = comment_edit_history))]
+pub struct CommentEditHistoryForm {
+  pub comment_id: CommentId,
+  pub content: String,
+}
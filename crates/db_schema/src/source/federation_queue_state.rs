@@ -0,0 +1,42 @@
+use crate::newtypes::InstanceId;
+#[cfg(feature = "full")]
+use crate::schema::federation_queue_state;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use std::fmt::Debug;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_queue_state))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delivery progress of the outgoing activity queue for a single remote instance.
+pub struct FederationQueueState {
+  pub id: i32,
+  pub instance_id: InstanceId,
+  /// Id of the last [crate::source::sent_activity::SentActivity] which was delivered, or which
+  /// didn't need to be delivered to this instance.
+  pub last_successful_id: i32,
+  /// Number of consecutive failed delivery attempts.
+  pub fail_count: i32,
+  pub last_retry: Option<DateTime<Utc>>,
+  /// When an activity was last delivered successfully.
+  pub last_successful_send: Option<DateTime<Utc>>,
+  /// How long the remote inbox took to respond to the last delivery.
+  pub inbox_latency_ms: Option<i32>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = federation_queue_state))]
+pub struct FederationQueueStateForm {
+  pub instance_id: InstanceId,
+  pub last_successful_id: i32,
+  pub fail_count: i32,
+  pub last_retry: Option<DateTime<Utc>>,
+  pub last_successful_send: Option<DateTime<Utc>>,
+  pub inbox_latency_ms: Option<i32>,
+}
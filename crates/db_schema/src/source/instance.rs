@@ -22,6 +22,11 @@ pub struct Instance {
   pub updated: Option<DateTime<Utc>>,
   pub software: Option<String>,
   pub version: Option<String>,
+  /// When an activity from this instance was last received.
+  pub last_received_activity: Option<DateTime<Utc>>,
+  /// Whether the instance couldn't be reached for a long time. No activities are sent to dead
+  /// instances.
+  pub dead: bool,
 }
 
 #[derive(Clone, TypedBuilder)]
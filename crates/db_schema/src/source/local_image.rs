@@ -0,0 +1,36 @@
+use crate::newtypes::LocalUserId;
+#[cfg(feature = "full")]
+use crate::schema::local_image;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = local_image))]
+#[cfg_attr(feature = "full", diesel(primary_key(pictrs_alias)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An image which a local user uploaded to pict-rs.
+pub struct LocalImage {
+  pub local_user_id: LocalUserId,
+  /// The file name of the image, as used in `/pictrs/image/{alias}`.
+  pub pictrs_alias: String,
+  /// Token to delete the image from pict-rs. Never sent to clients.
+  #[serde(skip)]
+  pub pictrs_delete_token: String,
+  pub published: DateTime<Utc>,
+  /// The size of the upload in bytes, which counts towards the storage quota.
+  pub file_size: i64,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = local_image))]
+pub struct LocalImageForm {
+  pub local_user_id: LocalUserId,
+  pub pictrs_alias: String,
+  pub pictrs_delete_token: String,
+  pub file_size: i64,
+}
@@ -5,12 +5,12 @@ use crate::{
   ListingType,
   RegistrationMode,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 use typed_builder::TypedBuilder;
-use chrono::{DateTime, Utc};
 
 #[skip_serializing_none]
 #[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +60,12 @@ pub struct LocalSite {
   pub registration_mode: RegistrationMode,
   /// Whether to email admins on new reports.
   pub reports_email_admins: bool,
+  /// The max size of a single image upload in bytes.
+  pub image_upload_max_bytes: i64,
+  /// The MIME types which can be uploaded, like `image/png`. `image/*` allows all image types.
+  pub image_upload_allowed_mime_types: Vec<String>,
+  /// The max total size of the uploads of a single user in bytes.
+  pub image_upload_quota_bytes: Option<i64>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -88,6 +94,9 @@ pub struct LocalSiteInsertForm {
   pub captcha_difficulty: Option<String>,
   pub registration_mode: Option<RegistrationMode>,
   pub reports_email_admins: Option<bool>,
+  pub image_upload_max_bytes: Option<i64>,
+  pub image_upload_allowed_mime_types: Option<Vec<String>>,
+  pub image_upload_quota_bytes: Option<i64>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -114,5 +123,8 @@ pub struct LocalSiteUpdateForm {
   pub captcha_difficulty: Option<String>,
   pub registration_mode: Option<RegistrationMode>,
   pub reports_email_admins: Option<bool>,
+  pub image_upload_max_bytes: Option<i64>,
+  pub image_upload_allowed_mime_types: Option<Vec<String>>,
+  pub image_upload_quota_bytes: Option<Option<i64>>,
   pub updated: Option<Option<DateTime<Utc>>>,
 }
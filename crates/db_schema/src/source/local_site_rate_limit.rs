@@ -35,6 +35,13 @@ pub struct LocalSiteRateLimit {
   pub search_per_second: i32,
   pub published: DateTime<Utc>,
   pub updated: Option<DateTime<Utc>>,
+  pub message_per_user: i32,
+  pub post_per_user: i32,
+  pub image_per_user: i32,
+  pub comment_per_user: i32,
+  pub search_per_user: i32,
+  pub import_user_settings: i32,
+  pub import_user_settings_per_second: i32,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -56,6 +63,13 @@ pub struct LocalSiteRateLimitInsertForm {
   pub comment_per_second: Option<i32>,
   pub search: Option<i32>,
   pub search_per_second: Option<i32>,
+  pub message_per_user: Option<i32>,
+  pub post_per_user: Option<i32>,
+  pub image_per_user: Option<i32>,
+  pub comment_per_user: Option<i32>,
+  pub search_per_user: Option<i32>,
+  pub import_user_settings: Option<i32>,
+  pub import_user_settings_per_second: Option<i32>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -75,5 +89,12 @@ pub struct LocalSiteRateLimitUpdateForm {
   pub comment_per_second: Option<i32>,
   pub search: Option<i32>,
   pub search_per_second: Option<i32>,
+  pub message_per_user: Option<i32>,
+  pub post_per_user: Option<i32>,
+  pub image_per_user: Option<i32>,
+  pub comment_per_user: Option<i32>,
+  pub search_per_user: Option<i32>,
+  pub import_user_settings: Option<i32>,
+  pub import_user_settings_per_second: Option<i32>,
   pub updated: Option<Option<DateTime<Utc>>>,
 }
@@ -5,12 +5,12 @@ use crate::{
   ListingType,
   SortType,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 use typed_builder::TypedBuilder;
-use chrono::{DateTime, Utc};
 
 #[skip_serializing_none]
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -54,6 +54,12 @@ pub struct LocalUser {
   pub totp_2fa_url: Option<String>,
   /// Open links in a new tab.
   pub open_links_in_new_tab: bool,
+  /// Whether to get push notifications for replies.
+  pub push_notify_replies: bool,
+  /// Whether to get push notifications for mentions.
+  pub push_notify_mentions: bool,
+  /// Whether to get push notifications for private messages.
+  pub push_notify_private_messages: bool,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -82,6 +88,9 @@ pub struct LocalUserInsertForm {
   pub totp_2fa_secret: Option<Option<String>>,
   pub totp_2fa_url: Option<Option<String>>,
   pub open_links_in_new_tab: Option<bool>,
+  pub push_notify_replies: Option<bool>,
+  pub push_notify_mentions: Option<bool>,
+  pub push_notify_private_messages: Option<bool>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -107,4 +116,7 @@ pub struct LocalUserUpdateForm {
   pub totp_2fa_secret: Option<Option<String>>,
   pub totp_2fa_url: Option<Option<String>>,
   pub open_links_in_new_tab: Option<bool>,
+  pub push_notify_replies: Option<bool>,
+  pub push_notify_mentions: Option<bool>,
+  pub push_notify_private_messages: Option<bool>,
 }
@@ -0,0 +1,40 @@
+use crate::newtypes::LocalUserId;
+#[cfg(feature = "full")]
+use crate::schema::login_token;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = login_token))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A login session, created whenever a login token (jwt) is issued.
+pub struct LoginToken {
+  pub id: i32,
+  /// Sha256 hash of the jwt. Never sent to clients.
+  #[serde(skip)]
+  pub token_encrypted: String,
+  pub local_user_id: LocalUserId,
+  /// IP address where the login was made from, if known.
+  pub ip: Option<String>,
+  /// User agent of the browser or app which requested the login, if known.
+  pub user_agent: Option<String>,
+  pub published: DateTime<Utc>,
+  /// Last time that the token was used to authenticate an api request.
+  pub last_used: DateTime<Utc>,
+  pub expires: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = login_token))]
+pub struct LoginTokenCreateForm {
+  pub token_encrypted: String,
+  pub local_user_id: LocalUserId,
+  pub ip: Option<String>,
+  pub user_agent: Option<String>,
+  pub expires: DateTime<Utc>,
+}
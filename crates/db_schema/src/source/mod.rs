@@ -9,28 +9,45 @@ pub mod comment;
 pub mod comment_reply;
 pub mod comment_report;
 pub mod community;
+pub mod community_automod_rule;
 pub mod community_block;
+pub mod community_post_tag;
 pub mod custom_emoji;
 pub mod custom_emoji_keyword;
+pub mod edit_history;
 pub mod email_verification;
 pub mod federation_allowlist;
 pub mod federation_blocklist;
+pub mod federation_queue_state;
 pub mod instance;
 pub mod language;
+pub mod local_image;
 pub mod local_site;
 pub mod local_site_rate_limit;
 pub mod local_user;
+pub mod login_token;
 pub mod moderator;
+#[cfg(feature = "full")]
+pub mod oidc;
 pub mod password_reset_request;
 pub mod person;
 pub mod person_block;
 pub mod person_mention;
 pub mod post;
+pub mod post_poll;
 pub mod post_report;
 pub mod private_message;
 pub mod private_message_report;
+#[cfg(feature = "full")]
+pub mod push_subscription;
+pub mod rate_limit_bucket;
 pub mod registration_application;
+#[cfg(feature = "full")]
+pub mod remote_image;
+pub mod report_note;
 pub mod secret;
+#[cfg(feature = "full")]
+pub mod sent_activity;
 pub mod site;
 pub mod tagline;
 
@@ -1,4 +1,4 @@
-use crate::newtypes::{CommentId, CommunityId, PersonId, PostId};
+use crate::newtypes::{CommentId, CommunityId, PersonId, PostId, PrivateMessageReportId};
 #[cfg(feature = "full")]
 use crate::schema::{
   admin_purge_comment,
@@ -15,13 +15,14 @@ use crate::schema::{
   mod_remove_comment,
   mod_remove_community,
   mod_remove_post,
+  mod_resolve_report,
   mod_transfer_community,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
-use chrono::{DateTime, Utc};
 
 #[skip_serializing_none]
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -36,6 +37,8 @@ pub struct ModRemovePost {
   pub reason: Option<String>,
   pub removed: bool,
   pub when_: DateTime<Utc>,
+  /// Whether the post was removed by automod, in the name of the moderator.
+  pub automod: bool,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
@@ -104,6 +107,8 @@ pub struct ModRemoveComment {
   pub reason: Option<String>,
   pub removed: bool,
   pub when_: DateTime<Utc>,
+  /// Whether the comment was removed by automod, in the name of the moderator.
+  pub automod: bool,
 }
 
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
@@ -115,6 +120,37 @@ pub struct ModRemoveCommentForm {
   pub removed: Option<bool>,
 }
 
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_resolve_report))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When a moderator resolves a report of a post, comment or private message.
+pub struct ModResolveReport {
+  pub id: i32,
+  pub mod_person_id: PersonId,
+  /// Not set for reports of private messages.
+  pub post_id: Option<PostId>,
+  /// Only set for reports of comments.
+  pub comment_id: Option<CommentId>,
+  pub resolved: bool,
+  pub reason: Option<String>,
+  pub when_: DateTime<Utc>,
+  /// Only set for reports of private messages, which are otherwise not shown in the modlog.
+  pub private_message_report_id: Option<PrivateMessageReportId>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = mod_resolve_report))]
+pub struct ModResolveReportForm {
+  pub mod_person_id: PersonId,
+  pub post_id: Option<PostId>,
+  pub comment_id: Option<CommentId>,
+  pub private_message_report_id: Option<PrivateMessageReportId>,
+  pub resolved: Option<bool>,
+  pub reason: Option<String>,
+}
+
 #[skip_serializing_none]
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
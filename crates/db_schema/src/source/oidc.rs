@@ -0,0 +1,54 @@
+use crate::{
+  newtypes::LocalUserId,
+  schema::{oidc_identity, oidc_login_state},
+};
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+
+/// An account at an OpenID Connect provider which is linked to a local user.
+#[derive(Clone, PartialEq, Eq, Debug, Queryable, Identifiable)]
+#[diesel(table_name = oidc_identity)]
+pub struct OidcIdentity {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  /// Name of the provider in the config file.
+  pub provider: String,
+  /// Identifier of the account at the provider.
+  pub subject: String,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = oidc_identity)]
+pub struct OidcIdentityForm {
+  pub local_user_id: LocalUserId,
+  pub provider: String,
+  pub subject: String,
+}
+
+/// A login which was started, and waits for the user to return from the provider.
+#[derive(Clone, PartialEq, Eq, Debug, Queryable, Identifiable)]
+#[diesel(table_name = oidc_login_state)]
+pub struct OidcLoginState {
+  pub id: i32,
+  /// Random value which is passed through the provider, to match the response to this login.
+  pub state: String,
+  pub provider: String,
+  /// Secret for PKCE, which proves to the provider that the code is redeemed by the same party
+  /// which started the login.
+  pub code_verifier: String,
+  pub redirect_uri: String,
+  pub published: DateTime<Utc>,
+  /// Set if the login links the provider account to this user, instead of logging in.
+  pub link_local_user_id: Option<LocalUserId>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = oidc_login_state)]
+pub struct OidcLoginStateForm {
+  pub state: String,
+  pub provider: String,
+  pub code_verifier: String,
+  pub redirect_uri: String,
+  pub link_local_user_id: Option<LocalUserId>,
+}
@@ -4,12 +4,12 @@ use crate::{
   newtypes::{DbUrl, InstanceId, PersonId},
   source::placeholder_apub_url,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 use typed_builder::TypedBuilder;
-use chrono::{DateTime, Utc};
 
 #[skip_serializing_none]
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -57,6 +57,11 @@ pub struct Person {
   /// When their ban, if it exists, expires, if at all.
   pub ban_expires: Option<DateTime<Utc>>,
   pub instance_id: InstanceId,
+  /// Other accounts of the person, which are allowed to move to this account.
+  #[cfg_attr(feature = "full", ts(type = "string[]"))]
+  pub also_known_as: Vec<DbUrl>,
+  /// The account which the person moved to, if any.
+  pub moved_to: Option<DbUrl>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -88,6 +93,8 @@ pub struct PersonInsertForm {
   pub admin: Option<bool>,
   pub bot_account: Option<bool>,
   pub ban_expires: Option<DateTime<Utc>>,
+  pub also_known_as: Option<Vec<DbUrl>>,
+  pub moved_to: Option<DbUrl>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -113,6 +120,8 @@ pub struct PersonUpdateForm {
   pub admin: Option<bool>,
   pub bot_account: Option<bool>,
   pub ban_expires: Option<Option<DateTime<Utc>>>,
+  pub also_known_as: Option<Vec<DbUrl>>,
+  pub moved_to: Option<Option<DbUrl>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
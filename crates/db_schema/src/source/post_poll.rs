@@ -0,0 +1,81 @@
+use crate::newtypes::{PersonId, PostId, PostPollId, PostPollOptionId};
+#[cfg(feature = "full")]
+use crate::schema::{post_poll, post_poll_option, post_poll_vote};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+use typed_builder::TypedBuilder;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = post_poll))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll which is attached to a post.
+pub struct PostPoll {
+  pub id: PostPollId,
+  pub post_id: PostId,
+  /// Whether voters can choose more than one option.
+  pub multiple_choice: bool,
+  /// No more votes are accepted after this time.
+  pub end_time: Option<DateTime<Utc>>,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_poll))]
+pub struct PostPollForm {
+  #[builder(!default)]
+  pub post_id: PostId,
+  pub multiple_choice: Option<bool>,
+  pub end_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = post_poll_option))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post_poll::PostPoll, foreign_key = poll_id)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An option which can be voted for in a poll.
+pub struct PostPollOption {
+  pub id: PostPollOptionId,
+  pub poll_id: PostPollId,
+  pub name: String,
+  pub vote_count: i32,
+}
+
+#[derive(Debug, Clone, TypedBuilder)]
+#[builder(field_defaults(default))]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_poll_option))]
+pub struct PostPollOptionForm {
+  #[builder(!default)]
+  pub poll_id: PostPollId,
+  #[builder(!default)]
+  pub name: String,
+  /// Only set for remote polls, local vote counts are kept up to date by the database.
+  pub vote_count: Option<i32>,
+}
+
+#[derive(PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Associations, Identifiable))]
+#[cfg_attr(feature = "full", diesel(table_name = post_poll_vote))]
+#[cfg_attr(feature = "full", diesel(belongs_to(crate::source::post_poll::PostPollOption, foreign_key = option_id)))]
+pub struct PostPollVote {
+  pub id: i32,
+  pub option_id: PostPollOptionId,
+  pub person_id: PersonId,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = post_poll_vote))]
+pub struct PostPollVoteForm {
+  pub option_id: PostPollOptionId,
+  pub person_id: PersonId,
+}
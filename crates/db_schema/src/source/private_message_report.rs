@@ -1,11 +1,11 @@
 use crate::newtypes::{PersonId, PrivateMessageId, PrivateMessageReportId};
 #[cfg(feature = "full")]
 use crate::schema::private_message_report;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
-use chrono::{DateTime, Utc};
 
 #[skip_serializing_none]
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone)]
@@ -28,6 +28,10 @@ pub struct PrivateMessageReport {
   pub resolver_id: Option<PersonId>,
   pub published: DateTime<Utc>,
   pub updated: Option<DateTime<Utc>>,
+  /// The moderator who claimed the report.
+  pub assignee_id: Option<PersonId>,
+  /// Why the report was resolved.
+  pub resolution_reason: Option<String>,
 }
 
 #[derive(Clone)]
@@ -0,0 +1,27 @@
+use crate::{newtypes::LocalUserId, schema::push_subscription};
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+
+/// A browser or app of a local user which receives web push notifications.
+#[derive(Clone, PartialEq, Eq, Debug, Queryable, Identifiable)]
+#[diesel(table_name = push_subscription)]
+pub struct PushSubscription {
+  pub id: i32,
+  pub local_user_id: LocalUserId,
+  /// The url of the push service where notifications are delivered to.
+  pub endpoint: String,
+  /// Public key of the subscriber, which the notifications are encrypted for.
+  pub p256dh: String,
+  /// Secret of the subscriber, which authenticates the encrypted notifications.
+  pub auth: String,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = push_subscription)]
+pub struct PushSubscriptionForm {
+  pub local_user_id: LocalUserId,
+  pub endpoint: String,
+  pub p256dh: String,
+  pub auth: String,
+}
@@ -0,0 +1,16 @@
+#[cfg(feature = "full")]
+use crate::schema::rate_limit_bucket;
+use chrono::{DateTime, Utc};
+
+/// Persisted state of an in-memory rate limit bucket, so that rate limits survive restarts.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "full", derive(Queryable, Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = rate_limit_bucket))]
+pub struct RateLimitBucket {
+  /// The rate limited ip address, ip range or user.
+  pub key: String,
+  /// The rate limited action, eg post or comment.
+  pub action: String,
+  pub tokens: f32,
+  pub last_checked: DateTime<Utc>,
+}
@@ -0,0 +1,20 @@
+use crate::{newtypes::DbUrl, schema::remote_image};
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+
+/// A copy of a remote image, which the image proxy keeps in the image storage.
+#[derive(Clone, PartialEq, Eq, Debug, Queryable)]
+#[diesel(table_name = remote_image)]
+pub struct RemoteImage {
+  pub link: DbUrl,
+  /// The file name of the copy, as used in `/pictrs/image/{alias}`.
+  pub pictrs_alias: String,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = remote_image)]
+pub struct RemoteImageForm {
+  pub link: DbUrl,
+  pub pictrs_alias: String,
+}
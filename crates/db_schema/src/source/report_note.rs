@@ -0,0 +1,38 @@
+use crate::newtypes::{CommentReportId, PersonId, PostReportId, PrivateMessageReportId};
+#[cfg(feature = "full")]
+use crate::schema::report_note;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Queryable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = report_note))]
+#[cfg_attr(feature = "full", ts(export))]
+/// An internal note of the moderators on a report. Exactly one of the report ids is set.
+pub struct ReportNote {
+  pub id: i32,
+  pub post_report_id: Option<PostReportId>,
+  pub comment_report_id: Option<CommentReportId>,
+  pub private_message_report_id: Option<PrivateMessageReportId>,
+  pub creator_id: PersonId,
+  pub content: String,
+  pub published: DateTime<Utc>,
+  /// The note which this one replies to.
+  pub parent_id: Option<i32>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable))]
+#[cfg_attr(feature = "full", diesel(table_name = report_note))]
+pub struct ReportNoteForm {
+  pub post_report_id: Option<PostReportId>,
+  pub comment_report_id: Option<CommentReportId>,
+  pub private_message_report_id: Option<PrivateMessageReportId>,
+  pub creator_id: PersonId,
+  pub content: String,
+  pub parent_id: Option<i32>,
+}
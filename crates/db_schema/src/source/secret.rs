@@ -7,4 +7,6 @@ use crate::schema::secret;
 pub struct Secret {
   pub id: i32,
   pub jwt_secret: String,
+  /// Signs the links of the image proxy.
+  pub image_proxy_secret: String,
 }
@@ -0,0 +1,26 @@
+use crate::{newtypes::DbUrl, schema::sent_activity, ActorType};
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+
+/// An outgoing activity which is waiting to be delivered to remote instances.
+#[derive(PartialEq, Eq, Debug, Queryable, Identifiable)]
+#[diesel(table_name = sent_activity)]
+pub struct SentActivity {
+  pub id: i32,
+  pub activity_id: i32,
+  /// Inboxes of remote actors which should receive the activity.
+  pub send_inboxes: Vec<Option<DbUrl>>,
+  pub actor_type: ActorType,
+  /// The local actor whose key is used to sign the activity.
+  pub actor_apub_id: DbUrl,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = sent_activity)]
+pub struct SentActivityForm {
+  pub activity_id: i32,
+  pub send_inboxes: Vec<Option<DbUrl>>,
+  pub actor_type: ActorType,
+  pub actor_apub_id: DbUrl,
+}
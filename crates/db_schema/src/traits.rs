@@ -128,6 +128,7 @@ pub trait Reportable {
     pool: &DbPool,
     report_id: Self::IdType,
     resolver_id: PersonId,
+    reason: Option<String>,
   ) -> Result<usize, Error>
   where
     Self: Sized;
@@ -138,6 +139,13 @@ pub trait Reportable {
   ) -> Result<usize, Error>
   where
     Self: Sized;
+  async fn assign(
+    pool: &DbPool,
+    report_id: Self::IdType,
+    assignee_id: Option<PersonId>,
+  ) -> Result<usize, Error>
+  where
+    Self: Sized;
 }
 
 pub trait JoinView {
@@ -6,16 +6,20 @@ use crate::{
   SortType,
 };
 use activitypub_federation::{fetch::object_id::ObjectId, traits::Object};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use deadpool::Runtime;
 use diesel::{
   backend::Backend,
   deserialize::FromSql,
+  expression::ValidGrouping,
   pg::Pg,
+  query_builder::{AstPass, QueryFragment},
   result::{ConnectionError, ConnectionResult, Error as DieselError, Error::QueryBuilderError},
   serialize::{Output, ToSql},
+  sql_query,
   sql_types::{Text, Timestamptz},
-  PgConnection, expression::ValidGrouping, query_builder::{QueryFragment, AstPass}, QueryResult,
+  PgConnection,
+  QueryResult,
 };
 use diesel_async::{
   pg::AsyncPgConnection,
@@ -23,9 +27,10 @@ use diesel_async::{
     deadpool::{Object as PooledConnection, Pool},
     AsyncDieselConnectionManager,
   },
+  RunQueryDsl,
 };
 use diesel_migrations::EmbeddedMigrations;
-use futures_util::{future::BoxFuture, FutureExt};
+use futures_util::{future::BoxFuture, FutureExt, StreamExt};
 use lemmy_utils::{error::LemmyError, settings::structs::Settings};
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -39,12 +44,16 @@ use std::{
   sync::Arc,
   time::{Duration, SystemTime},
 };
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio_postgres::AsyncMessage;
+use tokio_postgres_rustls::MakeRustlsConnect;
 use tracing::{error, info};
 use url::Url;
 
 const FETCH_LIMIT_DEFAULT: i64 = 10;
 pub const FETCH_LIMIT_MAX: i64 = 50;
 const POOL_TIMEOUT: Option<Duration> = Some(Duration::from_secs(5));
+const LISTEN_RETRY_DELAY: Duration = Duration::from_secs(5);
 
 pub type DbPool = Pool<AsyncPgConnection>;
 
@@ -171,15 +180,17 @@ async fn build_db_pool_settings_opt(settings: Option<&Settings>) -> Result<DbPoo
   Ok(pool)
 }
 
+fn tls_connector() -> MakeRustlsConnect {
+  let rustls_config = rustls::ClientConfig::builder()
+    .with_safe_defaults()
+    .with_custom_certificate_verifier(Arc::new(NoCertVerifier {}))
+    .with_no_client_auth();
+  MakeRustlsConnect::new(rustls_config)
+}
+
 fn establish_connection(config: &str) -> BoxFuture<ConnectionResult<AsyncPgConnection>> {
   let fut = async {
-    let rustls_config = rustls::ClientConfig::builder()
-      .with_safe_defaults()
-      .with_custom_certificate_verifier(Arc::new(NoCertVerifier {}))
-      .with_no_client_auth();
-
-    let tls = tokio_postgres_rustls::MakeRustlsConnect::new(rustls_config);
-    let (client, conn) = tokio_postgres::connect(config, tls)
+    let (client, conn) = tokio_postgres::connect(config, tls_connector())
       .await
       .map_err(|e| ConnectionError::BadConnection(e.to_string()))?;
     tokio::spawn(async move {
@@ -192,6 +203,60 @@ fn establish_connection(config: &str) -> BoxFuture<ConnectionResult<AsyncPgConne
   fut.boxed()
 }
 
+/// Sends a notification to everyone who listens on the channel, including other Lemmy processes
+/// which use the same database.
+pub async fn notify(pool: &DbPool, channel: &str, payload: &str) -> Result<(), DieselError> {
+  let conn = &mut get_conn(pool).await?;
+  sql_query("SELECT pg_notify($1, $2)")
+    .bind::<Text, _>(channel)
+    .bind::<Text, _>(payload)
+    .execute(conn)
+    .await?;
+  Ok(())
+}
+
+/// Listens on the channel with its own database connection, and returns the payloads of the
+/// notifications. The connection is reestablished when it fails, and closed once the receiver
+/// is dropped.
+pub fn listen(db_url: String, channel: &'static str) -> UnboundedReceiver<String> {
+  let (sender, receiver) = unbounded_channel();
+  tokio::spawn(async move {
+    while !sender.is_closed() {
+      if let Err(e) = listen_until_closed(&db_url, channel, &sender).await {
+        error!("Listening on {channel} failed: {e}");
+      }
+      tokio::time::sleep(LISTEN_RETRY_DELAY).await;
+    }
+  });
+  receiver
+}
+
+async fn listen_until_closed(
+  db_url: &str,
+  channel: &str,
+  sender: &UnboundedSender<String>,
+) -> Result<(), LemmyError> {
+  let (client, mut conn) = tokio_postgres::connect(db_url, tls_connector()).await?;
+  // The connection needs to be polled while the LISTEN query runs, so it gets its own task
+  let forward = tokio::spawn({
+    let sender = sender.clone();
+    async move {
+      let mut messages = futures_util::stream::poll_fn(move |cx| conn.poll_message(cx));
+      while let Some(message) = messages.next().await {
+        if let AsyncMessage::Notification(notification) = message? {
+          if sender.send(notification.payload().to_string()).is_err() {
+            break;
+          }
+        }
+      }
+      Ok::<_, tokio_postgres::Error>(())
+    }
+  });
+  client.batch_execute(&format!("LISTEN {channel}")).await?;
+  forward.await??;
+  Ok(())
+}
+
 struct NoCertVerifier {}
 
 impl ServerCertVerifier for NoCertVerifier {
@@ -247,9 +312,15 @@ pub fn naive_now() -> DateTime<Utc> {
   chrono::prelude::Utc::now()
 }
 
+/// Parses a time given in microseconds since the unix epoch, as used in pagination cursors.
+pub fn datetime_from_micros(micros: i64) -> Option<DateTime<Utc>> {
+  NaiveDateTime::from_timestamp_micros(micros).map(|t| Utc.from_utc_datetime(&t))
+}
+
 pub fn post_to_comment_sort_type(sort: SortType) -> CommentSortType {
   match sort {
     SortType::Active | SortType::Hot => CommentSortType::Hot,
+    SortType::Relevance => CommentSortType::Relevance,
     SortType::New | SortType::NewComments | SortType::MostComments => CommentSortType::New,
     SortType::Old => CommentSortType::Old,
     SortType::TopHour
@@ -272,13 +343,20 @@ static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
 });
 
 pub mod functions {
-  use diesel::sql_types::{BigInt, Text, Timestamptz};
+  use diesel::sql_types::{Array, BigInt, Nullable, Text, Timestamptz};
+  use diesel_full_text_search::TsQuery;
 
   sql_function! {
     fn hot_rank(score: BigInt, time: Timestamptz) -> Integer;
   }
 
   sql_function!(fn lower(x: Text) -> Text);
+
+  sql_function!(fn array_to_string(array: Array<Nullable<Text>>, delimiter: Text) -> Text);
+
+  // Parses the query with `websearch_to_tsquery` for every text search configuration, so quotes,
+  // `or` and `-` work like in search engines.
+  sql_function!(fn lemmy_search_query(q: Text) -> TsQuery);
 }
 
 pub const DELETED_REPLACEMENT_TEXT: &str = "*Permanently Deleted*";
@@ -315,21 +393,22 @@ where
 pub struct now;
 
 impl diesel::Expression for now {
-    type SqlType = Timestamptz;
+  type SqlType = Timestamptz;
 }
 
 impl<DB: Backend> QueryFragment<DB> for now {
-    fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, DB>) -> QueryResult<()> {
-        out.push_sql("CURRENT_TIMESTAMP");
-        Ok(())
-    }
+  fn walk_ast<'b>(&'b self, mut out: AstPass<'_, 'b, DB>) -> QueryResult<()> {
+    out.push_sql("CURRENT_TIMESTAMP");
+    Ok(())
+  }
 }
 
-
 #[cfg(test)]
 mod tests {
   use super::{fuzzy_search, *};
   use crate::utils::is_email_regex;
+  use serial_test::serial;
+  use std::time::Duration;
 
   #[test]
   fn test_fuzzy_search() {
@@ -370,4 +449,21 @@ mod tests {
       Ok(Some(Some(url))) if url == Url::parse(example_url).unwrap().into()
     ));
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_notify_listen() {
+    let pool = &build_db_pool_for_tests().await;
+    let mut payloads = listen(get_database_url(None), "lemmy_test_channel");
+
+    // The listener connects in the background, so notify until it receives something
+    let payload = loop {
+      notify(pool, "lemmy_test_channel", "hello").await.unwrap();
+      let received = tokio::time::timeout(Duration::from_millis(100), payloads.recv()).await;
+      if let Ok(payload) = received {
+        break payload;
+      }
+    };
+    assert_eq!(Some("hello".to_string()), payload);
+  }
 }
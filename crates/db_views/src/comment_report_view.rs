@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -46,7 +47,8 @@ impl CommentReportView {
   ) -> Result<Self, Error> {
     let conn = &mut get_conn(pool).await?;
 
-    let (person_alias_1, person_alias_2) = diesel::alias!(person as person1, person as person2);
+    let (person_alias_1, person_alias_2, person_alias_3) =
+      diesel::alias!(person as person1, person as person2, person as person3);
 
     let res = comment_report::table
       .find(report_id)
@@ -76,17 +78,22 @@ impl CommentReportView {
         person_alias_2
           .on(comment_report::resolver_id.eq(person_alias_2.field(person::id).nullable())),
       )
+      .left_join(
+        person_alias_3
+          .on(comment_report::assignee_id.eq(person_alias_3.field(person::id).nullable())),
+      )
       .select((
         comment_report::all_columns,
-        comment::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Comment::as_select(),
+        Post::as_select(),
+        Community::as_select(),
         person::all_columns,
         person_alias_1.fields(person::all_columns),
         comment_aggregates::all_columns,
         community_person_ban::all_columns.nullable(),
         comment_like::score.nullable(),
         person_alias_2.fields(person::all_columns).nullable(),
+        person_alias_3.fields(person::all_columns).nullable(),
       ))
       .first::<<CommentReportView as JoinView>::JoinTuple>(conn)
       .await?;
@@ -150,13 +157,15 @@ pub struct CommentReportQuery<'a> {
   page: Option<i64>,
   limit: Option<i64>,
   unresolved_only: Option<bool>,
+  escalated_only: Option<bool>,
 }
 
 impl<'a> CommentReportQuery<'a> {
   pub async fn list(self) -> Result<Vec<CommentReportView>, Error> {
     let conn = &mut get_conn(self.pool).await?;
 
-    let (person_alias_1, person_alias_2) = diesel::alias!(person as person1, person as person2);
+    let (person_alias_1, person_alias_2, person_alias_3) =
+      diesel::alias!(person as person1, person as person2, person as person3);
 
     let mut query = comment_report::table
       .inner_join(comment::table)
@@ -190,17 +199,22 @@ impl<'a> CommentReportQuery<'a> {
         person_alias_2
           .on(comment_report::resolver_id.eq(person_alias_2.field(person::id).nullable())),
       )
+      .left_join(
+        person_alias_3
+          .on(comment_report::assignee_id.eq(person_alias_3.field(person::id).nullable())),
+      )
       .select((
         comment_report::all_columns,
-        comment::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Comment::as_select(),
+        Post::as_select(),
+        Community::as_select(),
         person::all_columns,
         person_alias_1.fields(person::all_columns),
         comment_aggregates::all_columns,
         community_person_ban::all_columns.nullable(),
         comment_like::score.nullable(),
         person_alias_2.fields(person::all_columns).nullable(),
+        person_alias_3.fields(person::all_columns).nullable(),
       ))
       .into_boxed();
 
@@ -212,6 +226,10 @@ impl<'a> CommentReportQuery<'a> {
       query = query.filter(comment_report::resolved.eq(false));
     }
 
+    if self.escalated_only.unwrap_or(false) {
+      query = query.filter(comment_report::escalated.eq(true));
+    }
+
     let (limit, offset) = limit_and_offset(self.page, self.limit)?;
 
     query = query
@@ -253,6 +271,7 @@ impl JoinView for CommentReportView {
     Option<CommunityPersonBan>,
     Option<i16>,
     Option<Person>,
+    Option<Person>,
   );
 
   fn from_tuple(a: Self::JoinTuple) -> Self {
@@ -267,6 +286,7 @@ impl JoinView for CommentReportView {
       creator_banned_from_community: a.7.is_some(),
       my_vote: a.8,
       resolver: a.9,
+      assignee: a.10,
     }
   }
 }
@@ -286,6 +306,7 @@ mod tests {
     },
     traits::{Crud, Joinable, Reportable},
     utils::build_db_pool_for_tests,
+    CommunityVisibility,
   };
   use serial_test::serial;
 
@@ -418,6 +439,8 @@ mod tests {
         shared_inbox_url: inserted_community.shared_inbox_url,
         moderators_url: inserted_community.moderators_url,
         featured_url: inserted_community.featured_url,
+        visibility: CommunityVisibility::Public,
+        edit_history_public: false,
         instance_id: inserted_instance.id,
       },
       creator: Person {
@@ -443,6 +466,8 @@ mod tests {
         private_key: inserted_jessica.private_key,
         public_key: inserted_jessica.public_key,
         last_refreshed_at: inserted_jessica.last_refreshed_at,
+        also_known_as: vec![],
+        moved_to: None,
       },
       comment_creator: Person {
         id: inserted_timmy.id,
@@ -467,6 +492,8 @@ mod tests {
         private_key: inserted_timmy.private_key.clone(),
         public_key: inserted_timmy.public_key.clone(),
         last_refreshed_at: inserted_timmy.last_refreshed_at,
+        also_known_as: vec![],
+        moved_to: None,
       },
       creator_banned_from_community: false,
       counts: CommentAggregates {
@@ -481,6 +508,7 @@ mod tests {
       },
       my_vote: None,
       resolver: None,
+      assignee: None,
     };
 
     assert_eq!(read_jessica_report_view, expected_jessica_report_view);
@@ -510,6 +538,8 @@ mod tests {
       private_key: inserted_sara.private_key,
       public_key: inserted_sara.public_key,
       last_refreshed_at: inserted_sara.last_refreshed_at,
+      also_known_as: vec![],
+      moved_to: None,
     };
 
     // Do a batch read of timmys reports
@@ -537,7 +567,7 @@ mod tests {
     assert_eq!(2, report_count);
 
     // Try to resolve the report
-    CommentReport::resolve(pool, inserted_jessica_report.id, inserted_timmy.id)
+    CommentReport::resolve(pool, inserted_jessica_report.id, inserted_timmy.id, None)
       .await
       .unwrap();
     let read_jessica_report_view_after_resolve =
@@ -580,6 +610,8 @@ mod tests {
       matrix_user_id: None,
       ban_expires: None,
       instance_id: inserted_instance.id,
+      also_known_as: vec![],
+      moved_to: None,
     });
 
     assert_eq!(
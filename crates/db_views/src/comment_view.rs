@@ -1,14 +1,19 @@
-use crate::structs::CommentView;
+use crate::{post_view::community_visibility_filter, structs::CommentView};
+use chrono::{DateTime, Utc};
 use diesel::{
-  result::Error,
+  pg::Pg,
+  result::{Error, Error::QueryBuilderError},
+  sql_types,
   BoolExpressionMethods,
+  BoxableExpression,
   ExpressionMethods,
   JoinOnDsl,
   NullableExpressionMethods,
-  PgTextExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
+use diesel_full_text_search::{ts_rank_cd, TsVectorExtensions};
 use diesel_ltree::{nlevel, subpath, Ltree, LtreeExtensions};
 use lemmy_db_schema::{
   aggregates::structs::CommentAggregates,
@@ -25,6 +30,7 @@ use lemmy_db_schema::{
     local_user_language,
     person,
     person_block,
+    person_follower,
     post,
   },
   source::{
@@ -36,7 +42,13 @@ use lemmy_db_schema::{
     post::Post,
   },
   traits::JoinView,
-  utils::{fuzzy_search, get_conn, limit_and_offset, DbPool},
+  utils::{
+    datetime_from_micros,
+    functions::lemmy_search_query,
+    get_conn,
+    limit_and_offset,
+    DbPool,
+  },
   CommentSortType,
   ListingType,
 };
@@ -119,10 +131,10 @@ impl CommentView {
         ),
       )
       .select((
-        comment::all_columns,
+        Comment::as_select(),
         person::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
         comment_aggregates::all_columns,
         community_person_ban::all_columns.nullable(),
         community_follower::all_columns.nullable(),
@@ -152,6 +164,8 @@ impl CommentView {
       saved: saved.is_some(),
       creator_blocked: creator_blocked.is_some(),
       my_vote,
+      content_html: None,
+      image_proxy_links: None,
     })
   }
 }
@@ -171,9 +185,13 @@ pub struct CommentQuery<'a> {
   search_term: Option<String>,
   saved_only: Option<bool>,
   show_deleted_and_removed: Option<bool>,
+  /// Used to show comments in private communities to mods and admins
+  is_mod_or_admin: Option<bool>,
   page: Option<i64>,
   limit: Option<i64>,
   max_depth: Option<i32>,
+  /// Only list the comments after this one, instead of using the page
+  page_cursor: Option<CommentCursor>,
 }
 
 impl<'a> CommentQuery<'a> {
@@ -239,10 +257,10 @@ impl<'a> CommentQuery<'a> {
         ),
       )
       .select((
-        comment::all_columns,
+        Comment::as_select(),
         person::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
         comment_aggregates::all_columns,
         community_person_ban::all_columns.nullable(),
         community_follower::all_columns.nullable(),
@@ -252,6 +270,13 @@ impl<'a> CommentQuery<'a> {
       ))
       .into_boxed();
 
+    if !self.is_mod_or_admin.unwrap_or(false) {
+      query = query.filter(community_visibility_filter(
+        person_id_join,
+        self.local_user.is_some(),
+      ));
+    }
+
     if let Some(creator_id) = self.creator_id {
       query = query.filter(comment::creator_id.eq(creator_id));
     };
@@ -264,8 +289,8 @@ impl<'a> CommentQuery<'a> {
       query = query.filter(comment::path.contained_by(parent_path));
     };
 
-    if let Some(search_term) = self.search_term {
-      query = query.filter(comment::content.ilike(fuzzy_search(&search_term)));
+    if let Some(search_term) = &self.search_term {
+      query = query.filter(comment::search_vector.matches(lemmy_search_query(search_term)));
     };
 
     if let Some(community_id) = self.community_id {
@@ -291,6 +316,16 @@ impl<'a> CommentQuery<'a> {
               .or(community_follower::person_id.eq(person_id_join)),
           )
         }
+        ListingType::FollowedUsers => {
+          query = query.filter(
+            comment::creator_id.eq_any(
+              person_follower::table
+                .select(person_follower::person_id)
+                .filter(person_follower::follower_id.eq(person_id_join))
+                .filter(person_follower::pending.eq(false)),
+            ),
+          )
+        }
       }
     }
 
@@ -343,18 +378,62 @@ impl<'a> CommentQuery<'a> {
       // TODO a kludge to prevent attacks. Limit comments to 300 for now.
       // (i64::MAX, 0)
       (300, 0)
+    } else if self.page_cursor.is_some() {
+      limit_and_offset(None, self.limit)?
     } else {
       // limit_and_offset_unlimited(self.page, self.limit)
       limit_and_offset(self.page, self.limit)?
     };
 
-    query = match self.sort.unwrap_or(CommentSortType::Hot) {
+    let sort = self.sort.unwrap_or(CommentSortType::Hot);
+    query = match sort {
       CommentSortType::Hot => query.then_order_by(comment_aggregates::hot_rank.desc()),
       CommentSortType::New => query.then_order_by(comment::published.desc()),
       CommentSortType::Old => query.then_order_by(comment::published.asc()),
       CommentSortType::Top => query.order_by(comment_aggregates::score.desc()),
+      CommentSortType::Relevance => match &self.search_term {
+        Some(search_term) => query
+          .then_order_by(ts_rank_cd(comment::search_vector, lemmy_search_query(search_term)).desc())
+          .then_order_by(comment::published.desc()),
+        None => query.then_order_by(comment_aggregates::hot_rank.desc()),
+      },
+    };
+
+    // Break ties by id, so that the comments are always in the same order for the page cursor
+    query = if matches!(sort, CommentSortType::Old) {
+      query.then_order_by(comment::id.asc())
+    } else {
+      query.then_order_by(comment::id.desc())
     };
 
+    if let Some(cursor) = self.page_cursor {
+      if self.max_depth.is_some() {
+        return Err(QueryBuilderError(
+          "Comment trees can't be paged with a cursor".into(),
+        ));
+      }
+      let after: Box<dyn BoxableExpression<_, Pg, SqlType = sql_types::Bool>> =
+        if matches!(sort, CommentSortType::Old) {
+          Box::new(comment::id.gt(cursor.comment_id))
+        } else {
+          Box::new(comment::id.lt(cursor.comment_id))
+        };
+      let after: Box<dyn BoxableExpression<_, Pg, SqlType = sql_types::Bool>> = match sort {
+        CommentSortType::Relevance if self.search_term.is_some() => {
+          return Err(QueryBuilderError(
+            "Search results can't be paged with a cursor".into(),
+          ))
+        }
+        CommentSortType::Hot | CommentSortType::Relevance => {
+          after_cursor!(after, comment_aggregates::hot_rank, cursor.hot_rank, desc)
+        }
+        CommentSortType::New => after_cursor!(after, comment::published, cursor.published, desc),
+        CommentSortType::Old => after_cursor!(after, comment::published, cursor.published, asc),
+        CommentSortType::Top => after_cursor!(after, comment_aggregates::score, cursor.score, desc),
+      };
+      query = query.filter(after);
+    }
+
     // Note: deleted and removed comments are done on the front side
     let res = query
       .limit(limit)
@@ -366,6 +445,51 @@ impl<'a> CommentQuery<'a> {
   }
 }
 
+/// The sort keys of the last comment on a page, to continue the listing after it. They are kept in
+/// the cursor instead of being read again, so that the pages stay stable while the hot ranks change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentCursor {
+  comment_id: CommentId,
+  hot_rank: i32,
+  score: i64,
+  published: DateTime<Utc>,
+}
+
+impl CommentCursor {
+  /// Encodes the cursor as an opaque string which clients pass back to get the next page.
+  pub fn encode(&self) -> String {
+    format!(
+      "{}_{}_{}_{}",
+      self.comment_id.0,
+      self.hot_rank,
+      self.score,
+      self.published.timestamp_micros()
+    )
+  }
+
+  pub fn decode(cursor: &str) -> Option<Self> {
+    let mut parts = cursor.split('_');
+    let cursor = CommentCursor {
+      comment_id: CommentId(parts.next()?.parse().ok()?),
+      hot_rank: parts.next()?.parse().ok()?,
+      score: parts.next()?.parse().ok()?,
+      published: datetime_from_micros(parts.next()?.parse().ok()?)?,
+    };
+    parts.next().is_none().then_some(cursor)
+  }
+}
+
+impl From<&CommentView> for CommentCursor {
+  fn from(view: &CommentView) -> Self {
+    CommentCursor {
+      comment_id: view.comment.id,
+      hot_rank: view.counts.hot_rank,
+      score: view.counts.score,
+      published: view.comment.published,
+    }
+  }
+}
+
 impl JoinView for CommentView {
   type JoinTuple = CommentViewTuple;
   fn from_tuple(a: Self::JoinTuple) -> Self {
@@ -380,6 +504,8 @@ impl JoinView for CommentView {
       saved: a.7.is_some(),
       creator_blocked: a.8.is_some(),
       my_vote: a.9,
+      content_html: None,
+      image_proxy_links: None,
     }
   }
 }
@@ -388,6 +514,7 @@ impl JoinView for CommentView {
 mod tests {
   use crate::comment_view::{
     Comment,
+    CommentCursor,
     CommentQuery,
     CommentSortType,
     CommentView,
@@ -415,6 +542,7 @@ mod tests {
     },
     traits::{Blockable, Crud, Likeable},
     utils::build_db_pool_for_tests,
+    CommunityVisibility,
     SubscribedType,
   };
   use serial_test::serial;
@@ -781,6 +909,57 @@ mod tests {
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_page_cursor() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    for sort in [
+      CommentSortType::New,
+      CommentSortType::Old,
+      CommentSortType::Hot,
+      CommentSortType::Top,
+    ] {
+      let all_comments = CommentQuery::builder()
+        .pool(pool)
+        .post_id(Some(data.inserted_post.id))
+        .sort(Some(sort))
+        .build()
+        .list()
+        .await
+        .unwrap();
+
+      // Page through the same listing, two comments at a time
+      let mut paged_comments = vec![];
+      let mut page_cursor = None;
+      loop {
+        let page = CommentQuery::builder()
+          .pool(pool)
+          .post_id(Some(data.inserted_post.id))
+          .sort(Some(sort))
+          .limit(Some(2))
+          .page_cursor(page_cursor)
+          .build()
+          .list()
+          .await
+          .unwrap();
+        let cursor = match page.last() {
+          Some(last) => CommentCursor::from(last),
+          None => break,
+        };
+        assert_eq!(Some(cursor), CommentCursor::decode(&cursor.encode()));
+        page_cursor = Some(cursor);
+        paged_comments.extend(page);
+      }
+
+      assert_eq!(6, all_comments.len());
+      assert_eq!(all_comments, paged_comments);
+    }
+
+    cleanup(data, pool).await;
+  }
+
   async fn cleanup(data: Data, pool: &DbPool) {
     CommentLike::remove(pool, data.inserted_person.id, data.inserted_comment_0.id)
       .await
@@ -811,6 +990,8 @@ mod tests {
     CommentView {
       creator_banned_from_community: false,
       my_vote: None,
+      content_html: None,
+      image_proxy_links: None,
       subscribed: SubscribedType::NotSubscribed,
       saved: false,
       creator_blocked: false,
@@ -852,6 +1033,8 @@ mod tests {
         private_key: data.inserted_person.private_key.clone(),
         public_key: data.inserted_person.public_key.clone(),
         last_refreshed_at: data.inserted_person.last_refreshed_at,
+        also_known_as: vec![],
+        moved_to: None,
       },
       post: Post {
         id: data.inserted_post.id,
@@ -875,6 +1058,7 @@ mod tests {
         language_id: Default::default(),
         featured_community: false,
         featured_local: false,
+        scheduled_publish_time: None,
       },
       community: Community {
         id: data.inserted_community.id,
@@ -901,6 +1085,8 @@ mod tests {
         shared_inbox_url: data.inserted_community.shared_inbox_url.clone(),
         moderators_url: data.inserted_community.moderators_url.clone(),
         featured_url: data.inserted_community.featured_url.clone(),
+        visibility: CommunityVisibility::Public,
+        edit_history_public: false,
       },
       counts: CommentAggregates {
         id: agg.id,
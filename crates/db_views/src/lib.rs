@@ -1,6 +1,19 @@
 #[cfg(test)]
 extern crate serial_test;
 
+/// Wraps the filter for the rows after a pagination cursor with an earlier column of the ordering.
+/// A row comes after the cursor if it is past the cursor value in this column, or equal to it and
+/// after the cursor in the later columns.
+#[cfg(feature = "full")]
+macro_rules! after_cursor {
+  ($after:expr, $column:expr, $value:expr, desc) => {
+    Box::new($column.lt($value).or($column.eq($value).and($after)))
+  };
+  ($after:expr, $column:expr, $value:expr, asc) => {
+    Box::new($column.gt($value).or($column.eq($value).and($after)))
+  };
+}
+
 #[cfg(feature = "full")]
 pub mod comment_report_view;
 #[cfg(feature = "full")]
@@ -10,6 +23,8 @@ pub mod custom_emoji_view;
 #[cfg(feature = "full")]
 pub mod local_user_view;
 #[cfg(feature = "full")]
+pub mod post_poll_view;
+#[cfg(feature = "full")]
 pub mod post_report_view;
 #[cfg(feature = "full")]
 pub mod post_view;
@@ -20,5 +35,7 @@ pub mod private_message_view;
 #[cfg(feature = "full")]
 pub mod registration_application_view;
 #[cfg(feature = "full")]
+pub mod report_note_view;
+#[cfg(feature = "full")]
 pub mod site_view;
 pub mod structs;
@@ -0,0 +1,42 @@
+use crate::structs::PostPollView;
+use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{PersonId, PostId, PostPollOptionId},
+  schema::{post_poll_option, post_poll_vote},
+  source::post_poll::{PostPoll, PostPollOption},
+  utils::{get_conn, DbPool},
+};
+
+impl PostPollView {
+  /// Reads the poll attached to a post, if there is one.
+  pub async fn read(
+    pool: &DbPool,
+    post_id: PostId,
+    my_person_id: Option<PersonId>,
+  ) -> Result<Option<Self>, Error> {
+    let Some(poll) = PostPoll::read_for_post(pool, post_id).await? else {
+      return Ok(None);
+    };
+    let options = PostPollOption::list_for_poll(pool, poll.id).await?;
+
+    let my_votes = if let Some(person_id) = my_person_id {
+      let conn = &mut get_conn(pool).await?;
+      post_poll_vote::table
+        .inner_join(post_poll_option::table)
+        .filter(post_poll_option::poll_id.eq(poll.id))
+        .filter(post_poll_vote::person_id.eq(person_id))
+        .select(post_poll_vote::option_id)
+        .load::<PostPollOptionId>(conn)
+        .await?
+    } else {
+      vec![]
+    };
+
+    Ok(Some(PostPollView {
+      poll,
+      options,
+      my_votes,
+    }))
+  }
+}
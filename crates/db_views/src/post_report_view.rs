@@ -6,6 +6,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -42,6 +43,7 @@ type PostReportViewTuple = (
   Option<i16>,
   PostAggregates,
   Option<Person>,
+  Option<Person>,
 );
 
 impl PostReportView {
@@ -54,7 +56,8 @@ impl PostReportView {
     my_person_id: PersonId,
   ) -> Result<Self, Error> {
     let conn = &mut get_conn(pool).await?;
-    let (person_alias_1, person_alias_2) = diesel::alias!(person as person1, person as person2);
+    let (person_alias_1, person_alias_2, person_alias_3) =
+      diesel::alias!(person as person1, person as person2, person as person3);
 
     let (
       post_report,
@@ -66,6 +69,7 @@ impl PostReportView {
       post_like,
       counts,
       resolver,
+      assignee,
     ) = post_report::table
       .find(report_id)
       .inner_join(post::table)
@@ -90,16 +94,20 @@ impl PostReportView {
       .left_join(
         person_alias_2.on(post_report::resolver_id.eq(person_alias_2.field(person::id).nullable())),
       )
+      .left_join(
+        person_alias_3.on(post_report::assignee_id.eq(person_alias_3.field(person::id).nullable())),
+      )
       .select((
         post_report::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
         person::all_columns,
         person_alias_1.fields(person::all_columns),
         community_person_ban::all_columns.nullable(),
         post_like::score.nullable(),
         post_aggregates::all_columns,
         person_alias_2.fields(person::all_columns.nullable()),
+        person_alias_3.fields(person::all_columns.nullable()),
       ))
       .first::<PostReportViewTuple>(conn)
       .await?;
@@ -116,6 +124,7 @@ impl PostReportView {
       my_vote,
       counts,
       resolver,
+      assignee,
     })
   }
 
@@ -172,12 +181,14 @@ pub struct PostReportQuery<'a> {
   page: Option<i64>,
   limit: Option<i64>,
   unresolved_only: Option<bool>,
+  escalated_only: Option<bool>,
 }
 
 impl<'a> PostReportQuery<'a> {
   pub async fn list(self) -> Result<Vec<PostReportView>, Error> {
     let conn = &mut get_conn(self.pool).await?;
-    let (person_alias_1, person_alias_2) = diesel::alias!(person as person1, person as person2);
+    let (person_alias_1, person_alias_2, person_alias_3) =
+      diesel::alias!(person as person1, person as person2, person as person3);
 
     let mut query = post_report::table
       .inner_join(post::table)
@@ -202,16 +213,20 @@ impl<'a> PostReportQuery<'a> {
       .left_join(
         person_alias_2.on(post_report::resolver_id.eq(person_alias_2.field(person::id).nullable())),
       )
+      .left_join(
+        person_alias_3.on(post_report::assignee_id.eq(person_alias_3.field(person::id).nullable())),
+      )
       .select((
         post_report::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
         person::all_columns,
         person_alias_1.fields(person::all_columns),
         community_person_ban::all_columns.nullable(),
         post_like::score.nullable(),
         post_aggregates::all_columns,
         person_alias_2.fields(person::all_columns.nullable()),
+        person_alias_3.fields(person::all_columns.nullable()),
       ))
       .into_boxed();
 
@@ -223,6 +238,10 @@ impl<'a> PostReportQuery<'a> {
       query = query.filter(post_report::resolved.eq(false));
     }
 
+    if self.escalated_only.unwrap_or(false) {
+      query = query.filter(post_report::escalated.eq(true));
+    }
+
     let (limit, offset) = limit_and_offset(self.page, self.limit)?;
 
     query = query
@@ -263,6 +282,7 @@ impl JoinView for PostReportView {
       my_vote: a.6,
       counts: a.7,
       resolver: a.8,
+      assignee: a.9,
     }
   }
 }
@@ -281,6 +301,7 @@ mod tests {
     },
     traits::{Crud, Joinable, Reportable},
     utils::build_db_pool_for_tests,
+    CommunityVisibility,
   };
   use serial_test::serial;
 
@@ -357,6 +378,12 @@ mod tests {
 
     let inserted_sara_report = PostReport::report(pool, &sara_report_form).await.unwrap();
 
+    // automod doesn't report the post again in the name of sara
+    let automod_report = PostReport::report_automod(pool, &sara_report_form)
+      .await
+      .unwrap();
+    assert!(automod_report.is_none());
+
     // jessica reports
     let jessica_report_form = PostReportForm {
       creator_id: inserted_jessica.id,
@@ -405,6 +432,8 @@ mod tests {
         shared_inbox_url: inserted_community.shared_inbox_url.clone(),
         moderators_url: inserted_community.moderators_url.clone(),
         featured_url: inserted_community.featured_url.clone(),
+        visibility: CommunityVisibility::Public,
+        edit_history_public: false,
       },
       creator: Person {
         id: inserted_jessica.id,
@@ -429,6 +458,8 @@ mod tests {
         private_key: inserted_jessica.private_key,
         public_key: inserted_jessica.public_key,
         last_refreshed_at: inserted_jessica.last_refreshed_at,
+        also_known_as: vec![],
+        moved_to: None,
       },
       post_creator: Person {
         id: inserted_timmy.id,
@@ -453,6 +484,8 @@ mod tests {
         private_key: inserted_timmy.private_key.clone(),
         public_key: inserted_timmy.public_key.clone(),
         last_refreshed_at: inserted_timmy.last_refreshed_at,
+        also_known_as: vec![],
+        moved_to: None,
       },
       creator_banned_from_community: false,
       my_vote: None,
@@ -472,6 +505,7 @@ mod tests {
         hot_rank_active: 1728,
       },
       resolver: None,
+      assignee: None,
     };
 
     assert_eq!(read_jessica_report_view, expected_jessica_report_view);
@@ -502,6 +536,8 @@ mod tests {
       private_key: inserted_sara.private_key,
       public_key: inserted_sara.public_key,
       last_refreshed_at: inserted_sara.last_refreshed_at,
+      also_known_as: vec![],
+      moved_to: None,
     };
 
     // Do a batch read of timmys reports
@@ -529,9 +565,14 @@ mod tests {
     assert_eq!(2, report_count);
 
     // Try to resolve the report
-    PostReport::resolve(pool, inserted_jessica_report.id, inserted_timmy.id)
-      .await
-      .unwrap();
+    PostReport::resolve(
+      pool,
+      inserted_jessica_report.id,
+      inserted_timmy.id,
+      Some("spam".into()),
+    )
+    .await
+    .unwrap();
     let read_jessica_report_view_after_resolve =
       PostReportView::read(pool, inserted_jessica_report.id, inserted_timmy.id)
         .await
@@ -544,6 +585,9 @@ mod tests {
     expected_jessica_report_view_after_resolve
       .post_report
       .resolver_id = Some(inserted_timmy.id);
+    expected_jessica_report_view_after_resolve
+      .post_report
+      .resolution_reason = Some("spam".into());
     expected_jessica_report_view_after_resolve
       .post_report
       .updated = read_jessica_report_view_after_resolve.post_report.updated;
@@ -570,6 +614,8 @@ mod tests {
       private_key: inserted_timmy.private_key.clone(),
       public_key: inserted_timmy.public_key.clone(),
       last_refreshed_at: inserted_timmy.last_refreshed_at,
+      also_known_as: vec![],
+      moved_to: None,
     });
 
     assert_eq!(
@@ -597,6 +643,31 @@ mod tests {
         .unwrap();
     assert_eq!(1, report_count_after_resolved);
 
+    // Timmy claims saras report and hands it over to the admins
+    let sara_report_id = expected_sara_report_view.post_report.id;
+    PostReport::assign(pool, sara_report_id, Some(inserted_timmy.id))
+      .await
+      .unwrap();
+    PostReport::escalate(pool, sara_report_id, true)
+      .await
+      .unwrap();
+    let escalated_reports = PostReportQuery::builder()
+      .pool(pool)
+      .my_person_id(inserted_timmy.id)
+      .admin(true)
+      .escalated_only(Some(true))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert_eq!(1, escalated_reports.len());
+    assert_eq!(sara_report_id, escalated_reports[0].post_report.id);
+    assert!(escalated_reports[0].post_report.escalated);
+    assert_eq!(
+      Some(inserted_timmy.id),
+      escalated_reports[0].assignee.as_ref().map(|a| a.id)
+    );
+
     Person::delete(pool, inserted_timmy.id).await.unwrap();
     Person::delete(pool, inserted_sara.id).await.unwrap();
     Person::delete(pool, inserted_jessica.id).await.unwrap();
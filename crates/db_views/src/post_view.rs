@@ -1,48 +1,64 @@
 use crate::structs::PostView;
+use chrono::{DateTime, Utc};
 use diesel::{
   debug_query,
-  dsl::IntervalDsl,
+  dsl::{self, exists, IntervalDsl},
   expression::AsExpression,
   pg::Pg,
-  result::Error,
+  result::{Error, Error::QueryBuilderError},
   sql_function,
   sql_types::{self, Timestamptz},
   BoolExpressionMethods,
+  BoxableExpression,
   ExpressionMethods,
   IntoSql,
   JoinOnDsl,
   NullableExpressionMethods,
-  PgTextExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use diesel_full_text_search::{ts_rank_cd, TsVectorExtensions};
 use lemmy_db_schema::{
   aggregates::structs::PostAggregates,
-  newtypes::{CommunityId, LocalUserId, PersonId, PostId},
+  newtypes::{CommunityId, CommunityPostTagId, LocalUserId, PersonId, PostId},
   schema::{
     community,
     community_block,
     community_follower,
+    community_moderator,
     community_person_ban,
+    community_post_tag,
     local_user_language,
     person,
     person_block,
+    person_follower,
     person_post_aggregates,
     post,
     post_aggregates,
     post_like,
     post_read,
     post_saved,
+    post_tag,
   },
   source::{
     community::{Community, CommunityFollower, CommunityPersonBan},
+    community_post_tag::CommunityPostTag,
     local_user::LocalUser,
     person::Person,
     person_block::PersonBlock,
     post::{Post, PostRead, PostSaved},
   },
   traits::JoinView,
-  utils::{fuzzy_search, get_conn, limit_and_offset, DbPool, now},
+  utils::{
+    datetime_from_micros,
+    functions::lemmy_search_query,
+    get_conn,
+    limit_and_offset,
+    now,
+    DbPool,
+  },
+  CommunityVisibility,
   ListingType,
   SortType,
 };
@@ -131,9 +147,9 @@ impl PostView {
         ),
       )
       .select((
-        post::all_columns,
+        Post::as_select(),
         person::all_columns,
-        community::all_columns,
+        Community::as_select(),
         community_person_ban::all_columns.nullable(),
         post_aggregates::all_columns,
         community_follower::all_columns.nullable(),
@@ -157,6 +173,26 @@ impl PostView {
         .filter(post::deleted.eq(false));
     }
 
+    // Scheduled posts are only visible to their creator, mods and admins
+    if !is_mod_or_admin.unwrap_or(false) {
+      let moderated_communities = community_moderator::table
+        .filter(community_moderator::person_id.eq(person_id_join))
+        .select(community_moderator::community_id);
+      let person_alias_1 = diesel::alias!(person as person1);
+      let is_admin = exists(
+        person_alias_1
+          .filter(person_alias_1.field(person::id).eq(person_id_join))
+          .filter(person_alias_1.field(person::admin).eq(true)),
+      );
+      query = query.filter(
+        post::scheduled_publish_time
+          .is_null()
+          .or(post::creator_id.eq(person_id_join))
+          .or(post::community_id.eq_any(moderated_communities))
+          .or(is_admin),
+      );
+    }
+
     let (
       post,
       creator,
@@ -179,7 +215,7 @@ impl PostView {
       post_like
     };
 
-    Ok(PostView {
+    let mut post_view = PostView {
       post,
       creator,
       community,
@@ -191,8 +227,64 @@ impl PostView {
       creator_blocked: creator_blocked.is_some(),
       my_vote,
       unread_comments,
-    })
+      tags: vec![],
+      content_html: None,
+      image_proxy_links: None,
+    };
+    read_tags(conn, std::slice::from_mut(&mut post_view)).await?;
+    Ok(post_view)
+  }
+}
+
+/// Reads the tags of all the given posts at once.
+async fn read_tags(conn: &mut AsyncPgConnection, posts: &mut [PostView]) -> Result<(), Error> {
+  let post_ids = posts.iter().map(|p| p.post.id).collect::<Vec<_>>();
+  let tags = post_tag::table
+    .inner_join(community_post_tag::table)
+    .filter(post_tag::post_id.eq_any(post_ids))
+    .select((post_tag::post_id, community_post_tag::all_columns))
+    .order_by(community_post_tag::name)
+    .load::<(PostId, CommunityPostTag)>(conn)
+    .await?;
+  for (post_id, tag) in tags {
+    if let Some(post_view) = posts.iter_mut().find(|p| p.post.id == post_id) {
+      post_view.tags.push(tag);
+    }
+  }
+  Ok(())
+}
+
+type CommunityVisibilityFilter = dsl::Or<
+  dsl::Or<
+    dsl::EqAny<community::visibility, Vec<CommunityVisibility>>,
+    dsl::Eq<community_follower::pending, bool>,
+  >,
+  dsl::EqAny<
+    community::id,
+    dsl::Select<
+      dsl::Filter<community_moderator::table, dsl::Eq<community_moderator::person_id, PersonId>>,
+      community_moderator::community_id,
+    >,
+  >,
+>;
+
+/// Private communities only show their content to approved followers and mods, local only
+/// communities only to logged in users. Needs a left join of `community_follower` for the person.
+pub(crate) fn community_visibility_filter(
+  person_id: PersonId,
+  logged_in: bool,
+) -> CommunityVisibilityFilter {
+  let mut visible = vec![CommunityVisibility::Public];
+  if logged_in {
+    visible.push(CommunityVisibility::LocalOnly);
   }
+  let moderated_communities = community_moderator::table
+    .filter(community_moderator::person_id.eq(person_id))
+    .select(community_moderator::community_id);
+  community::visibility
+    .eq_any(visible)
+    .or(community_follower::pending.eq(false))
+    .or(community::id.eq_any(moderated_communities))
 }
 
 #[derive(TypedBuilder)]
@@ -204,14 +296,21 @@ pub struct PostQuery<'a> {
   sort: Option<SortType>,
   creator_id: Option<PersonId>,
   community_id: Option<CommunityId>,
+  /// Only list posts which have this tag
+  tag_id: Option<CommunityPostTagId>,
   local_user: Option<&'a LocalUser>,
   search_term: Option<String>,
   url_search: Option<String>,
   saved_only: Option<bool>,
+  /// Only list posts which are not published yet, and were created by the user or are in a
+  /// community they moderate
+  scheduled_only: Option<bool>,
   /// Used to show deleted or removed posts for admins
   is_mod_or_admin: Option<bool>,
   page: Option<i64>,
   limit: Option<i64>,
+  /// Only list the posts after this one, instead of using the page
+  page_cursor: Option<PostCursor>,
 }
 
 impl<'a> PostQuery<'a> {
@@ -290,9 +389,9 @@ impl<'a> PostQuery<'a> {
         ),
       )
       .select((
-        post::all_columns,
+        Post::as_select(),
         person::all_columns,
-        community::all_columns,
+        Community::as_select(),
         community_person_ban::all_columns.nullable(),
         post_aggregates::all_columns,
         community_follower::all_columns.nullable(),
@@ -317,6 +416,13 @@ impl<'a> PostQuery<'a> {
         .filter(post::deleted.eq(false));
     }
 
+    if !self.is_mod_or_admin.unwrap_or(false) {
+      query = query.filter(community_visibility_filter(
+        person_id_join,
+        self.local_user.is_some(),
+      ));
+    }
+
     if self.community_id.is_none() {
       query = query.then_order_by(post_aggregates::featured_local.desc());
     } else if let Some(community_id) = self.community_id {
@@ -325,10 +431,40 @@ impl<'a> PostQuery<'a> {
         .then_order_by(post_aggregates::featured_community.desc());
     }
 
+    let scheduled_only = self.scheduled_only.unwrap_or(false);
+    if scheduled_only {
+      query = query
+        .filter(post::scheduled_publish_time.is_not_null())
+        .then_order_by(post::scheduled_publish_time.asc());
+      if !self.is_mod_or_admin.unwrap_or(false) {
+        let moderated_communities = community_moderator::table
+          .filter(community_moderator::person_id.eq(person_id_join))
+          .select(community_moderator::community_id);
+        query = query.filter(
+          post::creator_id
+            .eq(person_id_join)
+            .or(post::community_id.eq_any(moderated_communities)),
+        );
+      }
+    } else {
+      // Scheduled posts stay hidden until they are published
+      query = query.filter(post::scheduled_publish_time.is_null());
+    }
+
     if let Some(creator_id) = self.creator_id {
       query = query.filter(post::creator_id.eq(creator_id));
     }
 
+    if let Some(tag_id) = self.tag_id {
+      query = query.filter(
+        post::id.eq_any(
+          post_tag::table
+            .filter(post_tag::tag_id.eq(tag_id))
+            .select(post_tag::post_id),
+        ),
+      );
+    }
+
     if let Some(listing_type) = self.listing_type {
       match listing_type {
         ListingType::Subscribed => {
@@ -348,6 +484,16 @@ impl<'a> PostQuery<'a> {
               .or(community_follower::person_id.eq(person_id_join)),
           )
         }
+        ListingType::FollowedUsers => {
+          query = query.filter(
+            post::creator_id.eq_any(
+              person_follower::table
+                .select(person_follower::person_id)
+                .filter(person_follower::follower_id.eq(person_id_join))
+                .filter(person_follower::pending.eq(false)),
+            ),
+          )
+        }
       }
     }
 
@@ -355,13 +501,8 @@ impl<'a> PostQuery<'a> {
       query = query.filter(post::url.eq(url_search));
     }
 
-    if let Some(search_term) = self.search_term {
-      let searcher = fuzzy_search(&search_term);
-      query = query.filter(
-        post::name
-          .ilike(searcher.clone())
-          .or(post::body.ilike(searcher)),
-      );
+    if let Some(search_term) = &self.search_term {
+      query = query.filter(post::search_vector.matches(lemmy_search_query(search_term)));
     }
 
     if !self.local_user.map(|l| l.show_nsfw).unwrap_or(false) {
@@ -378,8 +519,9 @@ impl<'a> PostQuery<'a> {
       query = query.filter(post_saved::post_id.is_not_null());
     }
     // Only hide the read posts, if the saved_only is false. Otherwise ppl with the hide_read
-    // setting wont be able to see saved posts.
-    else if !self.local_user.map(|l| l.show_read_posts).unwrap_or(true) {
+    // setting wont be able to see saved posts. The same goes for scheduled posts, which are
+    // marked as read for their creator.
+    else if !scheduled_only && !self.local_user.map(|l| l.show_read_posts).unwrap_or(true) {
       query = query.filter(post_read::post_id.is_null());
     }
 
@@ -392,9 +534,16 @@ impl<'a> PostQuery<'a> {
       query = query.filter(person_block::person_id.is_null());
     }
 
-    query = match self.sort.unwrap_or(SortType::Hot) {
+    let sort = self.sort.unwrap_or(SortType::Hot);
+    query = match sort {
       SortType::Active => query.then_order_by(post_aggregates::hot_rank_active.desc()),
       SortType::Hot => query.then_order_by(post_aggregates::hot_rank.desc()),
+      SortType::Relevance => match &self.search_term {
+        Some(search_term) => query
+          .then_order_by(ts_rank_cd(post::search_vector, lemmy_search_query(search_term)).desc())
+          .then_order_by(post_aggregates::published.desc()),
+        None => query.then_order_by(post_aggregates::hot_rank.desc()),
+      },
       SortType::New => query.then_order_by(post_aggregates::published.desc()),
       SortType::Old => query.then_order_by(post_aggregates::published.asc()),
       SortType::NewComments => query.then_order_by(post_aggregates::newest_comment_time.desc()),
@@ -446,7 +595,98 @@ impl<'a> PostQuery<'a> {
         .then_order_by(post_aggregates::published.desc()),
     };
 
-    let (limit, offset) = limit_and_offset(self.page, self.limit)?;
+    // Break ties by id, so that the posts are always in the same order for the page cursor
+    query = if sort == SortType::Old {
+      query.then_order_by(post_aggregates::post_id.asc())
+    } else {
+      query.then_order_by(post_aggregates::post_id.desc())
+    };
+
+    if let Some(cursor) = self.page_cursor {
+      // Build the filter from the last column of the ordering to the first
+      let mut after: Box<dyn BoxableExpression<_, Pg, SqlType = sql_types::Bool>> =
+        if sort == SortType::Old {
+          Box::new(post_aggregates::post_id.gt(cursor.post_id))
+        } else {
+          Box::new(post_aggregates::post_id.lt(cursor.post_id))
+        };
+      after = match sort {
+        SortType::Active => after_cursor!(
+          after,
+          post_aggregates::hot_rank_active,
+          cursor.hot_rank_active,
+          desc
+        ),
+        SortType::Relevance if self.search_term.is_some() => {
+          return Err(QueryBuilderError(
+            "Search results can't be paged with a cursor".into(),
+          ))
+        }
+        SortType::Hot | SortType::Relevance => {
+          after_cursor!(after, post_aggregates::hot_rank, cursor.hot_rank, desc)
+        }
+        SortType::New => after_cursor!(after, post_aggregates::published, cursor.published, desc),
+        SortType::Old => after_cursor!(after, post_aggregates::published, cursor.published, asc),
+        SortType::NewComments => after_cursor!(
+          after,
+          post_aggregates::newest_comment_time,
+          cursor.newest_comment_time,
+          desc
+        ),
+        SortType::MostComments => {
+          let after = after_cursor!(after, post_aggregates::published, cursor.published, desc);
+          after_cursor!(after, post_aggregates::comments, cursor.comments, desc)
+        }
+        SortType::TopAll
+        | SortType::TopYear
+        | SortType::TopMonth
+        | SortType::TopWeek
+        | SortType::TopDay
+        | SortType::TopHour
+        | SortType::TopSixHour
+        | SortType::TopTwelveHour
+        | SortType::TopThreeMonths
+        | SortType::TopSixMonths
+        | SortType::TopNineMonths => {
+          let after = after_cursor!(after, post_aggregates::published, cursor.published, desc);
+          after_cursor!(after, post_aggregates::score, cursor.score, desc)
+        }
+      };
+      if scheduled_only {
+        let scheduled_publish_time = cursor
+          .scheduled_publish_time
+          .ok_or_else(|| QueryBuilderError("Cursor is not for a scheduled post".into()))?;
+        after = after_cursor!(
+          after,
+          post::scheduled_publish_time.assume_not_null(),
+          scheduled_publish_time,
+          asc
+        );
+      }
+      after = if self.community_id.is_none() {
+        after_cursor!(
+          after,
+          post_aggregates::featured_local,
+          cursor.featured_local,
+          desc
+        )
+      } else {
+        after_cursor!(
+          after,
+          post_aggregates::featured_community,
+          cursor.featured_community,
+          desc
+        )
+      };
+      query = query.filter(after);
+    }
+
+    let page = if self.page_cursor.is_some() {
+      None
+    } else {
+      self.page
+    };
+    let (limit, offset) = limit_and_offset(page, self.limit)?;
 
     query = query.limit(limit).offset(offset);
 
@@ -454,7 +694,88 @@ impl<'a> PostQuery<'a> {
 
     let res = query.load::<PostViewTuple>(conn).await?;
 
-    Ok(res.into_iter().map(PostView::from_tuple).collect())
+    let mut posts = res
+      .into_iter()
+      .map(PostView::from_tuple)
+      .collect::<Vec<_>>();
+    read_tags(conn, &mut posts).await?;
+    Ok(posts)
+  }
+}
+
+/// The sort keys of the last post on a page, to continue the listing after it. They are kept in the
+/// cursor instead of being read again, so that the pages stay stable while the hot ranks change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PostCursor {
+  post_id: PostId,
+  featured_local: bool,
+  featured_community: bool,
+  hot_rank: i32,
+  hot_rank_active: i32,
+  published: DateTime<Utc>,
+  newest_comment_time: DateTime<Utc>,
+  comments: i64,
+  score: i64,
+  scheduled_publish_time: Option<DateTime<Utc>>,
+}
+
+impl PostCursor {
+  /// Encodes the cursor as an opaque string which clients pass back to get the next page.
+  pub fn encode(&self) -> String {
+    let scheduled_publish_time = self
+      .scheduled_publish_time
+      .map(|t| t.timestamp_micros().to_string())
+      .unwrap_or_default();
+    format!(
+      "{}_{}_{}_{}_{}_{}_{}_{}_{}_{}",
+      self.post_id.0,
+      self.featured_local,
+      self.featured_community,
+      self.hot_rank,
+      self.hot_rank_active,
+      self.published.timestamp_micros(),
+      self.newest_comment_time.timestamp_micros(),
+      self.comments,
+      self.score,
+      scheduled_publish_time
+    )
+  }
+
+  pub fn decode(cursor: &str) -> Option<Self> {
+    let mut parts = cursor.split('_');
+    let cursor = PostCursor {
+      post_id: PostId(parts.next()?.parse().ok()?),
+      featured_local: parts.next()?.parse().ok()?,
+      featured_community: parts.next()?.parse().ok()?,
+      hot_rank: parts.next()?.parse().ok()?,
+      hot_rank_active: parts.next()?.parse().ok()?,
+      published: datetime_from_micros(parts.next()?.parse().ok()?)?,
+      newest_comment_time: datetime_from_micros(parts.next()?.parse().ok()?)?,
+      comments: parts.next()?.parse().ok()?,
+      score: parts.next()?.parse().ok()?,
+      scheduled_publish_time: match parts.next()? {
+        "" => None,
+        t => Some(datetime_from_micros(t.parse().ok()?)?),
+      },
+    };
+    parts.next().is_none().then_some(cursor)
+  }
+}
+
+impl From<&PostView> for PostCursor {
+  fn from(view: &PostView) -> Self {
+    PostCursor {
+      post_id: view.post.id,
+      featured_local: view.counts.featured_local,
+      featured_community: view.counts.featured_community,
+      hot_rank: view.counts.hot_rank,
+      hot_rank_active: view.counts.hot_rank_active,
+      published: view.counts.published,
+      newest_comment_time: view.counts.newest_comment_time,
+      comments: view.counts.comments,
+      score: view.counts.score,
+      scheduled_publish_time: view.post.scheduled_publish_time,
+    }
   }
 }
 
@@ -473,30 +794,45 @@ impl JoinView for PostView {
       creator_blocked: a.8.is_some(),
       my_vote: a.9,
       unread_comments: a.10,
+      tags: vec![],
+      content_html: None,
+      image_proxy_links: None,
     }
   }
 }
 
 #[cfg(test)]
 mod tests {
-  use crate::post_view::{PostQuery, PostView};
+  use crate::post_view::{PostCursor, PostQuery, PostView};
+  use chrono::Duration;
   use lemmy_db_schema::{
     aggregates::structs::PostAggregates,
     impls::actor_language::UNDETERMINED_ID,
     newtypes::LanguageId,
     source::{
       actor_language::LocalUserLanguage,
-      community::{Community, CommunityInsertForm},
+      community::{
+        Community,
+        CommunityFollower,
+        CommunityFollowerForm,
+        CommunityInsertForm,
+        CommunityModerator,
+        CommunityModeratorForm,
+        CommunityUpdateForm,
+      },
       community_block::{CommunityBlock, CommunityBlockForm},
+      community_post_tag::{CommunityPostTag, CommunityPostTagInsertForm, PostTag},
       instance::Instance,
       language::Language,
       local_user::{LocalUser, LocalUserInsertForm, LocalUserUpdateForm},
-      person::{Person, PersonInsertForm},
+      person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm, PersonUpdateForm},
       person_block::{PersonBlock, PersonBlockForm},
       post::{Post, PostInsertForm, PostLike, PostLikeForm, PostUpdateForm},
     },
-    traits::{Blockable, Crud, Likeable},
-    utils::{build_db_pool_for_tests, DbPool},
+    traits::{Blockable, Crud, Followable, Joinable, Likeable},
+    utils::{build_db_pool_for_tests, naive_now, DbPool},
+    CommunityVisibility,
+    ListingType,
     SortType,
     SubscribedType,
   };
@@ -741,6 +1077,308 @@ mod tests {
     cleanup(data, pool).await;
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_followed_users() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let follow_form = PersonFollowerForm {
+      person_id: data.inserted_bot.id,
+      follower_id: data.inserted_person.id,
+      pending: false,
+    };
+    PersonFollower::follow(pool, &follow_form).await.unwrap();
+
+    let read_post_listing = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::New))
+      .listing_type(Some(ListingType::FollowedUsers))
+      .local_user(Some(&data.inserted_local_user))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    // Only the post of the followed bot
+    assert_eq!(1, read_post_listing.len());
+    assert_eq!(data.inserted_bot.id, read_post_listing[0].creator.id);
+
+    PersonFollower::unfollow(pool, &follow_form).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_search() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let read_post_listing = PostQuery::builder()
+      .pool(pool)
+      .search_term(Some("bot".to_string()))
+      .community_id(Some(data.inserted_community.id))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert_eq!(1, read_post_listing.len());
+    assert_eq!(data.inserted_bot.id, read_post_listing[0].creator.id);
+
+    // Both words next to each other rank higher than with another word in between
+    let read_post_listing = PostQuery::builder()
+      .pool(pool)
+      .sort(Some(SortType::Relevance))
+      .search_term(Some("test post".to_string()))
+      .community_id(Some(data.inserted_community.id))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert_eq!(2, read_post_listing.len());
+    assert_eq!(data.inserted_post.id, read_post_listing[0].post.id);
+    assert_eq!(data.inserted_bot.id, read_post_listing[1].creator.id);
+
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_tags() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let tag_form = CommunityPostTagInsertForm::builder()
+      .community_id(data.inserted_community.id)
+      .name("Meta".into())
+      .build();
+    let tag = CommunityPostTag::create(pool, &tag_form).await.unwrap();
+    PostTag::set(pool, data.inserted_post.id, vec![tag.id])
+      .await
+      .unwrap();
+
+    let read_post_listing = PostQuery::builder()
+      .pool(pool)
+      .tag_id(Some(tag.id))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert_eq!(1, read_post_listing.len());
+    assert_eq!(data.inserted_post.id, read_post_listing[0].post.id);
+    assert_eq!(vec![tag.clone()], read_post_listing[0].tags);
+
+    let read_post = PostView::read(pool, data.inserted_post.id, None, None)
+      .await
+      .unwrap();
+    assert_eq!(vec![tag], read_post.tags);
+
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_scheduled() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let scheduled_post_form = PostInsertForm::builder()
+      .name("scheduled post".to_string())
+      .creator_id(data.inserted_person.id)
+      .community_id(data.inserted_community.id)
+      .language_id(Some(LanguageId(47)))
+      .scheduled_publish_time(Some(naive_now() + Duration::days(1)))
+      .build();
+    let scheduled_post = Post::create(pool, &scheduled_post_form).await.unwrap();
+
+    // The scheduled post is hidden from the normal listing, even for its creator
+    let read_post_listing = PostQuery::builder()
+      .pool(pool)
+      .community_id(Some(data.inserted_community.id))
+      .local_user(Some(&data.inserted_local_user))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert!(read_post_listing
+      .iter()
+      .all(|p| p.post.id != scheduled_post.id));
+
+    let read_scheduled_listing = PostQuery::builder()
+      .pool(pool)
+      .local_user(Some(&data.inserted_local_user))
+      .scheduled_only(Some(true))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    assert_eq!(1, read_scheduled_listing.len());
+    assert_eq!(scheduled_post.id, read_scheduled_listing[0].post.id);
+
+    // Other users can't see it before it is published
+    let scheduled_read = PostView::read(pool, scheduled_post.id, None, None).await;
+    assert!(scheduled_read.is_err());
+    let scheduled_read = PostView::read(
+      pool,
+      scheduled_post.id,
+      Some(data.inserted_blocked_person.id),
+      None,
+    )
+    .await;
+    assert!(scheduled_read.is_err());
+
+    // But mods of the community can
+    let moderator_form = CommunityModeratorForm {
+      community_id: data.inserted_community.id,
+      person_id: data.inserted_blocked_person.id,
+    };
+    CommunityModerator::join(pool, &moderator_form)
+      .await
+      .unwrap();
+    let scheduled_read = PostView::read(
+      pool,
+      scheduled_post.id,
+      Some(data.inserted_blocked_person.id),
+      None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(scheduled_post.id, scheduled_read.post.id);
+    CommunityModerator::leave(pool, &moderator_form)
+      .await
+      .unwrap();
+
+    // And admins
+    let admin_form = PersonUpdateForm::builder().admin(Some(true)).build();
+    Person::update(pool, data.inserted_blocked_person.id, &admin_form)
+      .await
+      .unwrap();
+    let scheduled_read = PostView::read(
+      pool,
+      scheduled_post.id,
+      Some(data.inserted_blocked_person.id),
+      None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(scheduled_post.id, scheduled_read.post.id);
+
+    Post::delete(pool, scheduled_post.id).await.unwrap();
+    cleanup(data, pool).await;
+  }
+
+  async fn list_private(
+    pool: &DbPool,
+    data: &Data,
+    local_user: Option<&LocalUser>,
+  ) -> Result<Vec<PostView>, diesel::result::Error> {
+    PostQuery::builder()
+      .pool(pool)
+      .community_id(Some(data.inserted_community.id))
+      .local_user(local_user)
+      .build()
+      .list()
+      .await
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_private_community() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    let form = CommunityUpdateForm::builder()
+      .visibility(Some(CommunityVisibility::ApprovalRequired))
+      .build();
+    Community::update(pool, data.inserted_community.id, &form)
+      .await
+      .unwrap();
+
+    assert!(list_private(pool, &data, None).await.unwrap().is_empty());
+    assert!(list_private(pool, &data, Some(&data.inserted_local_user))
+      .await
+      .unwrap()
+      .is_empty());
+
+    // A pending follow doesn't give access yet
+    let follower_form = CommunityFollowerForm {
+      community_id: data.inserted_community.id,
+      person_id: data.inserted_person.id,
+      pending: true,
+    };
+    CommunityFollower::follow(pool, &follower_form)
+      .await
+      .unwrap();
+    assert!(list_private(pool, &data, Some(&data.inserted_local_user))
+      .await
+      .unwrap()
+      .is_empty());
+
+    CommunityFollower::follow_accepted(pool, data.inserted_community.id, data.inserted_person.id)
+      .await
+      .unwrap();
+    assert!(!list_private(pool, &data, Some(&data.inserted_local_user))
+      .await
+      .unwrap()
+      .is_empty());
+    assert!(list_private(pool, &data, None).await.unwrap().is_empty());
+
+    CommunityFollower::unfollow(pool, &follower_form)
+      .await
+      .unwrap();
+    cleanup(data, pool).await;
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_page_cursor() {
+    let pool = &build_db_pool_for_tests().await;
+    let data = init_data(pool).await;
+
+    for sort in [
+      SortType::New,
+      SortType::Old,
+      SortType::Hot,
+      SortType::TopAll,
+    ] {
+      let all_posts = PostQuery::builder()
+        .pool(pool)
+        .sort(Some(sort))
+        .community_id(Some(data.inserted_community.id))
+        .build()
+        .list()
+        .await
+        .unwrap();
+
+      // Page through the same listing, one post at a time
+      let mut paged_posts = vec![];
+      let mut page_cursor = None;
+      loop {
+        let page = PostQuery::builder()
+          .pool(pool)
+          .sort(Some(sort))
+          .community_id(Some(data.inserted_community.id))
+          .limit(Some(1))
+          .page_cursor(page_cursor)
+          .build()
+          .list()
+          .await
+          .unwrap();
+        let cursor = match page.last() {
+          Some(last) => PostCursor::from(last),
+          None => break,
+        };
+        assert_eq!(Some(cursor), PostCursor::decode(&cursor.encode()));
+        page_cursor = Some(cursor);
+        paged_posts.extend(page);
+      }
+
+      assert_eq!(3, all_posts.len());
+      assert_eq!(all_posts, paged_posts);
+    }
+
+    cleanup(data, pool).await;
+  }
+
   #[tokio::test]
   #[serial]
   async fn post_listing_like() {
@@ -979,9 +1617,13 @@ mod tests {
         language_id: LanguageId(47),
         featured_community: false,
         featured_local: false,
+        scheduled_publish_time: None,
       },
       my_vote: None,
       unread_comments: 0,
+      tags: vec![],
+      content_html: None,
+      image_proxy_links: None,
       creator: Person {
         id: inserted_person.id,
         name: inserted_person.name.clone(),
@@ -1005,6 +1647,8 @@ mod tests {
         private_key: inserted_person.private_key.clone(),
         public_key: inserted_person.public_key.clone(),
         last_refreshed_at: inserted_person.last_refreshed_at,
+        also_known_as: vec![],
+        moved_to: None,
       },
       creator_banned_from_community: false,
       community: Community {
@@ -1032,6 +1676,8 @@ mod tests {
         shared_inbox_url: inserted_community.shared_inbox_url.clone(),
         moderators_url: inserted_community.moderators_url.clone(),
         featured_url: inserted_community.featured_url.clone(),
+        visibility: CommunityVisibility::Public,
+        edit_history_public: false,
       },
       counts: PostAggregates {
         id: agg.id,
@@ -20,6 +20,7 @@ type PrivateMessageReportViewTuple = (
   Person,
   Person,
   Option<Person>,
+  Option<Person>,
 );
 
 impl PrivateMessageReportView {
@@ -28,31 +29,41 @@ impl PrivateMessageReportView {
   /// * `report_id` - the report id to obtain
   pub async fn read(pool: &DbPool, report_id: PrivateMessageReportId) -> Result<Self, Error> {
     let conn = &mut get_conn(pool).await?;
-    let (person_alias_1, person_alias_2) = diesel::alias!(person as person1, person as person2);
-
-    let (private_message_report, private_message, private_message_creator, creator, resolver) =
-      private_message_report::table
-        .find(report_id)
-        .inner_join(private_message::table)
-        .inner_join(person::table.on(private_message::creator_id.eq(person::id)))
-        .inner_join(
-          person_alias_1
-            .on(private_message_report::creator_id.eq(person_alias_1.field(person::id))),
-        )
-        .left_join(
-          person_alias_2.on(
-            private_message_report::resolver_id.eq(person_alias_2.field(person::id).nullable()),
-          ),
-        )
-        .select((
-          private_message_report::all_columns,
-          private_message::all_columns,
-          person::all_columns,
-          person_alias_1.fields(person::all_columns),
-          person_alias_2.fields(person::all_columns).nullable(),
-        ))
-        .first::<PrivateMessageReportViewTuple>(conn)
-        .await?;
+    let (person_alias_1, person_alias_2, person_alias_3) =
+      diesel::alias!(person as person1, person as person2, person as person3);
+
+    let (
+      private_message_report,
+      private_message,
+      private_message_creator,
+      creator,
+      resolver,
+      assignee,
+    ) = private_message_report::table
+      .find(report_id)
+      .inner_join(private_message::table)
+      .inner_join(person::table.on(private_message::creator_id.eq(person::id)))
+      .inner_join(
+        person_alias_1.on(private_message_report::creator_id.eq(person_alias_1.field(person::id))),
+      )
+      .left_join(
+        person_alias_2
+          .on(private_message_report::resolver_id.eq(person_alias_2.field(person::id).nullable())),
+      )
+      .left_join(
+        person_alias_3
+          .on(private_message_report::assignee_id.eq(person_alias_3.field(person::id).nullable())),
+      )
+      .select((
+        private_message_report::all_columns,
+        private_message::all_columns,
+        person::all_columns,
+        person_alias_1.fields(person::all_columns),
+        person_alias_2.fields(person::all_columns).nullable(),
+        person_alias_3.fields(person::all_columns).nullable(),
+      ))
+      .first::<PrivateMessageReportViewTuple>(conn)
+      .await?;
 
     Ok(Self {
       private_message_report,
@@ -60,6 +71,7 @@ impl PrivateMessageReportView {
       private_message_creator,
       creator,
       resolver,
+      assignee,
     })
   }
 
@@ -91,7 +103,8 @@ pub struct PrivateMessageReportQuery<'a> {
 impl<'a> PrivateMessageReportQuery<'a> {
   pub async fn list(self) -> Result<Vec<PrivateMessageReportView>, Error> {
     let conn = &mut get_conn(self.pool).await?;
-    let (person_alias_1, person_alias_2) = diesel::alias!(person as person1, person as person2);
+    let (person_alias_1, person_alias_2, person_alias_3) =
+      diesel::alias!(person as person1, person as person2, person as person3);
 
     let mut query = private_message_report::table
       .inner_join(private_message::table)
@@ -103,12 +116,17 @@ impl<'a> PrivateMessageReportQuery<'a> {
         person_alias_2
           .on(private_message_report::resolver_id.eq(person_alias_2.field(person::id).nullable())),
       )
+      .left_join(
+        person_alias_3
+          .on(private_message_report::assignee_id.eq(person_alias_3.field(person::id).nullable())),
+      )
       .select((
         private_message_report::all_columns,
         private_message::all_columns,
         person::all_columns,
         person_alias_1.fields(person::all_columns),
         person_alias_2.fields(person::all_columns).nullable(),
+        person_alias_3.fields(person::all_columns).nullable(),
       ))
       .into_boxed();
 
@@ -143,6 +161,7 @@ impl JoinView for PrivateMessageReportView {
       private_message_creator: a.2,
       creator: a.3,
       resolver: a.4,
+      assignee: a.5,
     }
   }
 }
@@ -225,7 +244,7 @@ mod tests {
     let inserted_admin = Person::create(pool, &new_person_3).await.unwrap();
 
     // admin resolves the report (after taking appropriate action)
-    PrivateMessageReport::resolve(pool, pm_report.id, inserted_admin.id)
+    PrivateMessageReport::resolve(pool, pm_report.id, inserted_admin.id, None)
       .await
       .unwrap();
 
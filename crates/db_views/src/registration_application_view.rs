@@ -288,6 +288,9 @@ mod tests {
         totp_2fa_url: inserted_sara_local_user.totp_2fa_url,
         password_encrypted: inserted_sara_local_user.password_encrypted,
         open_links_in_new_tab: inserted_sara_local_user.open_links_in_new_tab,
+        push_notify_replies: inserted_sara_local_user.push_notify_replies,
+        push_notify_mentions: inserted_sara_local_user.push_notify_mentions,
+        push_notify_private_messages: inserted_sara_local_user.push_notify_private_messages,
       },
       creator: Person {
         id: inserted_sara_person.id,
@@ -312,6 +315,8 @@ mod tests {
         private_key: inserted_sara_person.private_key,
         public_key: inserted_sara_person.public_key,
         last_refreshed_at: inserted_sara_person.last_refreshed_at,
+        also_known_as: vec![],
+        moved_to: None,
       },
       admin: None,
     };
@@ -390,6 +395,8 @@ mod tests {
       private_key: inserted_timmy_person.private_key,
       public_key: inserted_timmy_person.public_key,
       last_refreshed_at: inserted_timmy_person.last_refreshed_at,
+      also_known_as: vec![],
+      moved_to: None,
     });
     assert_eq!(read_sara_app_view_after_approve, expected_sara_app_view);
 
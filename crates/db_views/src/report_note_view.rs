@@ -0,0 +1,218 @@
+use crate::structs::ReportNoteView;
+use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::{CommentReportId, PostReportId, PrivateMessageReportId},
+  schema::{person, report_note},
+  source::{person::Person, report_note::ReportNote},
+  traits::JoinView,
+  utils::{get_conn, DbPool},
+};
+use typed_builder::TypedBuilder;
+
+type ReportNoteViewTuple = (ReportNote, Person);
+
+impl ReportNoteView {
+  pub async fn read(pool: &DbPool, report_note_id: i32) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let res = report_note::table
+      .find(report_note_id)
+      .inner_join(person::table)
+      .select((report_note::all_columns, person::all_columns))
+      .first::<ReportNoteViewTuple>(conn)
+      .await?;
+    Ok(Self::from_tuple(res))
+  }
+}
+
+#[derive(TypedBuilder)]
+#[builder(field_defaults(default))]
+pub struct ReportNoteQuery<'a> {
+  #[builder(!default)]
+  pool: &'a DbPool,
+  post_report_id: Option<PostReportId>,
+  comment_report_id: Option<CommentReportId>,
+  private_message_report_id: Option<PrivateMessageReportId>,
+}
+
+impl<'a> ReportNoteQuery<'a> {
+  /// Lists the notes of a report, from oldest to newest.
+  pub async fn list(self) -> Result<Vec<ReportNoteView>, Error> {
+    let conn = &mut get_conn(self.pool).await?;
+    let mut query = report_note::table
+      .inner_join(person::table)
+      .select((report_note::all_columns, person::all_columns))
+      .into_boxed();
+
+    if let Some(post_report_id) = self.post_report_id {
+      query = query.filter(report_note::post_report_id.eq(post_report_id));
+    }
+    if let Some(comment_report_id) = self.comment_report_id {
+      query = query.filter(report_note::comment_report_id.eq(comment_report_id));
+    }
+    if let Some(private_message_report_id) = self.private_message_report_id {
+      query = query.filter(report_note::private_message_report_id.eq(private_message_report_id));
+    }
+
+    let res = query
+      .order_by(report_note::published.asc())
+      .then_order_by(report_note::id.asc())
+      .load::<ReportNoteViewTuple>(conn)
+      .await?;
+
+    Ok(res.into_iter().map(ReportNoteView::from_tuple).collect())
+  }
+}
+
+impl JoinView for ReportNoteView {
+  type JoinTuple = ReportNoteViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      report_note: a.0,
+      creator: a.1,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::report_note_view::{ReportNoteQuery, ReportNoteView};
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      post_report::{PostReport, PostReportForm},
+      report_note::{ReportNote, ReportNoteForm},
+    },
+    traits::{Crud, Reportable},
+    utils::build_db_pool_for_tests,
+  };
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_report_notes() {
+    let pool = &build_db_pool_for_tests().await;
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("report_note_mod".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_mod = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("report_note_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A post with notes".into())
+      .creator_id(inserted_mod.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    // Everyone can only report a post once
+    let mut reporter_ids = vec![];
+    let mut report_ids = vec![];
+    for name in ["report_note_a", "report_note_b"] {
+      let new_reporter = PersonInsertForm::builder()
+        .name(name.into())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build();
+      let inserted_reporter = Person::create(pool, &new_reporter).await.unwrap();
+      reporter_ids.push(inserted_reporter.id);
+
+      let report_form = PostReportForm {
+        creator_id: inserted_reporter.id,
+        post_id: inserted_post.id,
+        original_post_name: "Orig post".into(),
+        original_post_url: None,
+        original_post_body: None,
+        reason: "spam".into(),
+      };
+      let inserted_report = PostReport::report(pool, &report_form).await.unwrap();
+      report_ids.push(inserted_report.id);
+    }
+
+    let mut note_ids = vec![];
+    for (report_id, content) in [
+      (report_ids[0], "looking into it"),
+      (report_ids[1], "other report"),
+      (report_ids[0], "it's spam"),
+    ] {
+      let note_form = ReportNoteForm {
+        post_report_id: Some(report_id),
+        comment_report_id: None,
+        private_message_report_id: None,
+        creator_id: inserted_mod.id,
+        content: content.into(),
+        parent_id: None,
+      };
+      let inserted_note = ReportNote::create(pool, &note_form).await.unwrap();
+      note_ids.push(inserted_note.id);
+    }
+
+    // Replies to another note of the same report
+    let reply_form = ReportNoteForm {
+      post_report_id: Some(report_ids[0]),
+      comment_report_id: None,
+      private_message_report_id: None,
+      creator_id: inserted_mod.id,
+      content: "agreed".into(),
+      parent_id: Some(note_ids[2]),
+    };
+    let reply = ReportNote::create(pool, &reply_form).await.unwrap();
+    let read_reply = ReportNote::read(pool, reply.id).await.unwrap();
+    assert_eq!(reply, read_reply);
+
+    let read_note = ReportNoteView::read(pool, note_ids[0]).await.unwrap();
+    assert_eq!("looking into it", read_note.report_note.content);
+    assert_eq!(inserted_mod.id, read_note.creator.id);
+
+    let notes = ReportNoteQuery::builder()
+      .pool(pool)
+      .post_report_id(Some(report_ids[0]))
+      .build()
+      .list()
+      .await
+      .unwrap();
+    let contents: Vec<_> = notes
+      .iter()
+      .map(|n| n.report_note.content.as_str())
+      .collect();
+    assert_eq!(vec!["looking into it", "it's spam", "agreed"], contents);
+    assert_eq!(Some(note_ids[2]), notes[2].report_note.parent_id);
+
+    // A note must belong to exactly one report
+    let invalid_form = ReportNoteForm {
+      post_report_id: None,
+      comment_report_id: None,
+      private_message_report_id: None,
+      creator_id: inserted_mod.id,
+      content: "nowhere".into(),
+      parent_id: None,
+    };
+    assert!(ReportNote::create(pool, &invalid_form).await.is_err());
+
+    for reporter_id in reporter_ids {
+      Person::delete(pool, reporter_id).await.unwrap();
+    }
+    Person::delete(pool, inserted_mod.id).await.unwrap();
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -1,9 +1,11 @@
 use lemmy_db_schema::{
   aggregates::structs::{CommentAggregates, PersonAggregates, PostAggregates, SiteAggregates},
+  newtypes::PostPollOptionId,
   source::{
     comment::Comment,
     comment_report::CommentReport,
     community::Community,
+    community_post_tag::CommunityPostTag,
     custom_emoji::CustomEmoji,
     custom_emoji_keyword::CustomEmojiKeyword,
     local_site::LocalSite,
@@ -11,16 +13,19 @@ use lemmy_db_schema::{
     local_user::LocalUser,
     person::Person,
     post::Post,
+    post_poll::{PostPoll, PostPollOption},
     post_report::PostReport,
     private_message::PrivateMessage,
     private_message_report::PrivateMessageReport,
     registration_application::RegistrationApplication,
+    report_note::ReportNote,
     site::Site,
   },
   SubscribedType,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use std::collections::HashMap;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
@@ -40,6 +45,7 @@ pub struct CommentReportView {
   pub creator_banned_from_community: bool,
   pub my_vote: Option<i16>,
   pub resolver: Option<Person>,
+  pub assignee: Option<Person>,
 }
 
 #[skip_serializing_none]
@@ -58,6 +64,11 @@ pub struct CommentView {
   pub saved: bool,
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
+  /// The comment content rendered as html, if it was requested with `render_html`.
+  pub content_html: Option<String>,
+  /// Proxied links for the remote images in the markdown of this comment, by their original link. Only
+  /// present if the image proxy is turned on.
+  pub image_proxy_links: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -85,6 +96,7 @@ pub struct PostReportView {
   pub my_vote: Option<i16>,
   pub counts: PostAggregates,
   pub resolver: Option<Person>,
+  pub assignee: Option<Person>,
 }
 
 #[skip_serializing_none]
@@ -104,6 +116,13 @@ pub struct PostView {
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
   pub unread_comments: i64,
+  /// The tags of the post, sorted by name.
+  pub tags: Vec<CommunityPostTag>,
+  /// The post body rendered as html, if it was requested with `render_html`.
+  pub content_html: Option<String>,
+  /// Proxied links for the remote images in the markdown of this post, by their original link. Only
+  /// present if the image proxy is turned on.
+  pub image_proxy_links: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -127,6 +146,7 @@ pub struct PrivateMessageReportView {
   pub private_message_creator: Person,
   pub creator: Person,
   pub resolver: Option<Person>,
+  pub assignee: Option<Person>,
 }
 
 #[skip_serializing_none]
@@ -141,6 +161,15 @@ pub struct RegistrationApplicationView {
   pub admin: Option<Person>,
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A moderator note on a report.
+pub struct ReportNoteView {
+  pub report_note: ReportNote,
+  pub creator: Person,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -160,3 +189,13 @@ pub struct CustomEmojiView {
   pub custom_emoji: CustomEmoji,
   pub keywords: Vec<CustomEmojiKeyword>,
 }
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A poll view, including the options the current user voted for.
+pub struct PostPollView {
+  pub poll: PostPoll,
+  pub options: Vec<PostPollOption>,
+  pub my_votes: Vec<PostPollOptionId>,
+}
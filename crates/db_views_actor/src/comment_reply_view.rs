@@ -6,6 +6,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -123,10 +124,10 @@ impl CommentReplyView {
       )
       .select((
         comment_reply::all_columns,
-        comment::all_columns,
+        Comment::as_select(),
         person::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
         person_alias_1.fields(person::all_columns),
         comment_aggregates::all_columns,
         community_person_ban::all_columns.nullable(),
@@ -239,10 +240,10 @@ impl<'a> CommentReplyQuery<'a> {
       )
       .select((
         comment_reply::all_columns,
-        comment::all_columns,
+        Comment::as_select(),
         person::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
         person_alias_1.fields(person::all_columns),
         comment_aggregates::all_columns,
         community_person_ban::all_columns.nullable(),
@@ -266,7 +267,9 @@ impl<'a> CommentReplyQuery<'a> {
     };
 
     query = match self.sort.unwrap_or(CommentSortType::New) {
-      CommentSortType::Hot => query.then_order_by(comment_aggregates::hot_rank.desc()),
+      CommentSortType::Hot | CommentSortType::Relevance => {
+        query.then_order_by(comment_aggregates::hot_rank.desc())
+      }
       CommentSortType::New => query.then_order_by(comment_reply::published.desc()),
       CommentSortType::Old => query.then_order_by(comment_reply::published.asc()),
       CommentSortType::Top => query.order_by(comment_aggregates::score.desc()),
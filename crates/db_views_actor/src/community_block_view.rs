@@ -1,5 +1,5 @@
 use crate::structs::CommunityBlockView;
-use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel::{result::Error, ExpressionMethods, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
@@ -17,7 +17,7 @@ impl CommunityBlockView {
     let res = community_block::table
       .inner_join(person::table)
       .inner_join(community::table)
-      .select((person::all_columns, community::all_columns))
+      .select((person::all_columns, Community::as_select()))
       .filter(community_block::person_id.eq(person_id))
       .filter(community::deleted.eq(false))
       .filter(community::removed.eq(false))
@@ -5,14 +5,15 @@ use diesel::{
   sql_function,
   ExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::{CommunityId, DbUrl, PersonId},
-  schema::{community, community_follower, person},
+  schema::{community, community_follower, community_moderator, person},
   source::{community::Community, person::Person},
   traits::JoinView,
-  utils::{get_conn, DbPool},
+  utils::{get_conn, limit_and_offset, DbPool},
 };
 
 type CommunityFollowerViewTuple = (Community, Person);
@@ -27,6 +28,7 @@ impl CommunityFollowerView {
     let conn = &mut get_conn(pool).await?;
     let res = community_follower::table
       .filter(community_follower::community_id.eq(community_id))
+      .filter(community_follower::pending.eq(false))
       .filter(not(person::local))
       .inner_join(person::table)
       .select(coalesce(person::shared_inbox_url, person::inbox_url))
@@ -55,7 +57,7 @@ impl CommunityFollowerView {
     let res = community_follower::table
       .inner_join(community::table)
       .inner_join(person::table)
-      .select((community::all_columns, person::all_columns))
+      .select((Community::as_select(), person::all_columns))
       .filter(community_follower::person_id.eq(person_id))
       .filter(community::deleted.eq(false))
       .filter(community::removed.eq(false))
@@ -65,6 +67,47 @@ impl CommunityFollowerView {
 
     Ok(res.into_iter().map(Self::from_tuple).collect())
   }
+
+  /// Lists the follows of local communities which are waiting for approval. Admins see those of
+  /// all communities, others only of the communities they moderate.
+  pub async fn list_pending(
+    pool: &DbPool,
+    person_id: PersonId,
+    is_admin: bool,
+    community_id: Option<CommunityId>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = community_follower::table
+      .inner_join(community::table)
+      .inner_join(person::table)
+      .select((Community::as_select(), person::all_columns))
+      .filter(community_follower::pending.eq(true))
+      .filter(community::local.eq(true))
+      .into_boxed();
+
+    if !is_admin {
+      let moderated_communities = community_moderator::table
+        .filter(community_moderator::person_id.eq(person_id))
+        .select(community_moderator::community_id);
+      query = query.filter(community::id.eq_any(moderated_communities));
+    }
+
+    if let Some(community_id) = community_id {
+      query = query.filter(community::id.eq(community_id));
+    }
+
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let res = query
+      .order_by(community_follower::published.asc())
+      .limit(limit)
+      .offset(offset)
+      .load::<CommunityFollowerViewTuple>(conn)
+      .await?;
+
+    Ok(res.into_iter().map(Self::from_tuple).collect())
+  }
 }
 
 impl JoinView for CommunityFollowerView {
@@ -1,5 +1,5 @@
 use crate::structs::CommunityModeratorView;
-use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel::{result::Error, ExpressionMethods, QueryDsl, SelectableHelper};
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::{CommunityId, PersonId},
@@ -17,7 +17,7 @@ impl CommunityModeratorView {
     let res = community_moderator::table
       .inner_join(community::table)
       .inner_join(person::table)
-      .select((community::all_columns, person::all_columns))
+      .select((Community::as_select(), person::all_columns))
       .filter(community_moderator::community_id.eq(community_id))
       .load::<CommunityModeratorViewTuple>(conn)
       .await?;
@@ -30,7 +30,7 @@ impl CommunityModeratorView {
     let res = community_moderator::table
       .inner_join(community::table)
       .inner_join(person::table)
-      .select((community::all_columns, person::all_columns))
+      .select((Community::as_select(), person::all_columns))
       .filter(community_moderator::person_id.eq(person_id))
       .filter(community::deleted.eq(false))
       .filter(community::removed.eq(false))
@@ -47,7 +47,7 @@ impl CommunityModeratorView {
     let res = community_moderator::table
       .inner_join(community::table)
       .inner_join(person::table)
-      .select((community::all_columns, person::all_columns))
+      .select((Community::as_select(), person::all_columns))
       // A hacky workaround instead of group_bys
       // https://stackoverflow.com/questions/24042359/how-to-join-only-one-row-in-joined-table-with-postgres
       .distinct_on(community_moderator::community_id)
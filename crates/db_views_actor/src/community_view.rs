@@ -7,8 +7,10 @@ use diesel::{
   NullableExpressionMethods,
   PgTextExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
+use diesel_full_text_search::{ts_rank_cd, TsVectorExtensions};
 use lemmy_db_schema::{
   aggregates::structs::CommunityAggregates,
   newtypes::{CommunityId, PersonId},
@@ -19,7 +21,7 @@ use lemmy_db_schema::{
     local_user::LocalUser,
   },
   traits::JoinView,
-  utils::{fuzzy_search, get_conn, limit_and_offset, DbPool},
+  utils::{functions::lemmy_search_query, fuzzy_search, get_conn, limit_and_offset, DbPool},
   ListingType,
   SortType,
 };
@@ -61,7 +63,7 @@ impl CommunityView {
         ),
       )
       .select((
-        community::all_columns,
+        Community::as_select(),
         community_aggregates::all_columns,
         community_follower::all_columns.nullable(),
         community_block::all_columns.nullable(),
@@ -82,6 +84,7 @@ impl CommunityView {
       subscribed: CommunityFollower::to_subscribed_type(&follower),
       blocked: blocked.is_some(),
       counts,
+      image_proxy_links: None,
     })
   }
 
@@ -158,18 +161,19 @@ impl<'a> CommunityQuery<'a> {
         ),
       )
       .select((
-        community::all_columns,
+        Community::as_select(),
         community_aggregates::all_columns,
         community_follower::all_columns.nullable(),
         community_block::all_columns.nullable(),
       ))
       .into_boxed();
 
-    if let Some(search_term) = self.search_term {
-      let searcher = fuzzy_search(&search_term);
-      query = query
-        .filter(community::name.ilike(searcher.clone()))
-        .or_filter(community::title.ilike(searcher));
+    if let Some(search_term) = &self.search_term {
+      query = query.filter(
+        community::name
+          .ilike(fuzzy_search(search_term))
+          .or(community::search_vector.matches(lemmy_search_query(search_term))),
+      );
     };
 
     // Hide deleted and removed for non-admins or mods
@@ -185,6 +189,14 @@ impl<'a> CommunityQuery<'a> {
     }
     match self.sort.unwrap_or(Hot) {
       Hot | Active => query = query.order_by(community_aggregates::hot_rank.desc()),
+      Relevance => match &self.search_term {
+        Some(search_term) => {
+          query = query
+            .order_by(ts_rank_cd(community::search_vector, lemmy_search_query(search_term)).desc())
+            .then_order_by(community_aggregates::subscribers.desc())
+        }
+        None => query = query.order_by(community_aggregates::hot_rank.desc()),
+      },
       NewComments | TopDay | TopTwelveHour | TopSixHour | TopHour => {
         query = query.order_by(community_aggregates::users_active_day.desc())
       }
@@ -239,6 +251,7 @@ impl JoinView for CommunityView {
       counts: a.1,
       subscribed: CommunityFollower::to_subscribed_type(&a.2),
       blocked: a.3.is_some(),
+      image_proxy_links: None,
     }
   }
 }
@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -124,10 +125,10 @@ impl PersonMentionView {
       )
       .select((
         person_mention::all_columns,
-        comment::all_columns,
+        Comment::as_select(),
         person::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
         person_alias_1.fields(person::all_columns),
         comment_aggregates::all_columns,
         community_person_ban::all_columns.nullable(),
@@ -244,10 +245,10 @@ impl<'a> PersonMentionQuery<'a> {
       )
       .select((
         person_mention::all_columns,
-        comment::all_columns,
+        Comment::as_select(),
         person::all_columns,
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
         person_alias_1.fields(person::all_columns),
         comment_aggregates::all_columns,
         community_person_ban::all_columns.nullable(),
@@ -271,7 +272,9 @@ impl<'a> PersonMentionQuery<'a> {
     };
 
     query = match self.sort.unwrap_or(CommentSortType::Hot) {
-      CommentSortType::Hot => query.then_order_by(comment_aggregates::hot_rank.desc()),
+      CommentSortType::Hot | CommentSortType::Relevance => {
+        query.then_order_by(comment_aggregates::hot_rank.desc())
+      }
       CommentSortType::New => query.then_order_by(comment::published.desc()),
       CommentSortType::Old => query.then_order_by(comment::published.asc()),
       CommentSortType::Top => query.order_by(comment_aggregates::score.desc()),
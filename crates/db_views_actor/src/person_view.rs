@@ -97,7 +97,7 @@ impl<'a> PersonQuery<'a> {
     query = match self.sort.unwrap_or(SortType::Hot) {
       SortType::New | SortType::NewComments => query.order_by(person::published.desc()),
       SortType::Old => query.order_by(person::published.asc()),
-      SortType::Hot | SortType::Active | SortType::TopAll => {
+      SortType::Hot | SortType::Active | SortType::TopAll | SortType::Relevance => {
         query.order_by(person_aggregates::comment_score.desc())
       }
       SortType::MostComments => query.order_by(person_aggregates::comment_count.desc()),
@@ -148,6 +148,7 @@ impl JoinView for PersonView {
     Self {
       person: a.0,
       counts: a.1,
+      image_proxy_links: None,
     }
   }
 }
@@ -12,6 +12,7 @@ use lemmy_db_schema::{
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
+use std::collections::HashMap;
 #[cfg(feature = "full")]
 use ts_rs::TS;
 
@@ -49,6 +50,7 @@ pub struct CommunityPersonBanView {
   pub person: Person,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -58,6 +60,9 @@ pub struct CommunityView {
   pub subscribed: SubscribedType,
   pub blocked: bool,
   pub counts: CommunityAggregates,
+  /// Proxied links for the remote images in the markdown of this community, by their original link. Only
+  /// present if the image proxy is turned on.
+  pub image_proxy_links: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -109,6 +114,7 @@ pub struct CommentReplyView {
   pub my_vote: Option<i16>,                // Left join to CommentLike
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -116,4 +122,7 @@ pub struct CommentReplyView {
 pub struct PersonView {
   pub person: Person,
   pub counts: PersonAggregates,
+  /// Proxied links for the remote images in the markdown of this person, by their original link. Only
+  /// present if the image proxy is turned on.
+  pub image_proxy_links: Option<HashMap<String, String>>,
 }
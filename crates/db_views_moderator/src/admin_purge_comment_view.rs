@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -15,6 +16,7 @@ use lemmy_db_schema::{
   source::{moderator::AdminPurgeComment, person::Person, post::Post},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type AdminPurgeCommentViewTuple = (AdminPurgeComment, Option<Person>, Post);
@@ -36,7 +38,7 @@ impl AdminPurgeCommentView {
       .select((
         admin_purge_comment::all_columns,
         person::all_columns.nullable(),
-        post::all_columns,
+        Post::as_select(),
       ))
       .into_boxed();
 
@@ -44,12 +46,31 @@ impl AdminPurgeCommentView {
       query = query.filter(admin_purge_comment::admin_person_id.eq(admin_person_id));
     };
 
+    if let Some(since) = params.since {
+      query = query.filter(admin_purge_comment::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(admin_purge_comment::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::AdminPurgeComment) {
+      query = query.filter(
+        admin_purge_comment::when_.lt(when_).or(
+          admin_purge_comment::when_
+            .eq(when_)
+            .and(admin_purge_comment::id.lt(id)),
+        ),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(admin_purge_comment::when_.desc())
+      .then_order_by(admin_purge_comment::id.desc())
       .load::<AdminPurgeCommentViewTuple>(conn)
       .await?;
 
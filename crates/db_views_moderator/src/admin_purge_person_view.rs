@@ -15,6 +15,7 @@ use lemmy_db_schema::{
   source::{moderator::AdminPurgePerson, person::Person},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type AdminPurgePersonViewTuple = (AdminPurgePerson, Option<Person>);
@@ -42,12 +43,31 @@ impl AdminPurgePersonView {
       query = query.filter(admin_purge_person::admin_person_id.eq(admin_person_id));
     };
 
+    if let Some(since) = params.since {
+      query = query.filter(admin_purge_person::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(admin_purge_person::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::AdminPurgePerson) {
+      query = query.filter(
+        admin_purge_person::when_.lt(when_).or(
+          admin_purge_person::when_
+            .eq(when_)
+            .and(admin_purge_person::id.lt(id)),
+        ),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(admin_purge_person::when_.desc())
+      .then_order_by(admin_purge_person::id.desc())
       .load::<AdminPurgePersonViewTuple>(conn)
       .await?;
 
@@ -27,5 +27,9 @@ pub mod mod_remove_community_view;
 #[cfg(feature = "full")]
 pub mod mod_remove_post_view;
 #[cfg(feature = "full")]
+pub mod mod_resolve_report_view;
+#[cfg(feature = "full")]
 pub mod mod_transfer_community_view;
+#[cfg(feature = "full")]
+pub mod modlog_entry;
 pub mod structs;
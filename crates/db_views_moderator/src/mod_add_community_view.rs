@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -15,6 +16,7 @@ use lemmy_db_schema::{
   source::{community::Community, moderator::ModAddCommunity, person::Person},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type ModAddCommunityViewTuple = (ModAddCommunity, Option<Person>, Community, Person);
@@ -39,7 +41,7 @@ impl ModAddCommunityView {
       .select((
         mod_add_community::all_columns,
         person::all_columns.nullable(),
-        community::all_columns,
+        Community::as_select(),
         person_alias_1.fields(person::all_columns),
       ))
       .into_boxed();
@@ -56,12 +58,31 @@ impl ModAddCommunityView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
+    if let Some(since) = params.since {
+      query = query.filter(mod_add_community::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_add_community::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModAddCommunity) {
+      query = query.filter(
+        mod_add_community::when_.lt(when_).or(
+          mod_add_community::when_
+            .eq(when_)
+            .and(mod_add_community::id.lt(id)),
+        ),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(mod_add_community::when_.desc())
+      .then_order_by(mod_add_community::id.desc())
       .load::<ModAddCommunityViewTuple>(conn)
       .await?;
 
@@ -15,6 +15,7 @@ use lemmy_db_schema::{
   source::{moderator::ModAdd, person::Person},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type ModAddViewTuple = (ModAdd, Option<Person>, Person);
@@ -48,12 +49,29 @@ impl ModAddView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
+    if let Some(since) = params.since {
+      query = query.filter(mod_add::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_add::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModAdd) {
+      query = query.filter(
+        mod_add::when_
+          .lt(when_)
+          .or(mod_add::when_.eq(when_).and(mod_add::id.lt(id))),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(mod_add::when_.desc())
+      .then_order_by(mod_add::id.desc())
       .load::<ModAddViewTuple>(conn)
       .await?;
 
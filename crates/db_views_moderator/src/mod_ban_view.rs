@@ -15,6 +15,7 @@ use lemmy_db_schema::{
   source::{moderator::ModBan, person::Person},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type ModBanViewTuple = (ModBan, Option<Person>, Person);
@@ -48,12 +49,29 @@ impl ModBanView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
+    if let Some(since) = params.since {
+      query = query.filter(mod_ban::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_ban::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModBan) {
+      query = query.filter(
+        mod_ban::when_
+          .lt(when_)
+          .or(mod_ban::when_.eq(when_).and(mod_ban::id.lt(id))),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(mod_ban::when_.desc())
+      .then_order_by(mod_ban::id.desc())
       .load::<ModBanViewTuple>(conn)
       .await?;
 
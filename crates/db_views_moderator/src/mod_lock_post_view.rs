@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -15,6 +16,7 @@ use lemmy_db_schema::{
   source::{community::Community, moderator::ModLockPost, person::Person, post::Post},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type ModLockPostViewTuple = (ModLockPost, Option<Person>, Post, Community);
@@ -39,8 +41,8 @@ impl ModLockPostView {
       .select((
         mod_lock_post::all_columns,
         person::all_columns.nullable(),
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
       ))
       .into_boxed();
 
@@ -56,12 +58,33 @@ impl ModLockPostView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
+    if let Some(post_id) = params.post_id {
+      query = query.filter(mod_lock_post::post_id.eq(post_id));
+    };
+
+    if let Some(since) = params.since {
+      query = query.filter(mod_lock_post::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_lock_post::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModLockPost) {
+      query = query.filter(
+        mod_lock_post::when_
+          .lt(when_)
+          .or(mod_lock_post::when_.eq(when_).and(mod_lock_post::id.lt(id))),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(mod_lock_post::when_.desc())
+      .then_order_by(mod_lock_post::id.desc())
       .load::<ModLockPostViewTuple>(conn)
       .await?;
 
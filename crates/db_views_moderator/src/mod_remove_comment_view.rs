@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -21,6 +22,7 @@ use lemmy_db_schema::{
   },
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type ModRemoveCommentViewTuple = (
@@ -40,8 +42,10 @@ impl ModRemoveCommentView {
     let show_mod_names = !params.hide_modlog_names;
     let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
 
+    // Removals by automod are only made in the name of a moderator, so no moderator is shown
     let admin_names_join = mod_remove_comment::mod_person_id
       .eq(person::id)
+      .and(mod_remove_comment::automod.eq(false))
       .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
     let mut query = mod_remove_comment::table
       .left_join(person::table.on(admin_names_join))
@@ -52,10 +56,10 @@ impl ModRemoveCommentView {
       .select((
         mod_remove_comment::all_columns,
         person::all_columns.nullable(),
-        comment::all_columns,
+        Comment::as_select(),
         person_alias_1.fields(person::all_columns),
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
       ))
       .into_boxed();
 
@@ -64,19 +68,50 @@ impl ModRemoveCommentView {
     };
 
     if let Some(mod_person_id) = params.mod_person_id {
-      query = query.filter(mod_remove_comment::mod_person_id.eq(mod_person_id));
+      query = query.filter(
+        mod_remove_comment::mod_person_id
+          .eq(mod_person_id)
+          .and(mod_remove_comment::automod.eq(false)),
+      );
     };
 
     if let Some(other_person_id) = params.other_person_id {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
+    if let Some(post_id) = params.post_id {
+      query = query.filter(comment::post_id.eq(post_id));
+    };
+
+    if let Some(comment_id) = params.comment_id {
+      query = query.filter(mod_remove_comment::comment_id.eq(comment_id));
+    };
+
+    if let Some(since) = params.since {
+      query = query.filter(mod_remove_comment::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_remove_comment::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModRemoveComment) {
+      query = query.filter(
+        mod_remove_comment::when_.lt(when_).or(
+          mod_remove_comment::when_
+            .eq(when_)
+            .and(mod_remove_comment::id.lt(id)),
+        ),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(mod_remove_comment::when_.desc())
+      .then_order_by(mod_remove_comment::id.desc())
       .load::<ModRemoveCommentViewTuple>(conn)
       .await?;
 
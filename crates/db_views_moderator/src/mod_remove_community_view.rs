@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -15,6 +16,7 @@ use lemmy_db_schema::{
   source::{community::Community, moderator::ModRemoveCommunity, person::Person},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type ModRemoveCommunityTuple = (ModRemoveCommunity, Option<Person>, Community);
@@ -35,7 +37,7 @@ impl ModRemoveCommunityView {
       .select((
         mod_remove_community::all_columns,
         person::all_columns.nullable(),
-        community::all_columns,
+        Community::as_select(),
       ))
       .into_boxed();
 
@@ -43,12 +45,31 @@ impl ModRemoveCommunityView {
       query = query.filter(mod_remove_community::mod_person_id.eq(mod_person_id));
     };
 
+    if let Some(since) = params.since {
+      query = query.filter(mod_remove_community::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_remove_community::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModRemoveCommunity) {
+      query = query.filter(
+        mod_remove_community::when_.lt(when_).or(
+          mod_remove_community::when_
+            .eq(when_)
+            .and(mod_remove_community::id.lt(id)),
+        ),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(mod_remove_community::when_.desc())
+      .then_order_by(mod_remove_community::id.desc())
       .load::<ModRemoveCommunityTuple>(conn)
       .await?;
 
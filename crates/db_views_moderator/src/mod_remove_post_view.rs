@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -15,6 +16,7 @@ use lemmy_db_schema::{
   source::{community::Community, moderator::ModRemovePost, person::Person, post::Post},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type ModRemovePostViewTuple = (ModRemovePost, Option<Person>, Post, Community);
@@ -28,8 +30,10 @@ impl ModRemovePostView {
     let show_mod_names = !params.hide_modlog_names;
     let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
 
+    // Removals by automod are only made in the name of a moderator, so no moderator is shown
     let admin_names_join = mod_remove_post::mod_person_id
       .eq(person::id)
+      .and(mod_remove_post::automod.eq(false))
       .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
     let mut query = mod_remove_post::table
       .left_join(person::table.on(admin_names_join))
@@ -39,8 +43,8 @@ impl ModRemovePostView {
       .select((
         mod_remove_post::all_columns,
         person::all_columns.nullable(),
-        post::all_columns,
-        community::all_columns,
+        Post::as_select(),
+        Community::as_select(),
       ))
       .into_boxed();
 
@@ -49,19 +53,46 @@ impl ModRemovePostView {
     };
 
     if let Some(mod_person_id) = params.mod_person_id {
-      query = query.filter(mod_remove_post::mod_person_id.eq(mod_person_id));
+      query = query.filter(
+        mod_remove_post::mod_person_id
+          .eq(mod_person_id)
+          .and(mod_remove_post::automod.eq(false)),
+      );
     };
 
     if let Some(other_person_id) = params.other_person_id {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
+    if let Some(post_id) = params.post_id {
+      query = query.filter(mod_remove_post::post_id.eq(post_id));
+    };
+
+    if let Some(since) = params.since {
+      query = query.filter(mod_remove_post::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_remove_post::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModRemovePost) {
+      query = query.filter(
+        mod_remove_post::when_.lt(when_).or(
+          mod_remove_post::when_
+            .eq(when_)
+            .and(mod_remove_post::id.lt(id)),
+        ),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(mod_remove_post::when_.desc())
+      .then_order_by(mod_remove_post::id.desc())
       .load::<ModRemovePostViewTuple>(conn)
       .await?;
 
@@ -0,0 +1,121 @@
+use crate::structs::{ModResolveReportView, ModlogListParams};
+use diesel::{
+  pg::Pg,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  IntoSql,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+  Selectable,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  schema::{comment, community, mod_resolve_report, person, post},
+  source::{
+    comment::Comment,
+    community::Community,
+    moderator::ModResolveReport,
+    person::Person,
+    post::Post,
+  },
+  traits::JoinView,
+  utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
+};
+
+type ModResolveReportViewTuple = (
+  ModResolveReport,
+  Option<Person>,
+  Option<Post>,
+  Option<Comment>,
+  Option<Community>,
+);
+
+impl ModResolveReportView {
+  pub async fn list(pool: &DbPool, params: ModlogListParams) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let admin_person_id_join = params.mod_person_id.unwrap_or(PersonId(-1));
+    let show_mod_names = !params.hide_modlog_names;
+    let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
+
+    let admin_names_join = mod_resolve_report::mod_person_id
+      .eq(person::id)
+      .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
+    let mut query = mod_resolve_report::table
+      .left_join(person::table.on(admin_names_join))
+      .left_join(post::table)
+      .left_join(comment::table.on(mod_resolve_report::comment_id.eq(comment::id.nullable())))
+      .left_join(community::table.on(post::community_id.eq(community::id)))
+      .select((
+        mod_resolve_report::all_columns,
+        person::all_columns.nullable(),
+        <Post as Selectable<Pg>>::construct_selection().nullable(),
+        <Comment as Selectable<Pg>>::construct_selection().nullable(),
+        <Community as Selectable<Pg>>::construct_selection().nullable(),
+      ))
+      .into_boxed();
+
+    if let Some(community_id) = params.community_id {
+      query = query.filter(post::community_id.eq(community_id));
+    };
+
+    if let Some(mod_person_id) = params.mod_person_id {
+      query = query.filter(mod_resolve_report::mod_person_id.eq(mod_person_id));
+    };
+
+    if let Some(post_id) = params.post_id {
+      query = query.filter(mod_resolve_report::post_id.eq(post_id));
+    };
+
+    if let Some(comment_id) = params.comment_id {
+      query = query.filter(mod_resolve_report::comment_id.eq(comment_id));
+    };
+
+    if let Some(since) = params.since {
+      query = query.filter(mod_resolve_report::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_resolve_report::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModResolveReport) {
+      query = query.filter(
+        mod_resolve_report::when_.lt(when_).or(
+          mod_resolve_report::when_
+            .eq(when_)
+            .and(mod_resolve_report::id.lt(id)),
+        ),
+      );
+    };
+
+    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+
+    let res = query
+      .limit(limit)
+      .offset(offset)
+      .order_by(mod_resolve_report::when_.desc())
+      .then_order_by(mod_resolve_report::id.desc())
+      .load::<ModResolveReportViewTuple>(conn)
+      .await?;
+
+    let results = res.into_iter().map(Self::from_tuple).collect();
+    Ok(results)
+  }
+}
+
+impl JoinView for ModResolveReportView {
+  type JoinTuple = ModResolveReportViewTuple;
+  fn from_tuple(a: Self::JoinTuple) -> Self {
+    Self {
+      mod_resolve_report: a.0,
+      moderator: a.1,
+      post: a.2,
+      comment: a.3,
+      community: a.4,
+    }
+  }
+}
@@ -7,6 +7,7 @@ use diesel::{
   JoinOnDsl,
   NullableExpressionMethods,
   QueryDsl,
+  SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
@@ -15,6 +16,7 @@ use lemmy_db_schema::{
   source::{community::Community, moderator::ModTransferCommunity, person::Person},
   traits::JoinView,
   utils::{get_conn, limit_and_offset, DbPool},
+  ModlogActionType,
 };
 
 type ModTransferCommunityViewTuple = (ModTransferCommunity, Option<Person>, Community, Person);
@@ -41,7 +43,7 @@ impl ModTransferCommunityView {
       .select((
         mod_transfer_community::all_columns,
         person::all_columns.nullable(),
-        community::all_columns,
+        Community::as_select(),
         person_alias_1.fields(person::all_columns),
       ))
       .into_boxed();
@@ -58,12 +60,31 @@ impl ModTransferCommunityView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
+    if let Some(since) = params.since {
+      query = query.filter(mod_transfer_community::when_.ge(since));
+    };
+
+    if let Some(until) = params.until {
+      query = query.filter(mod_transfer_community::when_.lt(until));
+    };
+
+    if let Some((when_, id)) = params.cursor_before(ModlogActionType::ModTransferCommunity) {
+      query = query.filter(
+        mod_transfer_community::when_.lt(when_).or(
+          mod_transfer_community::when_
+            .eq(when_)
+            .and(mod_transfer_community::id.lt(id)),
+        ),
+      );
+    };
+
     let (limit, offset) = limit_and_offset(params.page, params.limit)?;
 
     let res = query
       .limit(limit)
       .offset(offset)
       .order_by(mod_transfer_community::when_.desc())
+      .then_order_by(mod_transfer_community::id.desc())
       .load::<ModTransferCommunityViewTuple>(conn)
       .await?;
 
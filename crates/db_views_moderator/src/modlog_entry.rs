@@ -0,0 +1,312 @@
+use crate::structs::{
+  AdminPurgeCommentView,
+  AdminPurgeCommunityView,
+  AdminPurgePersonView,
+  AdminPurgePostView,
+  ModAddCommunityView,
+  ModAddView,
+  ModBanFromCommunityView,
+  ModBanView,
+  ModFeaturePostView,
+  ModHideCommunityView,
+  ModLockPostView,
+  ModRemoveCommentView,
+  ModRemoveCommunityView,
+  ModRemovePostView,
+  ModResolveReportView,
+  ModTransferCommunityView,
+  ModlogCursor,
+  ModlogEntry,
+  ModlogListParams,
+};
+use chrono::{DateTime, Utc};
+use diesel::result::Error;
+use lemmy_db_schema::{
+  utils::{datetime_from_micros, limit_and_offset, DbPool},
+  ModlogActionType::{self, *},
+};
+use std::{
+  cmp::{Ordering, Reverse},
+  str::FromStr,
+};
+
+impl ModlogListParams {
+  /// Returns the time and id before which entries of the given type come after the cursor. For
+  /// entries of the same time, the position of their type in [ModlogActionType] decides the order.
+  pub(crate) fn cursor_before(&self, type_: ModlogActionType) -> Option<(DateTime<Utc>, i32)> {
+    let cursor = self.cursor?;
+    let id = match (type_ as i32).cmp(&(cursor.type_ as i32)) {
+      Ordering::Less => i32::MAX,
+      Ordering::Equal => cursor.id,
+      Ordering::Greater => i32::MIN,
+    };
+    Some((cursor.when_, id))
+  }
+
+  fn includes(&self, type_filter: ModlogActionType, type_: ModlogActionType) -> bool {
+    // These are only shown in the full modlog, when a community isn't given
+    let site_wide = matches!(
+      type_,
+      ModBan
+        | ModAdd
+        | ModRemoveCommunity
+        | AdminPurgePerson
+        | AdminPurgeCommunity
+        | AdminPurgePost
+        | AdminPurgeComment
+    );
+    let has_other_person = !matches!(
+      type_,
+      ModHideCommunity
+        | ModRemoveCommunity
+        | AdminPurgePerson
+        | AdminPurgeCommunity
+        | AdminPurgePost
+        | AdminPurgeComment
+        | ModResolveReport
+    );
+    let has_post = matches!(
+      type_,
+      ModRemovePost | ModLockPost | ModFeaturePost | ModRemoveComment | ModResolveReport
+    );
+    let has_comment = matches!(type_, ModRemoveComment | ModResolveReport);
+    (type_filter == All || type_filter == type_)
+      && !(site_wide && self.community_id.is_some())
+      && (has_other_person || self.other_person_id.is_none())
+      && (has_post || self.post_id.is_none())
+      && (has_comment || self.comment_id.is_none())
+  }
+}
+
+impl ModlogCursor {
+  /// Encodes the cursor as an opaque string which clients pass back to get the next page.
+  pub fn encode(&self) -> String {
+    format!(
+      "{}_{}_{}",
+      self.when_.timestamp_micros(),
+      self.type_,
+      self.id
+    )
+  }
+
+  pub fn decode(cursor: &str) -> Option<Self> {
+    let mut parts = cursor.splitn(3, '_');
+    let micros: i64 = parts.next()?.parse().ok()?;
+    let type_ = ModlogActionType::from_str(parts.next()?).ok()?;
+    let id = parts.next()?.parse().ok()?;
+    let when_ = datetime_from_micros(micros)?;
+    Some(ModlogCursor { when_, type_, id })
+  }
+
+  fn sort_key(&self) -> (DateTime<Utc>, i32, i32) {
+    (self.when_, self.type_ as i32, self.id)
+  }
+}
+
+impl ModlogEntry {
+  /// The position of this entry in the merged modlog.
+  pub fn cursor(&self) -> ModlogCursor {
+    let (when_, type_, id) = match self {
+      ModlogEntry::ModRemovePost(v) => {
+        (v.mod_remove_post.when_, ModRemovePost, v.mod_remove_post.id)
+      }
+      ModlogEntry::ModLockPost(v) => (v.mod_lock_post.when_, ModLockPost, v.mod_lock_post.id),
+      ModlogEntry::ModFeaturePost(v) => (
+        v.mod_feature_post.when_,
+        ModFeaturePost,
+        v.mod_feature_post.id,
+      ),
+      ModlogEntry::ModRemoveComment(v) => (
+        v.mod_remove_comment.when_,
+        ModRemoveComment,
+        v.mod_remove_comment.id,
+      ),
+      ModlogEntry::ModRemoveCommunity(v) => (
+        v.mod_remove_community.when_,
+        ModRemoveCommunity,
+        v.mod_remove_community.id,
+      ),
+      ModlogEntry::ModBanFromCommunity(v) => (
+        v.mod_ban_from_community.when_,
+        ModBanFromCommunity,
+        v.mod_ban_from_community.id,
+      ),
+      ModlogEntry::ModAddCommunity(v) => (
+        v.mod_add_community.when_,
+        ModAddCommunity,
+        v.mod_add_community.id,
+      ),
+      ModlogEntry::ModTransferCommunity(v) => (
+        v.mod_transfer_community.when_,
+        ModTransferCommunity,
+        v.mod_transfer_community.id,
+      ),
+      ModlogEntry::ModAdd(v) => (v.mod_add.when_, ModAdd, v.mod_add.id),
+      ModlogEntry::ModBan(v) => (v.mod_ban.when_, ModBan, v.mod_ban.id),
+      ModlogEntry::ModHideCommunity(v) => (
+        v.mod_hide_community.when_,
+        ModHideCommunity,
+        v.mod_hide_community.id,
+      ),
+      ModlogEntry::AdminPurgePerson(v) => (
+        v.admin_purge_person.when_,
+        AdminPurgePerson,
+        v.admin_purge_person.id,
+      ),
+      ModlogEntry::AdminPurgeCommunity(v) => (
+        v.admin_purge_community.when_,
+        AdminPurgeCommunity,
+        v.admin_purge_community.id,
+      ),
+      ModlogEntry::AdminPurgePost(v) => (
+        v.admin_purge_post.when_,
+        AdminPurgePost,
+        v.admin_purge_post.id,
+      ),
+      ModlogEntry::AdminPurgeComment(v) => (
+        v.admin_purge_comment.when_,
+        AdminPurgeComment,
+        v.admin_purge_comment.id,
+      ),
+      ModlogEntry::ModResolveReport(v) => (
+        v.mod_resolve_report.when_,
+        ModResolveReport,
+        v.mod_resolve_report.id,
+      ),
+    };
+    ModlogCursor { when_, type_, id }
+  }
+
+  /// Lists the entries of all modlog tables, merged and ordered from newest to oldest.
+  ///
+  /// Each table is queried for a full page after the cursor, so that the merged page is complete.
+  pub async fn list(
+    pool: &DbPool,
+    type_filter: ModlogActionType,
+    params: ModlogListParams,
+  ) -> Result<Vec<Self>, Error> {
+    let (limit, _) = limit_and_offset(None, params.limit)?;
+    let params = ModlogListParams {
+      page: None,
+      limit: Some(limit),
+      ..params
+    };
+    let includes = |type_| params.includes(type_filter, type_);
+    let mut entries = vec![];
+
+    if includes(ModRemovePost) {
+      let views = ModRemovePostView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModRemovePost));
+    }
+    if includes(ModLockPost) {
+      let views = ModLockPostView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModLockPost));
+    }
+    if includes(ModFeaturePost) {
+      let views = ModFeaturePostView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModFeaturePost));
+    }
+    if includes(ModRemoveComment) {
+      let views = ModRemoveCommentView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModRemoveComment));
+    }
+    if includes(ModRemoveCommunity) {
+      let views = ModRemoveCommunityView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModRemoveCommunity));
+    }
+    if includes(ModBanFromCommunity) {
+      let views = ModBanFromCommunityView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModBanFromCommunity));
+    }
+    if includes(ModAddCommunity) {
+      let views = ModAddCommunityView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModAddCommunity));
+    }
+    if includes(ModTransferCommunity) {
+      let views = ModTransferCommunityView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModTransferCommunity));
+    }
+    if includes(ModAdd) {
+      let views = ModAddView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModAdd));
+    }
+    if includes(ModBan) {
+      let views = ModBanView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModBan));
+    }
+    if includes(ModHideCommunity) {
+      let views = ModHideCommunityView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModHideCommunity));
+    }
+    if includes(AdminPurgePerson) {
+      let views = AdminPurgePersonView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::AdminPurgePerson));
+    }
+    if includes(AdminPurgeCommunity) {
+      let views = AdminPurgeCommunityView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::AdminPurgeCommunity));
+    }
+    if includes(AdminPurgePost) {
+      let views = AdminPurgePostView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::AdminPurgePost));
+    }
+    if includes(AdminPurgeComment) {
+      let views = AdminPurgeCommentView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::AdminPurgeComment));
+    }
+    if includes(ModResolveReport) {
+      let views = ModResolveReportView::list(pool, params).await?;
+      entries.extend(views.into_iter().map(ModlogEntry::ModResolveReport));
+    }
+
+    entries.sort_by_key(|e| Reverse(e.cursor().sort_key()));
+    entries.truncate(usize::try_from(limit).unwrap_or_default());
+    Ok(entries)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+
+  use crate::structs::{ModlogCursor, ModlogListParams};
+  use chrono::{TimeZone, Utc};
+  use lemmy_db_schema::ModlogActionType;
+
+  #[test]
+  fn test_cursor() {
+    let cursor = ModlogCursor {
+      when_: Utc.timestamp_opt(1_689_000_000, 123_456_000).unwrap(),
+      type_: ModlogActionType::ModRemovePost,
+      id: 42,
+    };
+    let encoded = cursor.encode();
+    assert_eq!("1689000000123456_ModRemovePost_42", encoded);
+    assert_eq!(Some(cursor), ModlogCursor::decode(&encoded));
+    assert_eq!(None, ModlogCursor::decode("1689000000123456_NotAType_42"));
+    assert_eq!(None, ModlogCursor::decode("garbage"));
+
+    let params = ModlogListParams {
+      community_id: None,
+      mod_person_id: None,
+      other_person_id: None,
+      post_id: None,
+      comment_id: None,
+      since: None,
+      until: None,
+      cursor: Some(cursor),
+      page: None,
+      limit: None,
+      hide_modlog_names: false,
+    };
+    // Ties in time are ordered by type, so the cursor excludes all later types at that time
+    let (when_, id) = params
+      .cursor_before(ModlogActionType::ModRemovePost)
+      .unwrap();
+    assert_eq!((cursor.when_, 42), (when_, id));
+    let (_, id) = params.cursor_before(ModlogActionType::All).unwrap();
+    assert_eq!(i32::MAX, id);
+    let (_, id) = params.cursor_before(ModlogActionType::ModLockPost).unwrap();
+    assert_eq!(i32::MIN, id);
+  }
+}
@@ -1,5 +1,6 @@
+use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
-  newtypes::{CommunityId, PersonId},
+  newtypes::{CommentId, CommunityId, PersonId, PostId},
   source::{
     comment::Comment,
     community::Community,
@@ -18,11 +19,13 @@ use lemmy_db_schema::{
       ModRemoveComment,
       ModRemoveCommunity,
       ModRemovePost,
+      ModResolveReport,
       ModTransferCommunity,
     },
     person::Person,
     post::Post,
   },
+  ModlogActionType,
 };
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
@@ -98,6 +101,20 @@ pub struct ModLockPostView {
   pub community: Community,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When a moderator resolves a report of a post, comment or private message. For private messages
+/// only the id of the report is shown.
+pub struct ModResolveReportView {
+  pub mod_resolve_report: ModResolveReport,
+  pub moderator: Option<Person>,
+  pub post: Option<Post>,
+  pub comment: Option<Comment>,
+  pub community: Option<Community>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -210,7 +227,52 @@ pub struct ModlogListParams {
   pub community_id: Option<CommunityId>,
   pub mod_person_id: Option<PersonId>,
   pub other_person_id: Option<PersonId>,
+  /// Only actions on this post, or on its comments.
+  pub post_id: Option<PostId>,
+  /// Only actions on this comment.
+  pub comment_id: Option<CommentId>,
+  /// Only actions at or after this time.
+  pub since: Option<DateTime<Utc>>,
+  /// Only actions before this time.
+  pub until: Option<DateTime<Utc>>,
+  /// Only actions which come after this entry in the merged modlog.
+  pub cursor: Option<ModlogCursor>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
   pub hide_modlog_names: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The position of an entry in the merged modlog, which is ordered by time, type and id.
+pub struct ModlogCursor {
+  pub when_: DateTime<Utc>,
+  pub type_: ModlogActionType,
+  pub id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+#[serde(tag = "type_")]
+/// A modlog entry of any type.
+#[allow(clippy::large_enum_variant)]
+pub enum ModlogEntry {
+  ModRemovePost(ModRemovePostView),
+  ModLockPost(ModLockPostView),
+  ModFeaturePost(ModFeaturePostView),
+  ModRemoveComment(ModRemoveCommentView),
+  ModRemoveCommunity(ModRemoveCommunityView),
+  ModBanFromCommunity(ModBanFromCommunityView),
+  ModAddCommunity(ModAddCommunityView),
+  ModTransferCommunity(ModTransferCommunityView),
+  ModAdd(ModAddView),
+  ModBan(ModBanView),
+  ModHideCommunity(ModHideCommunityView),
+  AdminPurgePerson(AdminPurgePersonView),
+  AdminPurgeCommunity(AdminPurgeCommunityView),
+  AdminPurgePost(AdminPurgePostView),
+  AdminPurgeComment(AdminPurgeCommentView),
+  ModResolveReport(ModResolveReportView),
+}
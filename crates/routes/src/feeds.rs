@@ -1,10 +1,14 @@
 use actix_web::{error::ErrorBadRequest, web, Error, HttpRequest, HttpResponse, Result};
 use anyhow::anyhow;
 use chrono::{DateTime, DateTime<Utc>, Utc};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, utils::validate_login_token};
 use lemmy_db_schema::{
   newtypes::LocalUserId,
-  source::{community::Community, local_user::LocalUser, person::Person},
+  source::{
+    community::Community,
+    local_user::LocalUser,
+    person::Person,
+  },
   traits::{ApubActor, Crud},
   utils::DbPool,
   CommentSortType,
@@ -309,7 +313,9 @@ async fn get_feed_front(
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
   let site_view = SiteView::read_local(pool).await?;
-  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
+  let claims = Claims::decode(jwt, jwt_secret)?.claims;
+  let local_user_id = LocalUserId(claims.sub);
+  validate_login_token(pool, &claims, jwt).await?;
   let local_user = LocalUser::read(pool, local_user_id).await?;
 
   let posts = PostQuery::builder()
@@ -347,7 +353,9 @@ async fn get_feed_inbox(
   protocol_and_hostname: &str,
 ) -> Result<ChannelBuilder, LemmyError> {
   let site_view = SiteView::read_local(pool).await?;
-  let local_user_id = LocalUserId(Claims::decode(jwt, jwt_secret)?.claims.sub);
+  let claims = Claims::decode(jwt, jwt_secret)?.claims;
+  let local_user_id = LocalUserId(claims.sub);
+  validate_login_token(pool, &claims, jwt).await?;
   let local_user = LocalUser::read(pool, local_user_id).await?;
   let person_id = local_user.person_id;
   let show_bot_accounts = local_user.show_bot_accounts;
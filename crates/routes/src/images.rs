@@ -1,21 +1,22 @@
-use actix_web::{
-  body::BodyStream,
-  error,
-  http::{
-    header::{HeaderName, ACCEPT_ENCODING, HOST},
-    StatusCode,
-  },
-  web,
-  Error,
-  HttpRequest,
-  HttpResponse,
+use actix_multipart::Multipart;
+use actix_web::{error, http::header::AUTHORIZATION, web, Error, HttpRequest, HttpResponse};
+use futures::stream::StreamExt;
+use lemmy_api_common::{
+  context::LemmyContext,
+  image_proxy::{serve_proxied_image, ImageProxyParams},
+  image_storage::{image_storage, sniff_mime_type, ImageParams, StoredImage, UploadedFile},
+  utils::local_user_view_from_jwt,
 };
-use futures::stream::{Stream, StreamExt};
-use lemmy_api_common::{context::LemmyContext, utils::local_user_view_from_jwt};
-use lemmy_db_schema::source::local_site::LocalSite;
-use lemmy_utils::{claims::Claims, rate_limit::RateLimitCell, REQWEST_TIMEOUT};
-use reqwest::Body;
-use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+use lemmy_db_schema::source::{
+  local_image::{LocalImage, LocalImageForm},
+  local_site::LocalSite,
+};
+use lemmy_utils::{
+  error::LemmyError,
+  rate_limit::RateLimitCell,
+  utils::validation::is_allowed_mime_type,
+};
+use reqwest_middleware::ClientWithMiddleware;
 use serde::{Deserialize, Serialize};
 
 pub fn config(
@@ -32,25 +33,16 @@ pub fn config(
     )
     // This has optional query params: /image/{filename}?format=jpg&thumbnail=256
     .service(web::resource("/pictrs/image/{filename}").route(web::get().to(full_res)))
-    .service(web::resource("/pictrs/image/delete/{token}/{filename}").route(web::get().to(delete)));
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct Image {
-  file: String,
-  delete_token: String,
+    .service(web::resource("/pictrs/image/delete/{token}/{filename}").route(web::get().to(delete)))
+    // Remote images, linked as /image_proxy?url={remote}&sig={signature}
+    .service(web::resource("/image_proxy").route(web::get().to(image_proxy)));
 }
 
+/// The upload response of pictrs, which is also used for the other image storages.
 #[derive(Debug, Serialize, Deserialize)]
 struct Images {
   msg: String,
-  files: Option<Vec<Image>>,
-}
-
-#[derive(Deserialize)]
-struct PictrsParams {
-  format: Option<String>,
-  thumbnail: Option<i32>,
+  files: Option<Vec<StoredImage>>,
 }
 
 #[derive(Deserialize)]
@@ -61,201 +53,182 @@ enum PictrsPurgeParams {
   Alias(String),
 }
 
-fn adapt_request(
-  request: &HttpRequest,
-  client: &ClientWithMiddleware,
-  url: String,
-) -> RequestBuilder {
-  // remove accept-encoding header so that pictrs doesnt compress the response
-  const INVALID_HEADERS: &[HeaderName] = &[ACCEPT_ENCODING, HOST];
-
-  let client_request = client
-    .request(request.method().clone(), url)
-    .timeout(REQWEST_TIMEOUT);
-
-  request
+/// The login token from the `Authorization` header, or the `jwt` cookie which lemmy-ui sets.
+fn request_jwt(req: &HttpRequest) -> Option<String> {
+  req
     .headers()
-    .iter()
-    .fold(client_request, |client_req, (key, value)| {
-      if INVALID_HEADERS.contains(key) {
-        client_req
-      } else {
-        client_req.header(key, value)
-      }
-    })
+    .get(AUTHORIZATION)
+    .and_then(|header| header.to_str().ok())
+    .and_then(|header| header.strip_prefix("Bearer "))
+    .map(ToString::to_string)
+    .or_else(|| req.cookie("jwt").map(|cookie| cookie.value().to_string()))
 }
 
 async fn upload(
   req: HttpRequest,
-  body: web::Payload,
+  body: Multipart,
   client: web::Data<ClientWithMiddleware>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  // TODO: check rate limit here
-  let jwt = req
-    .cookie("jwt")
-    .expect("No auth header for picture upload");
-
-  if Claims::decode(jwt.value(), &context.secret().jwt_secret).is_err() {
+  let Some(jwt) = request_jwt(&req) else {
     return Ok(HttpResponse::Unauthorized().finish());
   };
-
-  let pictrs_config = context.settings().pictrs_config()?;
-  let image_url = format!("{}image", pictrs_config.url);
-
-  let mut client_req = adapt_request(&req, &client, image_url);
-
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string())
+  let Ok(local_user_view) = local_user_view_from_jwt(&jwt, &context).await else {
+    return Ok(HttpResponse::Unauthorized().finish());
   };
 
-  let res = client_req
-    .body(Body::wrap_stream(make_send(body)))
-    .send()
+  // The whole upload is checked before anything is stored
+  let local_site = LocalSite::read(context.pool())
     .await
     .map_err(error::ErrorBadRequest)?;
+  let remaining_quota = match local_site.image_upload_quota_bytes {
+    Some(quota) => {
+      let used =
+        LocalImage::total_size_for_local_user(context.pool(), local_user_view.local_user.id)
+          .await
+          .map_err(error::ErrorBadRequest)?;
+      Some(quota - used)
+    }
+    None => None,
+  };
+  let files = read_upload(body, &local_site, remaining_quota).await?;
+
+  let images = image_storage(&client, context.settings())?
+    .upload(&files, req.head().peer_addr)
+    .await?;
+
+  // Remember who uploaded the images, so that they can be listed and purged later. The storage
+  // returns the files in the order of the upload.
+  for (image, file) in images.iter().zip(&files) {
+    let form = LocalImageForm {
+      local_user_id: local_user_view.local_user.id,
+      pictrs_alias: image.file.clone(),
+      pictrs_delete_token: image.delete_token.clone(),
+      file_size: file.data.len() as i64,
+    };
+    LocalImage::create(context.pool(), &form)
+      .await
+      .map_err(error::ErrorBadRequest)?;
+  }
+
+  Ok(HttpResponse::Created().json(Images {
+    msg: "ok".to_string(),
+    files: Some(images),
+  }))
+}
+
+/// Maximum number of files in a single upload.
+const MAX_UPLOAD_FILES: usize = 10;
+
+/// Reads all files of the upload, and checks their type and size against the site limits. The
+/// limits are checked while the upload is streamed, so that large uploads are rejected early.
+async fn read_upload(
+  mut body: Multipart,
+  local_site: &LocalSite,
+  remaining_quota: Option<i64>,
+) -> Result<Vec<UploadedFile>, LemmyError> {
+  let max_file_bytes = local_site.image_upload_max_bytes;
+  let max_total_bytes = max_file_bytes.saturating_mul(MAX_UPLOAD_FILES as i64);
+  let mut total_bytes = 0;
+  let mut files = vec![];
+  while let Some(field) = body.next().await {
+    if files.len() >= MAX_UPLOAD_FILES {
+      return Err(LemmyError::from_message("too_many_images"));
+    }
+    let mut field = field.map_err(|_| LemmyError::from_message("invalid_image_upload"))?;
+    let declared_type = field
+      .content_type()
+      .map(|mime| mime.essence_str().to_string())
+      .unwrap_or_default();
+    if !is_allowed_mime_type(&local_site.image_upload_allowed_mime_types, &declared_type) {
+      return Err(LemmyError::from_message("image_type_not_allowed"));
+    }
+
+    let mut data = web::BytesMut::new();
+    while let Some(chunk) = field.next().await {
+      let chunk = chunk.map_err(|_| LemmyError::from_message("invalid_image_upload"))?;
+      data.extend_from_slice(&chunk);
+      total_bytes += chunk.len() as i64;
+      if data.len() as i64 > max_file_bytes || total_bytes > max_total_bytes {
+        return Err(LemmyError::from_message("image_too_large"));
+      }
+      if remaining_quota.is_some_and(|remaining| total_bytes > remaining) {
+        return Err(LemmyError::from_message("image_quota_exceeded"));
+      }
+    }
 
-  let status = res.status();
-  let images = res.json::<Images>().await.map_err(error::ErrorBadRequest)?;
+    // The declared type comes from the client, so the type of the content has to match as well
+    let content_type = sniff_mime_type(&data)
+      .filter(|mime| is_allowed_mime_type(&local_site.image_upload_allowed_mime_types, mime))
+      .ok_or_else(|| LemmyError::from_message("image_type_not_allowed"))?;
 
-  Ok(HttpResponse::build(status).json(images))
+    files.push(UploadedFile {
+      content_disposition: field.content_disposition().clone(),
+      content_type: content_type.to_string(),
+      data: data.freeze(),
+    });
+  }
+  Ok(files)
 }
 
-async fn full_res(
-  filename: web::Path<String>,
-  web::Query(params): web::Query<PictrsParams>,
-  req: HttpRequest,
-  client: web::Data<ClientWithMiddleware>,
-  context: web::Data<LemmyContext>,
-) -> Result<HttpResponse, Error> {
-  // block access to images if instance is private and unauthorized, public
+/// Blocks access to images if the instance is private and the request is unauthorized.
+async fn can_view_images(req: &HttpRequest, context: &LemmyContext) -> Result<bool, Error> {
   let local_site = LocalSite::read(context.pool())
     .await
     .map_err(error::ErrorBadRequest)?;
-  if local_site.private_instance {
-    let jwt = req
-      .cookie("jwt")
-      .expect("No auth header for picture access");
-    if local_user_view_from_jwt(jwt.value(), &context)
-      .await
-      .is_err()
-    {
-      return Ok(HttpResponse::Unauthorized().finish());
-    };
+  if !local_site.private_instance {
+    return Ok(true);
   }
-  let name = &filename.into_inner();
-
-  // If there are no query params, the URL is original
-  let pictrs_config = context.settings().pictrs_config()?;
-  let url = if params.format.is_none() && params.thumbnail.is_none() {
-    format!("{}image/original/{}", pictrs_config.url, name,)
-  } else {
-    // Take file type from name, or jpg if nothing is given
-    let format = params
-      .format
-      .unwrap_or_else(|| name.split('.').last().unwrap_or("jpg").to_string());
-
-    let mut url = format!("{}image/process.{}?src={}", pictrs_config.url, format, name,);
-
-    if let Some(size) = params.thumbnail {
-      url = format!("{url}&thumbnail={size}",);
-    }
-    url
+  let Some(jwt) = request_jwt(req) else {
+    return Ok(false);
   };
-
-  image(url, req, client).await
+  Ok(local_user_view_from_jwt(&jwt, context).await.is_ok())
 }
 
-async fn image(
-  url: String,
+async fn full_res(
+  filename: web::Path<String>,
+  web::Query(params): web::Query<ImageParams>,
   req: HttpRequest,
   client: web::Data<ClientWithMiddleware>,
+  context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
-  let mut client_req = adapt_request(&req, &client, url);
-
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string());
-  }
-
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string());
-  }
-
-  let res = client_req.send().await.map_err(error::ErrorBadRequest)?;
-
-  if res.status() == StatusCode::NOT_FOUND {
-    return Ok(HttpResponse::NotFound().finish());
-  }
-
-  let mut client_res = HttpResponse::build(res.status());
-
-  for (name, value) in res.headers().iter().filter(|(h, _)| *h != "connection") {
-    client_res.insert_header((name.clone(), value.clone()));
+  if !can_view_images(&req, &context).await? {
+    return Ok(HttpResponse::Unauthorized().finish());
   }
+  let name = &filename.into_inner();
 
-  Ok(client_res.body(BodyStream::new(res.bytes_stream())))
+  Ok(
+    image_storage(&client, context.settings())?
+      .serve(name, &params, req.headers(), req.head().peer_addr)
+      .await?,
+  )
 }
 
 async fn delete(
   components: web::Path<(String, String)>,
-  req: HttpRequest,
   client: web::Data<ClientWithMiddleware>,
   context: web::Data<LemmyContext>,
 ) -> Result<HttpResponse, Error> {
   let (token, file) = components.into_inner();
 
-  let pictrs_config = context.settings().pictrs_config()?;
-  let url = format!("{}image/delete/{}/{}", pictrs_config.url, &token, &file);
-
-  let mut client_req = adapt_request(&req, &client, url);
-
-  if let Some(addr) = req.head().peer_addr {
-    client_req = client_req.header("X-Forwarded-For", addr.to_string());
-  }
-
-  let res = client_req.send().await.map_err(error::ErrorBadRequest)?;
-
-  Ok(HttpResponse::build(res.status()).body(BodyStream::new(res.bytes_stream())))
-}
-
-fn make_send<S>(mut stream: S) -> impl Stream<Item = S::Item> + Send + Unpin + 'static
-where
-  S: Stream + Unpin + 'static,
-  S::Item: Send,
-{
-  // NOTE: the 8 here is arbitrary
-  let (tx, rx) = tokio::sync::mpsc::channel(8);
-
-  // NOTE: spawning stream into a new task can potentially hit this bug:
-  // - https://github.com/actix/actix-web/issues/1679
-  //
-  // Since 4.0.0-beta.2 this issue is incredibly less frequent. I have not personally reproduced it.
-  // That said, it is still technically possible to encounter.
-  actix_web::rt::spawn(async move {
-    while let Some(res) = stream.next().await {
-      if tx.send(res).await.is_err() {
-        break;
-      }
-    }
-  });
-
-  SendStream { rx }
-}
+  image_storage(&client, context.settings())?
+    .delete(&file, &token)
+    .await?;
+  LocalImage::delete(context.pool(), &file)
+    .await
+    .map_err(error::ErrorBadRequest)?;
 
-struct SendStream<T> {
-  rx: tokio::sync::mpsc::Receiver<T>,
+  Ok(HttpResponse::NoContent().finish())
 }
 
-impl<T> Stream for SendStream<T>
-where
-  T: Send,
-{
-  type Item = T;
-
-  fn poll_next(
-    mut self: std::pin::Pin<&mut Self>,
-    cx: &mut std::task::Context<'_>,
-  ) -> std::task::Poll<Option<Self::Item>> {
-    std::pin::Pin::new(&mut self.rx).poll_recv(cx)
+async fn image_proxy(
+  web::Query(params): web::Query<ImageProxyParams>,
+  req: HttpRequest,
+  context: web::Data<LemmyContext>,
+) -> Result<HttpResponse, Error> {
+  if !can_view_images(&req, &context).await? {
+    return Ok(HttpResponse::Unauthorized().finish());
   }
+
+  Ok(serve_proxied_image(params, req.headers(), req.head().peer_addr, &context).await?)
 }
@@ -1,4 +1,5 @@
 pub mod feeds;
 pub mod images;
+pub mod live_events;
 pub mod nodeinfo;
 pub mod webfinger;
@@ -0,0 +1,178 @@
+use actix_web::{
+  http::header::{CacheControl, CacheDirective, ContentEncoding},
+  web,
+  web::Bytes,
+  HttpResponse,
+};
+use lemmy_api_common::{
+  build_response::build_report_count_response,
+  context::LemmyContext,
+  live::{GetLiveEvents, LiveEvent},
+  live_notification::{LiveNotification, LiveNotificationListener},
+  sensitive::Sensitive,
+  utils::local_user_view_from_jwt,
+};
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::{community::CommunityFollower, person::Person},
+};
+use lemmy_db_views_actor::structs::CommunityModeratorView;
+use lemmy_utils::error::LemmyError;
+use std::{collections::HashSet, convert::Infallible, time::Duration};
+use tokio::{
+  sync::broadcast::{error::RecvError, Receiver},
+  time::{interval, Interval},
+};
+use tracing::warn;
+
+/// Proxies in front of Lemmy close connections which stay silent for too long.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Streams the live events of the user as server-sent events.
+pub async fn live_events(
+  data: web::Query<GetLiveEvents>,
+  context: web::Data<LemmyContext>,
+  listener: web::Data<LiveNotificationListener>,
+) -> Result<HttpResponse, LemmyError> {
+  let local_user_view = local_user_view_from_jwt(&data.auth, &context).await?;
+  let mut stream = LiveEventStream {
+    context,
+    auth: data.into_inner().auth,
+    person: local_user_view.person,
+    followed: HashSet::new(),
+    moderated: HashSet::new(),
+    notifications: listener.subscribe(),
+    keep_alive: interval(KEEP_ALIVE_INTERVAL),
+  };
+  stream.refresh_communities().await?;
+
+  let body = futures::stream::unfold(stream, |mut stream| async move {
+    let message = stream.next_message().await?;
+    Some((Ok::<_, Infallible>(message), stream))
+  });
+  Ok(
+    HttpResponse::Ok()
+      .content_type("text/event-stream")
+      .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+      // Compression would hold back the events until its buffer is full
+      .insert_header(ContentEncoding::Identity)
+      .streaming(body),
+  )
+}
+
+struct LiveEventStream {
+  context: web::Data<LemmyContext>,
+  auth: Sensitive<String>,
+  person: Person,
+  followed: HashSet<CommunityId>,
+  moderated: HashSet<CommunityId>,
+  notifications: Receiver<LiveNotification>,
+  keep_alive: Interval,
+}
+
+impl LiveEventStream {
+  /// Waits for the next event for the user, or a keep-alive comment. Returns `None` once the
+  /// listener stops, or the login of the user is no longer valid.
+  async fn next_message(&mut self) -> Option<Bytes> {
+    loop {
+      tokio::select! {
+        notification = self.notifications.recv() => match notification {
+          Ok(notification) => match self.event_for(notification).await {
+            Ok(Some(event)) => match serde_json::to_string(&event) {
+              Ok(json) => return Some(Bytes::from(format!("data: {json}\n\n"))),
+              Err(e) => warn!("Failed to serialize live event: {e}"),
+            },
+            Ok(None) => {}
+            Err(e) => warn!("Failed to build live event: {e}"),
+          },
+          // Notifications which were missed are skipped, clients refetch when they reconnect
+          Err(RecvError::Lagged(_)) => {}
+          Err(RecvError::Closed) => return None,
+        },
+        _ = self.keep_alive.tick() => {
+          // The stream is closed once the login token is revoked or expires, or the user is banned
+          match local_user_view_from_jwt(&self.auth, &self.context).await {
+            Ok(local_user_view) => self.person = local_user_view.person,
+            Err(_) => return None,
+          }
+          // Also picks up the communities which were followed since the stream was opened
+          if let Err(e) = self.refresh_communities().await {
+            warn!("Failed to refresh communities of live event stream: {e}");
+          }
+          return Some(Bytes::from_static(b": keep-alive\n\n"));
+        }
+      }
+    }
+  }
+
+  async fn refresh_communities(&mut self) -> Result<(), LemmyError> {
+    let pool = self.context.pool();
+    let person_id = self.person.id;
+    self.followed = CommunityFollower::list_approved_community_ids(pool, person_id)
+      .await?
+      .into_iter()
+      .collect();
+    self.moderated = CommunityModeratorView::for_person(pool, person_id)
+      .await?
+      .into_iter()
+      .map(|m| m.community.id)
+      .collect();
+    Ok(())
+  }
+
+  /// Returns the event which the user gets for the notification, if any.
+  async fn event_for(
+    &self,
+    notification: LiveNotification,
+  ) -> Result<Option<LiveEvent>, LemmyError> {
+    let person_id = self.person.id;
+    let event = match notification {
+      LiveNotification::CommentReply {
+        recipient_id,
+        comment_reply_id,
+        comment_id,
+      } if recipient_id == person_id => LiveEvent::CommentReply {
+        comment_reply_id,
+        comment_id,
+      },
+      LiveNotification::PersonMention {
+        recipient_id,
+        person_mention_id,
+        comment_id,
+      } if recipient_id == person_id => LiveEvent::PersonMention {
+        person_mention_id,
+        comment_id,
+      },
+      LiveNotification::PrivateMessage {
+        recipient_id,
+        private_message_id,
+      } if recipient_id == person_id => LiveEvent::PrivateMessage { private_message_id },
+      LiveNotification::Report { community_id }
+        if self.person.admin || community_id.is_some_and(|c| self.moderated.contains(&c)) =>
+      {
+        let counts = build_report_count_response(&self.context, &self.person, None).await?;
+        LiveEvent::ReportCount(counts)
+      }
+      LiveNotification::Post {
+        creator_id,
+        community_id,
+        post_id,
+      } if creator_id != person_id && self.followed.contains(&community_id) => LiveEvent::Post {
+        post_id,
+        community_id,
+      },
+      LiveNotification::Comment {
+        creator_id,
+        community_id,
+        post_id,
+        comment_id,
+      } if creator_id != person_id && self.followed.contains(&community_id) => LiveEvent::Comment {
+        comment_id,
+        post_id,
+        community_id,
+      },
+      _ => return Ok(None),
+    };
+    Ok(Some(event))
+  }
+}
@@ -1,9 +1,14 @@
 use crate::error::LemmyError;
+use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
 use serde::{Deserialize, Serialize};
-use chrono::Utc;
 type Jwt = String;
 
+/// How long a login token stays valid after it was issued.
+pub fn login_token_validity() -> Duration {
+  Duration::days(90)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
   /// local_user_id, standard claim by RFC 7519.
@@ -11,22 +16,28 @@ pub struct Claims {
   pub iss: String,
   /// Time when this token was issued as UNIX-timestamp in seconds
   pub iat: i64,
+  /// Time when this token expires as UNIX-timestamp in seconds. Missing in tokens which were
+  /// issued before login tokens expired.
+  #[serde(default)]
+  pub exp: Option<i64>,
 }
 
 impl Claims {
   pub fn decode(jwt: &str, jwt_secret: &str) -> Result<TokenData<Claims>, LemmyError> {
     let mut validation = Validation::default();
-    validation.validate_exp = false;
+    // Legacy tokens without expiry are accepted here, and expired through their login_token row
     validation.required_spec_claims.remove("exp");
     let key = DecodingKey::from_secret(jwt_secret.as_ref());
     Ok(decode::<Claims>(jwt, &key, &validation)?)
   }
 
   pub fn jwt(local_user_id: i32, jwt_secret: &str, hostname: &str) -> Result<Jwt, LemmyError> {
+    let now = Utc::now();
     let my_claims = Claims {
       sub: local_user_id,
       iss: hostname.to_string(),
-      iat: Utc::now().timestamp(),
+      iat: now.timestamp(),
+      exp: Some((now + login_token_validity()).timestamp()),
     };
 
     let key = EncodingKey::from_secret(jwt_secret.as_ref());
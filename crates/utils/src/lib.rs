@@ -10,9 +10,11 @@ pub mod settings;
 
 pub mod claims;
 pub mod error;
+pub mod oidc;
 pub mod request;
 pub mod utils;
 pub mod version;
+pub mod web_push;
 
 use std::time::Duration;
 
@@ -0,0 +1,410 @@
+use crate::{error::LemmyError, settings::structs::OidcProviderConfig};
+use base64::URL_SAFE_NO_PAD;
+use openssl::{rand::rand_bytes, sha::sha256};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::Deserialize;
+use url::Url;
+
+/// Endpoints of a provider, as published in its discovery document.
+#[derive(Debug, Deserialize)]
+pub struct ProviderMetadata {
+  pub issuer: Url,
+  pub authorization_endpoint: Url,
+  pub token_endpoint: Url,
+  pub userinfo_endpoint: Url,
+}
+
+/// The claims about the user which Lemmy needs to log in or create an account.
+#[derive(Debug, Deserialize)]
+pub struct UserInfo {
+  /// Identifier of the account, which stays the same even if the username or email changes.
+  pub sub: String,
+  pub email: Option<String>,
+  #[serde(default)]
+  pub email_verified: bool,
+  pub preferred_username: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+  access_token: String,
+}
+
+/// Secrets for a single login, which is started with [authorization_url] and finished with
+/// [fetch_user_info].
+pub struct LoginSecrets {
+  /// Passed through the provider, to match its response to the login.
+  pub state: String,
+  /// Sent when redeeming the code, only its hash is included in the authorization url (PKCE).
+  pub code_verifier: String,
+}
+
+impl LoginSecrets {
+  pub fn generate() -> Result<Self, LemmyError> {
+    Ok(LoginSecrets {
+      state: random_string()?,
+      code_verifier: random_string()?,
+    })
+  }
+}
+
+fn random_string() -> Result<String, LemmyError> {
+  let mut bytes = [0; 32];
+  rand_bytes(&mut bytes)?;
+  Ok(base64::encode_config(bytes, URL_SAFE_NO_PAD))
+}
+
+/// The `S256` code challenge for a PKCE code verifier, as described in RFC 7636.
+fn code_challenge(code_verifier: &str) -> String {
+  base64::encode_config(sha256(code_verifier.as_bytes()), URL_SAFE_NO_PAD)
+}
+
+/// Fetches the discovery document of the provider.
+pub async fn discover(
+  client: &ClientWithMiddleware,
+  config: &OidcProviderConfig,
+) -> Result<ProviderMetadata, LemmyError> {
+  let issuer = config.issuer.as_str().trim_end_matches('/');
+  let metadata: ProviderMetadata = client
+    .get(format!("{issuer}/.well-known/openid-configuration"))
+    .send()
+    .await?
+    .error_for_status()?
+    .json()
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "oidc_provider_error"))?;
+  // Make sure that a misconfigured provider can't speak for another one
+  if metadata.issuer.as_str().trim_end_matches('/') != issuer {
+    return Err(LemmyError::from_message("oidc_provider_error"));
+  }
+  Ok(metadata)
+}
+
+/// The url of the provider where the user needs to be sent to log in.
+pub fn authorization_url(
+  metadata: &ProviderMetadata,
+  config: &OidcProviderConfig,
+  secrets: &LoginSecrets,
+  redirect_uri: &str,
+) -> Url {
+  let mut url = metadata.authorization_endpoint.clone();
+  url
+    .query_pairs_mut()
+    .append_pair("response_type", "code")
+    .append_pair("client_id", &config.client_id)
+    .append_pair("redirect_uri", redirect_uri)
+    .append_pair("scope", &config.scopes)
+    .append_pair("state", &secrets.state)
+    .append_pair("code_challenge", &code_challenge(&secrets.code_verifier))
+    .append_pair("code_challenge_method", "S256");
+  url
+}
+
+/// Redeems the code which the provider passed back to the redirect uri, and fetches the user info
+/// with the resulting access token.
+pub async fn fetch_user_info(
+  client: &ClientWithMiddleware,
+  metadata: &ProviderMetadata,
+  config: &OidcProviderConfig,
+  code: &str,
+  code_verifier: &str,
+  redirect_uri: &str,
+) -> Result<UserInfo, LemmyError> {
+  let token: TokenResponse = client
+    .post(metadata.token_endpoint.clone())
+    .basic_auth(&config.client_id, Some(&config.client_secret))
+    .form(&[
+      ("grant_type", "authorization_code"),
+      ("code", code),
+      ("redirect_uri", redirect_uri),
+      ("code_verifier", code_verifier),
+    ])
+    .send()
+    .await?
+    .error_for_status()
+    .map_err(|e| LemmyError::from_error_message(e, "oidc_invalid_code"))?
+    .json()
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "oidc_provider_error"))?;
+
+  client
+    .get(metadata.userinfo_endpoint.clone())
+    .bearer_auth(token.access_token)
+    .send()
+    .await?
+    .error_for_status()?
+    .json()
+    .await
+    .map_err(|e| LemmyError::from_error_message(e, "oidc_provider_error"))
+}
+
+/// A minimal provider which serves the endpoints used by Lemmy, for testing the login flow.
+#[cfg(any(test, feature = "oidc-mock"))]
+pub mod mock {
+  use super::{code_challenge, random_string};
+  use actix_web::{
+    dev::ServerHandle,
+    web::{self, Data, Form},
+    App,
+    HttpRequest,
+    HttpResponse,
+    HttpServer,
+  };
+  use serde::Deserialize;
+  use serde_json::json;
+  use std::{collections::HashMap, net::TcpListener, sync::Mutex};
+  use url::Url;
+
+  pub const CLIENT_ID: &str = "lemmy";
+  pub const CLIENT_SECRET: &str = "secret";
+  /// The access token is this prefix followed by the name of the user.
+  const ACCESS_TOKEN_PREFIX: &str = "mock_access_token_";
+
+  struct MockState {
+    issuer: String,
+    /// Issued codes, with the code challenge and redirect uri of the authorization request, and
+    /// the name of the user who logged in.
+    codes: Mutex<HashMap<String, (String, String, String)>>,
+  }
+
+  pub struct MockProvider {
+    pub issuer: Url,
+    state: Data<MockState>,
+    handle: ServerHandle,
+  }
+
+  impl MockProvider {
+    pub async fn start() -> Self {
+      let listener = TcpListener::bind(("127.0.0.1", 0)).expect("bind mock provider");
+      let addr = listener.local_addr().expect("mock provider address");
+      let issuer = Url::parse(&format!("http://{addr}")).expect("parse issuer");
+      let state = Data::new(MockState {
+        // Without trailing slash, like the issuers of most real providers
+        issuer: issuer.as_str().trim_end_matches('/').to_string(),
+        codes: Mutex::default(),
+      });
+      let app_state = state.clone();
+      let server = HttpServer::new(move || {
+        App::new()
+          .app_data(app_state.clone())
+          .route(
+            "/.well-known/openid-configuration",
+            web::get().to(discovery),
+          )
+          .route("/token", web::post().to(token))
+          .route("/userinfo", web::get().to(userinfo))
+      })
+      .workers(1)
+      .listen(listener)
+      .expect("listen mock provider")
+      .run();
+      let handle = server.handle();
+      actix_web::rt::spawn(server);
+      MockProvider {
+        issuer,
+        state,
+        handle,
+      }
+    }
+
+    /// Simulates the user logging in at the provider, which then redirects back with a code.
+    pub fn authorize(&self, authorization_url: &Url) -> (String, String) {
+      self.authorize_as(authorization_url, "mock")
+    }
+
+    /// Like [MockProvider::authorize], for another user. Their subject is `{name}_subject`, the
+    /// email `{name}@example.com` and the username `{name}_user`.
+    pub fn authorize_as(&self, authorization_url: &Url, name: &str) -> (String, String) {
+      let params: HashMap<_, _> = authorization_url.query_pairs().into_owned().collect();
+      assert_eq!(
+        Some("S256"),
+        params.get("code_challenge_method").map(|m| m.as_str())
+      );
+      let code = random_string().expect("generate code");
+      self.state.codes.lock().expect("lock codes").insert(
+        code.clone(),
+        (
+          params["code_challenge"].clone(),
+          params["redirect_uri"].clone(),
+          name.to_string(),
+        ),
+      );
+      (code, params["state"].clone())
+    }
+
+    pub async fn stop(self) {
+      self.handle.stop(true).await;
+    }
+  }
+
+  async fn discovery(state: Data<MockState>) -> HttpResponse {
+    let issuer = &state.issuer;
+    HttpResponse::Ok().json(json!({
+      "issuer": issuer,
+      "authorization_endpoint": format!("{issuer}/authorize"),
+      "token_endpoint": format!("{issuer}/token"),
+      "userinfo_endpoint": format!("{issuer}/userinfo"),
+    }))
+  }
+
+  #[derive(Deserialize)]
+  struct TokenForm {
+    code: String,
+    redirect_uri: String,
+    code_verifier: String,
+  }
+
+  async fn token(req: HttpRequest, form: Form<TokenForm>, state: Data<MockState>) -> HttpResponse {
+    let expected_auth = format!(
+      "Basic {}",
+      base64::encode(format!("{CLIENT_ID}:{CLIENT_SECRET}"))
+    );
+    let authorized = req
+      .headers()
+      .get("Authorization")
+      .is_some_and(|a| a.as_bytes() == expected_auth.as_bytes());
+    // Codes can only be redeemed once
+    let issued = state.codes.lock().expect("lock codes").remove(&form.code);
+    match issued {
+      Some((challenge, redirect_uri, name))
+        if authorized
+          && challenge == code_challenge(&form.code_verifier)
+          && redirect_uri == form.redirect_uri =>
+      {
+        HttpResponse::Ok().json(json!({
+          "access_token": format!("{ACCESS_TOKEN_PREFIX}{name}"),
+          "token_type": "Bearer",
+        }))
+      }
+      _ => HttpResponse::BadRequest().json(json!({ "error": "invalid_grant" })),
+    }
+  }
+
+  async fn userinfo(req: HttpRequest) -> HttpResponse {
+    let name = req
+      .headers()
+      .get("Authorization")
+      .and_then(|a| a.to_str().ok())
+      .and_then(|a| a.strip_prefix("Bearer "))
+      .and_then(|token| token.strip_prefix(ACCESS_TOKEN_PREFIX));
+    let Some(name) = name else {
+      return HttpResponse::Unauthorized().finish();
+    };
+    HttpResponse::Ok().json(json!({
+      "sub": format!("{name}_subject"),
+      "email": format!("{name}@example.com"),
+      "email_verified": true,
+      "preferred_username": format!("{name}_user"),
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    authorization_url,
+    discover,
+    fetch_user_info,
+    mock::{MockProvider, CLIENT_ID, CLIENT_SECRET},
+    LoginSecrets,
+  };
+  use crate::settings::structs::OidcProviderConfig;
+  use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+
+  const REDIRECT_URI: &str = "https://lemmy.example.com/oidc/callback";
+
+  fn client() -> ClientWithMiddleware {
+    ClientBuilder::new(reqwest::Client::new()).build()
+  }
+
+  fn provider_config(provider: &MockProvider) -> OidcProviderConfig {
+    OidcProviderConfig {
+      name: "mock".to_string(),
+      display_name: "Mock".to_string(),
+      issuer: provider.issuer.clone(),
+      client_id: CLIENT_ID.to_string(),
+      client_secret: CLIENT_SECRET.to_string(),
+      scopes: "openid email profile".to_string(),
+    }
+  }
+
+  #[actix_web::test]
+  async fn test_login_flow() {
+    let provider = MockProvider::start().await;
+    let config = provider_config(&provider);
+    let client = client();
+
+    let metadata = discover(&client, &config).await.unwrap();
+    let secrets = LoginSecrets::generate().unwrap();
+    let url = authorization_url(&metadata, &config, &secrets, REDIRECT_URI);
+    let (code, state) = provider.authorize(&url);
+    assert_eq!(secrets.state, state);
+
+    let user_info = fetch_user_info(
+      &client,
+      &metadata,
+      &config,
+      &code,
+      &secrets.code_verifier,
+      REDIRECT_URI,
+    )
+    .await
+    .unwrap();
+    assert_eq!("mock_subject", user_info.sub);
+    assert_eq!(Some("mock@example.com".to_string()), user_info.email);
+    assert!(user_info.email_verified);
+    assert_eq!(Some("mock_user".to_string()), user_info.preferred_username);
+
+    // Codes can only be redeemed once
+    let reused = fetch_user_info(
+      &client,
+      &metadata,
+      &config,
+      &code,
+      &secrets.code_verifier,
+      REDIRECT_URI,
+    )
+    .await;
+    assert!(reused.is_err());
+
+    provider.stop().await;
+  }
+
+  #[actix_web::test]
+  async fn test_wrong_code_verifier() {
+    let provider = MockProvider::start().await;
+    let config = provider_config(&provider);
+    let client = client();
+
+    let metadata = discover(&client, &config).await.unwrap();
+    let secrets = LoginSecrets::generate().unwrap();
+    let url = authorization_url(&metadata, &config, &secrets, REDIRECT_URI);
+    let (code, _) = provider.authorize(&url);
+
+    // An intercepted code is useless without the verifier
+    let other_secrets = LoginSecrets::generate().unwrap();
+    let user_info = fetch_user_info(
+      &client,
+      &metadata,
+      &config,
+      &code,
+      &other_secrets.code_verifier,
+      REDIRECT_URI,
+    )
+    .await;
+    assert!(user_info.is_err());
+
+    provider.stop().await;
+  }
+
+  #[actix_web::test]
+  async fn test_issuer_mismatch() {
+    let provider = MockProvider::start().await;
+    let mut config = provider_config(&provider);
+    config.issuer.set_path("/other");
+
+    assert!(discover(&client(), &config).await.is_err());
+
+    provider.stop().await;
+  }
+}
@@ -1,8 +1,12 @@
-use crate::error::LemmyError;
-use actix_web::dev::{ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform};
+use crate::{claims::Claims, error::LemmyError};
+use actix_web::{
+  dev::{ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform},
+  http::header::{AUTHORIZATION, COOKIE},
+  web::Query,
+};
 use enum_map::enum_map;
 use futures::future::{ok, Ready};
-use rate_limiter::{InstantSecs, RateLimitStorage, RateLimitType};
+use rate_limiter::{InstantSecs, PersistedBucket, RateLimitStorage, RateLimitType};
 use serde::{Deserialize, Serialize};
 use std::{
   future::Future,
@@ -27,12 +31,21 @@ pub struct RateLimitConfig {
   #[builder(default = 60)]
   /// Interval length for message limit, in seconds
   pub message_per_second: i32,
+  #[builder(default = 180)]
+  /// Maximum number of messages created in interval by a single logged in user, in addition to the
+  /// ip limit. Only applies to requests which send the login token in a header, cookie or query
+  /// param.
+  pub message_per_user: i32,
   #[builder(default = 6)]
   /// Maximum number of posts created in interval
   pub post: i32,
   #[builder(default = 300)]
   /// Interval length for post limit, in seconds
   pub post_per_second: i32,
+  #[builder(default = 6)]
+  /// Maximum number of posts created in interval by a single logged in user, in addition to the
+  /// ip limit
+  pub post_per_user: i32,
   #[builder(default = 3)]
   /// Maximum number of registrations in interval
   pub register: i32,
@@ -46,17 +59,35 @@ pub struct RateLimitConfig {
   /// Interval length for image uploads, in seconds
   pub image_per_second: i32,
   #[builder(default = 6)]
+  /// Maximum number of image uploads in interval by a single logged in user, in addition to the
+  /// ip limit
+  pub image_per_user: i32,
+  #[builder(default = 6)]
   /// Maximum number of comments created in interval
   pub comment: i32,
   #[builder(default = 600)]
   /// Interval length for comment limit, in seconds
   pub comment_per_second: i32,
+  #[builder(default = 6)]
+  /// Maximum number of comments created in interval by a single logged in user, in addition to the
+  /// ip limit
+  pub comment_per_user: i32,
   #[builder(default = 60)]
   /// Maximum number of searches created in interval
   pub search: i32,
   #[builder(default = 600)]
   /// Interval length for search limit, in seconds
   pub search_per_second: i32,
+  #[builder(default = 60)]
+  /// Maximum number of searches in interval by a single logged in user, in addition to the
+  /// ip limit
+  pub search_per_user: i32,
+  #[builder(default = 1)]
+  /// Maximum number of user settings imports in interval, per ip and per logged in user
+  pub import_user_settings: i32,
+  #[builder(default = 86400)]
+  /// Interval length for user settings imports, in seconds
+  pub import_user_settings_per_second: i32,
 }
 
 #[derive(Debug, Clone)]
@@ -68,6 +99,7 @@ struct RateLimit {
 #[derive(Debug, Clone)]
 pub struct RateLimitedGuard {
   rate_limit: Arc<Mutex<RateLimit>>,
+  jwt_secret: Arc<String>,
   type_: RateLimitType,
 }
 
@@ -76,11 +108,13 @@ pub struct RateLimitedGuard {
 pub struct RateLimitCell {
   tx: Sender<RateLimitConfig>,
   rate_limit: Arc<Mutex<RateLimit>>,
+  /// Used to find out the user of authenticated requests, for per-user rate limits
+  jwt_secret: Arc<String>,
 }
 
 impl RateLimitCell {
   /// Initialize cell if it wasnt initialized yet. Otherwise returns the existing cell.
-  pub async fn new(rate_limit_config: RateLimitConfig) -> &'static Self {
+  pub async fn new(rate_limit_config: RateLimitConfig, jwt_secret: String) -> &'static Self {
     static LOCAL_INSTANCE: OnceCell<RateLimitCell> = OnceCell::const_new();
     LOCAL_INSTANCE
       .get_or_init(|| async {
@@ -98,7 +132,11 @@ impl RateLimitCell {
               .rate_limit_config = r;
           }
         });
-        RateLimitCell { tx, rate_limit }
+        RateLimitCell {
+          tx,
+          rate_limit,
+          jwt_secret: Arc::new(jwt_secret),
+        }
       })
       .await
   }
@@ -125,6 +163,7 @@ impl RateLimitCell {
       RateLimitType::Image => rate_limit.image_per_second,
       RateLimitType::Comment => rate_limit.comment_per_second,
       RateLimitType::Search => rate_limit.search_per_second,
+      RateLimitType::ImportUserSettings => rate_limit.import_user_settings_per_second,
     }
     .into_values()
     .max()
@@ -138,6 +177,26 @@ impl RateLimitCell {
       .remove_older_than(duration, InstantSecs::now())
   }
 
+  /// Returns the state of all buckets, so that it can be persisted across restarts.
+  pub fn export_buckets(&self) -> Vec<PersistedBucket> {
+    self
+      .rate_limit
+      .lock()
+      .expect("Failed to lock rate limit mutex for reading")
+      .rate_limiter
+      .export(InstantSecs::now())
+  }
+
+  /// Restores buckets which were persisted with [Self::export_buckets].
+  pub fn import_buckets(&self, buckets: Vec<PersistedBucket>) {
+    self
+      .rate_limit
+      .lock()
+      .expect("Failed to lock rate limit mutex for updating")
+      .rate_limiter
+      .import(buckets, InstantSecs::now())
+  }
+
   pub fn message(&self) -> RateLimitedGuard {
     self.kind(RateLimitType::Message)
   }
@@ -162,9 +221,14 @@ impl RateLimitCell {
     self.kind(RateLimitType::Search)
   }
 
+  pub fn import_user_settings(&self) -> RateLimitedGuard {
+    self.kind(RateLimitType::ImportUserSettings)
+  }
+
   fn kind(&self, type_: RateLimitType) -> RateLimitedGuard {
     RateLimitedGuard {
       rate_limit: self.rate_limit.clone(),
+      jwt_secret: self.jwt_secret.clone(),
       type_,
     }
   }
@@ -177,26 +241,115 @@ pub struct RateLimitedMiddleware<S> {
 
 impl RateLimitedGuard {
   /// Returns true if the request passed the rate limit, false if it failed and should be rejected.
-  pub fn check(self, ip_addr: IpAddr) -> bool {
+  ///
+  /// Requests by logged in users need to pass both the ip limit and the per-user limit. Posts,
+  /// comments and user settings imports are only limited per user by their handlers, with
+  /// [Self::check_user].
+  pub fn check(self, ip_addr: IpAddr, local_user_id: Option<i32>) -> bool {
     // Does not need to be blocking because the RwLock in settings never held across await points,
     // and the operation here locks only long enough to clone
     let mut guard = self
       .rate_limit
       .lock()
       .expect("Failed to lock rate limit mutex for reading");
-    let rate_limit = &guard.rate_limit_config;
-
-    let (kind, interval) = match self.type_ {
-      RateLimitType::Message => (rate_limit.message, rate_limit.message_per_second),
-      RateLimitType::Post => (rate_limit.post, rate_limit.post_per_second),
-      RateLimitType::Register => (rate_limit.register, rate_limit.register_per_second),
-      RateLimitType::Image => (rate_limit.image, rate_limit.image_per_second),
-      RateLimitType::Comment => (rate_limit.comment, rate_limit.comment_per_second),
-      RateLimitType::Search => (rate_limit.search, rate_limit.search_per_second),
-    };
+    let (kind, interval, per_user) = limits(&guard.rate_limit_config, self.type_);
     let limiter = &mut guard.rate_limiter;
+    let now = InstantSecs::now();
 
-    limiter.check_rate_limit_full(self.type_, ip_addr, kind, interval, InstantSecs::now())
+    // Requests which were already rejected by ip don't use up a token of the user
+    if !limiter.check_rate_limit_full(self.type_, ip_addr, kind, interval, now) {
+      return false;
+    }
+    let checked_by_handler = matches!(
+      self.type_,
+      RateLimitType::Post | RateLimitType::Comment | RateLimitType::ImportUserSettings
+    );
+    match (local_user_id, per_user) {
+      (Some(local_user_id), Some(per_user)) if !checked_by_handler => {
+        limiter.check_rate_limit_user(self.type_, local_user_id, per_user, interval, now)
+      }
+      _ => true,
+    }
+  }
+
+  /// Checks only the per-user limit. This is used by handlers which find out the user from the
+  /// `auth` field of the request body, which the middleware doesn't read.
+  pub fn check_user(self, local_user_id: i32) -> bool {
+    let mut guard = self
+      .rate_limit
+      .lock()
+      .expect("Failed to lock rate limit mutex for reading");
+    let (_, interval, per_user) = limits(&guard.rate_limit_config, self.type_);
+    match per_user {
+      Some(per_user) => guard.rate_limiter.check_rate_limit_user(
+        self.type_,
+        local_user_id,
+        per_user,
+        interval,
+        InstantSecs::now(),
+      ),
+      None => true,
+    }
+  }
+
+  /// Finds out which user made the request, by decoding the login token from the
+  /// `Authorization` header, the `jwt` cookie or the `auth` query param, in this order. The
+  /// request body is not read. The token is not checked against the database, a valid signature
+  /// is enough for rate limiting.
+  fn local_user_id(&self, req: &ServiceRequest) -> Option<i32> {
+    let token = req
+      .headers()
+      .get(AUTHORIZATION)
+      .and_then(|header| header.to_str().ok())
+      .and_then(|header| header.strip_prefix("Bearer "))
+      .map(ToString::to_string)
+      .or_else(|| jwt_cookie(req))
+      .or_else(|| {
+        Query::<AuthParam>::from_query(req.query_string())
+          .ok()
+          .and_then(|query| query.into_inner().auth)
+      })?;
+    Claims::decode(&token, &self.jwt_secret)
+      .ok()
+      .map(|claims| claims.claims.sub)
+  }
+}
+
+/// The ip limit, interval and per-user limit of the given action.
+fn limits(rate_limit: &RateLimitConfig, type_: RateLimitType) -> (i32, i32, Option<i32>) {
+  match type_ {
+    RateLimitType::Message => (
+      rate_limit.message,
+      rate_limit.message_per_second,
+      Some(rate_limit.message_per_user),
+    ),
+    RateLimitType::Post => (
+      rate_limit.post,
+      rate_limit.post_per_second,
+      Some(rate_limit.post_per_user),
+    ),
+    // Registering happens before there is any user to limit
+    RateLimitType::Register => (rate_limit.register, rate_limit.register_per_second, None),
+    RateLimitType::Image => (
+      rate_limit.image,
+      rate_limit.image_per_second,
+      Some(rate_limit.image_per_user),
+    ),
+    RateLimitType::Comment => (
+      rate_limit.comment,
+      rate_limit.comment_per_second,
+      Some(rate_limit.comment_per_user),
+    ),
+    RateLimitType::Search => (
+      rate_limit.search,
+      rate_limit.search_per_second,
+      Some(rate_limit.search_per_user),
+    ),
+    RateLimitType::ImportUserSettings => (
+      rate_limit.import_user_settings,
+      rate_limit.import_user_settings_per_second,
+      Some(rate_limit.import_user_settings),
+    ),
   }
 }
 
@@ -236,12 +389,13 @@ where
 
   fn call(&self, req: ServiceRequest) -> Self::Future {
     let ip_addr = get_ip(&req.connection_info());
+    let local_user_id = self.rate_limited.local_user_id(&req);
 
     let rate_limited = self.rate_limited.clone();
     let service = self.service.clone();
 
     Box::pin(async move {
-      if rate_limited.check(ip_addr) {
+      if rate_limited.check(ip_addr, local_user_id) {
         service.call(req).await
       } else {
         let (http_req, _) = req.into_parts();
@@ -254,6 +408,22 @@ where
   }
 }
 
+#[derive(Deserialize)]
+struct AuthParam {
+  auth: Option<String>,
+}
+
+/// Value of the `jwt` cookie, which is set by lemmy-ui.
+fn jwt_cookie(req: &ServiceRequest) -> Option<String> {
+  req
+    .headers()
+    .get_all(COOKIE)
+    .filter_map(|header| header.to_str().ok())
+    .flat_map(|header| header.split(';'))
+    .find_map(|cookie| cookie.trim().strip_prefix("jwt="))
+    .map(ToString::to_string)
+}
+
 fn get_ip(conn_info: &ConnectionInfo) -> IpAddr {
   conn_info
     .realip_remote_addr()
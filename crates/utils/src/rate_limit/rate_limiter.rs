@@ -1,8 +1,11 @@
+use chrono::{DateTime, Utc};
 use enum_map::{enum_map, EnumMap};
 use once_cell::sync::Lazy;
 use std::{
   collections::HashMap,
+  fmt::Write,
   net::{IpAddr, Ipv4Addr, Ipv6Addr},
+  str::FromStr,
   time::{Duration, Instant},
 };
 use tracing::debug;
@@ -44,7 +47,7 @@ struct RateLimitBucket {
   tokens: f32,
 }
 
-#[derive(Debug, enum_map::Enum, Copy, Clone, AsRefStr)]
+#[derive(Debug, enum_map::Enum, Copy, Clone, AsRefStr, EnumString)]
 pub(crate) enum RateLimitType {
   Message,
   Register,
@@ -52,6 +55,7 @@ pub(crate) enum RateLimitType {
   Image,
   Comment,
   Search,
+  ImportUserSettings,
 }
 
 type Map<K, C> = HashMap<K, RateLimitedGroup<C>>;
@@ -124,13 +128,26 @@ impl<C: Default> RateLimitedGroup<C> {
   }
 }
 
-/// Rate limiting based on rate type and IP addr
+/// State of a single bucket in a form that can be stored outside of the process.
+#[derive(PartialEq, Debug, Clone)]
+pub struct PersistedBucket {
+  /// Identifies the rate limited group, eg `ipv4:1.2.3.4`, `ipv6:20010db80000` or `user:5`
+  pub key: String,
+  /// Name of the [RateLimitType]
+  pub action: String,
+  pub tokens: f32,
+  pub last_checked: DateTime<Utc>,
+}
+
+/// Rate limiting based on rate type and IP addr or local user
 #[derive(PartialEq, Debug, Clone, Default)]
 pub struct RateLimitStorage {
   /// One bucket per individual IPv4 address
   ipv4_buckets: Map<Ipv4Addr, ()>,
   /// Seperate buckets for 48, 56, and 64 bit prefixes of IPv6 addresses
   ipv6_buckets: Map<[u8; 6], Map<u8, Map<u8, ()>>>,
+  /// One bucket per logged in user, keyed by local user id
+  user_buckets: Map<i32, ()>,
 }
 
 impl RateLimitStorage {
@@ -192,6 +209,29 @@ impl RateLimitStorage {
     result
   }
 
+  /// Same algorithm as [Self::check_rate_limit_full], but keyed by the local user id of an
+  /// authenticated request instead of the IP address.
+  pub(super) fn check_rate_limit_user(
+    &mut self,
+    type_: RateLimitType,
+    local_user_id: i32,
+    capacity: i32,
+    secs_to_refill: i32,
+    now: InstantSecs,
+  ) -> bool {
+    let group = self
+      .user_buckets
+      .entry(local_user_id)
+      .or_insert(RateLimitedGroup::new(now));
+    let result = group.check_total(type_, now, capacity, secs_to_refill);
+
+    if !result {
+      debug!("Rate limited user: {local_user_id}");
+    }
+
+    result
+  }
+
   /// Remove buckets older than the given duration
   pub(super) fn remove_older_than(&mut self, duration: Duration, now: InstantSecs) {
     // Only retain buckets that were last used after `instant`
@@ -207,6 +247,7 @@ impl RateLimitStorage {
     };
 
     self.ipv4_buckets.retain(|_, group| is_recently_used(group));
+    self.user_buckets.retain(|_, group| is_recently_used(group));
 
     self.ipv6_buckets.retain(|_, group_48| {
       group_48.children.retain(|_, group_56| {
@@ -218,6 +259,145 @@ impl RateLimitStorage {
       !group_48.children.is_empty()
     })
   }
+
+  /// Returns the state of all buckets which were used since they were created.
+  pub(super) fn export(&self, now: InstantSecs) -> Vec<PersistedBucket> {
+    let mut buckets = vec![];
+    for (ip, group) in &self.ipv4_buckets {
+      export_group(&mut buckets, format!("ipv4:{ip}"), group, now);
+    }
+    for (key_48, group_48) in &self.ipv6_buckets {
+      export_group(&mut buckets, ipv6_key(key_48), group_48, now);
+      for (key_56, group_56) in &group_48.children {
+        let key = ipv6_key(&[&key_48[..], &[*key_56]].concat());
+        export_group(&mut buckets, key, group_56, now);
+        for (key_64, group_64) in &group_56.children {
+          let key = ipv6_key(&[&key_48[..], &[*key_56, *key_64]].concat());
+          export_group(&mut buckets, key, group_64, now);
+        }
+      }
+    }
+    for (local_user_id, group) in &self.user_buckets {
+      export_group(&mut buckets, format!("user:{local_user_id}"), group, now);
+    }
+    buckets
+  }
+
+  /// Restores buckets previously returned by [Self::export]. Entries which can't be parsed are
+  /// skipped. Buckets last checked before this process was started are treated as if they were
+  /// checked at startup, so that downtime doesn't refill them.
+  pub(super) fn import(&mut self, buckets: Vec<PersistedBucket>, now: InstantSecs) {
+    let now_utc = Utc::now();
+    for persisted in buckets {
+      let Ok(type_) = RateLimitType::from_str(&persisted.action) else {
+        continue;
+      };
+      let secs_ago = u32::try_from((now_utc - persisted.last_checked).num_seconds()).unwrap_or(0);
+      let bucket = RateLimitBucket {
+        last_checked: InstantSecs {
+          secs: now.secs.saturating_sub(secs_ago),
+        },
+        tokens: persisted.tokens,
+      };
+      let Some(group) = self.group_for_key(&persisted.key, now) else {
+        continue;
+      };
+      #[allow(clippy::indexing_slicing)] // `EnumMap` has no `get` funciton
+      {
+        group[type_] = bucket;
+      }
+    }
+  }
+
+  /// Finds or creates the buckets for a key in the format of [PersistedBucket::key].
+  fn group_for_key(
+    &mut self,
+    key: &str,
+    now: InstantSecs,
+  ) -> Option<&mut EnumMap<RateLimitType, RateLimitBucket>> {
+    if let Some(ip) = key.strip_prefix("ipv4:") {
+      let ip = Ipv4Addr::from_str(ip).ok()?;
+      let group = self
+        .ipv4_buckets
+        .entry(ip)
+        .or_insert(RateLimitedGroup::new(now));
+      Some(&mut group.total)
+    } else if let Some(prefix) = key.strip_prefix("ipv6:") {
+      let prefix = parse_hex(prefix)?;
+      let key_48: [u8; 6] = prefix.get(..6)?.try_into().ok()?;
+      let rest = prefix.get(6..)?;
+      let group_48 = self
+        .ipv6_buckets
+        .entry(key_48)
+        .or_insert(RateLimitedGroup::new(now));
+      let Some((key_56, rest)) = rest.split_first() else {
+        return Some(&mut group_48.total);
+      };
+      let group_56 = group_48
+        .children
+        .entry(*key_56)
+        .or_insert(RateLimitedGroup::new(now));
+      match rest {
+        [] => Some(&mut group_56.total),
+        [key_64] => {
+          let group_64 = group_56
+            .children
+            .entry(*key_64)
+            .or_insert(RateLimitedGroup::new(now));
+          Some(&mut group_64.total)
+        }
+        _ => None,
+      }
+    } else if let Some(local_user_id) = key.strip_prefix("user:") {
+      let local_user_id = i32::from_str(local_user_id).ok()?;
+      let group = self
+        .user_buckets
+        .entry(local_user_id)
+        .or_insert(RateLimitedGroup::new(now));
+      Some(&mut group.total)
+    } else {
+      None
+    }
+  }
+}
+
+fn export_group<C>(
+  buckets: &mut Vec<PersistedBucket>,
+  key: String,
+  group: &RateLimitedGroup<C>,
+  now: InstantSecs,
+) {
+  let now_utc = Utc::now();
+  for (type_, bucket) in &group.total {
+    if bucket.tokens == UNINITIALIZED_TOKEN_AMOUNT {
+      continue;
+    }
+    let secs_ago = chrono::Duration::seconds(now.secs_since(bucket.last_checked).into());
+    buckets.push(PersistedBucket {
+      key: key.clone(),
+      action: type_.as_ref().to_string(),
+      tokens: bucket.tokens,
+      last_checked: now_utc - secs_ago,
+    });
+  }
+}
+
+fn ipv6_key(prefix: &[u8]) -> String {
+  prefix.iter().fold(String::from("ipv6:"), |mut key, byte| {
+    let _ = write!(key, "{byte:02x}");
+    key
+  })
+}
+
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+  hex
+    .as_bytes()
+    .chunks(2)
+    .map(|pair| match pair {
+      [_, _] => u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok(),
+      _ => None,
+    })
+    .collect()
 }
 
 fn split_ipv6(ip: Ipv6Addr) -> ([u8; 6], u8, u8) {
@@ -307,6 +487,7 @@ mod tests {
           }
         ),]
         .into(),
+        user_buckets: Default::default(),
       }
     );
 
@@ -315,4 +496,51 @@ mod tests {
     assert!(rate_limiter.ipv4_buckets.is_empty());
     assert!(rate_limiter.ipv6_buckets.is_empty());
   }
+
+  #[test]
+  fn test_rate_limiter_user() {
+    let mut rate_limiter = super::RateLimitStorage::default();
+    let now = super::InstantSecs::now();
+
+    for _ in 0..2 {
+      assert!(rate_limiter.check_rate_limit_user(super::RateLimitType::Post, 5, 2, 60, now));
+    }
+    assert!(!rate_limiter.check_rate_limit_user(super::RateLimitType::Post, 5, 2, 60, now));
+    // Other users and actions have their own buckets
+    assert!(rate_limiter.check_rate_limit_user(super::RateLimitType::Post, 6, 2, 60, now));
+    assert!(rate_limiter.check_rate_limit_user(super::RateLimitType::Comment, 5, 2, 60, now));
+  }
+
+  #[test]
+  fn test_export_import() {
+    let mut rate_limiter = super::RateLimitStorage::default();
+    let now = super::InstantSecs::now();
+
+    for ip in ["123.123.123.123", "1:2:3:0405:6::"] {
+      let ip = ip.parse().unwrap();
+      rate_limiter.check_rate_limit_full(super::RateLimitType::Message, ip, 2, 1, now);
+    }
+    rate_limiter.check_rate_limit_user(super::RateLimitType::Post, 5, 3, 1, now);
+
+    let exported = rate_limiter.export(now);
+    let mut keys = exported
+      .iter()
+      .map(|b| format!("{} {}", b.key, b.action))
+      .collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(
+      vec![
+        "ipv4:123.123.123.123 Message",
+        "ipv6:000100020003 Message",
+        "ipv6:00010002000304 Message",
+        "ipv6:0001000200030405 Message",
+        "user:5 Post",
+      ],
+      keys
+    );
+
+    let mut imported = super::RateLimitStorage::default();
+    imported.import(exported, now);
+    assert_eq!(rate_limiter, imported);
+  }
 }
@@ -13,10 +13,25 @@ pub struct Settings {
   /// Pictrs image server configuration.
   #[default(Some(Default::default()))]
   pub(crate) pictrs: Option<PictrsConfig>,
+  /// Where uploaded images are stored. By default they are sent to pictrs.
+  #[default(Default::default())]
+  pub image_storage: ImageStorageConfig,
+  /// Remote images in posts, comments and profiles can be loaded through this instance, so that
+  /// other servers don't see the IP addresses of its users.
+  #[default(Default::default())]
+  pub image_proxy: ImageProxyConfig,
   /// Email sending configuration. All options except login/password are mandatory
   #[default(None)]
   #[doku(example = "Some(Default::default())")]
   pub email: Option<EmailConfig>,
+  /// Web push notification configuration. Push notifications are disabled if this is not set.
+  #[default(None)]
+  #[doku(example = "Some(Default::default())")]
+  pub web_push: Option<WebPushConfig>,
+  /// OpenID Connect providers which users can register and log in with, in addition to a
+  /// username and password.
+  #[default(vec![])]
+  pub oidc_providers: Vec<OidcProviderConfig>,
   /// Parameters for automatic configuration of new instance (only used at first start)
   #[default(None)]
   #[doku(example = "Some(Default::default())")]
@@ -39,12 +54,18 @@ pub struct Settings {
   #[default(None)]
   #[doku(skip)]
   pub opentelemetry_url: Option<Url>,
-  /// The number of activitypub federation workers that can be in-flight concurrently
+  /// The number of outgoing activitypub deliveries that can be in-flight concurrently, 0 for no
+  /// limit
   #[default(0)]
   pub worker_count: usize,
-  /// The number of activitypub federation retry workers that can be in-flight concurrently
+  /// The number of retries of failed outgoing activitypub deliveries that can be in-flight
+  /// concurrently, 0 for no limit
   #[default(0)]
   pub retry_count: usize,
+  /// Instances which couldn't be reached for this many days are marked as dead. No activities are
+  /// sent to dead instances, until they are reachable again.
+  #[default(3)]
+  pub dead_instance_days: u32,
   // Prometheus configuration.
   #[default(None)]
   #[doku(example = "Some(Default::default())")]
@@ -64,6 +85,45 @@ pub struct PictrsConfig {
   pub api_key: Option<String>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageStorageConfig {
+  /// Either "pictrs", or "filesystem" to store images in a local directory without running
+  /// pictrs. The filesystem backend only supports images, and no videos.
+  #[default(ImageStorageBackend::Pictrs)]
+  pub backend: ImageStorageBackend,
+  /// Directory where the filesystem backend stores images
+  #[default("images".to_string())]
+  pub directory: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Document)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageStorageBackend {
+  Pictrs,
+  Filesystem,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImageProxyConfig {
+  /// "off" links remote images directly, "proxy" loads them through this instance and keeps them
+  /// in memory for a while, and "store" keeps a copy of them in the image storage.
+  #[default(ImageProxyMode::Off)]
+  pub mode: ImageProxyMode,
+  /// Size of the memory cache for the "proxy" mode, in megabytes
+  #[default(100)]
+  pub cache_size_mb: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Document)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProxyMode {
+  Off,
+  Proxy,
+  Store,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
 #[serde(default)]
 pub struct DatabaseConfig {
@@ -144,6 +204,52 @@ pub struct EmailConfig {
   pub tls_type: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(deny_unknown_fields)]
+pub struct WebPushConfig {
+  /// Private VAPID key which identifies the instance to push services, as base64url encoded P-256
+  /// private key. Can be generated with `npx web-push generate-vapid-keys`
+  #[doku(example = "private_key_generated_by_web_push")]
+  pub vapid_private_key: String,
+  /// Contact which push services can use in case of problems, eg "mailto:admin@example.com".
+  /// Defaults to the url of the instance
+  #[default(None)]
+  #[doku(example = "mailto:admin@example.com")]
+  pub vapid_subject: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
+#[serde(deny_unknown_fields)]
+pub struct OidcProviderConfig {
+  /// Identifier of the provider, which is used in the API. Changing it unlinks all accounts of
+  /// this provider.
+  #[doku(example = "example")]
+  pub name: String,
+  /// Name of the provider which is shown on the login page
+  #[doku(example = "Example SSO")]
+  pub display_name: String,
+  /// The issuer url, where the discovery document is available under
+  /// `/.well-known/openid-configuration`
+  #[default(Url::parse("http://localhost:8080").expect("parse issuer url"))]
+  #[doku(example = "https://auth.example.com")]
+  pub issuer: Url,
+  /// Client id of the instance at the provider
+  #[doku(example = "lemmy")]
+  pub client_id: String,
+  /// Client secret of the instance at the provider
+  #[doku(example = "client_secret")]
+  pub client_secret: String,
+  /// Scopes which are requested. Email and username are taken from the user info if available.
+  #[default("openid email profile")]
+  #[doku(example = "openid email profile")]
+  #[serde(default = "default_oidc_scopes")]
+  pub scopes: String,
+}
+
+fn default_oidc_scopes() -> String {
+  "openid email profile".to_string()
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document)]
 #[serde(deny_unknown_fields)]
 pub struct SetupConfig {
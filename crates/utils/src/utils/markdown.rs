@@ -1,13 +1,22 @@
-use markdown_it::MarkdownIt;
+use custom_emoji_rule::CustomEmoji;
+use link_rule::{ActorLink, ActorLinkKind};
+use markdown_it::{plugins::cmark::inline::image::Image, MarkdownIt};
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
 
+mod custom_emoji_rule;
+mod link_rule;
 mod spoiler_rule;
 
+pub use custom_emoji_rule::CustomEmojiImage;
+
 static MARKDOWN_PARSER: Lazy<MarkdownIt> = Lazy::new(|| {
   let mut parser = MarkdownIt::new();
   markdown_it::plugins::cmark::add(&mut parser);
   markdown_it::plugins::extra::add(&mut parser);
   spoiler_rule::add(&mut parser);
+  link_rule::add(&mut parser);
+  custom_emoji_rule::add(&mut parser);
 
   parser
 });
@@ -16,9 +25,80 @@ pub fn markdown_to_html(text: &str) -> String {
   MARKDOWN_PARSER.parse(text).xrender()
 }
 
+/// The data of the local instance which is needed to render links to communities and users, and
+/// custom emojis. Without it, they are rendered as plain text.
+pub struct MarkdownContext {
+  /// For example `https://lemmy.ml`, the links point to pages there.
+  pub protocol_and_hostname: String,
+  pub hostname: String,
+  /// The custom emojis of the instance, by shortcode.
+  pub custom_emojis: HashMap<String, CustomEmojiImage>,
+}
+
+impl MarkdownContext {
+  fn actor_url(&self, link: &ActorLink) -> String {
+    let path = match link.kind {
+      ActorLinkKind::Community => "c",
+      ActorLinkKind::Person => "u",
+    };
+    if link.domain == self.hostname {
+      format!("{}/{path}/{}", self.protocol_and_hostname, link.name)
+    } else {
+      format!(
+        "{}/{path}/{}@{}",
+        self.protocol_and_hostname, link.name, link.domain
+      )
+    }
+  }
+}
+
+/// Renders markdown like [markdown_to_html], and also links communities and users, and shows
+/// custom emojis. Raw html in the markdown is escaped, and links with dangerous schemes like
+/// `javascript:` are left out, so the result can be shown to users as is.
+///
+/// Images whose link is in `image_links` are loaded from the replacement link instead, the
+/// markdown itself stays as it is.
+pub fn markdown_to_html_with_context(
+  text: &str,
+  context: &MarkdownContext,
+  image_links: Option<&HashMap<String, String>>,
+) -> String {
+  let mut ast = MARKDOWN_PARSER.parse(text);
+  ast.walk_mut(|node, _depth| {
+    if let Some(image) = node.cast_mut::<Image>() {
+      if let Some(link) = image_links.and_then(|links| links.get(&image.url)) {
+        image.url = link.clone();
+      }
+    } else if let Some(link) = node.cast_mut::<ActorLink>() {
+      link.url = Some(context.actor_url(link));
+    } else if let Some(emoji) = node.cast_mut::<CustomEmoji>() {
+      emoji.image = context.custom_emojis.get(&emoji.shortcode).cloned();
+    }
+  });
+  ast.xrender()
+}
+
+/// The links of all images in the markdown.
+pub fn markdown_image_links(src: &str) -> Vec<String> {
+  let mut links = vec![];
+  MARKDOWN_PARSER.parse(src).walk(|node, _depth| {
+    if let Some(image) = node.cast::<Image>() {
+      links.push(image.url.clone());
+    }
+  });
+  links
+}
+
 #[cfg(test)]
 mod tests {
-  use crate::utils::markdown::markdown_to_html;
+  use crate::utils::markdown::{
+    markdown_image_links,
+    markdown_to_html,
+    markdown_to_html_with_context,
+    CustomEmojiImage,
+    MarkdownContext,
+  };
+  use std::collections::HashMap;
 
   #[test]
   fn test_basic_markdown() {
@@ -63,6 +143,11 @@ mod tests {
         "<p><img src=\"https://image.com\" alt=\"My linked image\" title=\"image alt text\" /></p>\n"
       ),
       // Ensure any custom plugins are added to 'MARKDOWN_PARSER' implementation.
+      (
+        "custom emojis stay as they are",
+        "hello :Wave:",
+        "<p>hello :Wave:</p>\n"
+      ),
       (
         "basic spoiler",
         "::: spoiler click to see more\nhow spicy!\n:::\n",
@@ -80,4 +165,61 @@ mod tests {
       );
     });
   }
+
+  #[test]
+  fn test_markdown_image_links() {
+    let input = "![alt](https://remote.com/a.png \"title\") and ![](https://local.com/b.png)\n\
+      [not an image](https://remote.com/c.png)\n\n`![code](https://remote.com/d.png)`";
+    assert_eq!(
+      vec!["https://remote.com/a.png", "https://local.com/b.png"],
+      markdown_image_links(input)
+    );
+  }
+
+  #[test]
+  fn test_markdown_to_html_with_context() {
+    let context = MarkdownContext {
+      protocol_and_hostname: "https://lemmy.example".to_string(),
+      hostname: "lemmy.example".to_string(),
+      custom_emojis: HashMap::from([(
+        "party".to_string(),
+        CustomEmojiImage {
+          image_url: "https://lemmy.example/pictrs/image/party.gif".to_string(),
+          alt_text: "party".to_string(),
+        },
+      )]),
+    };
+    let input = "Welcome to !main@lemmy.example, @alice@remote.example :party:\n\n\
+      <script>alert(1)</script> [link](javascript:alert(1))";
+    let expected = "<p>Welcome to <a href=\"https://lemmy.example/c/main\" class=\"community-link\">\
+      !main@lemmy.example</a>, <a href=\"https://lemmy.example/u/alice@remote.example\" \
+      class=\"user-link\">@alice@remote.example</a> <img src=\"https://lemmy.example/pictrs/image/party.gif\" \
+      alt=\"party\" title=\"party\" class=\"icon icon-emoji\" /></p>\n\
+      <p>&lt;script&gt;alert(1)&lt;/script&gt; [link](javascript:alert(1))</p>\n";
+    assert_eq!(
+      expected,
+      markdown_to_html_with_context(input, &context, None)
+    );
+
+    // Images are loaded from the given replacement links
+    let image_links = HashMap::from([(
+      "https://remote.example/a.png".to_string(),
+      "https://lemmy.example/image_proxy?url=a".to_string(),
+    )]);
+    assert_eq!(
+      "<p><img src=\"https://lemmy.example/image_proxy?url=a\" alt=\"a\" /> \
+        <img src=\"https://remote.example/b.png\" alt=\"b\" /></p>\n",
+      markdown_to_html_with_context(
+        "![a](https://remote.example/a.png) ![b](https://remote.example/b.png)",
+        &context,
+        Some(&image_links)
+      )
+    );
+
+    // Without the context, they stay as they are
+    assert_eq!(
+      "<p>!main@lemmy.example :party:</p>\n",
+      markdown_to_html("!main@lemmy.example :party:")
+    );
+  }
 }
@@ -0,0 +1,131 @@
+// Custom Markdown plugin for the custom emojis of the instance.
+//
+// FORMAT:
+// Input Markdown: :shortcode:
+// Output HTML: <img src="IMAGE_URL" alt="ALT_TEXT" title="shortcode" class="icon icon-emoji" />
+//
+// The emojis are stored in the database, so the parser only marks the shortcodes. They are
+// rendered as plain text until the image is filled in, see `markdown_to_html_with_context`.
+
+use markdown_it::{
+  parser::inline::{InlineRule, InlineState},
+  MarkdownIt,
+  Node,
+  NodeValue,
+  Renderer,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomEmojiImage {
+  pub image_url: String,
+  pub alt_text: String,
+}
+
+#[derive(Debug)]
+pub struct CustomEmoji {
+  /// Lowercased, for the lookup of the emoji.
+  pub shortcode: String,
+  /// The text as it was written, which is shown if there is no emoji with the shortcode.
+  pub text: String,
+  pub image: Option<CustomEmojiImage>,
+}
+
+static SHORTCODE_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^:([\w+-]+):").expect("compile custom emoji markdown regex."));
+
+impl NodeValue for CustomEmoji {
+  fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+    let Some(image) = &self.image else {
+      fmt.text(&self.text);
+      return;
+    };
+    let mut attrs = node.attrs.clone();
+    attrs.push(("src", image.image_url.clone()));
+    attrs.push(("alt", image.alt_text.clone()));
+    attrs.push(("title", self.shortcode.clone()));
+    attrs.push(("class", "icon icon-emoji".to_string()));
+    fmt.self_close("img", &attrs);
+  }
+}
+
+struct CustomEmojiScanner;
+
+impl InlineRule for CustomEmojiScanner {
+  const MARKER: char = ':';
+
+  fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+    let captures = SHORTCODE_REGEX.captures(&state.src[state.pos..state.pos_max])?;
+    let node = Node::new(CustomEmoji {
+      shortcode: captures[1].to_lowercase(),
+      text: captures[0].to_string(),
+      image: None,
+    });
+    Some((node, captures[0].len()))
+  }
+}
+
+pub fn add(markdown_parser: &mut MarkdownIt) {
+  markdown_parser.inline.add_rule::<CustomEmojiScanner>();
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::markdown::custom_emoji_rule::{add, CustomEmoji, CustomEmojiImage};
+  use markdown_it::MarkdownIt;
+
+  #[test]
+  fn test_custom_emoji_markdown() {
+    let tests: Vec<_> = vec![
+      (
+        "custom emoji",
+        "hello :Wave:!",
+        "<p>hello <img src=\"https://example.com/wave.png\" alt=\"waving hand\" title=\"wave\" class=\"icon icon-emoji\" />!</p>\n",
+      ),
+      (
+        "unknown shortcode",
+        "hello :unknown:",
+        "<p>hello :unknown:</p>\n",
+      ),
+      (
+        "unknown shortcode keeps its case",
+        "Note:Important:",
+        "<p>Note:Important:</p>\n",
+      ),
+      (
+        "time",
+        "at 12:30:00",
+        "<p>at 12:30:00</p>\n",
+      ),
+      (
+        "inline code",
+        "`:wave:`",
+        "<p><code>:wave:</code></p>\n",
+      ),
+    ];
+
+    tests.iter().for_each(|&(msg, input, expected)| {
+      let md = &mut MarkdownIt::new();
+      markdown_it::plugins::cmark::add(md);
+      add(md);
+
+      let mut ast = md.parse(input);
+      ast.walk_mut(|node, _| {
+        if let Some(emoji) = node.cast_mut::<CustomEmoji>() {
+          emoji.image = (emoji.shortcode == "wave").then(|| CustomEmojiImage {
+            image_url: "https://example.com/wave.png".to_string(),
+            alt_text: "waving hand".to_string(),
+          });
+        }
+      });
+      assert_eq!(
+        ast.xrender(),
+        expected,
+        "Testing {}, with original input '{}'",
+        msg,
+        input
+      );
+    });
+  }
+}
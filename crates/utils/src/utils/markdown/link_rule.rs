@@ -0,0 +1,178 @@
+// Custom Markdown plugin for links to communities and users on any instance.
+//
+// FORMAT:
+// Input Markdown: !community@instance.tld and @user@instance.tld
+// Output HTML: <a href="https://local.tld/c/community@instance.tld" class="community-link">
+//   !community@instance.tld</a>, and the same with /u/ and user-link for users
+//
+// The links point to pages on the local instance, so that readers can follow the community or
+// user from there. The parser doesn't know the local instance, so the nodes are rendered as plain
+// text until a url is filled in, see `markdown_to_html_with_context`.
+
+use markdown_it::{
+  parser::inline::{InlineRule, InlineState},
+  MarkdownIt,
+  Node,
+  NodeValue,
+  Renderer,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActorLinkKind {
+  Community,
+  Person,
+}
+
+impl ActorLinkKind {
+  fn marker(self) -> char {
+    match self {
+      ActorLinkKind::Community => '!',
+      ActorLinkKind::Person => '@',
+    }
+  }
+}
+
+#[derive(Debug)]
+pub struct ActorLink {
+  pub kind: ActorLinkKind,
+  pub name: String,
+  pub domain: String,
+  pub url: Option<String>,
+}
+
+// Names like in MentionData, but a trailing dot ends the domain, so that the end of a sentence
+// isn't part of the link.
+static ACTOR_LINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"^(?P<name>[\w.]+)@(?P<domain>[a-zA-Z0-9-]+(\.[a-zA-Z0-9-]+)*(:\d+)?)")
+    .expect("compile actor link markdown regex.")
+});
+
+impl ActorLink {
+  fn text(&self) -> String {
+    format!("{}{}@{}", self.kind.marker(), self.name, self.domain)
+  }
+}
+
+impl NodeValue for ActorLink {
+  fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+    let Some(url) = &self.url else {
+      fmt.text(&self.text());
+      return;
+    };
+    let class = match self.kind {
+      ActorLinkKind::Community => "community-link",
+      ActorLinkKind::Person => "user-link",
+    };
+    let mut attrs = node.attrs.clone();
+    attrs.push(("href", url.clone()));
+    attrs.push(("class", class.to_string()));
+    fmt.open("a", &attrs);
+    fmt.text(&self.text());
+    fmt.close("a");
+  }
+}
+
+fn scan(state: &mut InlineState, kind: ActorLinkKind) -> Option<(Node, usize)> {
+  // Links can't be nested, and the marker must start a word, so that email addresses don't match
+  if state.link_level > 0 || !state.src[state.pos..].starts_with(kind.marker()) {
+    return None;
+  }
+  let previous = state.src[..state.pos].chars().next_back();
+  if previous.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '@' || c == '!') {
+    return None;
+  }
+
+  let start = state.pos + 1;
+  let captures = ACTOR_LINK_REGEX.captures(&state.src[start..state.pos_max])?;
+  let node = Node::new(ActorLink {
+    kind,
+    name: captures["name"].to_string(),
+    domain: captures["domain"].to_string(),
+    url: None,
+  });
+  Some((node, captures[0].len() + 1))
+}
+
+struct CommunityLinkScanner;
+
+impl InlineRule for CommunityLinkScanner {
+  const MARKER: char = '!';
+
+  fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+    scan(state, ActorLinkKind::Community)
+  }
+}
+
+struct PersonLinkScanner;
+
+impl InlineRule for PersonLinkScanner {
+  const MARKER: char = '@';
+
+  fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+    scan(state, ActorLinkKind::Person)
+  }
+}
+
+pub fn add(markdown_parser: &mut MarkdownIt) {
+  markdown_parser.inline.add_rule::<CommunityLinkScanner>();
+  markdown_parser.inline.add_rule::<PersonLinkScanner>();
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::utils::markdown::link_rule::{add, ActorLink};
+  use markdown_it::MarkdownIt;
+
+  #[test]
+  fn test_actor_link_markdown() {
+    let tests: Vec<_> = vec![
+      (
+        "community link",
+        "visit !lemmy@lemmy.ml.",
+        "<p>visit <a href=\"/lemmy@lemmy.ml\" class=\"community-link\">!lemmy@lemmy.ml</a>.</p>\n",
+      ),
+      (
+        "user link with port",
+        "thanks @dessalines@localhost:8536",
+        "<p>thanks <a href=\"/dessalines@localhost:8536\" class=\"user-link\">@dessalines@localhost:8536</a></p>\n",
+      ),
+      (
+        "email address",
+        "mail me@example.com",
+        "<p>mail me@example.com</p>\n",
+      ),
+      (
+        "image",
+        "![alt](https://example.com/image.png)",
+        "<p><img src=\"https://example.com/image.png\" alt=\"alt\" /></p>\n",
+      ),
+      (
+        "inside of a link",
+        "[!lemmy@lemmy.ml](https://lemmy.ml/c/lemmy)",
+        "<p><a href=\"https://lemmy.ml/c/lemmy\">!lemmy@lemmy.ml</a></p>\n",
+      ),
+    ];
+
+    tests.iter().for_each(|&(msg, input, expected)| {
+      let md = &mut MarkdownIt::new();
+      markdown_it::plugins::cmark::add(md);
+      add(md);
+
+      let mut ast = md.parse(input);
+      ast.walk_mut(|node, _| {
+        if let Some(link) = node.cast_mut::<ActorLink>() {
+          link.url = Some(format!("/{}@{}", link.name, link.domain));
+        }
+      });
+      assert_eq!(
+        ast.xrender(),
+        expected,
+        "Testing {}, with original input '{}'",
+        msg,
+        input
+      );
+    });
+  }
+}
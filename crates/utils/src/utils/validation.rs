@@ -2,8 +2,9 @@ use crate::error::{LemmyError, LemmyResult};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use regex::{Regex, RegexBuilder};
+use std::net::IpAddr;
 use totp_rs::{Secret, TOTP};
-use url::Url;
+use url::{Host, Url};
 
 static VALID_ACTOR_NAME_REGEX: Lazy<Regex> =
   Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_]{3,}$").expect("compile regex"));
@@ -12,6 +13,10 @@ static VALID_POST_TITLE_REGEX: Lazy<Regex> =
 static VALID_MATRIX_ID_REGEX: Lazy<Regex> = Lazy::new(|| {
   Regex::new(r"^@[A-Za-z0-9._=-]+:[A-Za-z0-9.-]+\.[A-Za-z]{2,}$").expect("compile regex")
 });
+static VALID_THEME_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^[a-zA-Z0-9_.-]{1,50}$").expect("compile regex"));
+static VALID_INTERFACE_LANGUAGE_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"^[a-zA-Z_-]{2,20}$").expect("compile regex"));
 // taken from https://en.wikipedia.org/wiki/UTM_parameters
 static CLEAN_URL_PARAMS_REGEX: Lazy<Regex> = Lazy::new(|| {
   Regex::new(r"^utm_source|utm_medium|utm_campaign|utm_term|utm_content|gclid|gclsrc|dclid|fbclid$")
@@ -24,6 +29,10 @@ const BIO_MAX_LENGTH: usize = 300;
 const SITE_NAME_MAX_LENGTH: usize = 20;
 const SITE_NAME_MIN_LENGTH: usize = 1;
 const SITE_DESCRIPTION_MAX_LENGTH: usize = 150;
+const POLL_OPTIONS_MIN: usize = 2;
+const POLL_OPTIONS_MAX: usize = 20;
+const POLL_OPTION_MAX_LENGTH: usize = 200;
+const POST_TAG_MAX_LENGTH: usize = 50;
 //Invisible unicode characters, taken from https://invisible-characters.com/
 const FORBIDDEN_DISPLAY_CHARS: [char; 53] = [
   '\u{0009}',
@@ -147,10 +156,55 @@ pub fn is_valid_body_field(body: &Option<String>, post: bool) -> LemmyResult<()>
   }
 }
 
+/// Checks the number of poll options, and that each option is non-empty and unique.
+pub fn is_valid_poll_options(options: &[String]) -> LemmyResult<()> {
+  if options.len() < POLL_OPTIONS_MIN || options.len() > POLL_OPTIONS_MAX {
+    return Err(LemmyError::from_message("invalid_poll_option_count"));
+  }
+  for option in options {
+    if option.trim().is_empty() || option.chars().count() > POLL_OPTION_MAX_LENGTH {
+      return Err(LemmyError::from_message("invalid_poll_option"));
+    }
+  }
+  if !options.iter().all_unique() {
+    return Err(LemmyError::from_message("duplicate_poll_option"));
+  }
+  Ok(())
+}
+
+/// Post tags are shown next to the title, so they need to be short and on a single line.
+pub fn is_valid_post_tag_name(name: &str) -> LemmyResult<()> {
+  let check =
+    !name.trim().is_empty() && name.chars().count() <= POST_TAG_MAX_LENGTH && !has_newline(name);
+  if !check {
+    Err(LemmyError::from_message("invalid_post_tag_name"))
+  } else {
+    Ok(())
+  }
+}
+
 pub fn is_valid_bio_field(bio: &str) -> LemmyResult<()> {
   max_length_check(bio, BIO_MAX_LENGTH, String::from("bio_length_overflow"))
 }
 
+/// Themes are file names of the frontend, like `darkly` or `litely-red`.
+pub fn is_valid_theme(theme: &str) -> LemmyResult<()> {
+  if VALID_THEME_REGEX.is_match(theme) {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("invalid_theme"))
+  }
+}
+
+/// Interface languages are language codes like `pt_BR`, or `browser`.
+pub fn is_valid_interface_language(language: &str) -> LemmyResult<()> {
+  if VALID_INTERFACE_LANGUAGE_REGEX.is_match(language) {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("invalid_interface_language"))
+  }
+}
+
 /// Checks the site name length, the limit as defined in the DB.
 pub fn site_name_length_check(name: &str) -> LemmyResult<()> {
   min_max_length_check(
@@ -302,19 +356,116 @@ pub fn check_site_visibility_valid(
   Ok(())
 }
 
+/// Checks that the allowed upload types are MIME types like `image/png`, or wildcards for all
+/// subtypes like `image/*`.
+pub fn check_mime_type_patterns(patterns: &[String]) -> LemmyResult<()> {
+  let valid = patterns
+    .iter()
+    .all(|pattern| match pattern.split_once('/') {
+      Some((type_, subtype)) => {
+        !type_.is_empty()
+          && type_ != "*"
+          && !subtype.is_empty()
+          && !subtype.contains('/')
+          && !pattern.contains(char::is_whitespace)
+      }
+      None => false,
+    });
+  if !valid {
+    Err(LemmyError::from_message("invalid_mime_type"))
+  } else {
+    Ok(())
+  }
+}
+
+/// Whether an upload with the given MIME type matches one of the allowed patterns.
+pub fn is_allowed_mime_type(patterns: &[String], mime_type: &str) -> bool {
+  let Some((type_, _)) = mime_type.split_once('/') else {
+    return false;
+  };
+  patterns
+    .iter()
+    .any(|pattern| match pattern.strip_suffix("/*") {
+      Some(allowed_type) => allowed_type.eq_ignore_ascii_case(type_),
+      None => pattern.eq_ignore_ascii_case(mime_type),
+    })
+}
+
+/// Whether the address is reachable on the public internet. Requests to other addresses could
+/// reach services on the local network of the server, like the database or pictrs.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+  match ip {
+    IpAddr::V4(ip) => {
+      let [first, second, ..] = ip.octets();
+      !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "This network", shared address space for carrier-grade NAT, benchmarking and reserved
+        || first == 0
+        || (first == 100 && (second & 0xc0) == 64)
+        || (first == 198 && (second & 0xfe) == 18)
+        || first >= 240)
+    }
+    IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+      Some(ip) => is_public_ip(ip.into()),
+      None => {
+        let first = ip.segments()[0];
+        !(ip.is_loopback()
+          || ip.is_unspecified()
+          || ip.is_multicast()
+          // Unique local and link local addresses
+          || (first & 0xfe00) == 0xfc00
+          || (first & 0xffc0) == 0xfe80)
+      }
+    },
+  }
+}
+
+/// Checks the host of the url, without resolving it. Domains can still resolve to private
+/// addresses, so this has to be checked again before connecting.
+pub fn is_public_url(url: &Url) -> bool {
+  if !matches!(url.scheme(), "http" | "https") {
+    return false;
+  }
+  match url.host() {
+    Some(Host::Domain(domain)) => {
+      let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+      domain.contains('.')
+        && !domain.ends_with(".localhost")
+        && !domain.ends_with(".local")
+        && !domain.ends_with(".internal")
+    }
+    Some(Host::Ipv4(ip)) => is_public_ip(ip.into()),
+    Some(Host::Ipv6(ip)) => is_public_ip(ip.into()),
+    None => false,
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::build_totp_2fa;
   use crate::utils::validation::{
     build_and_check_regex,
+    check_mime_type_patterns,
     check_site_visibility_valid,
     clean_url_params,
     generate_totp_2fa_secret,
+    is_allowed_mime_type,
+    is_public_ip,
+    is_public_url,
     is_valid_actor_name,
     is_valid_bio_field,
     is_valid_display_name,
+    is_valid_interface_language,
     is_valid_matrix_id,
+    is_valid_poll_options,
+    is_valid_post_tag_name,
     is_valid_post_title,
+    is_valid_theme,
     site_description_length_check,
     site_name_length_check,
     BIO_MAX_LENGTH,
@@ -373,6 +524,24 @@ mod tests {
     assert!(is_valid_post_title("\n \n \n \n    		").is_err()); // tabs/spaces/newlines
   }
 
+  #[test]
+  fn test_valid_poll_options() {
+    let options = |o: &[&str]| o.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert!(is_valid_poll_options(&options(&["yes", "no"])).is_ok());
+    assert!(is_valid_poll_options(&options(&["yes"])).is_err());
+    assert!(is_valid_poll_options(&options(&["yes", " "])).is_err());
+    assert!(is_valid_poll_options(&options(&["yes", "yes"])).is_err());
+    assert!(is_valid_poll_options(&vec!["x".to_string(); 21]).is_err());
+  }
+
+  #[test]
+  fn test_valid_post_tag_name() {
+    assert!(is_valid_post_tag_name("Question").is_ok());
+    assert!(is_valid_post_tag_name("  ").is_err());
+    assert!(is_valid_post_tag_name("two\nlines").is_err());
+    assert!(is_valid_post_tag_name(&"x".repeat(51)).is_err());
+  }
+
   #[test]
   fn test_valid_matrix_id() {
     assert!(is_valid_matrix_id("@dess:matrix.org").is_ok());
@@ -381,6 +550,18 @@ mod tests {
     assert!(is_valid_matrix_id("@dess:matrix.org t").is_err());
   }
 
+  #[test]
+  fn test_valid_theme_and_interface_language() {
+    assert!(is_valid_theme("browser").is_ok());
+    assert!(is_valid_theme("litely-red").is_ok());
+    assert!(is_valid_theme("../theme").is_err());
+    assert!(is_valid_theme("").is_err());
+    assert!(is_valid_interface_language("pt_BR").is_ok());
+    assert!(is_valid_interface_language("zh-Hant").is_ok());
+    assert!(is_valid_interface_language("e").is_err());
+    assert!(is_valid_interface_language(&"x".repeat(21)).is_err());
+  }
+
   #[test]
   fn test_build_totp() {
     let generated_secret = generate_totp_2fa_secret();
@@ -519,4 +700,56 @@ mod tests {
     assert!(check_site_visibility_valid(false, false, &Some(true), &None).is_ok());
     assert!(check_site_visibility_valid(false, false, &None, &Some(true)).is_ok());
   }
+
+  #[test]
+  fn test_mime_types() {
+    let patterns = vec!["image/*".to_string(), "video/mp4".to_string()];
+    assert!(check_mime_type_patterns(&patterns).is_ok());
+    assert!(check_mime_type_patterns(&["image".to_string()]).is_err());
+    assert!(check_mime_type_patterns(&["*/*".to_string()]).is_err());
+    assert!(check_mime_type_patterns(&["image/ png".to_string()]).is_err());
+
+    assert!(is_allowed_mime_type(&patterns, "image/png"));
+    assert!(is_allowed_mime_type(&patterns, "Image/WEBP"));
+    assert!(is_allowed_mime_type(&patterns, "video/mp4"));
+    assert!(!is_allowed_mime_type(&patterns, "video/webm"));
+    assert!(!is_allowed_mime_type(&patterns, "image"));
+    assert!(!is_allowed_mime_type(&patterns, "application/pdf"));
+    assert!(!is_allowed_mime_type(&[], "image/png"));
+  }
+
+  #[test]
+  fn test_public_addresses() {
+    for ip in ["1.1.1.1", "93.184.216.34", "2606:4700:4700::1111"] {
+      assert!(is_public_ip(ip.parse().unwrap()), "{ip}");
+    }
+    for ip in [
+      "127.0.0.1",
+      "10.0.0.1",
+      "172.17.0.2",
+      "192.168.1.1",
+      "169.254.169.254",
+      "100.64.0.1",
+      "0.0.0.0",
+      "::1",
+      "fd00::1",
+      "fe80::1",
+      "::ffff:127.0.0.1",
+    ] {
+      assert!(!is_public_ip(ip.parse().unwrap()), "{ip}");
+    }
+
+    let url = |url: &str| Url::parse(url).unwrap();
+    assert!(is_public_url(&url("https://example.com/image.png")));
+    assert!(is_public_url(&url("http://1.1.1.1/image.png")));
+    assert!(!is_public_url(&url("https://localhost/image.png")));
+    assert!(!is_public_url(&url(
+      "http://pictrs:8080/image/original/a.png"
+    )));
+    assert!(!is_public_url(&url(
+      "http://169.254.169.254/latest/meta-data"
+    )));
+    assert!(!is_public_url(&url("http://[::1]/image.png")));
+    assert!(!is_public_url(&url("ftp://example.com/image.png")));
+  }
 }
@@ -0,0 +1,298 @@
+use crate::{
+  error::LemmyError,
+  settings::structs::WebPushConfig,
+  utils::validation::is_public_url,
+};
+use base64::URL_SAFE_NO_PAD;
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use openssl::{
+  bn::{BigNum, BigNumContext},
+  derive::Deriver,
+  ec::{EcGroup, EcKey, EcPoint, PointConversionForm},
+  hash::MessageDigest,
+  nid::Nid,
+  pkey::{PKey, Private, Public},
+  rand::rand_bytes,
+  sign::Signer,
+  symm::{encrypt_aead, Cipher},
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Size of the single record which the payload is encrypted into. Push services accept at most
+/// 4096 bytes.
+const RECORD_SIZE: u32 = 4096;
+
+/// Length of the authentication tag which AES-GCM appends to the record.
+const TAG_LENGTH: usize = 16;
+
+/// Length of the auth secret of a push subscription.
+const AUTH_SECRET_LENGTH: usize = 16;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VapidClaims {
+  /// Origin of the push service.
+  aud: String,
+  exp: i64,
+  /// Contact of the instance admin.
+  sub: String,
+}
+
+/// Checks that notifications can be sent to a push subscription.
+pub fn validate_subscription(endpoint: &str, p256dh: &str, auth: &str) -> Result<(), LemmyError> {
+  // Push services are only reachable over https, and never in the local network of the server
+  let valid = Url::parse(endpoint).is_ok_and(|e| e.scheme() == "https" && is_public_url(&e))
+    && decode_public_key(p256dh).is_ok()
+    && decode_base64(auth).map(|a| a.len()) == Some(AUTH_SECRET_LENGTH);
+  if valid {
+    Ok(())
+  } else {
+    Err(LemmyError::from_message("invalid_push_subscription"))
+  }
+}
+
+/// The public VAPID key, which clients pass to the browser when subscribing.
+pub fn vapid_public_key(config: &WebPushConfig) -> Result<String, LemmyError> {
+  let key = vapid_key(config)?;
+  Ok(base64::encode_config(
+    public_key_bytes(&key)?,
+    URL_SAFE_NO_PAD,
+  ))
+}
+
+/// Builds the `Authorization` header for a push request to the given endpoint, as described in
+/// RFC 8292.
+pub fn vapid_authorization(
+  config: &WebPushConfig,
+  endpoint: &Url,
+  protocol_and_hostname: &str,
+) -> Result<String, LemmyError> {
+  let key = vapid_key(config)?;
+  let claims = VapidClaims {
+    aud: endpoint.origin().ascii_serialization(),
+    // Push services reject tokens which are valid for more than 24 hours
+    exp: (Utc::now() + Duration::hours(12)).timestamp(),
+    sub: config
+      .vapid_subject
+      .clone()
+      .unwrap_or_else(|| protocol_and_hostname.to_string()),
+  };
+  let pkcs8 = PKey::from_ec_key(key.clone())?.private_key_to_pkcs8()?;
+  let token = encode(
+    &Header::new(Algorithm::ES256),
+    &claims,
+    &EncodingKey::from_ec_der(&pkcs8),
+  )?;
+  let public_key = base64::encode_config(public_key_bytes(&key)?, URL_SAFE_NO_PAD);
+  Ok(format!("vapid t={token}, k={public_key}"))
+}
+
+/// Encrypts the payload for a push subscription with the `aes128gcm` content encoding, as
+/// described in RFC 8291.
+pub fn encrypt_payload(payload: &[u8], p256dh: &str, auth: &str) -> Result<Vec<u8>, LemmyError> {
+  let receiver_key = decode_public_key(p256dh)?;
+  let auth_secret =
+    decode_base64(auth).ok_or_else(|| LemmyError::from_message("invalid_push_subscription"))?;
+  // A new key and salt for every message, so that messages can't be linked to each other
+  let group = p256_group()?;
+  let sender_key = EcKey::generate(&group)?;
+  let mut salt = [0; 16];
+  rand_bytes(&mut salt)?;
+  encrypt_with(payload, &receiver_key, &auth_secret, &sender_key, &salt)
+}
+
+fn encrypt_with(
+  payload: &[u8],
+  receiver_key: &EcKey<Public>,
+  auth_secret: &[u8],
+  sender_key: &EcKey<Private>,
+  salt: &[u8],
+) -> Result<Vec<u8>, LemmyError> {
+  // The payload is followed by a delimiter byte, and the record by the authentication tag
+  if payload.len() + 1 + TAG_LENGTH > RECORD_SIZE as usize {
+    return Err(LemmyError::from_message("push_payload_too_large"));
+  }
+
+  let receiver_public = public_key_bytes(receiver_key)?;
+  let sender_public = public_key_bytes(sender_key)?;
+  let sender_pkey = PKey::from_ec_key(sender_key.clone())?;
+  let receiver_pkey = PKey::from_ec_key(receiver_key.clone())?;
+  let mut deriver = Deriver::new(&sender_pkey)?;
+  deriver.set_peer(&receiver_pkey)?;
+  let ecdh_secret = deriver.derive_to_vec()?;
+
+  let key_info = [b"WebPush: info\0", &receiver_public[..], &sender_public[..]].concat();
+  let ikm = hkdf(auth_secret, &ecdh_secret, &key_info, 32)?;
+  let content_key = hkdf(salt, &ikm, b"Content-Encoding: aes128gcm\0", 16)?;
+  let nonce = hkdf(salt, &ikm, b"Content-Encoding: nonce\0", 12)?;
+
+  let mut plaintext = payload.to_vec();
+  // Marks the last (and only) record
+  plaintext.push(2);
+  let mut tag = [0; TAG_LENGTH];
+  let ciphertext = encrypt_aead(
+    Cipher::aes_128_gcm(),
+    &content_key,
+    Some(&nonce),
+    &[],
+    &plaintext,
+    &mut tag,
+  )?;
+
+  let mut body = salt.to_vec();
+  body.extend(RECORD_SIZE.to_be_bytes());
+  body.push(u8::try_from(sender_public.len())?);
+  body.extend(sender_public);
+  body.extend(ciphertext);
+  body.extend(tag);
+  Ok(body)
+}
+
+/// HKDF with SHA-256, for output lengths of up to one hash.
+fn hkdf(salt: &[u8], ikm: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, LemmyError> {
+  let prk = hmac_sha256(salt, &[ikm])?;
+  let mut okm = hmac_sha256(&prk, &[info, &[1]])?;
+  okm.truncate(length);
+  Ok(okm)
+}
+
+fn hmac_sha256(key: &[u8], data: &[&[u8]]) -> Result<Vec<u8>, LemmyError> {
+  let key = PKey::hmac(key)?;
+  let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+  for d in data {
+    signer.update(d)?;
+  }
+  Ok(signer.sign_to_vec()?)
+}
+
+fn vapid_key(config: &WebPushConfig) -> Result<EcKey<Private>, LemmyError> {
+  let private_number = decode_base64(&config.vapid_private_key)
+    .map(|k| BigNum::from_slice(&k))
+    .ok_or_else(|| LemmyError::from_message("invalid_vapid_private_key"))??;
+  let group = p256_group()?;
+  let mut public_point = EcPoint::new(&group)?;
+  let context = BigNumContext::new()?;
+  public_point.mul_generator(&group, &private_number, &context)?;
+  let key = EcKey::from_private_components(&group, &private_number, &public_point)?;
+  key.check_key()?;
+  Ok(key)
+}
+
+fn decode_public_key(p256dh: &str) -> Result<EcKey<Public>, LemmyError> {
+  let bytes =
+    decode_base64(p256dh).ok_or_else(|| LemmyError::from_message("invalid_push_subscription"))?;
+  let group = p256_group()?;
+  let mut context = BigNumContext::new()?;
+  let point = EcPoint::from_bytes(&group, &bytes, &mut context)?;
+  Ok(EcKey::from_public_key(&group, &point)?)
+}
+
+fn public_key_bytes<T>(key: &EcKey<T>) -> Result<Vec<u8>, LemmyError>
+where
+  T: openssl::pkey::HasPublic,
+{
+  let mut context = BigNumContext::new()?;
+  Ok(
+    key
+      .public_key()
+      .to_bytes(key.group(), PointConversionForm::UNCOMPRESSED, &mut context)?,
+  )
+}
+
+fn p256_group() -> Result<EcGroup, LemmyError> {
+  Ok(EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?)
+}
+
+/// Browsers encode the keys as base64url, but some clients add padding.
+fn decode_base64(value: &str) -> Option<Vec<u8>> {
+  base64::decode_config(value.trim_end_matches('='), URL_SAFE_NO_PAD).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::unwrap_used)]
+
+  use super::*;
+  use jsonwebtoken::{decode, DecodingKey, Validation};
+
+  fn decode_private_key(private: &str, public: &str) -> EcKey<Private> {
+    let public = decode_public_key(public).unwrap();
+    let private_number = BigNum::from_slice(&decode_base64(private).unwrap()).unwrap();
+    EcKey::from_private_components(public.group(), &private_number, public.public_key()).unwrap()
+  }
+
+  /// The example of RFC 8291, appendix A.
+  #[test]
+  fn test_encrypt_payload() {
+    let receiver_key = decode_public_key(
+      "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4",
+    )
+    .unwrap();
+    let sender_key = decode_private_key(
+      "yfWPiYE-n46HLnH0KqZOF1fJJU3MYrct3AELtAQ-oRw",
+      "BP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A8",
+    );
+    let auth_secret = decode_base64("BTBZMqHH6r4Tts7J_aSIgg").unwrap();
+    let salt = decode_base64("DGv6ra1nlYgDCS1FRnbzlw").unwrap();
+
+    let body = encrypt_with(
+      b"When I grow up, I want to be a watermelon",
+      &receiver_key,
+      &auth_secret,
+      &sender_key,
+      &salt,
+    )
+    .unwrap();
+    assert_eq!(
+      "DGv6ra1nlYgDCS1FRnbzlwAAEABBBP4z9KsN6nGRTbVYI_c7VJSPQTBtkgcy27mlmlMoZIIgDll6e3vCYLocInmYWAmS6TlzAC8wEqKK6PBru3jl7A_yl95bQpu6cVPTpK4Mqgkf1CXztLVBSt2Ks3oZwbuwXPXLWyouBWLVWGNWQexSgSxsj_Qulcy4a-fN",
+      base64::encode_config(body, URL_SAFE_NO_PAD)
+    );
+
+    assert!(encrypt_with(&[0; 4080], &receiver_key, &auth_secret, &sender_key, &salt).is_err());
+  }
+
+  #[test]
+  fn test_vapid_authorization() {
+    let config = WebPushConfig {
+      vapid_private_key: "IQ9Ur0ykXoHS9gzfYX0aBjy9lvdrjx_PFUXmie9YRcY".to_string(),
+      vapid_subject: None,
+    };
+    let endpoint = Url::parse("https://push.example.com/send/abc").unwrap();
+    let authorization =
+      vapid_authorization(&config, &endpoint, "https://lemmy.example.com").unwrap();
+
+    let (token, public_key) = authorization
+      .strip_prefix("vapid t=")
+      .and_then(|a| a.split_once(", k="))
+      .unwrap();
+    assert_eq!(vapid_public_key(&config).unwrap(), public_key);
+
+    let public_pem = vapid_key(&config).unwrap().public_key_to_pem().unwrap();
+    let mut validation = Validation::new(Algorithm::ES256);
+    validation.set_audience(&["https://push.example.com"]);
+    let claims = decode::<VapidClaims>(
+      token,
+      &DecodingKey::from_ec_pem(&public_pem).unwrap(),
+      &validation,
+    )
+    .unwrap()
+    .claims;
+    assert_eq!("https://lemmy.example.com", claims.sub);
+  }
+
+  #[test]
+  fn test_validate_subscription() {
+    let endpoint = "https://push.example.com/send/abc";
+    let p256dh =
+      "BCVxsr7N_eNgVRqvHtD0zTZsEc6-VV-JvLexhqUzORcxaOzi6-AYWXvTBHm4bjyPjs7Vd8pZGH6SRpkNtoIAiw4";
+    let auth = "BTBZMqHH6r4Tts7J_aSIgg";
+    assert!(validate_subscription(endpoint, p256dh, auth).is_ok());
+    assert!(validate_subscription(endpoint, p256dh, "BTBZMqHH6r4Tts7J_aSIgg==").is_ok());
+    assert!(validate_subscription("http://push.example.com/send", p256dh, auth).is_err());
+    assert!(validate_subscription("https://localhost/send", p256dh, auth).is_err());
+    assert!(validate_subscription("https://10.0.0.1/send", p256dh, auth).is_err());
+    assert!(validate_subscription(endpoint, p256dh, "short").is_err());
+    assert!(validate_subscription(endpoint, "BCVxsr7N", auth).is_err());
+  }
+}
@@ -0,0 +1,16 @@
+-- Tokens issued before this migration have no row and no expiry. They are not logged out, but
+-- stored with a fresh expiry on first use, see validate_login_token().
+-- Store every issued login token, so that sessions can be listed, expired and revoked
+create table login_token (
+  id serial primary key,
+  token_encrypted text not null unique,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  ip text,
+  user_agent text,
+  published timestamptz not null default now(),
+  last_used timestamptz not null default now(),
+  expires timestamptz not null
+);
+
+create index idx_login_token_local_user on login_token (local_user_id);
+create index idx_login_token_expires on login_token (expires);
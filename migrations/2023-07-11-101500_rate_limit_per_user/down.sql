@@ -0,0 +1,8 @@
+alter table local_site_rate_limit
+  drop column message_per_user,
+  drop column post_per_user,
+  drop column image_per_user,
+  drop column comment_per_user,
+  drop column search_per_user;
+
+drop table rate_limit_bucket;
@@ -0,0 +1,18 @@
+-- Per-account rate limits, which apply in addition to the per-ip limits. They use the same
+-- interval lengths as the corresponding ip limits.
+alter table local_site_rate_limit
+  add column message_per_user int not null default 180,
+  add column post_per_user int not null default 6,
+  add column image_per_user int not null default 6,
+  add column comment_per_user int not null default 6,
+  add column search_per_user int not null default 60;
+
+-- In-memory rate limit buckets are written here periodically and on shutdown, so that
+-- they survive restarts
+create table rate_limit_bucket (
+  key text not null,
+  action text not null,
+  tokens real not null,
+  last_checked timestamptz not null,
+  primary key (key, action)
+);
@@ -0,0 +1,5 @@
+drop table federation_queue_state;
+
+drop table sent_activity;
+
+drop type actor_type_enum;
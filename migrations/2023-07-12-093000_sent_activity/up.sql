@@ -0,0 +1,22 @@
+create type actor_type_enum as enum ('Site', 'Community', 'Person');
+
+-- Outgoing activities which need to be delivered to remote instances. The activity json itself
+-- is stored in the activity table.
+create table sent_activity (
+  id serial primary key,
+  activity_id int references activity on update cascade on delete cascade not null unique,
+  send_inboxes text[] not null,
+  actor_type actor_type_enum not null,
+  actor_apub_id text not null,
+  published timestamptz not null default now()
+);
+
+-- Delivery cursor for each remote instance, pointing to the last sent_activity which was
+-- delivered to it
+create table federation_queue_state (
+  id serial primary key,
+  instance_id int references instance on update cascade on delete cascade not null unique,
+  last_successful_id int not null,
+  fail_count int not null default 0,
+  last_retry timestamptz
+);
@@ -0,0 +1,7 @@
+alter table federation_queue_state
+  drop column last_successful_send,
+  drop column inbox_latency_ms;
+
+alter table instance
+  drop column last_received_activity,
+  drop column dead;
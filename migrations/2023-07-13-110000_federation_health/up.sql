@@ -0,0 +1,10 @@
+-- Delivery statistics for the outgoing activity queue of each remote instance
+alter table federation_queue_state
+  add column last_successful_send timestamptz,
+  add column inbox_latency_ms int;
+
+-- Instances which couldn't be reached for a while are marked as dead, and no activities are sent
+-- to them until they are reachable again
+alter table instance
+  add column last_received_activity timestamptz,
+  add column dead boolean not null default false;
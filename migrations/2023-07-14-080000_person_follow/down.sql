@@ -0,0 +1,21 @@
+-- update the default listing types
+update local_user set default_listing_type = 'Subscribed' where default_listing_type = 'FollowedUsers';
+
+-- rename the old enum
+alter type listing_type_enum rename to listing_type_enum__;
+-- create the new enum
+CREATE TYPE listing_type_enum AS ENUM ('All', 'Local', 'Subscribed');
+
+-- alter all your enum columns
+alter table local_user alter column default_listing_type drop default;
+alter table local_user
+  alter column default_listing_type type listing_type_enum using default_listing_type::text::listing_type_enum;
+alter table local_user alter column default_listing_type set default 'Local';
+
+alter table local_site alter column default_post_listing_type drop default;
+alter table local_site
+  alter column default_post_listing_type type listing_type_enum using default_post_listing_type::text::listing_type_enum;
+alter table local_site alter column default_post_listing_type set default 'Local';
+
+-- drop the old enum
+drop type listing_type_enum__;
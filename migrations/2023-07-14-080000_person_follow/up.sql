@@ -0,0 +1 @@
+ALTER TYPE listing_type_enum ADD VALUE 'FollowedUsers';
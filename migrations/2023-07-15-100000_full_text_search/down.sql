@@ -0,0 +1,23 @@
+-- update the default sort type
+update local_user set default_sort_type = 'Hot' where default_sort_type = 'Relevance';
+
+-- rename the old enum
+alter type sort_type_enum rename to sort_type_enum__;
+-- create the new enum
+CREATE TYPE sort_type_enum AS ENUM ('Active', 'Hot', 'New', 'Old', 'TopDay', 'TopWeek', 'TopMonth', 'TopYear', 'TopAll', 'MostComments', 'NewComments', 'TopHour', 'TopSixHour', 'TopTwelveHour', 'TopThreeMonths', 'TopSixMonths', 'TopNineMonths');
+
+-- alter all your enum columns
+alter table local_user alter column default_sort_type drop default;
+alter table local_user
+  alter column default_sort_type type sort_type_enum using default_sort_type::text::sort_type_enum;
+alter table local_user alter column default_sort_type set default 'Active';
+
+-- drop the old enum
+drop type sort_type_enum__;
+
+alter table post drop column search_vector;
+alter table comment drop column search_vector;
+alter table community drop column search_vector;
+
+drop function lemmy_search_query;
+drop function lemmy_ts_config;
@@ -0,0 +1,103 @@
+-- Text search configuration for a language_id. Language ids are fixed, so this can be immutable,
+-- which is required for use in generated columns. Languages without a matching configuration
+-- use 'simple', which doesn't do any stemming.
+create function lemmy_ts_config (language_id int)
+  returns regconfig
+  language sql
+  immutable parallel safe
+  as $$
+  select case language_id
+    when 8 then 'arabic'
+    when 22 then 'catalan'
+    when 31 then 'danish'
+    when 32 then 'german'
+    when 36 then 'greek'
+    when 37 then 'english'
+    when 39 then 'spanish'
+    when 41 then 'basque'
+    when 44 then 'finnish'
+    when 47 then 'french'
+    when 49 then 'irish'
+    when 57 then 'hindi'
+    when 61 then 'hungarian'
+    when 62 then 'armenian'
+    when 65 then 'indonesian'
+    when 72 then 'italian'
+    when 97 then 'lithuanian'
+    when 111 then 'norwegian'
+    when 113 then 'nepali'
+    when 115 then 'dutch'
+    when 116 then 'norwegian'
+    when 117 then 'norwegian'
+    when 130 then 'portuguese'
+    when 134 then 'romanian'
+    when 135 then 'russian'
+    when 149 then 'serbian'
+    when 153 then 'swedish'
+    when 155 then 'tamil'
+    when 164 then 'turkish'
+    when 179 then 'yiddish'
+    else 'simple'
+  end::regconfig
+$$;
+
+-- Parses a search query with every text search configuration, so that it matches content in any
+-- language.
+create function lemmy_search_query (q text)
+  returns tsquery
+  language sql
+  immutable parallel safe
+  as $$
+  select websearch_to_tsquery('simple', q) ||
+    websearch_to_tsquery('arabic', q) ||
+    websearch_to_tsquery('armenian', q) ||
+    websearch_to_tsquery('basque', q) ||
+    websearch_to_tsquery('catalan', q) ||
+    websearch_to_tsquery('danish', q) ||
+    websearch_to_tsquery('dutch', q) ||
+    websearch_to_tsquery('english', q) ||
+    websearch_to_tsquery('finnish', q) ||
+    websearch_to_tsquery('french', q) ||
+    websearch_to_tsquery('german', q) ||
+    websearch_to_tsquery('greek', q) ||
+    websearch_to_tsquery('hindi', q) ||
+    websearch_to_tsquery('hungarian', q) ||
+    websearch_to_tsquery('indonesian', q) ||
+    websearch_to_tsquery('irish', q) ||
+    websearch_to_tsquery('italian', q) ||
+    websearch_to_tsquery('lithuanian', q) ||
+    websearch_to_tsquery('nepali', q) ||
+    websearch_to_tsquery('norwegian', q) ||
+    websearch_to_tsquery('portuguese', q) ||
+    websearch_to_tsquery('romanian', q) ||
+    websearch_to_tsquery('russian', q) ||
+    websearch_to_tsquery('serbian', q) ||
+    websearch_to_tsquery('spanish', q) ||
+    websearch_to_tsquery('swedish', q) ||
+    websearch_to_tsquery('tamil', q) ||
+    websearch_to_tsquery('turkish', q) ||
+    websearch_to_tsquery('yiddish', q)
+$$;
+
+alter table post
+  add column search_vector tsvector not null generated always as (
+    setweight(to_tsvector(lemmy_ts_config(language_id), name), 'A') ||
+    setweight(to_tsvector(lemmy_ts_config(language_id), coalesce(body, '')), 'B')
+  ) stored;
+
+alter table comment
+  add column search_vector tsvector not null generated always as (
+    to_tsvector(lemmy_ts_config(language_id), content)
+  ) stored;
+
+alter table community
+  add column search_vector tsvector not null generated always as (
+    setweight(to_tsvector('simple', title), 'A') ||
+    setweight(to_tsvector('simple', coalesce(description, '')), 'B')
+  ) stored;
+
+create index idx_post_search_vector on post using gin (search_vector);
+create index idx_comment_search_vector on comment using gin (search_vector);
+create index idx_community_search_vector on community using gin (search_vector);
+
+ALTER TYPE sort_type_enum ADD VALUE 'Relevance';
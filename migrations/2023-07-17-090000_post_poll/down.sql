@@ -0,0 +1,5 @@
+drop trigger post_poll_option_vote_count on post_poll_vote;
+drop function post_poll_option_vote_count;
+drop table post_poll_vote;
+drop table post_poll_option;
+drop table post_poll;
@@ -0,0 +1,46 @@
+-- Polls which are attached to a post, federated as ActivityPub Question
+create table post_poll (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade not null unique,
+  multiple_choice boolean not null default false,
+  end_time timestamptz,
+  published timestamptz not null default now()
+);
+
+-- For remote polls, vote_count is the total reported by the instance of the poll
+create table post_poll_option (
+  id serial primary key,
+  poll_id int references post_poll on update cascade on delete cascade not null,
+  name text not null,
+  vote_count int not null default 0,
+  unique (poll_id, name)
+);
+
+create table post_poll_vote (
+  id serial primary key,
+  option_id int references post_poll_option on update cascade on delete cascade not null,
+  person_id int references person on update cascade on delete cascade not null,
+  published timestamptz not null default now(),
+  unique (option_id, person_id)
+);
+
+create function post_poll_option_vote_count()
+returns trigger language plpgsql
+as $$
+begin
+  IF (TG_OP = 'INSERT') THEN
+    update post_poll_option
+    set vote_count = vote_count + 1
+    where id = NEW.option_id;
+  ELSIF (TG_OP = 'DELETE') THEN
+    update post_poll_option
+    set vote_count = vote_count - 1
+    where id = OLD.option_id;
+  END IF;
+  return null;
+end $$;
+
+create trigger post_poll_option_vote_count
+after insert or delete on post_poll_vote
+for each row
+execute procedure post_poll_option_vote_count();
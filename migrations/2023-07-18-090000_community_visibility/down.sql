@@ -0,0 +1,4 @@
+alter table community
+    drop column visibility;
+
+drop type community_visibility_enum;
@@ -0,0 +1,8 @@
+create type community_visibility_enum as enum (
+    'Public',
+    'ApprovalRequired',
+    'LocalOnly'
+);
+
+alter table community
+    add column visibility community_visibility_enum not null default 'Public';
@@ -0,0 +1,3 @@
+drop table community_automod_rule;
+drop type automod_action_enum;
+drop type automod_rule_type_enum;
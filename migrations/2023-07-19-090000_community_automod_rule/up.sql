@@ -0,0 +1,26 @@
+-- Automatic moderation rules which mods can configure for their community
+create type automod_rule_type_enum as enum (
+  'MinAccountAge',
+  'MinScore',
+  'BannedDomain',
+  'TitleRegex',
+  'RequiredKeyword'
+);
+
+create type automod_action_enum as enum (
+  'Reject',
+  'Remove',
+  'Report'
+);
+
+create table community_automod_rule (
+  id serial primary key,
+  community_id int references community on update cascade on delete cascade not null,
+  rule_type automod_rule_type_enum not null,
+  value text not null,
+  action automod_action_enum not null,
+  reason text,
+  published timestamptz not null default now()
+);
+
+create index idx_community_automod_rule_community on community_automod_rule (community_id);
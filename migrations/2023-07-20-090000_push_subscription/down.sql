@@ -0,0 +1,5 @@
+alter table local_user drop column push_notify_replies;
+alter table local_user drop column push_notify_mentions;
+alter table local_user drop column push_notify_private_messages;
+
+drop table push_subscription;
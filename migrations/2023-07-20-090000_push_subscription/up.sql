@@ -0,0 +1,15 @@
+-- Web push subscriptions of the browsers and apps of local users
+create table push_subscription (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  endpoint text not null unique,
+  p256dh text not null,
+  auth text not null,
+  published timestamptz not null default now()
+);
+
+create index idx_push_subscription_local_user on push_subscription (local_user_id);
+
+alter table local_user add column push_notify_replies boolean not null default true;
+alter table local_user add column push_notify_mentions boolean not null default true;
+alter table local_user add column push_notify_private_messages boolean not null default true;
@@ -0,0 +1,13 @@
+drop table mod_resolve_report;
+drop table report_note;
+
+alter table private_message_report drop column assignee_id;
+alter table private_message_report drop column resolution_reason;
+
+alter table comment_report drop column assignee_id;
+alter table comment_report drop column escalated;
+alter table comment_report drop column resolution_reason;
+
+alter table post_report drop column assignee_id;
+alter table post_report drop column escalated;
+alter table post_report drop column resolution_reason;
@@ -0,0 +1,39 @@
+-- Lets moderators claim reports, and keeps the reason why a report was resolved
+alter table post_report add column assignee_id int references person on update cascade on delete set null;
+alter table post_report add column escalated boolean not null default false;
+alter table post_report add column resolution_reason text;
+
+alter table comment_report add column assignee_id int references person on update cascade on delete set null;
+alter table comment_report add column escalated boolean not null default false;
+alter table comment_report add column resolution_reason text;
+
+-- Private message reports only go to the admins, so they can't be escalated
+alter table private_message_report add column assignee_id int references person on update cascade on delete set null;
+alter table private_message_report add column resolution_reason text;
+
+-- Internal notes of the moderators on a report, each note belongs to exactly one report
+create table report_note (
+  id serial primary key,
+  post_report_id int references post_report on update cascade on delete cascade,
+  comment_report_id int references comment_report on update cascade on delete cascade,
+  private_message_report_id int references private_message_report on update cascade on delete cascade,
+  creator_id int references person on update cascade on delete cascade not null,
+  content text not null,
+  published timestamptz not null default now(),
+  check (num_nonnulls(post_report_id, comment_report_id, private_message_report_id) = 1)
+);
+
+create index idx_report_note_post_report on report_note (post_report_id);
+create index idx_report_note_comment_report on report_note (comment_report_id);
+create index idx_report_note_private_message_report on report_note (private_message_report_id);
+
+-- Resolved reports of posts and comments in the modlog
+create table mod_resolve_report (
+  id serial primary key,
+  mod_person_id int references person on update cascade on delete cascade not null,
+  post_id int references post on update cascade on delete cascade not null,
+  comment_id int references comment on update cascade on delete cascade,
+  resolved boolean not null default true,
+  reason text,
+  when_ timestamptz not null default now()
+);
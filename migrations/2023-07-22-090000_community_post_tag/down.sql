@@ -0,0 +1,2 @@
+drop table post_tag;
+drop table community_post_tag;
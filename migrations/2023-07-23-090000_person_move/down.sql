@@ -0,0 +1,3 @@
+alter table person
+  drop column also_known_as,
+  drop column moved_to;
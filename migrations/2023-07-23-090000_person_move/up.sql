@@ -0,0 +1,5 @@
+-- Account migration: the accounts which a person is also known as, and the account which it moved
+-- to
+alter table person
+  add column also_known_as text[] not null default '{}',
+  add column moved_to varchar(255);
@@ -0,0 +1,3 @@
+drop table post_edit_history;
+drop table comment_edit_history;
+alter table community drop column edit_history_public;
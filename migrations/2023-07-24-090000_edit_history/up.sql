@@ -0,0 +1,24 @@
+-- The previous versions of edited posts and comments
+create table post_edit_history (
+  id serial primary key,
+  post_id int references post on update cascade on delete cascade not null,
+  name varchar(200) not null,
+  url varchar(512),
+  body text,
+  -- When this version was replaced by the edit
+  published timestamptz not null default now()
+);
+
+create index idx_post_edit_history_post on post_edit_history (post_id);
+
+create table comment_edit_history (
+  id serial primary key,
+  comment_id int references comment on update cascade on delete cascade not null,
+  content text not null,
+  published timestamptz not null default now()
+);
+
+create index idx_comment_edit_history_comment on comment_edit_history (comment_id);
+
+-- Otherwise only mods can see the edit history
+alter table community add column edit_history_public boolean not null default false;
@@ -0,0 +1,2 @@
+drop table oidc_login_state;
+drop table oidc_identity;
@@ -0,0 +1,22 @@
+-- Accounts of external OpenID Connect providers which local users can log in with
+create table oidc_identity (
+  id serial primary key,
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  -- Name of the provider in the config file
+  provider varchar(255) not null,
+  -- Identifier of the account at the provider (the `sub` claim)
+  subject text not null,
+  published timestamptz not null default now(),
+  unique (provider, subject),
+  unique (local_user_id, provider)
+);
+
+-- Logins which were started, but where the user hasn't returned from the provider yet
+create table oidc_login_state (
+  id serial primary key,
+  state text not null unique,
+  provider varchar(255) not null,
+  code_verifier text not null,
+  redirect_uri text not null,
+  published timestamptz not null default now()
+);
@@ -0,0 +1 @@
+drop table local_image;
@@ -0,0 +1,9 @@
+-- Images which local users uploaded to pict-rs
+create table local_image (
+  local_user_id int references local_user on update cascade on delete cascade not null,
+  pictrs_alias text primary key,
+  pictrs_delete_token text not null,
+  published timestamptz not null default now()
+);
+
+create index idx_local_image_local_user on local_image (local_user_id);
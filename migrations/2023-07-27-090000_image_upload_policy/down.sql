@@ -0,0 +1,5 @@
+alter table local_site drop column image_upload_max_bytes;
+alter table local_site drop column image_upload_allowed_mime_types;
+alter table local_site drop column image_upload_quota_bytes;
+
+alter table local_image drop column file_size;
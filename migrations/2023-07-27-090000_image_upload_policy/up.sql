@@ -0,0 +1,7 @@
+-- Limits for image uploads, which are checked before the upload is passed to pict-rs
+alter table local_site add column image_upload_max_bytes bigint not null default 10485760;
+alter table local_site add column image_upload_allowed_mime_types text[] not null default '{image/*,video/*}';
+-- The total size of uploads per user, unlimited if null
+alter table local_site add column image_upload_quota_bytes bigint;
+
+alter table local_image add column file_size bigint not null default 0;
@@ -0,0 +1 @@
+drop table remote_image;
@@ -0,0 +1,6 @@
+-- Copies of remote images, which the image proxy keeps in the image storage
+create table remote_image (
+  link text primary key,
+  pictrs_alias text not null,
+  published timestamptz not null default now()
+);
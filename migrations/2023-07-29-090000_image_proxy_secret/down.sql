@@ -0,0 +1,2 @@
+alter table secret
+  drop column image_proxy_secret;
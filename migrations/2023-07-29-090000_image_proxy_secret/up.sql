@@ -0,0 +1,4 @@
+-- Signs the links of the image proxy. It is separate from the jwt secret, so that signatures for
+-- arbitrary urls reveal nothing about the key for logins.
+alter table secret
+  add column image_proxy_secret varchar not null default encode(gen_random_bytes(32), 'hex');
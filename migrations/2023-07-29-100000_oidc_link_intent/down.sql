@@ -0,0 +1,2 @@
+alter table oidc_login_state
+  drop column link_local_user_id;
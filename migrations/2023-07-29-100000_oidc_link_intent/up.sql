@@ -0,0 +1,4 @@
+-- The user who started a login to link the provider account to their account. Only that user can
+-- finish it, and logins without it can't be used for linking.
+alter table oidc_login_state
+  add column link_local_user_id int references local_user on update cascade on delete cascade;
@@ -0,0 +1,20 @@
+-- Each person can vote only once in a poll. For multiple choice polls, all options are chosen with
+-- the same vote, so the unique votes per option are not enough.
+create table post_poll_voter (
+  poll_id int references post_poll on update cascade on delete cascade not null,
+  person_id int references person on update cascade on delete cascade not null,
+  published timestamptz not null default now(),
+  primary key (poll_id, person_id)
+);
+
+insert into post_poll_voter (poll_id, person_id, published)
+select
+  o.poll_id,
+  v.person_id,
+  min(v.published)
+from
+  post_poll_vote v
+  join post_poll_option o on o.id = v.option_id
+group by
+  o.poll_id,
+  v.person_id;
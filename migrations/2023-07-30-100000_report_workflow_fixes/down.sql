@@ -0,0 +1,6 @@
+alter table report_note drop column parent_id;
+
+delete from mod_resolve_report where post_id is null;
+alter table mod_resolve_report drop constraint mod_resolve_report_target;
+alter table mod_resolve_report drop column private_message_report_id;
+alter table mod_resolve_report alter column post_id set not null;
@@ -0,0 +1,10 @@
+-- Resolved reports of private messages are also in the modlog. Those entries only reference the
+-- report, so that the message stays private.
+alter table mod_resolve_report alter column post_id drop not null;
+alter table mod_resolve_report add column private_message_report_id int references private_message_report on update cascade on delete cascade;
+alter table mod_resolve_report add constraint mod_resolve_report_target check (num_nonnulls(post_id, private_message_report_id) = 1);
+
+-- Notes can reply to another note of the same report
+alter table report_note add column parent_id int references report_note on update cascade on delete cascade;
+
+create index idx_report_note_parent on report_note (parent_id);
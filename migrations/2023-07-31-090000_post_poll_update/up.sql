@@ -0,0 +1,6 @@
+-- Local polls whose new vote counts still have to be sent to other instances. Votes are collected
+-- for a while, so that one Update of the post is sent for many votes.
+create table post_poll_update (
+  poll_id int references post_poll on update cascade on delete cascade primary key,
+  published timestamptz not null default now()
+);
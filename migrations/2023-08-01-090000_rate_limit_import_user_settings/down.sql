@@ -0,0 +1,3 @@
+alter table local_site_rate_limit
+  drop column import_user_settings,
+  drop column import_user_settings_per_second;
@@ -0,0 +1,4 @@
+-- Importing user settings fetches many remote objects, so it is only allowed rarely
+alter table local_site_rate_limit
+  add column import_user_settings int not null default 1,
+  add column import_user_settings_per_second int not null default 86400;
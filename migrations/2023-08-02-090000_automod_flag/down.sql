@@ -0,0 +1,4 @@
+alter table mod_remove_post drop column automod;
+alter table mod_remove_comment drop column automod;
+alter table post_report drop column automod;
+alter table comment_report drop column automod;
@@ -0,0 +1,6 @@
+-- Removals and reports by automod are attributed to a moderator of the community, this marks them
+-- so that they aren't shown as actions of that moderator.
+alter table mod_remove_post add column automod boolean default false not null;
+alter table mod_remove_comment add column automod boolean default false not null;
+alter table post_report add column automod boolean default false not null;
+alter table comment_report add column automod boolean default false not null;
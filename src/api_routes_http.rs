@@ -1,14 +1,23 @@
 use actix_web::{guard, web, Error, HttpResponse, Result};
-use lemmy_api::Perform;
+use lemmy_api::{
+  local_user::{
+    login::login,
+    oidc::{oidc_authorize, oidc_login},
+  },
+  Perform,
+};
 use lemmy_api_common::{
   comment::{
+    AssignCommentReport,
     CreateComment,
     CreateCommentLike,
     CreateCommentReport,
     DeleteComment,
     DistinguishComment,
     EditComment,
+    EscalateCommentReport,
     GetComment,
+    GetCommentHistory,
     ListCommentReports,
     RemoveComment,
     ResolveCommentReport,
@@ -16,14 +25,22 @@ use lemmy_api_common::{
   },
   community::{
     AddModToCommunity,
+    ApproveFollow,
     BanFromCommunity,
     BlockCommunity,
+    CreateAutomodRule,
     CreateCommunity,
+    CreateCommunityPostTag,
+    DeleteAutomodRule,
     DeleteCommunity,
+    DeleteCommunityPostTag,
     EditCommunity,
+    EditCommunityPostTag,
     FollowCommunity,
     HideCommunity,
+    ListAutomodRules,
     ListCommunities,
+    ListPendingFollows,
     RemoveCommunity,
     TransferCommunity,
   },
@@ -34,40 +51,56 @@ use lemmy_api_common::{
     BanPerson,
     BlockPerson,
     ChangePassword,
+    CreateReportNote,
     DeleteAccount,
+    DeleteImage,
+    DeletePushSubscription,
+    FollowPerson,
     GetBannedPersons,
     GetCaptcha,
     GetPersonMentions,
     GetReplies,
     GetReportCount,
     GetUnreadCount,
-    Login,
+    ListLogins,
+    ListMyImages,
+    ListReportNotes,
+    Logout,
+    LogoutAll,
     MarkAllAsRead,
     MarkCommentReplyAsRead,
     MarkPersonMentionAsRead,
     PasswordChangeAfterReset,
     PasswordReset,
     Register,
+    RegisterPushSubscription,
     SaveUserSettings,
+    UnfollowPerson,
     VerifyEmail,
   },
   post::{
+    AssignPostReport,
     CreatePost,
     CreatePostLike,
     CreatePostReport,
     DeletePost,
     EditPost,
+    EscalatePostReport,
     FeaturePost,
     GetPost,
+    GetPostHistory,
     GetSiteMetadata,
     ListPostReports,
+    ListScheduledPosts,
     LockPost,
     MarkPostAsRead,
     RemovePost,
     ResolvePostReport,
     SavePost,
+    VotePostPoll,
   },
   private_message::{
+    AssignPrivateMessageReport,
     CreatePrivateMessage,
     CreatePrivateMessageReport,
     DeletePrivateMessage,
@@ -98,13 +131,16 @@ use lemmy_apub::{
   api::{
     list_comments::list_comments,
     list_posts::list_posts,
+    move_account::move_account,
     read_community::read_community,
     read_person::read_person,
     resolve_object::resolve_object,
     search::search,
+    user_settings_backup::{export_user_data, import_user_settings},
   },
   SendActivity,
 };
+use lemmy_routes::live_events::live_events;
 use lemmy_utils::rate_limit::RateLimitCell;
 use serde::Deserialize;
 
@@ -125,6 +161,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route(web::get().to(route_get::<GetModlog>)),
       )
+      .service(
+        web::resource("/events")
+          .wrap(rate_limit.message())
+          .route(web::get().to(live_events)),
+      )
       .service(
         web::resource("/search")
           .wrap(rate_limit.search())
@@ -162,7 +203,39 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           )
           .route("/transfer", web::post().to(route_post::<TransferCommunity>))
           .route("/ban_user", web::post().to(route_post::<BanFromCommunity>))
-          .route("/mod", web::post().to(route_post::<AddModToCommunity>)),
+          .route("/mod", web::post().to(route_post::<AddModToCommunity>))
+          .route(
+            "/follow/pending",
+            web::get().to(route_get::<ListPendingFollows>),
+          )
+          .route(
+            "/follow/approve",
+            web::post().to(route_post::<ApproveFollow>),
+          )
+          .route(
+            "/automod",
+            web::get().to(route_get_crud::<ListAutomodRules>),
+          )
+          .route(
+            "/automod",
+            web::post().to(route_post_crud::<CreateAutomodRule>),
+          )
+          .route(
+            "/automod/delete",
+            web::post().to(route_post_crud::<DeleteAutomodRule>),
+          )
+          .route(
+            "/tag",
+            web::post().to(route_post_crud::<CreateCommunityPostTag>),
+          )
+          .route(
+            "/tag",
+            web::put().to(route_post_crud::<EditCommunityPostTag>),
+          )
+          .route(
+            "/tag/delete",
+            web::post().to(route_post_crud::<DeleteCommunityPostTag>),
+          ),
       )
       .service(
         web::scope("/federated_instances")
@@ -191,13 +264,27 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/lock", web::post().to(route_post::<LockPost>))
           .route("/feature", web::post().to(route_post::<FeaturePost>))
           .route("/list", web::get().to(list_posts))
+          .route("/history", web::get().to(route_get::<GetPostHistory>))
+          .route(
+            "/scheduled/list",
+            web::get().to(route_get::<ListScheduledPosts>),
+          )
           .route("/like", web::post().to(route_post::<CreatePostLike>))
+          .route("/poll/vote", web::post().to(route_post::<VotePostPoll>))
           .route("/save", web::put().to(route_post::<SavePost>))
           .route("/report", web::post().to(route_post::<CreatePostReport>))
           .route(
             "/report/resolve",
             web::put().to(route_post::<ResolvePostReport>),
           )
+          .route(
+            "/report/assign",
+            web::put().to(route_post::<AssignPostReport>),
+          )
+          .route(
+            "/report/escalate",
+            web::put().to(route_post::<EscalatePostReport>),
+          )
           .route("/report/list", web::get().to(route_get::<ListPostReports>))
           .route(
             "/site_metadata",
@@ -230,11 +317,20 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/like", web::post().to(route_post::<CreateCommentLike>))
           .route("/save", web::put().to(route_post::<SaveComment>))
           .route("/list", web::get().to(list_comments))
+          .route("/history", web::get().to(route_get::<GetCommentHistory>))
           .route("/report", web::post().to(route_post::<CreateCommentReport>))
           .route(
             "/report/resolve",
             web::put().to(route_post::<ResolveCommentReport>),
           )
+          .route(
+            "/report/assign",
+            web::put().to(route_post::<AssignCommentReport>),
+          )
+          .route(
+            "/report/escalate",
+            web::put().to(route_post::<EscalateCommentReport>),
+          )
           .route(
             "/report/list",
             web::get().to(route_get::<ListCommentReports>),
@@ -263,6 +359,10 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             "/report/resolve",
             web::put().to(route_post::<ResolvePrivateMessageReport>),
           )
+          .route(
+            "/report/assign",
+            web::put().to(route_post::<AssignPrivateMessageReport>),
+          )
           .route(
             "/report/list",
             web::get().to(route_get::<ListPrivateMessageReports>),
@@ -277,6 +377,20 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.register())
           .route(web::post().to(route_post_crud::<Register>)),
       )
+      .service(
+        // Logging in with OpenID Connect can create an account, so it has the same rate limit
+        web::resource("/user/oidc/login")
+          .guard(guard::Post())
+          .wrap(rate_limit.register())
+          .route(web::post().to(oidc_login)),
+      )
+      .service(
+        // Importing fetches many remote objects, so it has its own strict rate limit
+        web::resource("/user/import")
+          .guard(guard::Post())
+          .wrap(rate_limit.import_user_settings())
+          .route(web::post().to(import_user_settings)),
+      )
       .service(
         // Handle captcha separately
         web::resource("/user/get_captcha")
@@ -298,8 +412,24 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/ban", web::post().to(route_post::<BanPerson>))
           .route("/banned", web::get().to(route_get::<GetBannedPersons>))
           .route("/block", web::post().to(route_post::<BlockPerson>))
+          .route("/follow", web::post().to(route_post::<FollowPerson>))
+          .route("/unfollow", web::post().to(route_post::<UnfollowPerson>))
           // Account actions. I don't like that they're in /user maybe /accounts
-          .route("/login", web::post().to(route_post::<Login>))
+          .route("/login", web::post().to(login))
+          .route("/oidc/authorize", web::post().to(oidc_authorize))
+          .route("/logout", web::post().to(route_post::<Logout>))
+          .route("/logout_all", web::post().to(route_post::<LogoutAll>))
+          .route("/list_logins", web::get().to(route_get::<ListLogins>))
+          .route(
+            "/push_subscription",
+            web::post().to(route_post::<RegisterPushSubscription>),
+          )
+          .route(
+            "/push_subscription/delete",
+            web::post().to(route_post::<DeletePushSubscription>),
+          )
+          .route("/images", web::get().to(route_get::<ListMyImages>))
+          .route("/images/delete", web::post().to(route_post::<DeleteImage>))
           .route(
             "/delete_account",
             web::post().to(route_post_crud::<DeleteAccount>),
@@ -325,7 +455,17 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             "/change_password",
             web::put().to(route_post::<ChangePassword>),
           )
+          .route("/move", web::post().to(move_account))
+          .route("/export", web::get().to(export_user_data))
           .route("/report_count", web::get().to(route_get::<GetReportCount>))
+          .route(
+            "/report_note",
+            web::post().to(route_post::<CreateReportNote>),
+          )
+          .route(
+            "/report_note/list",
+            web::get().to(route_get::<ListReportNotes>),
+          )
           .route("/unread_count", web::get().to(route_get::<GetUnreadCount>))
           .route("/verify_email", web::post().to(route_post::<VerifyEmail>))
           .route("/leave_admin", web::post().to(route_post::<LeaveAdmin>)),
@@ -5,6 +5,7 @@ use diesel::{
   ExpressionMethods,
   IntoSql,
   QueryDsl,
+  SelectableHelper,
   TextExpressionMethods,
 };
 use diesel_async::RunQueryDsl;
@@ -106,6 +107,7 @@ async fn community_updates_2020_04_02(
   let incorrect_communities = community
     .filter(actor_id.like("http://changeme%"))
     .filter(local.eq(true))
+    .select(Community::as_select())
     .load::<Community>(conn)
     .await?;
 
@@ -145,6 +147,7 @@ async fn post_updates_2020_04_03(
   let incorrect_posts = post
     .filter(ap_id.like("http://changeme%"))
     .filter(local.eq(true))
+    .select(Post::as_select())
     .load::<Post>(conn)
     .await?;
 
@@ -180,6 +183,7 @@ async fn comment_updates_2020_04_03(
   let incorrect_comments = comment
     .filter(ap_id.like("http://changeme%"))
     .filter(local.eq(true))
+    .select(Comment::as_select())
     .load::<Comment>(conn)
     .await?;
 
@@ -261,6 +265,7 @@ async fn post_thumbnail_url_updates_2020_07_27(
           .concat(thumbnail_url),
       ),
     )
+    .returning(Post::as_returning())
     .get_results::<Post>(conn)
     .await?;
 
@@ -303,6 +308,7 @@ async fn apub_columns_2021_02_02(pool: &DbPool) -> Result<(), LemmyError> {
     };
     let communities = community
       .filter(inbox_url.like("http://changeme%"))
+      .select(Community::as_select())
       .load::<Community>(conn)
       .await?;
 
@@ -316,6 +322,7 @@ async fn apub_columns_2021_02_02(pool: &DbPool) -> Result<(), LemmyError> {
           inbox_url.eq(inbox_url_),
           shared_inbox_url.eq(shared_inbox_url_),
         ))
+        .returning(Community::as_returning())
         .get_result::<Community>(conn)
         .await?;
     }
@@ -368,6 +375,7 @@ async fn regenerate_public_keys_2022_07_05(pool: &DbPool) -> Result<(), LemmyErr
     let communities: Vec<Community> = community
       .filter(local.eq(true))
       .filter(public_key.eq(""))
+      .select(Community::as_select())
       .load::<Community>(conn)
       .await?;
     for community_ in communities {
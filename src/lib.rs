@@ -14,16 +14,17 @@ use actix_web::{middleware, web::Data, App, HttpServer, Result};
 use lemmy_api_common::{
   context::LemmyContext,
   lemmy_db_views::structs::SiteView,
+  live_notification::LiveNotificationListener,
   request::build_user_agent,
   utils::{
     check_private_instance_and_federation_enabled,
     local_site_rate_limit_to_rate_limit_config,
   },
 };
-use lemmy_apub::{VerifyUrlData, FEDERATION_HTTP_FETCH_LIMIT};
+use lemmy_apub::{send_queue::SendQueueOptions, VerifyUrlData, FEDERATION_HTTP_FETCH_LIMIT};
 use lemmy_db_schema::{
-  source::secret::Secret,
-  utils::{build_db_pool, get_database_url, run_migrations},
+  source::{rate_limit_bucket::RateLimitBucket, secret::Secret},
+  utils::{build_db_pool, get_database_url, run_migrations, DbPool},
 };
 use lemmy_routes::{feeds, images, nodeinfo, webfinger};
 use lemmy_utils::{error::LemmyError, rate_limit::RateLimitCell, settings::SETTINGS};
@@ -31,7 +32,8 @@ use reqwest::Client;
 use reqwest_middleware::ClientBuilder;
 use reqwest_tracing::TracingMiddleware;
 use std::{env, thread, time::Duration};
-use tracing::subscriber::set_global_default;
+use tokio::runtime::Handle;
+use tracing::{error, subscriber::set_global_default};
 use tracing_actix_web::TracingLogger;
 use tracing_error::ErrorLayer;
 use tracing_log::LogTracer;
@@ -51,7 +53,8 @@ pub(crate) const REQWEST_TIMEOUT: Duration = Duration::from_secs(10);
 pub async fn start_lemmy_server() -> Result<(), LemmyError> {
   let args: Vec<String> = env::args().collect();
 
-  let scheduled_tasks_enabled = args.get(1) != Some(&"--disable-scheduled-tasks".to_string());
+  let scheduled_tasks_enabled = !args.contains(&"--disable-scheduled-tasks".to_string());
+  let activity_sending_enabled = !args.contains(&"--disable-activity-sending".to_string());
 
   let settings = SETTINGS.to_owned();
 
@@ -86,7 +89,8 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
   // Set up the rate limiter
   let rate_limit_config =
     local_site_rate_limit_to_rate_limit_config(&site_view.local_site_rate_limit);
-  let rate_limit_cell = RateLimitCell::new(rate_limit_config).await;
+  let rate_limit_cell = RateLimitCell::new(rate_limit_config, secret.jwt_secret.clone()).await;
+  restore_rate_limit_buckets(&pool, rate_limit_cell).await;
 
   println!(
     "Starting http server at {}:{}",
@@ -116,35 +120,50 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
     rate_limit_cell.clone(),
   );
 
-  if scheduled_tasks_enabled {
-    // Schedules various cleanup tasks for the DB
-    thread::spawn({
-      let context = context.clone();
-      move || {
-        scheduled_tasks::setup(db_url, user_agent, context)
-          .expect("Couldn't set up scheduled_tasks");
-      }
-    });
-  }
-
   #[cfg(feature = "prometheus-metrics")]
   serve_prometheus(settings.prometheus.as_ref(), context.clone());
 
   let settings_bind = settings.clone();
 
+  // The send queue needs the same options as the federation config
+  let send_queue_options = SendQueueOptions {
+    debug: cfg!(debug_assertions),
+    http_signature_compat: true,
+    worker_count: settings.worker_count,
+    retry_count: settings.retry_count,
+  };
   let federation_config = FederationConfig::builder()
     .domain(settings.hostname.clone())
     .app_data(context.clone())
     .client(client.clone())
     .http_fetch_limit(FEDERATION_HTTP_FETCH_LIMIT)
-    .worker_count(settings.worker_count)
-    .retry_count(settings.retry_count)
-    .debug(cfg!(debug_assertions))
-    .http_signature_compat(true)
+    .debug(send_queue_options.debug)
+    .http_signature_compat(send_queue_options.http_signature_compat)
     .url_verifier(Box::new(VerifyUrlData(context.pool().clone())))
     .build()
     .await?;
 
+  // Listens for live notifications of all Lemmy processes, for the event streams of this one
+  let live_notification_listener = LiveNotificationListener::start(db_url.clone());
+
+  if scheduled_tasks_enabled {
+    // Schedules various cleanup tasks for the DB
+    thread::spawn({
+      let context = context.clone();
+      let federation_config = federation_config.clone();
+      let runtime = Handle::current();
+      move || {
+        scheduled_tasks::setup(db_url, user_agent, context, federation_config, runtime)
+          .expect("Couldn't set up scheduled_tasks");
+      }
+    });
+  }
+
+  if federation_enabled && activity_sending_enabled {
+    // Delivers queued outgoing activities to remote instances
+    lemmy_apub::send_queue::start_send_queue(federation_config.clone(), send_queue_options)?;
+  }
+
   // this must come before the HttpServer creation
   // creates a middleware that populates http metrics for each path, method, and status code
   #[cfg(feature = "prometheus-metrics")]
@@ -174,6 +193,7 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
       .wrap(TracingLogger::<QuieterRootSpanBuilder>::new())
       .app_data(Data::new(context.clone()))
       .app_data(Data::new(rate_limit_cell.clone()))
+      .app_data(Data::new(live_notification_listener.clone()))
       .wrap(FederationMiddleware::new(federation_config.clone()));
 
     #[cfg(feature = "prometheus-metrics")]
@@ -196,9 +216,35 @@ pub async fn start_lemmy_server() -> Result<(), LemmyError> {
   .run()
   .await?;
 
+  // Save rate limits on graceful shutdown, so they dont get reset by restarts
+  persist_rate_limit_buckets(&pool, rate_limit_cell).await;
+
   Ok(())
 }
 
+async fn restore_rate_limit_buckets(pool: &DbPool, rate_limit_cell: &RateLimitCell) {
+  match RateLimitBucket::read_all(pool).await {
+    Ok(buckets) => rate_limit_cell.import_buckets(buckets.into_iter().map(Into::into).collect()),
+    Err(e) => error!("Failed to restore rate limit buckets: {e}"),
+  }
+}
+
+/// Writes the in-memory rate limit buckets to the database, so that they survive restarts.
+pub(crate) async fn persist_rate_limit_buckets(pool: &DbPool, rate_limit_cell: &RateLimitCell) {
+  let buckets = rate_limit_cell
+    .export_buckets()
+    .into_iter()
+    .map(Into::into)
+    .collect();
+  if let Err(e) = RateLimitBucket::upsert_all(pool, buckets).await {
+    error!("Failed to persist rate limit buckets: {e}");
+  }
+  // Other processes upsert their own buckets, so unused ones are only removed by age
+  if let Err(e) = RateLimitBucket::delete_older_than(pool, chrono::Duration::days(1)).await {
+    error!("Failed to remove old rate limit buckets: {e}");
+  }
+}
+
 pub fn init_logging(opentelemetry_url: &Option<Url>) -> Result<(), LemmyError> {
   LogTracer::init()?;
 
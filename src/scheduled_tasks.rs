@@ -1,3 +1,5 @@
+use crate::persist_rate_limit_buckets;
+use activitypub_federation::config::{Data, FederationConfig};
 use chrono::{DateTime, FixedOffset};
 use clokwerk::{Scheduler, TimeUnits as CTimeUnits};
 use diesel::{
@@ -11,16 +13,34 @@ use diesel::{
 };
 // Import week days and WeekDay
 use diesel::{sql_query, PgConnection, RunQueryDsl};
-use lemmy_api_common::context::LemmyContext;
+use lemmy_api_common::{context::LemmyContext, live_notification::LiveNotification};
+use lemmy_apub::activities::create_or_update::{
+  poll_vote::send_poll_updates,
+  post::send_scheduled_post,
+};
 use lemmy_db_schema::{
-  schema::{activity, captcha_answer, comment, community_person_ban, instance, person, post},
-  source::instance::{Instance, InstanceForm},
+  schema::{
+    activity,
+    captcha_answer,
+    comment,
+    community_person_ban,
+    instance,
+    login_token,
+    oidc_login_state,
+    person,
+    post,
+  },
+  source::{
+    instance::{Instance, InstanceForm},
+    post::Post,
+  },
   utils::{naive_now, DELETED_REPLACEMENT_TEXT},
 };
 use lemmy_routes::nodeinfo::NodeInfo;
 use lemmy_utils::{error::LemmyError, REQWEST_TIMEOUT};
 use reqwest::blocking::Client;
 use std::{thread, time::Duration};
+use tokio::runtime::Handle;
 use tracing::{error, info};
 
 /// Schedules various cleanup tasks for lemmy in a background thread
@@ -28,12 +48,16 @@ pub fn setup(
   db_url: String,
   user_agent: String,
   context_1: LemmyContext,
+  federation_config: FederationConfig<LemmyContext>,
+  runtime: Handle,
 ) -> Result<(), LemmyError> {
   // Setup the connections
   let mut scheduler = Scheduler::new();
 
   startup_jobs(&db_url);
 
+  let dead_instance_days = context_1.settings().dead_instance_days;
+
   // Update active counts every hour
   let url = db_url.clone();
   scheduler.every(CTimeUnits::hour(1)).run(move || {
@@ -49,11 +73,29 @@ pub fn setup(
     update_hot_ranks(&mut conn, true);
   });
 
+  // Publish scheduled posts which are due, every minute
+  let publish_runtime = runtime.clone();
+  let publish_config = federation_config.clone();
+  scheduler.every(CTimeUnits::minute(1)).run(move || {
+    let context = publish_config.to_request_data();
+    publish_runtime.block_on(publish_scheduled_posts(&context));
+  });
+
+  // Send the new vote counts of local polls to other instances, every 15 seconds
+  let poll_runtime = runtime.clone();
+  scheduler.every(CTimeUnits::seconds(15)).run(move || {
+    let context = federation_config.to_request_data();
+    if let Err(e) = poll_runtime.block_on(send_poll_updates(&context)) {
+      error!("Failed to send poll updates: {}", e);
+    }
+  });
+
   // Delete any captcha answers older than ten minutes, every ten minutes
   let url = db_url.clone();
   scheduler.every(CTimeUnits::minutes(10)).run(move || {
     let mut conn = PgConnection::establish(&url).expect("could not establish connection");
     delete_expired_captcha_answers(&mut conn);
+    delete_expired_oidc_login_states(&mut conn);
   });
 
   // Clear old activities every week
@@ -63,6 +105,15 @@ pub fn setup(
     clear_old_activities(&mut conn);
   });
 
+  // Save rate limit buckets every five minutes, so that they survive restarts
+  let context = context_1.clone();
+  scheduler.every(CTimeUnits::minutes(5)).run(move || {
+    runtime.block_on(persist_rate_limit_buckets(
+      context.pool(),
+      context.settings_updated_channel(),
+    ));
+  });
+
   // Remove old rate limit buckets after 1 to 2 hours of inactivity
   scheduler.every(CTimeUnits::hour(1)).run(move || {
     let hour = Duration::from_secs(3600);
@@ -76,10 +127,18 @@ pub fn setup(
     overwrite_deleted_posts_and_comments(&mut conn);
   });
 
-  // Update the Instance Software
+  // Delete expired login tokens every day
+  let url = db_url.clone();
+  scheduler.every(CTimeUnits::days(1)).run(move || {
+    let mut conn = PgConnection::establish(&url).expect("could not establish connection");
+    delete_expired_login_tokens(&mut conn);
+  });
+
+  // Update the Instance Software, and mark instances which can't be reached anymore as dead
   scheduler.every(CTimeUnits::days(1)).run(move || {
     let mut conn = PgConnection::establish(&db_url).expect("could not establish connection");
     update_instance_software(&mut conn, &user_agent);
+    update_dead_instances(&mut conn, dead_instance_days);
   });
 
   // Manually run the scheduler in an event loop
@@ -89,6 +148,28 @@ pub fn setup(
   }
 }
 
+/// Publish the scheduled posts which are due, and only then send them to the community followers
+async fn publish_scheduled_posts(context: &Data<LemmyContext>) {
+  match Post::publish_scheduled(context.pool()).await {
+    Ok(posts) => {
+      // Removed posts are published too, but not sent out
+      for post in posts.into_iter().filter(|p| !p.removed) {
+        if let Err(e) = send_scheduled_post(&post, context).await {
+          error!("Failed to send scheduled post {}: {}", post.id, e);
+        }
+        LiveNotification::Post {
+          creator_id: post.creator_id,
+          community_id: post.community_id,
+          post_id: post.id,
+        }
+        .send(context.pool())
+        .await;
+      }
+    }
+    Err(e) => error!("Failed to publish scheduled posts: {}", e),
+  }
+}
+
 /// Run these on server startup
 fn startup_jobs(db_url: &str) {
   let mut conn = PgConnection::establish(db_url).expect("could not establish connection");
@@ -97,6 +178,7 @@ fn startup_jobs(db_url: &str) {
   update_banned_when_expired(&mut conn);
   clear_old_activities(&mut conn);
   overwrite_deleted_posts_and_comments(&mut conn);
+  delete_expired_login_tokens(&mut conn);
 }
 
 /// Update the hot_rank columns for the aggregates tables
@@ -203,6 +285,36 @@ fn delete_expired_captcha_answers(conn: &mut PgConnection) {
   }
 }
 
+/// Logins which weren't finished within ten minutes can't be finished anymore
+fn delete_expired_oidc_login_states(conn: &mut PgConnection) {
+  match diesel::delete(
+    oidc_login_state::table
+      .filter(oidc_login_state::published.lt(now - IntervalDsl::minutes(10))),
+  )
+  .execute(conn)
+  {
+    Ok(_) => {
+      info!("Done.");
+    }
+    Err(e) => {
+      error!("Failed to clear old oidc login states: {}", e)
+    }
+  }
+}
+
+/// Expired tokens are rejected anyway, so there is no reason to keep them around
+fn delete_expired_login_tokens(conn: &mut PgConnection) {
+  info!("Deleting expired login tokens...");
+  match diesel::delete(login_token::table.filter(login_token::expires.lt(now))).execute(conn) {
+    Ok(_) => {
+      info!("Done.");
+    }
+    Err(e) => {
+      error!("Failed to delete expired login tokens: {}", e)
+    }
+  }
+}
+
 /// Clear old activities (this table gets very large)
 fn clear_old_activities(conn: &mut PgConnection) {
   info!("Clearing old activities...");
@@ -381,6 +493,33 @@ fn update_instance_software(conn: &mut PgConnection, user_agent: &str) {
   }
 }
 
+/// Marks instances as dead if there was no contact with them for the given number of days, and
+/// revives instances which are reachable again. Contact means a successful nodeinfo fetch, a
+/// received activity or a successful activity delivery.
+fn update_dead_instances(conn: &mut PgConnection, dead_instance_days: u32) {
+  info!("Updating dead instances...");
+
+  let days = i32::try_from(dead_instance_days).unwrap_or(i32::MAX);
+  match sql_query(
+    "update instance set dead = greatest(
+      published,
+      updated,
+      last_received_activity,
+      (select last_successful_send from federation_queue_state where instance_id = instance.id)
+    ) < now() - make_interval(days => $1)",
+  )
+  .bind::<Integer, _>(days)
+  .execute(conn)
+  {
+    Ok(_) => {
+      info!("Done.");
+    }
+    Err(e) => {
+      error!("Failed to update dead instances: {}", e)
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use lemmy_routes::nodeinfo::NodeInfo;